@@ -0,0 +1,155 @@
+use crate::lint::{Severity, Violation};
+use serde::Serialize;
+
+/// How one check in an audit came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// The check couldn't run, e.g. without a forge to compare releases with
+    Skip,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+            Status::Skip => "skip",
+        })
+    }
+}
+
+/// One check in an audit and the problems it found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    /// Why the check was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub problems: Vec<String>,
+}
+
+impl Check {
+    /// A check whose problems all count as `status`, passing without any
+    pub fn new(name: &'static str, status: Status, problems: Vec<String>) -> Self {
+        Check {
+            name,
+            status: if problems.is_empty() {
+                Status::Pass
+            } else {
+                status
+            },
+            reason: None,
+            problems,
+        }
+    }
+
+    /// A check that fails on lint errors and warns on anything else
+    pub fn violations(name: &'static str, violations: &[Violation]) -> Self {
+        let status = if violations.iter().any(|v| v.severity == Severity::Error) {
+            Status::Fail
+        } else {
+            Status::Warn
+        };
+        Check::new(
+            name,
+            status,
+            violations.iter().map(|v| v.to_string()).collect(),
+        )
+    }
+
+    pub fn skipped(name: &'static str, reason: impl ToString) -> Self {
+        Check {
+            name,
+            status: Status::Skip,
+            reason: Some(reason.to_string()),
+            problems: Vec::new(),
+        }
+    }
+}
+
+/// Every check on a repository's changelog, scored out of 100
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Audit {
+    pub path: String,
+    pub score: u32,
+    pub checks: Vec<Check>,
+}
+
+impl Audit {
+    /// Checks that ran score a point for passing and half for a warning.
+    /// When none could run there's nothing to hold against the changelog.
+    pub fn new(path: String, checks: Vec<Check>) -> Self {
+        let (points, ran): (u32, u32) =
+            checks
+                .iter()
+                .fold((0, 0), |(points, ran), check| match check.status {
+                    Status::Pass => (points + 2, ran + 2),
+                    Status::Warn => (points + 1, ran + 2),
+                    Status::Fail => (points, ran + 2),
+                    Status::Skip => (points, ran),
+                });
+        let score = (100 * points + ran / 2).checked_div(ran).unwrap_or(100);
+        Audit {
+            path,
+            score,
+            checks,
+        }
+    }
+
+    /// How many checks failed, counting warnings too when `strict`
+    pub fn failures(&self, strict: bool) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| match check.status {
+                Status::Fail => true,
+                Status::Warn => strict,
+                Status::Pass | Status::Skip => false,
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_score() {
+        let warning = Violation::new("stale-link", Severity::Warning, Some("0.9.0"), "stale");
+        let audit = Audit::new(
+            "CHANGELOG.md".to_string(),
+            vec![
+                Check::violations("lint", &[]),
+                Check::violations("links", &[warning]),
+                Check::new(
+                    "tags",
+                    Status::Fail,
+                    vec!["1.0.0: no v1.0.0 tag".to_string()],
+                ),
+                Check::skipped("releases", "no forge"),
+            ],
+        );
+        assert_eq!(
+            audit.checks.iter().map(|c| c.status).collect::<Vec<_>>(),
+            [Status::Pass, Status::Warn, Status::Fail, Status::Skip]
+        );
+        assert_eq!(audit.score, 50);
+        assert_eq!(
+            audit.checks[1].problems,
+            ["warning[stale-link] 0.9.0: stale"]
+        );
+        assert_eq!(audit.failures(false), 1);
+        assert_eq!(audit.failures(true), 2);
+
+        let clean = Audit::new("CHANGELOG.md".to_string(), audit.checks[..2].to_vec());
+        assert_eq!(clean.score, 75);
+        assert_eq!(clean.failures(false), 0);
+        assert_eq!(Audit::new(String::new(), Vec::new()).score, 100);
+    }
+}
@@ -0,0 +1,854 @@
+use crate::hooks::Hook;
+use crate::notify::Webhook;
+use crate::template::{Attribution, Template};
+use crate::version_files::VersionFile;
+use crate::versioning::Scheme;
+use crate::{Bump, ChangeType, Error, ForgeKind, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Name of the optional config file that lives next to CHANGELOG.md
+pub const FILENAME: &str = "changelog.toml";
+
+/// What release tags start with unless configured, as in `v1.2.3`
+pub const DEFAULT_TAG_PREFIX: &str = "v";
+
+/// Project settings read from `changelog.toml`. Every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub review: ReviewConfig,
+    pub release: ReleaseConfig,
+    pub forge: ForgeConfig,
+    pub http: HttpConfig,
+    pub entries: EntriesConfig,
+    pub links: LinksConfig,
+    pub sections: SectionsConfig,
+    pub lint: LintConfig,
+    pub hooks: HooksConfig,
+    pub notify: NotifyConfig,
+    /// Forge labels and what they mean, e.g. `bug = "fixed"` or `breaking = "major"`
+    pub labels: BTreeMap<String, LabelRule>,
+}
+
+/// What a forge label on a pull request means for the changelog
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum LabelRule {
+    /// Entries for the pull request belong in this section
+    Type(ChangeType),
+    /// The pull request calls for at least this version bump
+    Bump(Bump),
+    /// The pull request doesn't need a changelog entry
+    Skip,
+}
+
+impl TryFrom<String> for LabelRule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        // Anything else names a custom section, which is checked once the
+        // whole config is loaded
+        if let Ok(bump) = Bump::from_str(&value, true) {
+            Ok(LabelRule::Bump(bump))
+        } else if value.eq_ignore_ascii_case("skip") {
+            Ok(LabelRule::Skip)
+        } else {
+            value.parse().map(LabelRule::Type)
+        }
+    }
+}
+
+/// Labels understood when `changelog.toml` doesn't map them itself
+const DEFAULT_LABELS: &[(&str, LabelRule)] = &[
+    ("bug", LabelRule::Type(ChangeType::Fixed)),
+    ("bugfix", LabelRule::Type(ChangeType::Fixed)),
+    ("fix", LabelRule::Type(ChangeType::Fixed)),
+    ("enhancement", LabelRule::Type(ChangeType::Added)),
+    ("feature", LabelRule::Type(ChangeType::Added)),
+    ("deprecation", LabelRule::Type(ChangeType::Deprecated)),
+    ("removal", LabelRule::Type(ChangeType::Removed)),
+    ("security", LabelRule::Type(ChangeType::Security)),
+    ("breaking", LabelRule::Bump(Bump::Major)),
+    ("breaking-change", LabelRule::Bump(Bump::Major)),
+    ("skip-changelog", LabelRule::Skip),
+    ("no-changelog", LabelRule::Skip),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReviewConfig {
+    /// Append a link to the source commit to entries created by `review`
+    pub commit_links: bool,
+    /// Nest entries from scoped commits, like `feat(api): ...`, under a
+    /// `- **api:**` bullet in their section
+    pub group_scopes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReleaseConfig {
+    /// Files whose version is updated by every release, relative to the changelog
+    pub bump_files: Vec<VersionFile>,
+    /// Credit whoever cuts each release in a line below its heading
+    pub attribution: Option<AttributionConfig>,
+    /// How versions are numbered: `semver`, or calendar versions like
+    /// `YYYY.0M.MICRO` that `release` dates instead of bumping
+    pub scheme: Scheme,
+    /// What comes before the version in release tags, e.g. `release-` or
+    /// `mypkg-v`, or nothing for bare `1.2.3` tags
+    pub tag_prefix: String,
+    /// How release dates are written and linted
+    pub date_format: DateFormat,
+    /// Date releases by the day in UTC instead of the local timezone
+    pub utc: bool,
+    /// Which steps `release --workflow` skips and where it pushes, from
+    /// `[release.workflow]`
+    pub workflow: WorkflowConfig,
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        ReleaseConfig {
+            bump_files: Vec::new(),
+            attribution: None,
+            scheme: Scheme::default(),
+            tag_prefix: DEFAULT_TAG_PREFIX.to_string(),
+            date_format: DateFormat::default(),
+            utc: false,
+            workflow: WorkflowConfig::default(),
+        }
+    }
+}
+
+/// A chrono `strftime` format for release dates, `%Y-%m-%d` unless a team
+/// insists on something like `%d.%m.%Y`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DateFormat(String);
+
+impl DateFormat {
+    const ISO: &'static str = "%Y-%m-%d";
+
+    /// `date` written in this format
+    pub fn format(&self, date: NaiveDate) -> String {
+        date.format(&self.0).to_string()
+    }
+
+    /// `date` if it's written in this format
+    pub fn parse(&self, date: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(date, &self.0).ok()
+    }
+
+    /// `date` as a changelog or `--date` may have it: in this format, or in
+    /// ISO format from before another one was configured
+    pub fn read(&self, date: &str) -> Option<NaiveDate> {
+        self.parse(date)
+            .or_else(|| NaiveDate::parse_from_str(date, Self::ISO).ok())
+    }
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat(Self::ISO.to_string())
+    }
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = String;
+
+    fn try_from(format: String) -> std::result::Result<Self, String> {
+        let invalid = |reason: &str| format!("date format '{}' {}", format, reason);
+        // Release titles are split on these to find the date
+        if format.contains(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+            return Err(invalid("can't contain spaces, commas, or semicolons"));
+        }
+        let items = chrono::format::StrftimeItems::new(&format);
+        if items
+            .clone()
+            .any(|item| item == chrono::format::Item::Error)
+        {
+            return Err(invalid("is not a valid strftime format"));
+        }
+        let sample = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap_or_default();
+        let written = sample.format_with_items(items).to_string();
+        if NaiveDate::parse_from_str(&written, &format).ok() != Some(sample) {
+            return Err(invalid("needs a year, month, and day"));
+        }
+        Ok(DateFormat(format))
+    }
+}
+
+/// `YYYY-MM-DD` for the default format, or the format itself
+impl std::fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == Self::ISO {
+            f.write_str("YYYY-MM-DD")
+        } else {
+            f.write_str(&self.0)
+        }
+    }
+}
+
+/// What `release --workflow` does once the changelog is written
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkflowConfig {
+    /// Steps left out of every workflow release, e.g. `["publish"]`
+    pub skip: Vec<WorkflowStep>,
+    /// Remote the release commit and tag are pushed to
+    pub remote: String,
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        WorkflowConfig {
+            skip: Vec::new(),
+            remote: "origin".to_string(),
+        }
+    }
+}
+
+/// A step of `release --workflow`, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkflowStep {
+    /// Update the version in manifests and `bump_files`
+    Bump,
+    /// Commit the changelog and bumped files
+    Commit,
+    /// Tag the release
+    Tag,
+    /// Push the commit and tag
+    Push,
+    /// Create the forge release
+    Publish,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttributionConfig {
+    /// How the line reads, `Released by {name}` by default
+    pub template: Attribution,
+    /// Who to credit instead of the release's tagger, e.g. a release team
+    pub name: Option<String>,
+}
+
+/// Shell commands run around `release` and `add`, e.g.
+/// `post_release = "./scripts/announce"`. See [`crate::hooks::run`] for what
+/// they're handed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Runs before `release` writes the changelog, and stops it by failing
+    pub pre_release: Option<String>,
+    /// Runs after `release` is done, including any commit and tag
+    pub post_release: Option<String>,
+    /// Runs after `add` adds an entry
+    pub post_add: Option<String>,
+}
+
+impl HooksConfig {
+    /// The command configured for `hook`
+    pub fn command(&self, hook: Hook) -> Option<&str> {
+        match hook {
+            Hook::PreRelease => self.pre_release.as_deref(),
+            Hook::PostRelease => self.post_release.as_deref(),
+            Hook::PostAdd => self.post_add.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Webhooks `notify` posts to by name, e.g. `team = "https://hooks.slack.com/..."`
+    pub webhooks: BTreeMap<String, Webhook>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Programs `lint` runs with the changelog as JSON on stdin, printing a
+    /// JSON list of violations, e.g. `["./scripts/check-entries"]`
+    pub plugins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntriesConfig {
+    /// Layout of the bullets `add`, `review`, and `pr-notes` write, which
+    /// `fmt` also applies to existing entries that fit it
+    pub template: Option<Template>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinksConfig {
+    /// What a stable release's compare link starts from when pre-releases
+    /// of it came first
+    pub final_compare: FinalCompare,
+    /// Link shortcuts `fmt` expands in entries, e.g. `rfc = "https://docs.example.com/rfc/{id}"`
+    /// turns `{rfc:123}` into `[rfc:123](https://docs.example.com/rfc/123)`
+    pub shortcuts: BTreeMap<String, String>,
+    /// Link bare `#123` references and commit shas in entries to the forge
+    /// when formatting and adding them
+    pub autolink: bool,
+    /// Where version links go: definitions at the bottom, in the headings,
+    /// or nowhere
+    pub link_style: LinkStyle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStyle {
+    /// `## [1.2.0] - 2025-02-01` with `[1.2.0]: url` at the bottom
+    #[default]
+    Reference,
+    /// `## [1.2.0](url) - 2025-02-01`
+    Inline,
+    /// `## 1.2.0 - 2025-02-01`, without links
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalCompare {
+    /// The last pre-release, e.g. `v2.0.0-rc.2...v2.0.0`
+    #[default]
+    Prerelease,
+    /// The last stable release, e.g. `v1.9.0...v2.0.0`
+    Stable,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SectionsConfig {
+    /// Where sections go in a release, by name or alias. Sections left out
+    /// follow the listed ones in their usual order.
+    pub order: Vec<String>,
+    /// Sections beyond the six from Keep a Changelog
+    pub custom: Vec<CustomSection>,
+}
+
+/// A section like `Performance` or `Documentation`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomSection {
+    /// The `### Heading` entries are written under
+    pub name: String,
+    /// Short names to pass to `--type`, e.g. `perf`
+    pub aliases: Vec<String>,
+    /// Conventional commit types whose commits belong in the section
+    pub commit_types: Vec<String>,
+}
+
+impl SectionsConfig {
+    /// Every section, built-in and custom, in release order
+    pub fn all(&self) -> Vec<ChangeType> {
+        let usual = ChangeType::BUILT_IN.iter().cloned().chain(
+            self.custom
+                .iter()
+                .map(|section| ChangeType::Custom(section.name.clone())),
+        );
+        let mut sections: Vec<ChangeType> = self
+            .order
+            .iter()
+            .filter_map(|name| self.resolve(name))
+            .collect();
+        for section in usual {
+            if !sections.contains(&section) {
+                sections.push(section);
+            }
+        }
+        sections
+    }
+
+    /// The section a type name, heading, or alias refers to, matched
+    /// case-insensitively
+    pub fn resolve(&self, name: &str) -> Option<ChangeType> {
+        let name = name.trim();
+        ChangeType::built_in(name).or_else(|| {
+            self.custom
+                .iter()
+                .find(|section| {
+                    section.name.eq_ignore_ascii_case(name)
+                        || slug(&section.name).eq_ignore_ascii_case(name)
+                        || section.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+                })
+                .map(|section| ChangeType::Custom(section.name.clone()))
+        })
+    }
+
+    /// Like [`resolve`](Self::resolve), for a type given on the command line
+    pub fn section(&self, r#type: &ChangeType) -> Result<ChangeType> {
+        match r#type {
+            ChangeType::Custom(name) => self.resolve(name).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Unknown change type '{}', expected one of: {}",
+                    name,
+                    self.codes().join(", ")
+                ))
+            }),
+            built_in => Ok(built_in.clone()),
+        }
+    }
+
+    /// Section for a commit of a conventional commit type, like `feat`
+    pub fn commit_type(&self, commit_type: &str) -> Option<ChangeType> {
+        let custom = self.custom.iter().find(|section| {
+            section
+                .commit_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(commit_type))
+        });
+        match custom {
+            Some(section) => Some(ChangeType::Custom(section.name.clone())),
+            None if commit_type == "feat" => Some(ChangeType::Added),
+            None if commit_type == "fix" => Some(ChangeType::Fixed),
+            None => None,
+        }
+    }
+
+    /// Every section's name with its aliases, for help text
+    pub fn codes(&self) -> Vec<String> {
+        self.all()
+            .iter()
+            .map(|section| {
+                let aliases = match section {
+                    ChangeType::Custom(name) => self
+                        .custom
+                        .iter()
+                        .find(|s| &s.name == name)
+                        .map(|s| s.aliases.join(", "))
+                        .unwrap_or_default(),
+                    built_in => built_in.to_string()[..1].to_string(),
+                };
+                match aliases.is_empty() {
+                    true => section.to_string(),
+                    false => format!("{} ({})", section, aliases),
+                }
+            })
+            .collect()
+    }
+
+    fn check(&self) -> std::result::Result<(), String> {
+        let mut seen: Vec<String> = Vec::new();
+        for section in &self.custom {
+            if section.name.contains('\n') {
+                return Err(format!("section name '{}' spans lines", section.name));
+            }
+            let aliases = section.aliases.iter().map(String::as_str);
+            for key in std::iter::once(section.name.as_str()).chain(aliases) {
+                let key = slug(key.trim());
+                if key.is_empty()
+                    || ChangeType::built_in(&key).is_some()
+                    || seen.iter().any(|s| s.eq_ignore_ascii_case(&key))
+                {
+                    return Err(format!(
+                        "section name or alias '{}' is empty or already taken",
+                        key
+                    ));
+                }
+                seen.push(key);
+            }
+        }
+        match self.order.iter().find(|name| self.resolve(name).is_none()) {
+            Some(name) => Err(format!("unknown section '{}' in sections.order", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A section name as one word, e.g. `breaking-changes`
+fn slug(name: &str) -> String {
+    ChangeType::Custom(name.to_string()).to_string()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForgeConfig {
+    /// Forge software behind the origin remote, for self-hosted instances
+    /// that can't be recognized from their host name
+    #[serde(rename = "type")]
+    pub kind: Option<ForgeKind>,
+    /// Repository to link to and publish on instead of the git remotes,
+    /// `owner/name` or a URL like `--repo` takes
+    pub repo: Option<String>,
+    /// Git remote to find the repository from instead of trying `origin`,
+    /// `upstream`, and then any other remote on a forge we recognize
+    pub remote: Option<String>,
+}
+
+/// How forge API requests are made
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Times a request is retried after a dropped connection, a server error,
+    /// or a rate limit that resets within a minute
+    pub retries: u32,
+    /// Seconds before a request is abandoned
+    pub timeout: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            retries: 3,
+            timeout: 30,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config from `dir`, falling back to the defaults when there is no file
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(FILENAME);
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content, &path),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Parse and check a config read from `path`, which errors name
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let config: Config = toml::from_str(content).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        config.check().map_err(|message| Error::Config {
+            path: path.to_path_buf(),
+            message,
+        })?;
+        Ok(config)
+    }
+
+    /// Rule for a forge label, matched case-insensitively. Labels in the
+    /// config take precedence over the built-in ones.
+    pub fn label_rule(&self, label: &str) -> Option<&LabelRule> {
+        let configured = self.labels.iter().map(|(l, r)| (l.as_str(), r));
+        let defaults = DEFAULT_LABELS.iter().map(|(l, r)| (*l, r));
+        configured
+            .chain(defaults)
+            .find(|(l, _)| l.eq_ignore_ascii_case(label))
+            .map(|(_, r)| r)
+    }
+
+    /// Change type implied by a forge label
+    pub fn label_type(&self, label: &str) -> Option<ChangeType> {
+        match self.label_rule(label)? {
+            LabelRule::Type(change_type) => self.sections.section(change_type).ok(),
+            _ => None,
+        }
+    }
+
+    /// Settings that only make sense together, like labels naming sections
+    fn check(&self) -> std::result::Result<(), String> {
+        self.sections.check()?;
+        for (name, url) in &self.links.shortcuts {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(format!(
+                    "link shortcut '{}' can only have letters, numbers, '-', and '_'",
+                    name
+                ));
+            }
+            if !url.contains("{id}") {
+                return Err(format!(
+                    "link shortcut '{}' needs an {{id}} in its URL",
+                    name
+                ));
+            }
+        }
+        if let Some(repo) = &self.forge.repo {
+            if crate::forge::Remote::from_spec(repo, self.forge.kind).is_none() {
+                return Err(format!(
+                    "forge repo '{}' is not like owner/name or a repository URL",
+                    repo
+                ));
+            }
+        }
+        for (label, rule) in &self.labels {
+            if let LabelRule::Type(change_type) = rule {
+                if self.sections.section(change_type).is_err() {
+                    return Err(format!(
+                        "unknown label rule '{}' for '{}', expected a change type, major, minor, patch, or skip",
+                        change_type, label
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Largest version bump called for by any of `labels`
+    pub fn labels_bump<'a>(&self, labels: impl IntoIterator<Item = &'a str>) -> Option<Bump> {
+        labels
+            .into_iter()
+            .filter_map(|label| match self.label_rule(label)? {
+                LabelRule::Bump(bump) => Some(*bump),
+                _ => None,
+            })
+            .max()
+    }
+
+    /// Whether `labels` mark a pull request as needing a changelog entry
+    pub fn entry_required<'a>(&self, labels: impl IntoIterator<Item = &'a str>) -> bool {
+        !labels
+            .into_iter()
+            .any(|label| self.label_rule(label) == Some(&LabelRule::Skip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_config() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(Config::load(temp_dir.path()).unwrap(), Config::default());
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[review]\ncommit_links = true\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).unwrap().review.commit_links);
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release]\nbump_files = [\"Cargo.toml\", { path = \"VERSION\", pattern = \".+\" }]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().release.bump_files,
+            vec![
+                VersionFile::Manifest("Cargo.toml".into()),
+                VersionFile::Pattern {
+                    path: "VERSION".into(),
+                    pattern: ".+".to_string()
+                },
+            ]
+        );
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release.attribution]\nname = \"Release Team\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().release.attribution,
+            Some(AttributionConfig {
+                template: Attribution::default(),
+                name: Some("Release Team".to_string()),
+            })
+        );
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release.attribution]\ntemplate = \"Released\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release]\nscheme = \"YY.0M.MICRO\"\n",
+        )
+        .unwrap();
+        let release = Config::load(temp_dir.path()).unwrap().release;
+        assert!(release.scheme.is_valid("25.05.2"));
+        assert_eq!(release.tag_prefix, "v");
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release]\nscheme = \"YYYY.MINOR\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release]\ndate_format = \"%d.%m.%Y\"\n",
+        )
+        .unwrap();
+        let date_format = Config::load(temp_dir.path()).unwrap().release.date_format;
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(date_format.format(date), "01.03.2025");
+        assert_eq!(date_format.parse("01.03.2025"), Some(date));
+        assert_eq!(date_format.parse("2025-03-01"), None);
+        assert_eq!(date_format.read("2025-03-01"), Some(date));
+        for format in ["%d %m %Y", "%m/%Y", "%Q"] {
+            fs::write(
+                temp_dir.path().join(FILENAME),
+                format!("[release]\ndate_format = \"{}\"\n", format),
+            )
+            .unwrap();
+            assert!(Config::load(temp_dir.path()).is_err());
+        }
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[release.workflow]\nskip = [\"publish\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().release.workflow,
+            WorkflowConfig {
+                skip: vec![WorkflowStep::Publish],
+                remote: "origin".to_string(),
+            }
+        );
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[forge]\ntype = \"forgejo\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().forge.kind,
+            Some(ForgeKind::Gitea)
+        );
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[links]\nfinal_compare = \"stable\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().links.final_compare,
+            FinalCompare::Stable
+        );
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[links]\nlink_style = \"inline\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().links.link_style,
+            LinkStyle::Inline
+        );
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[links.shortcuts]\nrfc = \"https://docs.example.com/rfc/{id}\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().links.shortcuts["rfc"],
+            "https://docs.example.com/rfc/{id}"
+        );
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[links.shortcuts]\nrfc = \"https://docs.example.com/rfc\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(temp_dir.path().join(FILENAME), "[http]\nretries = 0\n").unwrap();
+        assert_eq!(
+            Config::load(temp_dir.path()).unwrap().http,
+            HttpConfig {
+                retries: 0,
+                timeout: 30
+            }
+        );
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[entries]\ntemplate = \"- {description} ({links})\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path())
+            .unwrap()
+            .entries
+            .template
+            .is_some());
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[entries]\ntemplate = \"- {summary}\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[labels]\nbug = \"security\"\nperf = \"changed\"\napi = \"minor\"\nchore = \"skip\"\n",
+        )
+        .unwrap();
+        let config = Config::load(temp_dir.path()).unwrap();
+        assert_eq!(config.label_type("Bug"), Some(ChangeType::Security));
+        assert_eq!(config.label_type("perf"), Some(ChangeType::Changed));
+        assert_eq!(config.label_type("enhancement"), Some(ChangeType::Added));
+        assert_eq!(config.label_type("question"), None);
+        assert_eq!(config.labels_bump(["api", "bug"]), Some(Bump::Minor));
+        assert_eq!(config.labels_bump(["api", "breaking"]), Some(Bump::Major));
+        assert_eq!(config.labels_bump(["bug"]), None);
+        assert!(config.entry_required(["bug"]));
+        assert!(!config.entry_required(["bug", "chore"]));
+        assert!(!config.entry_required(["CHORE"]));
+        assert!(!config.entry_required(["No-Changelog"]));
+        assert!(config.entry_required(Vec::<&str>::new()));
+
+        // Configured labels take precedence over the built-in skip labels
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[labels]\nno-changelog = \"fixed\"\nskip-changelog = \"patch\"\n",
+        )
+        .unwrap();
+        let config = Config::load(temp_dir.path()).unwrap();
+        assert!(config.entry_required(["no-changelog", "skip-changelog"]));
+        assert_eq!(config.label_type("no-changelog"), Some(ChangeType::Fixed));
+        assert_eq!(config.labels_bump(["skip-changelog"]), Some(Bump::Patch));
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[labels]\nbug = \"fixme\"\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[labels]\nperf = \"performance\"\n\n[sections]\norder = [\"Performance\"]\n\n[[sections.custom]]\nname = \"Performance\"\naliases = [\"perf\"]\ncommit_types = [\"perf\", \"refactor\"]\n",
+        )
+        .unwrap();
+        let config = Config::load(temp_dir.path()).unwrap();
+        let performance = ChangeType::Custom("Performance".to_string());
+        assert_eq!(config.label_type("perf"), Some(performance.clone()));
+        assert_eq!(config.sections.resolve("PERF"), Some(performance.clone()));
+        assert_eq!(config.sections.resolve("a"), Some(ChangeType::Added));
+        assert_eq!(
+            config.sections.commit_type("refactor"),
+            Some(performance.clone())
+        );
+        assert_eq!(config.sections.commit_type("feat"), Some(ChangeType::Added));
+        assert_eq!(config.sections.all()[..2], [performance, ChangeType::Added]);
+        assert_eq!(config.sections.codes()[0], "performance (perf)");
+
+        // Custom sections can't shadow the built-in ones or each other
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[[sections.custom]]\nname = \"Fixes\"\naliases = [\"f\"]\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+        fs::write(
+            temp_dir.path().join(FILENAME),
+            "[sections]\norder = [\"internal\"]\n",
+        )
+        .unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+
+        fs::write(temp_dir.path().join(FILENAME), "[review]\nunknown = 1\n").unwrap();
+        assert!(Config::load(temp_dir.path()).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::config::HttpConfig;
+use crate::notes::{self, Section};
+use crate::{forge, ChangeType, Remote, Result};
+use indexmap::IndexMap;
+
+/// A release section of the changelog. Unlike `parse_changelog::Release`
+/// it owns its text, so a release can be retitled or have its notes
+/// rewritten without borrowing from the content it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version from the heading, or `Unreleased`
+    pub version: String,
+    /// The heading without its `## `, e.g. `[1.2.0] - 2025-02-01`
+    pub title: String,
+    /// Everything below the heading
+    pub notes: String,
+}
+
+impl Release {
+    pub fn date(&self) -> Option<&str> {
+        notes::release_date(&self.title)
+    }
+
+    /// Note after the date, like `re-released 2025-01-03`
+    pub fn annotation(&self) -> Option<&str> {
+        notes::release_annotation(&self.title)
+    }
+
+    pub fn is_yanked(&self) -> bool {
+        notes::is_yanked(&self.title)
+    }
+
+    pub fn sections(&self) -> Vec<Section> {
+        notes::parse_sections(&self.notes)
+    }
+}
+
+impl From<parse_changelog::Release<'_>> for Release {
+    fn from(release: parse_changelog::Release<'_>) -> Self {
+        Release {
+            version: release.version.to_string(),
+            title: release.title.to_string(),
+            notes: release.notes.to_string(),
+        }
+    }
+}
+
+/// Releases keyed by version, in changelog order
+pub(crate) type Releases = IndexMap<String, Release>;
+
+/// Everything in a changelog around its releases, kept as written when the
+/// releases are rewritten: the header above the first release, and after the
+/// last one any sections of its own, link definitions that aren't for
+/// versions, and whatever follows the definitions, like a closing comment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub header: String,
+    /// From the first heading after the last release up to the link definitions
+    pub footer: String,
+    /// Link definitions at the end that aren't for versions
+    pub definitions: Vec<String>,
+    /// What follows the link definitions at the end
+    pub trailer: String,
+}
+
+impl Frame {
+    pub fn parse(content: &str) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let titles: Vec<&str> = crate::parser()
+            .parse_iter(content)
+            .map(|release| release.title)
+            .collect();
+        let headings: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                line.strip_prefix("## ")
+                    .is_some_and(|title| titles.contains(&title.trim()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let (Some(&first), Some(&last)) = (headings.first(), headings.last()) else {
+            return Frame {
+                header: content.trim_end().to_string(),
+                ..Default::default()
+            };
+        };
+
+        // The definitions end at the last one after the last release, and
+        // what follows them is only prose, not more of a release
+        let tail = &lines[last + 1..];
+        let end = tail.iter().rposition(|line| definition(line).is_some());
+        let end = end.filter(|&end| {
+            tail[end + 1..].iter().all(|line| {
+                let line = line.trim_start();
+                !line.starts_with('#') && !line.starts_with("- ") && !line.starts_with("* ")
+            })
+        });
+        let (body, trailer) = match end {
+            Some(end) => {
+                let start = tail[..end]
+                    .iter()
+                    .rposition(|line| !line.trim().is_empty() && definition(line).is_none())
+                    .map_or(0, |i| i + 1);
+                let definitions = tail[start..=end]
+                    .iter()
+                    .filter(|line| definition(line).is_some_and(|label| !is_version(label)))
+                    .map(|line| line.to_string())
+                    .collect();
+                (&tail[..start], (definitions, tail[end + 1..].join("\n")))
+            }
+            None => (tail, (Vec::new(), String::new())),
+        };
+        let footer = body
+            .iter()
+            .position(|line| line.starts_with("## ") || line.starts_with("# "))
+            .map_or(String::new(), |i| body[i..].join("\n"));
+        let (definitions, trailer) = trailer;
+
+        Frame {
+            header: lines[..first].join("\n").trim_end().to_string(),
+            footer: footer.trim().to_string(),
+            definitions,
+            trailer: trailer.trim().to_string(),
+        }
+    }
+}
+
+/// The label of a link definition like `[1.0.0]: https://...`
+pub(crate) fn definition(line: &str) -> Option<&str> {
+    let (label, url) = line.trim_start().strip_prefix('[')?.split_once("]:")?;
+    (!label.is_empty() && !url.trim().is_empty()).then_some(label)
+}
+
+/// Whether a link label names a version, or Unreleased, rather than
+/// something else the changelog links to
+fn is_version(label: &str) -> bool {
+    static FORMAT: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    FORMAT
+        .get_or_init(|| regex::Regex::new(crate::VERSION_FORMAT).expect("valid regex"))
+        .is_match(label)
+        || crate::is_unreleased(label)
+        // Labels match regardless of case, like `[unreleased]`
+        || label.eq_ignore_ascii_case("unreleased")
+}
+
+/// `notes` without the version link definitions that the last release in a
+/// file ends with, keeping any others its entries link to
+pub(crate) fn without_version_links(notes: &str) -> String {
+    notes
+        .lines()
+        .filter(|line| definition(line).is_none_or(|label| !is_version(label)))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// A changelog held in memory, for tools that read or edit one without going
+/// through a file. Edits work like the `add` and `release` commands, and
+/// version links are written for the origin remote's forge or, outside a
+/// repository, the one the changelog's own links point at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogDocument {
+    frame: Frame,
+    releases: Releases,
+    remote: Option<Remote>,
+}
+
+impl ChangelogDocument {
+    pub fn parse(content: &str) -> Result<Self> {
+        let remote = crate::infer_forge()
+            .or_else(|| crate::linked_forge(content))
+            .map(|forge| forge.remote().clone());
+        Ok(ChangelogDocument {
+            frame: Frame::parse(content),
+            releases: crate::parse_releases(content)?,
+            remote,
+        })
+    }
+
+    /// Write version links for `remote` instead, or leave them out with `None`
+    pub fn with_remote(mut self, remote: Option<Remote>) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// Released versions, newest first
+    pub fn releases(&self) -> impl Iterator<Item = &Release> {
+        self.releases
+            .iter()
+            .filter(|(key, _)| !crate::is_unreleased(key))
+            .map(|(_, release)| release)
+    }
+
+    /// A released version, or a release train queue like `Unreleased (1.x)`
+    pub fn get(&self, version: &str) -> Option<&Release> {
+        self.releases.get(version)
+    }
+
+    pub fn unreleased(&self) -> Option<&Release> {
+        self.releases.get("Unreleased")
+    }
+
+    /// Add an entry to the `section` of Unreleased, or of the queue a frozen
+    /// Unreleased redirects to. Continuation lines are indented under the
+    /// bullet; use [`crate::escape_entry`] first for text that shouldn't be
+    /// read as markdown. New sections go in the usual Keep a Changelog order,
+    /// with custom ones after.
+    pub fn add_entry(&mut self, section: &ChangeType, text: &str) -> Result<()> {
+        let text = text.replace('\n', "\n  ");
+        let version = crate::entry_version(&self.releases, None)?;
+        crate::insert_entry(
+            &mut self.releases,
+            &text,
+            section,
+            version.as_deref(),
+            ChangeType::BUILT_IN,
+        )
+    }
+
+    /// Move Unreleased into a new release dated `date`, or today. `version`
+    /// may also be `major`, `minor`, or `patch` to bump the latest release,
+    /// numbered with semver; the version released is returned.
+    pub fn release(&mut self, version: &str, date: Option<&str>) -> Result<String> {
+        crate::cut_release(
+            &mut self.releases,
+            version,
+            date,
+            None,
+            None,
+            &crate::config::ReleaseConfig::default(),
+        )
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let forge = self
+            .remote
+            .clone()
+            .map(|remote| forge::for_remote(remote, &HttpConfig::default()));
+        crate::render_changelog(
+            &self.releases,
+            &self.frame,
+            forge.as_deref(),
+            &crate::config::LinksConfig::default(),
+            crate::config::DEFAULT_TAG_PREFIX,
+            &crate::RepoRefs::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{set_test_github_repo, ForgeKind};
+
+    #[test]
+    fn test_frame() {
+        let content = "# Changelog\n\n<!-- keep -->\n\n## Contents\n\n- [1.0.0](#100)\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n## Older releases\n\nSee the [archive].\n\n[1.0.0]: https://example.com/v1.0.0\n[archive]: https://example.com/archive\n\n<!-- generated -->\n";
+        assert_eq!(
+            Frame::parse(content),
+            Frame {
+                header: "# Changelog\n\n<!-- keep -->\n\n## Contents\n\n- [1.0.0](#100)"
+                    .to_string(),
+                footer: "## Older releases\n\nSee the [archive].".to_string(),
+                definitions: vec!["[archive]: https://example.com/archive".to_string()],
+                trailer: "<!-- generated -->".to_string(),
+            }
+        );
+
+        // Without a heading of their own, notes after the last release are
+        // part of it, and only what follows the definitions is a trailer
+        let content = "# Changelog\n\n## [1.0.0]\n\n- first\n\nThanks!\n\n[1.0.0]: https://example.com\n\n<!-- generated -->\n";
+        let frame = Frame::parse(content);
+        assert_eq!(frame.footer, "");
+        assert_eq!(frame.trailer, "<!-- generated -->");
+        assert_eq!(
+            Frame::parse("# Changelog\n\n## [1.0.0]\n\n- first\n\n[1.0.0]: https://example.com\n\n- second\n").trailer,
+            ""
+        );
+        assert_eq!(Frame::parse("# Changelog\n").header, "# Changelog");
+
+        // Without a header the file starts at its first release, as before
+        let content = "## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        assert_eq!(Frame::parse(content).header, "");
+        let releases = crate::parse_releases(content).unwrap();
+        assert_eq!(
+            crate::changelog_to_markdown(&releases, content, None),
+            content
+        );
+    }
+
+    #[test]
+    fn test_document() {
+        set_test_github_repo(None, None);
+        let mut document = ChangelogDocument::parse(
+            "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+        assert_eq!(document.unreleased().unwrap().sections(), vec![]);
+        assert_eq!(document.releases().count(), 1);
+
+        document
+            .add_entry(&ChangeType::Fixed, "crash\non startup")
+            .unwrap();
+        document.add_entry(&ChangeType::Added, "export").unwrap();
+        assert_eq!(
+            document.release("minor", Some("2025-02-01")).unwrap(),
+            "1.1.0"
+        );
+
+        assert!(document.release("1.0.0", None).is_err());
+
+        let release = document.get("1.1.0").unwrap();
+        assert_eq!(release.date(), Some("2025-02-01"));
+        assert!(!release.is_yanked());
+        assert_eq!(
+            document.releases().map(|r| &r.version).collect::<Vec<_>>(),
+            vec!["1.1.0", "1.0.0"]
+        );
+
+        let document = document.with_remote(Some(Remote {
+            kind: ForgeKind::GitHub,
+            host: "github.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+        }));
+        let markdown = document.to_markdown();
+        assert!(markdown.starts_with("# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n"));
+        assert!(markdown.contains(
+            "## [1.1.0] - 2025-02-01\n\n### Added\n\n- export\n\n### Fixed\n\n- crash\n  on startup\n"
+        ));
+        assert!(markdown.ends_with("[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n"));
+
+        // Formatting is stable across a round trip
+        let reparsed = ChangelogDocument::parse(&markdown).unwrap();
+        assert_eq!(
+            reparsed.with_remote(document.remote.clone()).to_markdown(),
+            markdown
+        );
+    }
+}
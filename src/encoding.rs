@@ -0,0 +1,105 @@
+use clap::ValueEnum;
+
+const BOM: char = '\u{feff}';
+
+/// How lines end in a changelog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LineEnding {
+    /// `\n`, as on Linux and macOS
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows
+    Crlf,
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+        })
+    }
+}
+
+/// The line endings and byte order mark a changelog was written with, so it
+/// can be written back the same way. Everything in between works on `\n`
+/// without a byte order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Encoding {
+    pub line_ending: LineEnding,
+    pub bom: bool,
+}
+
+impl Encoding {
+    /// The encoding `text` uses: CRLF when most of its lines end that way,
+    /// and whether it starts with a byte order mark
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        Encoding {
+            line_ending: if crlf > lf {
+                LineEnding::Crlf
+            } else {
+                LineEnding::Lf
+            },
+            bom: text.starts_with(BOM),
+        }
+    }
+
+    /// `text` with `\n` line endings and no byte order mark
+    pub fn decode(text: &str) -> String {
+        text.strip_prefix(BOM).unwrap_or(text).replace("\r\n", "\n")
+    }
+
+    /// `text`, as [`decode`](Self::decode) leaves it, written this way
+    pub fn encode(self, text: &str) -> String {
+        let text = match self.line_ending {
+            LineEnding::Lf => text.to_string(),
+            LineEnding::Crlf => text.replace('\n', "\r\n"),
+        };
+        if self.bom {
+            format!("{}{}", BOM, text)
+        } else {
+            text
+        }
+    }
+
+    /// This encoding with `line_ending` instead, when one is given
+    pub fn with_line_ending(self, line_ending: Option<LineEnding>) -> Self {
+        Encoding {
+            line_ending: line_ending.unwrap_or(self.line_ending),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let text = "\u{feff}# Changelog\r\n\r\n## [Unreleased]\r\n- one\n";
+        let encoding = Encoding::detect(text);
+        assert_eq!(
+            encoding,
+            Encoding {
+                line_ending: LineEnding::Crlf,
+                bom: true
+            }
+        );
+        let decoded = Encoding::decode(text);
+        assert_eq!(decoded, "# Changelog\n\n## [Unreleased]\n- one\n");
+        assert_eq!(
+            encoding.encode(&decoded),
+            "\u{feff}# Changelog\r\n\r\n## [Unreleased]\r\n- one\r\n"
+        );
+        assert_eq!(
+            encoding
+                .with_line_ending(Some(LineEnding::Lf))
+                .encode(&decoded),
+            "\u{feff}# Changelog\n\n## [Unreleased]\n- one\n"
+        );
+        assert_eq!(Encoding::detect("# Changelog\n"), Encoding::default());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::hooks::Hook;
+use crate::ForgeKind;
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can go wrong while reading or updating a changelog
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("CHANGELOG.md does not exist. Run 'changelog init' first.")]
+    NotInitialized,
+    #[error("CHANGELOG.md already exists")]
+    AlreadyInitialized,
+    /// The changelog could not be parsed
+    #[error("{0}")]
+    Parse(String),
+    #[error("Version {0} not found")]
+    VersionNotFound(String),
+    #[error("No released versions found")]
+    NoReleases,
+    #[error("Unreleased is frozen. Run 'changelog thaw' to add entries again.")]
+    Frozen,
+    #[error("{0}")]
+    InvalidVersion(String),
+    /// Arguments that don't make sense for the current changelog
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Something the command needed, like an entry or section, isn't there
+    #[error("{0}")]
+    NotFound(String),
+    #[error("Invalid {}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+    #[error(
+        "Could not recognize the forge hosting the git remotes. Set [forge] type or repo in changelog.toml, or pass --repo."
+    )]
+    UnknownForge,
+    #[error("No {forge} token found. {hint}")]
+    NoToken {
+        forge: ForgeKind,
+        hint: &'static str,
+    },
+    #[error("{forge} API returned {status}: {message}")]
+    Api {
+        forge: ForgeKind,
+        status: u16,
+        message: String,
+    },
+    #[error("{forge} API rate limit exceeded{}", retry_hint(.retry_after))]
+    RateLimited {
+        forge: ForgeKind,
+        /// Seconds until the limit resets, when the forge says
+        retry_after: Option<u64>,
+    },
+    /// The forge has no API for this, or we don't support it yet
+    #[error("{forge} doesn't support {feature}")]
+    Unsupported {
+        forge: ForgeKind,
+        feature: &'static str,
+    },
+    #[error("Webhook returned {status}: {message}")]
+    Webhook { status: u16, message: String },
+    #[error("HTTP request failed: {0}")]
+    Http(#[source] Box<ureq::Transport>),
+    #[error("No editor found")]
+    NoEditor,
+    /// A lint plugin couldn't run or answered with something other than findings
+    #[error("Lint plugin {plugin} failed: {message}")]
+    Plugin { plugin: String, message: String },
+    /// A command from `[hooks]` couldn't start or exited unsuccessfully
+    #[error("{hook} hook failed: {message}")]
+    Hook { hook: Hook, message: String },
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+fn retry_hint(retry_after: &Option<u64>) -> String {
+    match retry_after {
+        Some(seconds) if *seconds >= 60 => {
+            format!("; try again in {} minutes", seconds.div_ceil(60))
+        }
+        Some(seconds) => format!("; try again in {} seconds", seconds),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::NaiveDate;
+
+/// Released versions as a feed for people to subscribe to, newest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    pub title: String,
+    /// Page the feed is about, like the repository or the published changelog
+    pub link: Option<String>,
+    pub items: Vec<Item>,
+}
+
+/// One release in a feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    /// The version, e.g. `1.2.0`
+    pub title: String,
+    pub link: Option<String>,
+    /// Stable identifier for readers to tell items apart
+    pub id: String,
+    pub date: NaiveDate,
+    /// Release notes as an HTML fragment
+    pub html: String,
+}
+
+impl Feed {
+    /// The feed as an Atom 1.0 document
+    pub fn atom(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("  <title>{}</title>\n", escape(&self.title)));
+        let id = self.link.clone().unwrap_or_else(|| urn(&self.title, None));
+        xml.push_str(&format!("  <id>{}</id>\n", escape(&id)));
+        if let Some(link) = &self.link {
+            xml.push_str(&format!("  <link href=\"{}\"/>\n", escape(link)));
+        }
+        if let Some(latest) = self.items.iter().map(|item| item.date).max() {
+            xml.push_str(&format!("  <updated>{}</updated>\n", rfc3339(latest)));
+        }
+        for item in &self.items {
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <title>{}</title>\n", escape(&item.title)));
+            xml.push_str(&format!("    <id>{}</id>\n", escape(&item.id)));
+            if let Some(link) = &item.link {
+                xml.push_str(&format!("    <link href=\"{}\"/>\n", escape(link)));
+            }
+            xml.push_str(&format!("    <updated>{}</updated>\n", rfc3339(item.date)));
+            xml.push_str(&format!(
+                "    <content type=\"html\">{}</content>\n",
+                escape(&item.html)
+            ));
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    /// The feed as an RSS 2.0 document
+    pub fn rss(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\">\n  <channel>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&self.title)));
+        if let Some(link) = &self.link {
+            xml.push_str(&format!("    <link>{}</link>\n", escape(link)));
+        }
+        xml.push_str(&format!(
+            "    <description>{}</description>\n",
+            escape(&self.title)
+        ));
+        for item in &self.items {
+            xml.push_str("    <item>\n");
+            xml.push_str(&format!("      <title>{}</title>\n", escape(&item.title)));
+            if let Some(link) = &item.link {
+                xml.push_str(&format!("      <link>{}</link>\n", escape(link)));
+            }
+            let permalink = item.link.as_ref() == Some(&item.id);
+            xml.push_str(&format!(
+                "      <guid isPermaLink=\"{}\">{}</guid>\n",
+                permalink,
+                escape(&item.id)
+            ));
+            xml.push_str(&format!(
+                "      <pubDate>{}</pubDate>\n",
+                rfc2822(item.date)
+            ));
+            xml.push_str(&format!(
+                "      <description>{}</description>\n",
+                escape(&item.html)
+            ));
+            xml.push_str("    </item>\n");
+        }
+        xml.push_str("  </channel>\n</rss>\n");
+        xml
+    }
+}
+
+/// Identifier for a feed or item that has no link of its own
+pub fn urn(title: &str, version: Option<&str>) -> String {
+    let name: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    match version {
+        Some(version) => format!("urn:changelog:{}:{}", name, version),
+        None => format!("urn:changelog:{}", name),
+    }
+}
+
+fn rfc3339(date: NaiveDate) -> String {
+    format!("{}T00:00:00Z", date.format("%Y-%m-%d"))
+}
+
+fn rfc2822(date: NaiveDate) -> String {
+    format!("{} 00:00:00 +0000", date.format("%a, %d %b %Y"))
+}
+
+/// `text` safe to put in XML or HTML text and attribute values
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_formats() {
+        let feed = Feed {
+            title: "app releases".to_string(),
+            link: Some("https://github.com/owner/app".to_string()),
+            items: vec![Item {
+                title: "1.1.0".to_string(),
+                link: Some("https://github.com/owner/app/compare/v1.0.0...v1.1.0".to_string()),
+                id: "https://github.com/owner/app/compare/v1.0.0...v1.1.0".to_string(),
+                date: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+                html: "<h3>Added</h3>\n<ul>\n<li>a &amp; b</li>\n</ul>\n".to_string(),
+            }],
+        };
+
+        let atom = feed.atom();
+        assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(atom.contains("  <updated>2025-02-01T00:00:00Z</updated>\n  <entry>"));
+        assert!(atom.contains(
+            "<content type=\"html\">&lt;h3&gt;Added&lt;/h3&gt;\n&lt;ul&gt;\n&lt;li&gt;a &amp;amp; b&lt;/li&gt;\n&lt;/ul&gt;\n</content>"
+        ));
+
+        let rss = feed.rss();
+        assert!(rss.contains("<link>https://github.com/owner/app</link>"));
+        assert!(rss.contains("<pubDate>Sat, 01 Feb 2025 00:00:00 +0000</pubDate>"));
+        assert!(rss.contains("<guid isPermaLink=\"true\">"));
+
+        assert_eq!(
+            urn("My App releases", Some("1.0.0")),
+            "urn:changelog:my-app-releases:1.0.0"
+        );
+    }
+}
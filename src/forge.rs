@@ -0,0 +1,595 @@
+use crate::config::HttpConfig;
+use crate::gitea::Gitea;
+use crate::github::GitHub;
+use crate::gitlab::GitLab;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// The code hosting software behind a remote, which decides the URL layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    #[serde(rename = "github")]
+    GitHub,
+    #[serde(rename = "gitlab")]
+    GitLab,
+    /// Gitea and Forgejo, including Codeberg
+    #[serde(alias = "forgejo", alias = "codeberg")]
+    Gitea,
+    Bitbucket,
+    #[serde(rename = "sourcehut", alias = "srht")]
+    SourceHut,
+}
+
+impl ForgeKind {
+    /// Guess the forge from a well known host or one that names its software
+    fn from_host(host: &str) -> Option<Self> {
+        let host = host.to_lowercase();
+        match host.as_str() {
+            "github.com" => Some(ForgeKind::GitHub),
+            "gitlab.com" => Some(ForgeKind::GitLab),
+            "codeberg.org" | "gitea.com" => Some(ForgeKind::Gitea),
+            "bitbucket.org" => Some(ForgeKind::Bitbucket),
+            "git.sr.ht" => Some(ForgeKind::SourceHut),
+            _ if host.contains("gitlab") => Some(ForgeKind::GitLab),
+            _ if host.contains("gitea") || host.contains("forgejo") => Some(ForgeKind::Gitea),
+            _ => None,
+        }
+    }
+
+    /// Where the forge's public instance lives
+    fn public_host(self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "github.com",
+            ForgeKind::GitLab => "gitlab.com",
+            ForgeKind::Gitea => "codeberg.org",
+            ForgeKind::Bitbucket => "bitbucket.org",
+            ForgeKind::SourceHut => "git.sr.ht",
+        }
+    }
+}
+
+impl std::fmt::Display for ForgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+            ForgeKind::Bitbucket => "Bitbucket",
+            ForgeKind::SourceHut => "SourceHut",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A repository on a code forge, as named by a git remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    pub kind: ForgeKind,
+    pub host: String,
+    /// User or organization; GitLab subgroups are included, e.g. `group/sub`
+    pub owner: String,
+    pub repo: String,
+}
+
+impl Remote {
+    /// Detect the forge for a git remote URL. `kind` overrides detection for
+    /// self-hosted instances whose host name doesn't give them away.
+    pub fn from_url(url: &str, kind: Option<ForgeKind>) -> Option<Self> {
+        let (host, path) = split_remote_url(url)?;
+        let kind = kind.or_else(|| ForgeKind::from_host(host))?;
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let (owner, repo) = path.rsplit_once('/')?;
+        let owner = match kind {
+            ForgeKind::SourceHut => owner.trim_start_matches('~'),
+            // Only GitLab nests projects in subgroups
+            ForgeKind::GitLab => owner,
+            _ => owner.split('/').next_back().unwrap_or(owner),
+        };
+        if owner.is_empty() || repo.is_empty() {
+            return None;
+        }
+
+        Some(Remote {
+            kind,
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    /// A repository named on the command line: a remote URL, or `owner/repo`
+    /// on the public instance of `kind`, GitHub by default
+    pub fn from_spec(spec: &str, kind: Option<ForgeKind>) -> Option<Self> {
+        if spec.contains("://") || spec.contains('@') {
+            return Self::from_url(spec, kind);
+        }
+        let kind = kind.unwrap_or(ForgeKind::GitHub);
+        Self::from_url(
+            &format!("https://{}/{}", kind.public_host(), spec),
+            Some(kind),
+        )
+    }
+
+    /// The first of a repository's `remotes`, given as name and URL, that's
+    /// on a forge we recognize: `origin`, then `upstream`, then the rest in
+    /// order. Forks and clones with other remote names still get links.
+    pub fn from_remotes(remotes: &[(String, String)], kind: Option<ForgeKind>) -> Option<Self> {
+        let rank = |name: &str| match name {
+            "origin" => 0,
+            "upstream" => 1,
+            _ => 2,
+        };
+        let mut remotes: Vec<_> = remotes.iter().collect();
+        remotes.sort_by_key(|(name, _)| rank(name));
+        remotes
+            .into_iter()
+            .find_map(|(_, url)| Self::from_url(url, kind))
+    }
+
+    /// The remote a changelog's version link points into, e.g.
+    /// `https://github.com/owner/repo/compare/v1.0.0...v1.1.0`
+    pub fn from_link(url: &str) -> Option<Self> {
+        const ROUTES: [&str; 8] = [
+            "/-/",
+            "/compare/",
+            "/commits/",
+            "/releases/",
+            "/branches/compare/",
+            "/src/",
+            "/log/",
+            "/refs/",
+        ];
+        let end = ROUTES.iter().filter_map(|route| url.find(route)).min()?;
+        Self::from_url(&url[..end], None)
+    }
+
+    /// `owner/repo`
+    pub fn slug(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+}
+
+/// Links into a forge's web interface and the parts of its API the changelog
+/// uses. Forges without an API we support only build links; the rest of
+/// their methods return [`Error::Unsupported`].
+pub trait Forge {
+    fn remote(&self) -> &Remote;
+
+    /// Web URL of the repository
+    fn base_url(&self) -> String {
+        let remote = self.remote();
+        format!("https://{}/{}/{}", remote.host, remote.owner, remote.repo)
+    }
+
+    /// Changes between two refs
+    fn compare_url(&self, from: &str, to: &str) -> String {
+        format!("{}/compare/{}...{}", self.base_url(), from, to)
+    }
+
+    /// Page for a single tag or the release made from it
+    fn tag_url(&self, tag: &str) -> String {
+        format!("{}/releases/tag/{}", self.base_url(), tag)
+    }
+
+    /// Page for a single commit
+    fn commit_url(&self, sha: &str) -> String {
+        format!("{}/commit/{}", self.base_url(), sha)
+    }
+
+    /// History of a branch, for linking to changes before anything is tagged
+    fn commits_url(&self, branch: &str) -> String {
+        format!("{}/commits/{}", self.base_url(), branch)
+    }
+
+    /// Page for an issue. Forges that number issues and pull requests
+    /// together redirect to the pull request when that's what it is.
+    fn issue_url(&self, number: u64) -> String {
+        format!("{}/issues/{}", self.base_url(), number)
+    }
+
+    /// Look up a pull (or merge) request by number
+    fn pull_request(&self, _number: u64) -> Result<PullRequest> {
+        Err(unsupported(self, "pull request lookup"))
+    }
+
+    /// Pull requests that contain the given commit, most relevant first
+    fn pulls_for_commit(&self, _sha: &str) -> Result<Vec<PullRequest>> {
+        Err(unsupported(self, "pull request lookup"))
+    }
+
+    /// Find a release, drafts included, by its tag name
+    fn release_by_tag(&self, tag: &str) -> Result<Option<ReleaseInfo>> {
+        Ok(self.releases()?.into_iter().find(|r| r.tag_name == tag))
+    }
+
+    /// Every release in the repository, drafts included
+    fn releases(&self) -> Result<Vec<ReleaseInfo>> {
+        Err(unsupported(self, "releases"))
+    }
+
+    fn create_release(&self, _release: &NewRelease) -> Result<ReleaseInfo> {
+        Err(unsupported(self, "releases"))
+    }
+
+    fn update_release(
+        &self,
+        _existing: &ReleaseInfo,
+        _release: &NewRelease,
+    ) -> Result<ReleaseInfo> {
+        Err(unsupported(self, "releases"))
+    }
+
+    /// Replace a release's notes, leaving its name and draft state alone
+    fn update_release_body(&self, _existing: &ReleaseInfo, _body: &str) -> Result<ReleaseInfo> {
+        Err(unsupported(self, "releases"))
+    }
+
+    /// A file on the default branch, or `None` when there's no such file
+    fn file(&self, _path: &str) -> Result<Option<String>> {
+        Err(unsupported(self, "file contents"))
+    }
+}
+
+fn unsupported(forge: &(impl Forge + ?Sized), feature: &'static str) -> Error {
+    Error::Unsupported {
+        forge: forge.remote().kind,
+        feature,
+    }
+}
+
+/// The implementation for the forge `remote` is hosted on
+pub fn for_remote(remote: Remote, http: &HttpConfig) -> Box<dyn Forge> {
+    match remote.kind {
+        ForgeKind::GitHub => Box::new(GitHub::new(remote, http)),
+        ForgeKind::GitLab => Box::new(GitLab::new(remote, http)),
+        ForgeKind::Gitea => Box::new(Gitea::new(remote, http)),
+        ForgeKind::Bitbucket => Box::new(Bitbucket(remote)),
+        ForgeKind::SourceHut => Box::new(SourceHut(remote)),
+    }
+}
+
+/// Payload for creating or updating a release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewRelease {
+    pub tag_name: String,
+    pub name: String,
+    pub body: String,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
+/// The parts of a release we care about
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseInfo {
+    /// Numeric id on forges that have one; GitLab identifies releases by tag
+    #[serde(default)]
+    pub id: u64,
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+/// The parts of a pull request we care about
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub user: User,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+/// Bitbucket Cloud; links only
+struct Bitbucket(Remote);
+
+impl Forge for Bitbucket {
+    fn remote(&self) -> &Remote {
+        &self.0
+    }
+
+    // Bitbucket lists the newer ref first, separated by a carriage return
+    fn compare_url(&self, from: &str, to: &str) -> String {
+        format!("{}/branches/compare/{}%0D{}", self.base_url(), to, from)
+    }
+
+    fn tag_url(&self, tag: &str) -> String {
+        format!("{}/src/{}", self.base_url(), tag)
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("{}/commits/{}", self.base_url(), sha)
+    }
+
+    fn commits_url(&self, branch: &str) -> String {
+        format!("{}/commits/branch/{}", self.base_url(), branch)
+    }
+}
+
+/// sourcehut; links only
+struct SourceHut(Remote);
+
+impl Forge for SourceHut {
+    fn remote(&self) -> &Remote {
+        &self.0
+    }
+
+    fn base_url(&self) -> String {
+        format!("https://{}/~{}/{}", self.0.host, self.0.owner, self.0.repo)
+    }
+
+    // sourcehut has no compare view, so show the history leading up to `to`
+    fn compare_url(&self, _from: &str, to: &str) -> String {
+        format!("{}/log/{}", self.base_url(), to)
+    }
+
+    fn tag_url(&self, tag: &str) -> String {
+        format!("{}/refs/{}", self.base_url(), tag)
+    }
+
+    fn commits_url(&self, branch: &str) -> String {
+        format!("{}/log/{}", self.base_url(), branch)
+    }
+
+    // Tickets live in a tracker of the same name on the todo service
+    fn issue_url(&self, number: u64) -> String {
+        let host = self.0.host.replacen("git.", "todo.", 1);
+        format!(
+            "https://{}/~{}/{}/{}",
+            host, self.0.owner, self.0.repo, number
+        )
+    }
+}
+
+/// The first non-empty variable of `vars`
+pub(crate) fn token_from_env(vars: &[&str]) -> Option<String> {
+    vars.iter()
+        .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()))
+}
+
+/// `segment` percent-encoded for use as a single URL path segment
+pub(crate) fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Host and repository path of an https, ssh, or scp-style git URL
+fn split_remote_url(url: &str) -> Option<(&str, &str)> {
+    let url = url.trim();
+    if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        Some((host, path))
+    } else {
+        // scp-like syntax: git@host:owner/repo.git
+        let (authority, path) = url.split_once(':')?;
+        let host = authority.rsplit('@').next()?;
+        Some((host, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forge(url: &str) -> Box<dyn Forge> {
+        for_remote(Remote::from_url(url, None).unwrap(), &HttpConfig::default())
+    }
+
+    #[test]
+    fn test_detect_forges() {
+        let github = forge("git@github.com:owner/repo.git");
+        assert_eq!(github.remote().kind, ForgeKind::GitHub);
+        assert_eq!(github.base_url(), "https://github.com/owner/repo");
+
+        let gitlab = forge("https://gitlab.com/group/sub/repo.git");
+        assert_eq!(gitlab.remote().kind, ForgeKind::GitLab);
+        assert_eq!(gitlab.remote().slug(), "group/sub/repo");
+
+        assert_eq!(
+            forge("ssh://git@codeberg.org:2222/owner/repo.git")
+                .remote()
+                .kind,
+            ForgeKind::Gitea
+        );
+        assert_eq!(
+            forge("https://user@bitbucket.org/owner/repo.git")
+                .remote()
+                .kind,
+            ForgeKind::Bitbucket
+        );
+        assert_eq!(
+            forge("https://git.sr.ht/~owner/repo").base_url(),
+            "https://git.sr.ht/~owner/repo"
+        );
+
+        assert_eq!(
+            Remote::from_url("git@git.example.com:team/app.git", None),
+            None
+        );
+        let self_hosted = for_remote(
+            Remote::from_url("git@git.example.com:team/app.git", Some(ForgeKind::GitLab)).unwrap(),
+            &HttpConfig::default(),
+        );
+        assert_eq!(
+            self_hosted.compare_url("v1.0.0", "v1.1.0"),
+            "https://git.example.com/team/app/-/compare/v1.0.0...v1.1.0"
+        );
+    }
+
+    #[test]
+    fn test_remote_from_spec() {
+        let github = Remote::from_spec("owner/repo", None).unwrap();
+        assert_eq!(github.kind, ForgeKind::GitHub);
+        assert_eq!(github.host, "github.com");
+        assert_eq!(github.slug(), "owner/repo");
+        assert_eq!(
+            Remote::from_spec("group/sub/app", Some(ForgeKind::GitLab))
+                .unwrap()
+                .slug(),
+            "group/sub/app"
+        );
+        assert_eq!(
+            Remote::from_spec("https://git.example.com/owner/repo", Some(ForgeKind::Gitea))
+                .unwrap()
+                .host,
+            "git.example.com"
+        );
+        assert!(Remote::from_spec("repo", None).is_none());
+    }
+
+    #[test]
+    fn test_remote_from_remotes() {
+        let remotes = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(name, url)| (name.to_string(), url.to_string()))
+                .collect()
+        };
+        let slug = |pairs: &[(&str, &str)]| {
+            Remote::from_remotes(&remotes(pairs), None).map(|remote| remote.slug())
+        };
+
+        assert_eq!(
+            slug(&[
+                ("fork", "git@github.com:me/app.git"),
+                ("upstream", "https://github.com/owner/app"),
+                ("origin", "https://github.com/other/app"),
+            ])
+            .as_deref(),
+            Some("other/app")
+        );
+        assert_eq!(
+            slug(&[
+                ("fork", "git@github.com:me/app.git"),
+                ("upstream", "https://github.com/owner/app"),
+                ("origin", "https://git.internal/app"),
+            ])
+            .as_deref(),
+            Some("owner/app")
+        );
+        assert_eq!(
+            slug(&[
+                ("mirror", "/srv/git/app.git"),
+                ("gh", "git@github.com:owner/app.git"),
+            ])
+            .as_deref(),
+            Some("owner/app")
+        );
+        assert_eq!(slug(&[("mirror", "/srv/git/app.git")]), None);
+    }
+
+    #[test]
+    fn test_forge_urls() {
+        let cases = [
+            (
+                "https://github.com/o/r",
+                "https://github.com/o/r/compare/v1.0.0...v1.1.0",
+                "https://github.com/o/r/releases/tag/v1.0.0",
+            ),
+            (
+                "https://gitlab.com/o/r",
+                "https://gitlab.com/o/r/-/compare/v1.0.0...v1.1.0",
+                "https://gitlab.com/o/r/-/tags/v1.0.0",
+            ),
+            (
+                "https://codeberg.org/o/r",
+                "https://codeberg.org/o/r/compare/v1.0.0...v1.1.0",
+                "https://codeberg.org/o/r/releases/tag/v1.0.0",
+            ),
+            (
+                "https://bitbucket.org/o/r",
+                "https://bitbucket.org/o/r/branches/compare/v1.1.0%0Dv1.0.0",
+                "https://bitbucket.org/o/r/src/v1.0.0",
+            ),
+            (
+                "https://git.sr.ht/~o/r",
+                "https://git.sr.ht/~o/r/log/v1.1.0",
+                "https://git.sr.ht/~o/r/refs/v1.0.0",
+            ),
+        ];
+        for (remote, compare, tag) in cases {
+            let forge = forge(remote);
+            assert_eq!(forge.compare_url("v1.0.0", "v1.1.0"), compare);
+            assert_eq!(forge.tag_url("v1.0.0"), tag);
+            assert_eq!(Remote::from_link(compare), Remote::from_link(tag));
+            assert_eq!(
+                Remote::from_link(tag).unwrap().slug(),
+                forge.remote().slug()
+            );
+        }
+        assert_eq!(Remote::from_link("https://example.com/o/r/x"), None);
+
+        let commits = [
+            (
+                "https://github.com/o/r",
+                "https://github.com/o/r/commits/main",
+            ),
+            (
+                "https://gitlab.com/o/r",
+                "https://gitlab.com/o/r/-/commits/main",
+            ),
+            (
+                "https://codeberg.org/o/r",
+                "https://codeberg.org/o/r/commits/branch/main",
+            ),
+            (
+                "https://bitbucket.org/o/r",
+                "https://bitbucket.org/o/r/commits/branch/main",
+            ),
+            ("https://git.sr.ht/~o/r", "https://git.sr.ht/~o/r/log/main"),
+        ];
+        for (remote, url) in commits {
+            let forge = forge(remote);
+            assert_eq!(forge.commits_url("main"), url);
+            assert_eq!(
+                Remote::from_link(url).unwrap().slug(),
+                forge.remote().slug()
+            );
+        }
+
+        assert_eq!(
+            forge("https://github.com/o/r").issue_url(12),
+            "https://github.com/o/r/issues/12"
+        );
+        assert_eq!(
+            forge("https://gitlab.com/g/r").issue_url(12),
+            "https://gitlab.com/g/r/-/issues/12"
+        );
+        assert_eq!(
+            forge("https://git.sr.ht/~o/r").issue_url(12),
+            "https://todo.sr.ht/~o/r/12"
+        );
+    }
+
+    #[test]
+    fn test_encode_segment() {
+        assert_eq!(encode_segment("group/sub/app"), "group%2Fsub%2Fapp");
+        assert_eq!(encode_segment("release/v1.0.0+1"), "release%2Fv1.0.0%2B1");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::SectionsConfig;
+use crate::notes::{parse_sections, Section};
+use crate::{ChangeType, Error, Result};
+use serde::Serialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Where pending fragments live, relative to the changelog
+pub const DIR: &str = ".changelog/unreleased";
+
+/// A file holding one branch's or pull request's entries until they are
+/// collected into the changelog. Fragments use the same `### Type` sections
+/// and bullets as a release, so they can be written by hand too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fragment {
+    pub path: PathBuf,
+    pub sections: Vec<Section>,
+}
+
+impl Fragment {
+    /// Every entry with the change type of its section, in file order
+    pub fn entries(&self, sections: &SectionsConfig) -> Result<Vec<(ChangeType, String)>> {
+        let mut entries = Vec::new();
+        for section in &self.sections {
+            let change_type = sections.resolve(&section.name).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Unknown section '{}' in {}",
+                    section.name,
+                    self.path.display()
+                ))
+            })?;
+            for entry in &section.entries {
+                entries.push((change_type.clone(), entry.clone()));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Every `.md` fragment in `dir`, sorted by file name. A missing directory
+/// just means there is nothing pending.
+pub fn load(dir: &Path) -> Result<Vec<Fragment>> {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut paths = Vec::new();
+    for entry in read_dir {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let sections = parse_sections(&fs::read_to_string(&path)?);
+            Ok(Fragment { path, sections })
+        })
+        .collect()
+}
+
+/// File name for a fragment called `name`, e.g. a branch like `fix/crash`
+pub fn file_name(name: &str) -> Option<String> {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches(|c| c == '-' || c == '.');
+    (!slug.is_empty()).then(|| format!("{}.md", slug))
+}
+
+/// Fragment `content` with another entry in its `r#type` section
+pub fn add_entry(content: &str, r#type: &ChangeType, description: &str) -> String {
+    let mut sections = parse_sections(content);
+    let name = r#type.heading();
+    match sections.iter_mut().find(|s| s.name == name) {
+        Some(section) => section.entries.push(description.to_string()),
+        None => sections.push(Section {
+            name,
+            entries: vec![description.to_string()],
+        }),
+    }
+    render(&sections)
+}
+
+fn render(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|section| {
+            let entries: Vec<String> = section
+                .entries
+                .iter()
+                .map(|entry| format!("- {}", entry.replace('\n', "\n  ")))
+                .collect();
+            format!("### {}\n\n{}\n", section.name, entries.join("\n"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_add_entry() {
+        let content = add_entry("", &ChangeType::Fixed, "crash on startup");
+        assert_eq!(content, "### Fixed\n\n- crash on startup\n");
+
+        let content = add_entry(&content, &ChangeType::Added, "export\nto csv");
+        let content = add_entry(&content, &ChangeType::Fixed, "typo");
+        assert_eq!(
+            content,
+            "### Fixed\n\n- crash on startup\n- typo\n\n### Added\n\n- export\n  to csv\n"
+        );
+    }
+
+    #[test]
+    fn test_load_fragments() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load(&temp_dir.path().join("missing")).unwrap().is_empty());
+
+        fs::write(temp_dir.path().join("b.md"), "### Fixed\n\n- crash\n").unwrap();
+        fs::write(temp_dir.path().join("a.md"), "### Added\n\n- export\n").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+        fs::write(temp_dir.path().join("c.md"), "### Improved\n\n- speed\n").unwrap();
+
+        let fragments = load(temp_dir.path()).unwrap();
+        assert_eq!(fragments.len(), 3);
+        assert_eq!(
+            fragments[0].entries(&SectionsConfig::default()).unwrap(),
+            vec![(ChangeType::Added, "export".to_string())]
+        );
+        assert!(fragments[2].entries(&SectionsConfig::default()).is_err());
+
+        assert_eq!(
+            file_name("fix/crash on start").as_deref(),
+            Some("fix-crash-on-start.md")
+        );
+        assert_eq!(file_name("/"), None);
+    }
+}
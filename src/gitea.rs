@@ -0,0 +1,118 @@
+use crate::config::HttpConfig;
+use crate::forge::{
+    encode_segment, token_from_env, Forge, ForgeKind, NewRelease, PullRequest, ReleaseInfo, Remote,
+};
+use crate::http::Client;
+use crate::{Error, Result};
+use serde::Serialize;
+
+/// Gitea and Forgejo (Codeberg included), through the v1 REST API
+pub struct Gitea {
+    remote: Remote,
+    http: Client,
+}
+
+impl Gitea {
+    pub fn new(remote: Remote, http: &HttpConfig) -> Self {
+        Gitea {
+            http: Client::new(ForgeKind::Gitea, &remote.host, http),
+            remote,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request> {
+        let token = token_from_env(&["GITEA_TOKEN", "FORGEJO_TOKEN"]).ok_or(Error::NoToken {
+            forge: ForgeKind::Gitea,
+            hint: "Set GITEA_TOKEN or FORGEJO_TOKEN.",
+        })?;
+        let url = format!(
+            "https://{}/api/v1/repos/{}/{}{}",
+            self.remote.host, self.remote.owner, self.remote.repo, path
+        );
+        Ok(self
+            .http
+            .request(method, &url)
+            .set("Accept", "application/json")
+            .set("Authorization", &format!("token {}", token)))
+    }
+
+    fn get(&self, path: &str) -> Result<ureq::Response> {
+        self.http.call(self.request("GET", path)?)
+    }
+
+    fn send(&self, method: &str, path: &str, body: impl Serialize) -> Result<ureq::Response> {
+        self.http.send_json(self.request(method, path)?, body)
+    }
+}
+
+impl Forge for Gitea {
+    fn remote(&self) -> &Remote {
+        &self.remote
+    }
+
+    fn commits_url(&self, branch: &str) -> String {
+        format!("{}/commits/branch/{}", self.base_url(), branch)
+    }
+
+    fn pull_request(&self, number: u64) -> Result<PullRequest> {
+        Ok(self.get(&format!("/pulls/{}", number))?.into_json()?)
+    }
+
+    fn pulls_for_commit(&self, sha: &str) -> Result<Vec<PullRequest>> {
+        // Only the pull request that merged the commit is known
+        match self.get(&format!("/commits/{}/pull", sha)) {
+            Ok(response) => Ok(vec![response.into_json()?]),
+            Err(Error::Api { status: 404, .. }) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn releases(&self) -> Result<Vec<ReleaseInfo>> {
+        let mut releases = Vec::new();
+        for page in 1.. {
+            let batch: Vec<ReleaseInfo> = self
+                .get(&format!("/releases?limit=50&page={}", page))?
+                .into_json()?;
+            let done = batch.len() < 50;
+            releases.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(releases)
+    }
+
+    fn release_by_tag(&self, tag: &str) -> Result<Option<ReleaseInfo>> {
+        match self.get(&format!("/releases/tags/{}", encode_segment(tag))) {
+            Ok(response) => Ok(Some(response.into_json()?)),
+            // Drafts aren't reachable by tag, so look through the list
+            Err(Error::Api { status: 404, .. }) => {
+                Ok(self.releases()?.into_iter().find(|r| r.tag_name == tag))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_release(&self, release: &NewRelease) -> Result<ReleaseInfo> {
+        Ok(self.send("POST", "/releases", release)?.into_json()?)
+    }
+
+    fn update_release(&self, existing: &ReleaseInfo, release: &NewRelease) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", existing.id);
+        Ok(self.send("PATCH", &path, release)?.into_json()?)
+    }
+
+    fn update_release_body(&self, existing: &ReleaseInfo, body: &str) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", existing.id);
+        let body = serde_json::json!({ "body": body });
+        Ok(self.send("PATCH", &path, body)?.into_json()?)
+    }
+
+    fn file(&self, path: &str) -> Result<Option<String>> {
+        match self.get(&format!("/raw/{}", path)) {
+            Ok(response) => Ok(Some(response.into_string()?)),
+            Err(Error::Api { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
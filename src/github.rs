@@ -0,0 +1,155 @@
+use crate::config::HttpConfig;
+use crate::forge::{
+    token_from_env, Forge, ForgeKind, NewRelease, PullRequest, ReleaseInfo, Remote,
+};
+use crate::http::Client;
+use crate::{Error, Result};
+use serde::Serialize;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// GitHub and GitHub Enterprise Server, through the REST API
+pub struct GitHub {
+    remote: Remote,
+    http: Client,
+    token: OnceLock<Option<String>>,
+}
+
+impl GitHub {
+    pub fn new(remote: Remote, http: &HttpConfig) -> Self {
+        let api_host = match remote.host.as_str() {
+            "github.com" => "api.github.com",
+            host => host,
+        };
+        GitHub {
+            http: Client::new(ForgeKind::GitHub, api_host, http),
+            remote,
+            token: OnceLock::new(),
+        }
+    }
+
+    fn api_url(&self) -> String {
+        if self.remote.host == "github.com" {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{}/api/v3", self.remote.host)
+        }
+    }
+
+    /// GITHUB_TOKEN, GH_TOKEN, or the token of a logged in `gh` CLI, looked up
+    /// the first time a request needs it
+    fn token(&self) -> Result<&str> {
+        self.token
+            .get_or_init(|| {
+                token_from_env(&["GITHUB_TOKEN", "GH_TOKEN"])
+                    .or_else(|| gh_auth_token(&self.remote.host))
+            })
+            .as_deref()
+            .ok_or(Error::NoToken {
+                forge: ForgeKind::GitHub,
+                hint: "Set GITHUB_TOKEN or run 'gh auth login'.",
+            })
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request> {
+        let url = format!(
+            "{}/repos/{}/{}{}",
+            self.api_url(),
+            self.remote.owner,
+            self.remote.repo,
+            path
+        );
+        Ok(self
+            .http
+            .request(method, &url)
+            .set("Accept", "application/vnd.github+json")
+            .set("Authorization", &format!("Bearer {}", self.token()?))
+            .set("X-GitHub-Api-Version", "2022-11-28"))
+    }
+
+    fn get(&self, path: &str) -> Result<ureq::Response> {
+        self.http.call(self.request("GET", path)?)
+    }
+
+    fn send(&self, method: &str, path: &str, body: impl Serialize) -> Result<ureq::Response> {
+        self.http.send_json(self.request(method, path)?, body)
+    }
+}
+
+impl Forge for GitHub {
+    fn remote(&self) -> &Remote {
+        &self.remote
+    }
+
+    fn pull_request(&self, number: u64) -> Result<PullRequest> {
+        Ok(self.get(&format!("/pulls/{}", number))?.into_json()?)
+    }
+
+    fn pulls_for_commit(&self, sha: &str) -> Result<Vec<PullRequest>> {
+        Ok(self.get(&format!("/commits/{}/pulls", sha))?.into_json()?)
+    }
+
+    fn release_by_tag(&self, tag: &str) -> Result<Option<ReleaseInfo>> {
+        match self.get(&format!("/releases/tags/{}", tag)) {
+            Ok(response) => Ok(Some(response.into_json()?)),
+            // Draft releases aren't reachable by tag, so look through the list
+            Err(Error::Api { status: 404, .. }) => {
+                Ok(self.releases()?.into_iter().find(|r| r.tag_name == tag))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn releases(&self) -> Result<Vec<ReleaseInfo>> {
+        let mut releases = Vec::new();
+        for page in 1.. {
+            let batch: Vec<ReleaseInfo> = self
+                .get(&format!("/releases?per_page=100&page={}", page))?
+                .into_json()?;
+            let done = batch.len() < 100;
+            releases.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(releases)
+    }
+
+    fn create_release(&self, release: &NewRelease) -> Result<ReleaseInfo> {
+        Ok(self.send("POST", "/releases", release)?.into_json()?)
+    }
+
+    fn update_release(&self, existing: &ReleaseInfo, release: &NewRelease) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", existing.id);
+        Ok(self.send("PATCH", &path, release)?.into_json()?)
+    }
+
+    fn update_release_body(&self, existing: &ReleaseInfo, body: &str) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", existing.id);
+        let body = serde_json::json!({ "body": body });
+        Ok(self.send("PATCH", &path, body)?.into_json()?)
+    }
+
+    fn file(&self, path: &str) -> Result<Option<String>> {
+        let request = self
+            .request("GET", &format!("/contents/{}", path))?
+            .set("Accept", "application/vnd.github.raw+json");
+        match self.http.call(request) {
+            Ok(response) => Ok(Some(response.into_string()?)),
+            Err(Error::Api { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn gh_auth_token(host: &str) -> Option<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let token = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!token.is_empty()).then_some(token)
+}
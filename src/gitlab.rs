@@ -0,0 +1,253 @@
+use crate::config::HttpConfig;
+use crate::forge::{
+    encode_segment, token_from_env, Forge, ForgeKind, Label, NewRelease, PullRequest, ReleaseInfo,
+    Remote, User,
+};
+use crate::http::Client;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// GitLab.com and self-managed GitLab, through the v4 REST API
+pub struct GitLab {
+    remote: Remote,
+    http: Client,
+}
+
+/// A merge request as the API returns it
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    title: String,
+    web_url: String,
+    author: Author,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    username: String,
+}
+
+impl From<MergeRequest> for PullRequest {
+    fn from(mr: MergeRequest) -> Self {
+        PullRequest {
+            number: mr.iid,
+            title: mr.title,
+            html_url: mr.web_url,
+            user: User {
+                login: mr.author.username,
+            },
+            body: mr.description,
+            labels: mr.labels.into_iter().map(|name| Label { name }).collect(),
+        }
+    }
+}
+
+/// A release as the API returns it
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "_links")]
+    links: ReleaseLinks,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseLinks {
+    #[serde(rename = "self")]
+    web_url: String,
+}
+
+impl From<Release> for ReleaseInfo {
+    fn from(release: Release) -> Self {
+        ReleaseInfo {
+            id: 0,
+            tag_name: release.tag_name,
+            html_url: release.links.web_url,
+            body: release.description,
+        }
+    }
+}
+
+impl GitLab {
+    pub fn new(remote: Remote, http: &HttpConfig) -> Self {
+        GitLab {
+            http: Client::new(ForgeKind::GitLab, &remote.host, http),
+            remote,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request> {
+        // Personal and project access tokens, or the job token inside GitLab CI
+        let (header, token) = match token_from_env(&["GITLAB_TOKEN", "GL_TOKEN"]) {
+            Some(token) => ("PRIVATE-TOKEN", token),
+            None => token_from_env(&["CI_JOB_TOKEN"])
+                .map(|token| ("JOB-TOKEN", token))
+                .ok_or(Error::NoToken {
+                    forge: ForgeKind::GitLab,
+                    hint: "Set GITLAB_TOKEN.",
+                })?,
+        };
+        let url = format!(
+            "https://{}/api/v4/projects/{}{}",
+            self.remote.host,
+            encode_segment(&self.remote.slug()),
+            path
+        );
+        Ok(self.http.request(method, &url).set(header, &token))
+    }
+
+    fn get(&self, path: &str) -> Result<ureq::Response> {
+        self.http.call(self.request("GET", path)?)
+    }
+
+    fn send(&self, method: &str, path: &str, body: impl Serialize) -> Result<ureq::Response> {
+        self.http.send_json(self.request(method, path)?, body)
+    }
+
+    fn release_payload(&self, release: &NewRelease) -> Result<serde_json::Value> {
+        if release.draft {
+            return Err(Error::Unsupported {
+                forge: ForgeKind::GitLab,
+                feature: "draft releases",
+            });
+        }
+        Ok(serde_json::json!({
+            "tag_name": release.tag_name,
+            "name": release.name,
+            "description": release.body,
+        }))
+    }
+}
+
+impl Forge for GitLab {
+    fn remote(&self) -> &Remote {
+        &self.remote
+    }
+
+    fn compare_url(&self, from: &str, to: &str) -> String {
+        format!("{}/-/compare/{}...{}", self.base_url(), from, to)
+    }
+
+    fn tag_url(&self, tag: &str) -> String {
+        format!("{}/-/tags/{}", self.base_url(), tag)
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("{}/-/commit/{}", self.base_url(), sha)
+    }
+
+    fn commits_url(&self, branch: &str) -> String {
+        format!("{}/-/commits/{}", self.base_url(), branch)
+    }
+
+    fn issue_url(&self, number: u64) -> String {
+        format!("{}/-/issues/{}", self.base_url(), number)
+    }
+
+    fn pull_request(&self, number: u64) -> Result<PullRequest> {
+        let mr: MergeRequest = self
+            .get(&format!("/merge_requests/{}", number))?
+            .into_json()?;
+        Ok(mr.into())
+    }
+
+    fn pulls_for_commit(&self, sha: &str) -> Result<Vec<PullRequest>> {
+        let mrs: Vec<MergeRequest> = self
+            .get(&format!("/repository/commits/{}/merge_requests", sha))?
+            .into_json()?;
+        Ok(mrs.into_iter().map(PullRequest::from).collect())
+    }
+
+    fn release_by_tag(&self, tag: &str) -> Result<Option<ReleaseInfo>> {
+        match self.get(&format!("/releases/{}", encode_segment(tag))) {
+            Ok(response) => Ok(Some(response.into_json::<Release>()?.into())),
+            Err(Error::Api { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn releases(&self) -> Result<Vec<ReleaseInfo>> {
+        let mut releases = Vec::new();
+        for page in 1.. {
+            let batch: Vec<Release> = self
+                .get(&format!("/releases?per_page=100&page={}", page))?
+                .into_json()?;
+            let done = batch.len() < 100;
+            releases.extend(batch.into_iter().map(ReleaseInfo::from));
+            if done {
+                break;
+            }
+        }
+        Ok(releases)
+    }
+
+    fn create_release(&self, release: &NewRelease) -> Result<ReleaseInfo> {
+        let payload = self.release_payload(release)?;
+        let release: Release = self.send("POST", "/releases", payload)?.into_json()?;
+        Ok(release.into())
+    }
+
+    fn update_release(&self, existing: &ReleaseInfo, release: &NewRelease) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", encode_segment(&existing.tag_name));
+        let payload = self.release_payload(release)?;
+        let release: Release = self.send("PUT", &path, payload)?.into_json()?;
+        Ok(release.into())
+    }
+
+    fn update_release_body(&self, existing: &ReleaseInfo, body: &str) -> Result<ReleaseInfo> {
+        let path = format!("/releases/{}", encode_segment(&existing.tag_name));
+        let payload = serde_json::json!({ "description": body });
+        let release: Release = self.send("PUT", &path, payload)?.into_json()?;
+        Ok(release.into())
+    }
+
+    fn file(&self, path: &str) -> Result<Option<String>> {
+        // HEAD is the default branch
+        let path = format!("/repository/files/{}/raw?ref=HEAD", encode_segment(path));
+        match self.get(&path) {
+            Ok(response) => Ok(Some(response.into_string()?)),
+            Err(Error::Api { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_request_as_pull_request() {
+        let mr: MergeRequest = serde_json::from_value(serde_json::json!({
+            "iid": 12,
+            "title": "fix: crash",
+            "web_url": "https://gitlab.com/group/app/-/merge_requests/12",
+            "author": { "username": "octocat" },
+            "description": null,
+            "labels": ["bug"]
+        }))
+        .unwrap();
+        let pull = PullRequest::from(mr);
+        assert_eq!(pull.number, 12);
+        assert_eq!(pull.user.login, "octocat");
+        assert_eq!(pull.labels[0].name, "bug");
+
+        let release: Release = serde_json::from_value(serde_json::json!({
+            "tag_name": "v1.0.0",
+            "description": "### Added\n\n- first",
+            "_links": { "self": "https://gitlab.com/group/app/-/releases/v1.0.0" }
+        }))
+        .unwrap();
+        let release = ReleaseInfo::from(release);
+        assert_eq!(
+            release.html_url,
+            "https://gitlab.com/group/app/-/releases/v1.0.0"
+        );
+        assert_eq!(release.body.as_deref(), Some("### Added\n\n- first"));
+    }
+}
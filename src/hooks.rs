@@ -0,0 +1,117 @@
+use crate::{Error, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+/// A point in `release` or `add` where a command from `[hooks]` in
+/// `changelog.toml` runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Before `release` writes the changelog. Failing stops the release.
+    PreRelease,
+    /// Once `release` has written, bumped, committed, and tagged whatever it
+    /// was asked to
+    PostRelease,
+    /// After `add` adds an entry
+    PostAdd,
+}
+
+impl Hook {
+    /// The hook's key in `changelog.toml`
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreRelease => "pre_release",
+            Hook::PostRelease => "post_release",
+            Hook::PostAdd => "post_add",
+        }
+    }
+}
+
+impl std::fmt::Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Run a hook's shell `command` in `dir`. It finds the version in
+/// `CHANGELOG_VERSION`, the version's notes in the file `CHANGELOG_NOTES_FILE`
+/// names, the changelog at `CHANGELOG_PATH`, and which hook it is in
+/// `CHANGELOG_HOOK`. Its output goes straight to ours.
+pub fn run(
+    hook: Hook,
+    command: &str,
+    dir: &Path,
+    changelog: &Path,
+    version: &str,
+    notes: &str,
+) -> Result<()> {
+    let failed = |message: String| Error::Hook { hook, message };
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(notes.as_bytes())?;
+    file.flush()?;
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .current_dir(dir)
+        .env("CHANGELOG_HOOK", hook.name())
+        .env("CHANGELOG_VERSION", version)
+        .env("CHANGELOG_NOTES_FILE", file.path())
+        .env("CHANGELOG_PATH", changelog)
+        .status()
+        .map_err(|e| failed(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(failed(format!("`{}` exited with {}", command, status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = temp_dir.path().join("CHANGELOG.md");
+        run(
+            Hook::PostRelease,
+            r#"echo "$CHANGELOG_HOOK $CHANGELOG_VERSION $(cat "$CHANGELOG_NOTES_FILE")" > out"#,
+            temp_dir.path(),
+            &changelog,
+            "1.2.0",
+            "### Added\n\n- export",
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("out")).unwrap(),
+            "post_release 1.2.0 ### Added\n\n- export\n"
+        );
+
+        let error = run(
+            Hook::PreRelease,
+            "exit 3",
+            temp_dir.path(),
+            &changelog,
+            "1.2.0",
+            "",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "pre_release hook failed: `exit 3` exited with exit status: 3"
+        );
+    }
+}
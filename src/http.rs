@@ -0,0 +1,309 @@
+use crate::config::HttpConfig;
+use crate::notify;
+use crate::{Error, ForgeKind, Result};
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest we'll wait on a rate limit or `Retry-After` before giving up
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// HTTP client for one forge's API, or for webhooks. Requests go through the
+/// proxy in `HTTPS_PROXY` (or `HTTP_PROXY` for plain http) unless `NO_PROXY`
+/// exempts the host, and failures that are likely to pass are retried with
+/// exponential backoff: rate limits that reset soon and connections that
+/// never got through for any request, dropped connections and 5xx responses
+/// only for requests that are safe to send twice.
+pub struct Client {
+    /// Whose API errors come from, or `None` for webhooks
+    forge: Option<ForgeKind>,
+    agent: ureq::Agent,
+    retries: u32,
+    /// Reported on the first request, so that building a client can't fail
+    proxy_error: Option<String>,
+}
+
+impl Client {
+    pub fn new(forge: ForgeKind, host: &str, config: &HttpConfig) -> Self {
+        Client {
+            forge: Some(forge),
+            ..Client::webhook(&format!("https://{}", host), config)
+        }
+    }
+
+    /// A client for posting to the webhook at `url`, like chat notifications
+    pub fn webhook(url: &str, config: &HttpConfig) -> Self {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(config.timeout))
+            .user_agent(concat!("changelog/", env!("CARGO_PKG_VERSION")));
+        let mut proxy_error = None;
+        if let Some(proxy) = proxy_for(url, env_var) {
+            match ureq::Proxy::new(&proxy) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => proxy_error = Some(format!("Invalid proxy '{}': {}", proxy, e)),
+            }
+        }
+
+        Client {
+            forge: None,
+            agent: builder.build(),
+            retries: config.retries,
+            proxy_error,
+        }
+    }
+
+    pub fn request(&self, method: &str, url: &str) -> ureq::Request {
+        self.agent.request(method, url)
+    }
+
+    pub fn call(&self, request: ureq::Request) -> Result<ureq::Response> {
+        self.send(request, None)
+    }
+
+    pub fn send_json(
+        &self,
+        request: ureq::Request,
+        body: impl Serialize,
+    ) -> Result<ureq::Response> {
+        self.send(request, Some(serde_json::to_value(body)?))
+    }
+
+    fn send(
+        &self,
+        request: ureq::Request,
+        body: Option<serde_json::Value>,
+    ) -> Result<ureq::Response> {
+        if let Some(message) = &self.proxy_error {
+            return Err(Error::InvalidInput(message.clone()));
+        }
+
+        let method = request.method().to_string();
+        let mut attempt = 0;
+        loop {
+            let result = match &body {
+                Some(body) => request.clone().send_json(body),
+                None => request.clone().call(),
+            };
+            let wait = match &result {
+                Ok(_) => None,
+                Err(e) if !retryable(&method, e) => None,
+                Err(ureq::Error::Status(_, response)) => {
+                    Some(retry_after(response).unwrap_or_else(|| backoff(attempt)))
+                }
+                Err(ureq::Error::Transport(_)) => Some(backoff(attempt)),
+            };
+            match wait {
+                Some(wait) if attempt < self.retries && wait <= MAX_WAIT => {
+                    thread::sleep(wait);
+                    attempt += 1;
+                }
+                _ => return result.map_err(|e| api_error(self.forge, e)),
+            }
+        }
+    }
+}
+
+/// Whether a failed request is worth sending again. Rate limits and
+/// connections that never got through are, whatever the method, since the
+/// server can't have acted on them. Server errors and dropped connections
+/// only are for methods where a repeat does no harm, so a POST that created a
+/// release before failing isn't sent twice.
+fn retryable(method: &str, error: &ureq::Error) -> bool {
+    let idempotent = matches!(method, "GET" | "HEAD" | "PUT" | "PATCH" | "DELETE");
+    match error {
+        ureq::Error::Status(status, response) => {
+            is_rate_limited(*status, response)
+                || (idempotent && matches!(status, 500 | 502 | 503 | 504))
+        }
+        ureq::Error::Transport(transport) => match transport.kind() {
+            ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => true,
+            ureq::ErrorKind::Io => idempotent,
+            _ => false,
+        },
+    }
+}
+
+/// GitHub answers 403 rather than 429 once the rate limit is used up
+fn is_rate_limited(status: u16, response: &ureq::Response) -> bool {
+    status == 429
+        || (status == 403
+            && (response.header("retry-after").is_some()
+                || response.header("x-ratelimit-remaining") == Some("0")))
+}
+
+/// How long the server asked us to wait, from `Retry-After` or the time the
+/// rate limit resets (GitHub's `x-ratelimit-reset`, GitLab's `ratelimit-reset`)
+fn retry_after(response: &ureq::Response) -> Option<Duration> {
+    if let Some(seconds) = response.header("retry-after").and_then(|s| s.parse().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    let reset: u64 = ["x-ratelimit-reset", "ratelimit-reset"]
+        .iter()
+        .find_map(|name| response.header(name)?.parse().ok())?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Duration::from_secs(reset.saturating_sub(now)))
+}
+
+/// 0.5s, 1s, 2s, ... capped at 8s
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 << attempt.min(4))
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// The proxy for requests to `url`, following the usual `HTTPS_PROXY` (or
+/// `HTTP_PROXY` for plain http), `ALL_PROXY`, and `NO_PROXY` conventions
+fn proxy_for(url: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let no_proxy = var("NO_PROXY").or_else(|| var("no_proxy"));
+    if no_proxy.is_some_and(|list| no_proxy_matches(&list, &notify::host(url))) {
+        return None;
+    }
+    let scheme = if url.to_lowercase().starts_with("http://") {
+        ["HTTP_PROXY", "http_proxy"]
+    } else {
+        ["HTTPS_PROXY", "https_proxy"]
+    };
+    scheme
+        .iter()
+        .chain(&["ALL_PROXY", "all_proxy"])
+        .find_map(|name| var(name))
+}
+
+/// Whether a comma separated `NO_PROXY` list covers `host`. `*` matches
+/// everything and `example.com` or `.example.com` match its subdomains too.
+fn no_proxy_matches(list: &str, host: &str) -> bool {
+    let host = host.to_lowercase();
+    list.split(',').map(str::trim).any(|entry| {
+        if entry == "*" {
+            return true;
+        }
+        let entry = entry.split(':').next().unwrap_or(entry);
+        let entry = entry.trim_start_matches('.').to_lowercase();
+        !entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry)))
+    })
+}
+
+fn api_error(forge: Option<ForgeKind>, error: ureq::Error) -> Error {
+    let Some(forge) = forge else {
+        return match error {
+            ureq::Error::Status(status, response) => Error::Webhook {
+                status,
+                message: response
+                    .into_string()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            },
+            ureq::Error::Transport(transport) => Error::Http(Box::new(transport)),
+        };
+    };
+    match error {
+        ureq::Error::Status(status, response) if is_rate_limited(status, &response) => {
+            Error::RateLimited {
+                forge,
+                retry_after: retry_after(&response).map(|wait| wait.as_secs()),
+            }
+        }
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            Error::Api {
+                forge,
+                status,
+                message: body.trim().to_string(),
+            }
+        }
+        ureq::Error::Transport(transport) => Error::Http(Box::new(transport)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_for() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert_eq!(proxy_for("https://github.com", env(&[])), None);
+        assert_eq!(
+            proxy_for(
+                "https://github.com",
+                env(&[
+                    ("HTTPS_PROXY", "http://proxy:3128"),
+                    ("ALL_PROXY", "socks5://other")
+                ])
+            )
+            .as_deref(),
+            Some("http://proxy:3128")
+        );
+        assert_eq!(
+            proxy_for(
+                "https://git.corp.example:3000/api",
+                env(&[
+                    ("https_proxy", "http://proxy:3128"),
+                    ("NO_PROXY", "localhost, .corp.example")
+                ])
+            ),
+            None
+        );
+        assert_eq!(
+            proxy_for(
+                "http://hooks.example/notify",
+                env(&[
+                    ("HTTPS_PROXY", "http://secure:3128"),
+                    ("http_proxy", "http://plain:3128")
+                ])
+            )
+            .as_deref(),
+            Some("http://plain:3128")
+        );
+        assert_eq!(
+            proxy_for(
+                "http://hooks.example/notify",
+                env(&[
+                    ("HTTPS_PROXY", "http://secure:3128"),
+                    ("ALL_PROXY", "socks5://other")
+                ])
+            )
+            .as_deref(),
+            Some("socks5://other")
+        );
+        assert!(no_proxy_matches("*", "gitlab.com"));
+        assert!(no_proxy_matches("example.com:443", "git.example.com"));
+        assert!(!no_proxy_matches("example.com", "notexample.com"));
+    }
+
+    #[test]
+    fn test_retryable() {
+        let status = |status: u16, headers: &str| {
+            let response = format!("HTTP/1.1 {} Error\r\n{}\r\n", status, headers);
+            ureq::Error::Status(status, response.parse().unwrap())
+        };
+
+        assert!(retryable("GET", &status(502, "")));
+        assert!(retryable("PATCH", &status(503, "")));
+        assert!(!retryable("POST", &status(502, "")));
+        assert!(!retryable("GET", &status(404, "")));
+        assert!(retryable("POST", &status(429, "Retry-After: 1\r\n")));
+        assert!(retryable(
+            "POST",
+            &status(403, "x-ratelimit-remaining: 0\r\n")
+        ));
+        assert!(!retryable("POST", &status(403, "")));
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(10), Duration::from_secs(8));
+    }
+}
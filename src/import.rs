@@ -0,0 +1,485 @@
+use crate::config::SectionsConfig;
+use crate::notes;
+use crate::{ChangeType, Error, Result};
+use chrono::DateTime;
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Deserialize;
+
+/// Changelog formats written by other tools that `import` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// conventional-changelog or standard-version output
+    #[value(name = "conventional", alias = "standard-version")]
+    Conventional,
+    /// git-cliff output
+    #[value(name = "cliff", alias = "git-cliff")]
+    Cliff,
+    /// A Debian package's debian/changelog
+    #[value(name = "debian")]
+    Debian,
+    /// Git tags, one per line, each optionally followed by its date, e.g.
+    /// from `git tag --format='%(refname:short) %(creatordate:short)'`
+    #[value(name = "tags")]
+    Tags,
+}
+
+/// A release read from another tool's changelog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRelease {
+    /// Semver version, or `Unreleased`
+    pub version: String,
+    /// In ISO format, rewritten in the configured one when imported
+    pub date: Option<String>,
+    pub entries: Vec<(ChangeType, String)>,
+}
+
+/// Work out which tool wrote `content`
+pub fn detect(content: &str) -> Format {
+    let debian = Regex::new(r"(?m)^\S+ \([^)]+\) [^;\n]*;\s*urgency=").unwrap();
+    let conventional =
+        Regex::new(r"(?m)^#{1,3} \[?v?\d[^\]\s]*\]?\([^)]*\) \(\d{4}-\d{2}-\d{2}\)").unwrap();
+    if debian.is_match(content) {
+        Format::Debian
+    } else if conventional.is_match(content) {
+        Format::Conventional
+    } else if content.lines().any(|line| line.starts_with('#')) {
+        Format::Cliff
+    } else {
+        Format::Tags
+    }
+}
+
+/// Releases in `content`, newest first. A version that appears more than
+/// once, like Debian revisions of the same upstream release, is merged into
+/// its newest entry.
+pub fn parse(content: &str, format: Format) -> Result<Vec<ImportedRelease>> {
+    let releases = match format {
+        Format::Conventional | Format::Cliff => parse_markdown(content, &CliffGroups::default()),
+        Format::Debian => parse_debian(content),
+        Format::Tags => parse_tags(content),
+    };
+    merged(releases).ok_or_else(|| {
+        Error::Parse(format!(
+            "No releases found to import as {}",
+            format.to_possible_value().unwrap().get_name()
+        ))
+    })
+}
+
+/// `releases` with repeated versions merged into the first, or `None` when
+/// there aren't any
+fn merged(releases: Vec<ImportedRelease>) -> Option<Vec<ImportedRelease>> {
+    let mut merged: Vec<ImportedRelease> = Vec::new();
+    for release in releases {
+        match merged.iter_mut().find(|r| r.version == release.version) {
+            Some(existing) => existing.entries.extend(release.entries),
+            None => merged.push(release),
+        }
+    }
+    (!merged.is_empty()).then_some(merged)
+}
+
+/// Releases from markdown with a heading per version and a heading per kind
+/// of change, as conventional-changelog and git-cliff write them
+fn parse_markdown(content: &str, groups: &CliffGroups) -> Vec<ImportedRelease> {
+    let version_heading = Regex::new(
+        r"^#{1,3}\s+(?:\[([^\]]+)\](?:\([^)]*\))?|(\S+))(?:\s+-)?(?:\s+\(?(\d{4}-\d{2}-\d{2})\)?)?",
+    )
+    .unwrap();
+    let section_heading = Regex::new(r"^#{2,4}\s+(.+)$").unwrap();
+
+    let mut releases: Vec<ImportedRelease> = Vec::new();
+    let mut section: Option<(ChangeType, bool)> = None;
+    for line in content.lines() {
+        let version = version_heading.captures(line).and_then(|captures| {
+            let name = captures.get(1).or_else(|| captures.get(2))?.as_str();
+            let version = normalize_version(name)?;
+            Some((version, captures.get(3).map(|m| m.as_str().to_string())))
+        });
+        if let Some((version, date)) = version {
+            releases.push(ImportedRelease {
+                version,
+                date,
+                entries: Vec::new(),
+            });
+            section = None;
+            continue;
+        }
+
+        let Some(release) = releases.last_mut() else {
+            continue;
+        };
+        if let Some(captures) = section_heading.captures(line) {
+            section = Some(groups.section_type(&captures[1]));
+        } else if let Some(entry) = line.strip_prefix("* ").or_else(|| line.strip_prefix("- ")) {
+            let (change_type, breaking) = section.clone().unwrap_or((ChangeType::Changed, false));
+            let entry = if breaking {
+                format!("{} {}", notes::BREAKING, entry.trim())
+            } else {
+                entry.trim().to_string()
+            };
+            release.entries.push((change_type, entry));
+        } else if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+            if let Some((_, entry)) = release.entries.last_mut() {
+                entry.push('\n');
+                entry.push_str(line.trim());
+            }
+        }
+    }
+    releases
+}
+
+/// Change type for another tool's section heading, and whether it lists
+/// breaking changes. Emoji and the `<!-- 0 -->` ordering comments git-cliff
+/// puts in front of group names are ignored.
+fn section_type(heading: &str) -> (ChangeType, bool) {
+    let name = group_name(heading);
+    if let Some(change_type) = ChangeType::from_section(&name) {
+        return (change_type, false);
+    }
+    match name.as_str() {
+        "features" | "feature" | "feat" | "new features" => (ChangeType::Added, false),
+        "bug fixes" | "bug fix" | "bugfixes" | "fixes" | "fix" => (ChangeType::Fixed, false),
+        "deprecations" => (ChangeType::Deprecated, false),
+        "removals" => (ChangeType::Removed, false),
+        "breaking changes" | "breaking" => (ChangeType::Changed, true),
+        // Performance, refactoring, docs, and other groups are all changes
+        _ => (ChangeType::Changed, false),
+    }
+}
+
+/// A git-cliff group or section heading without its ordering comment and
+/// emoji, lowercased
+fn group_name(heading: &str) -> String {
+    let comment = Regex::new(r"<!--.*?-->").unwrap();
+    comment
+        .replace_all(heading, "")
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim()
+        .to_lowercase()
+}
+
+/// The sections git-cliff's groups belong in, going by the `commit_parsers`
+/// in a `cliff.toml`: a group of `feat` commits is Added, one of `fix`
+/// commits is Fixed, and so on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliffGroups {
+    groups: Vec<(String, ChangeType)>,
+}
+
+#[derive(Deserialize)]
+struct CliffConfig {
+    #[serde(default)]
+    git: CliffGit,
+}
+
+#[derive(Default, Deserialize)]
+struct CliffGit {
+    #[serde(default)]
+    commit_parsers: Vec<CommitParser>,
+}
+
+#[derive(Deserialize)]
+struct CommitParser {
+    message: Option<String>,
+    group: Option<String>,
+    #[serde(default)]
+    skip: bool,
+}
+
+impl CliffGroups {
+    /// Groups from the contents of a `cliff.toml`, with commit types filed
+    /// like `review` files them under `sections`
+    pub fn parse(content: &str, sections: &SectionsConfig) -> Result<Self> {
+        let config: CliffConfig = toml::from_str(content)
+            .map_err(|e| Error::Parse(format!("Failed to parse cliff.toml: {}", e.message())))?;
+        let commit_type = Regex::new(r"^\^?\(?([a-z]+)").unwrap();
+        let mut groups = Vec::new();
+        for parser in config.git.commit_parsers {
+            let Some(group) = parser.group.filter(|_| !parser.skip) else {
+                continue;
+            };
+            let name = group_name(&group);
+            let from_message = parser
+                .message
+                .as_deref()
+                .and_then(|message| commit_type.captures(message))
+                .and_then(|captures| {
+                    let commit_type = &captures[1];
+                    sections.commit_type(commit_type).or(match commit_type {
+                        "deprecate" | "deprecated" => Some(ChangeType::Deprecated),
+                        "remove" | "removed" => Some(ChangeType::Removed),
+                        "security" | "sec" => Some(ChangeType::Security),
+                        _ => None,
+                    })
+                });
+            let change_type = from_message.unwrap_or_else(|| section_type(&group).0);
+            if !groups.iter().any(|(existing, _)| *existing == name) {
+                groups.push((name, change_type));
+            }
+        }
+        Ok(CliffGroups { groups })
+    }
+
+    /// Change type for a section heading in git-cliff's output, and whether
+    /// it lists breaking changes
+    fn section_type(&self, heading: &str) -> (ChangeType, bool) {
+        let (fallback, breaking) = section_type(heading);
+        let name = group_name(heading);
+        match self.groups.iter().find(|(group, _)| *group == name) {
+            Some((_, change_type)) if !breaking => (change_type.clone(), false),
+            _ => (fallback, breaking),
+        }
+    }
+}
+
+/// Releases in git-cliff's markdown output, newest first, filed into
+/// sections by `groups`
+pub fn parse_cliff(content: &str, groups: &CliffGroups) -> Result<Vec<ImportedRelease>> {
+    merged(parse_markdown(content, groups))
+        .ok_or_else(|| Error::Parse("No releases found in git-cliff output".to_string()))
+}
+
+/// Releases from a debian/changelog. Versions drop their epoch and Debian
+/// revision, and entries are sorted by the way they start.
+fn parse_debian(content: &str) -> Vec<ImportedRelease> {
+    let heading = Regex::new(r"^\S+ \(([^)]+)\) [^;]*;").unwrap();
+    let trailer = Regex::new(r"^ -- .*>\s+(.+)$").unwrap();
+
+    let mut releases: Vec<ImportedRelease> = Vec::new();
+    for line in content.lines() {
+        if let Some(captures) = heading.captures(line) {
+            let version = captures[1].split_once(':').map_or(&captures[1], |(_, v)| v);
+            let upstream = version.rsplit_once('-').map_or(version, |(v, _)| v);
+            if let Some(version) = normalize_version(&upstream.replace('~', "-")) {
+                releases.push(ImportedRelease {
+                    version,
+                    date: None,
+                    entries: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        let Some(release) = releases.last_mut() else {
+            continue;
+        };
+        if let Some(captures) = trailer.captures(line) {
+            // Later revisions come first, so keep the newest date
+            if release.date.is_none() {
+                release.date = DateTime::parse_from_rfc2822(captures[1].trim())
+                    .ok()
+                    .map(|date| date.format("%Y-%m-%d").to_string());
+            }
+        } else if let Some(entry) = line.trim_start().strip_prefix("* ") {
+            release.entries.push((
+                crate::keyword_type(entry).unwrap_or(ChangeType::Changed),
+                entry.trim().to_string(),
+            ));
+        } else if line.starts_with("    ") && !line.trim().is_empty() {
+            if let Some((_, entry)) = release.entries.last_mut() {
+                entry.push('\n');
+                entry.push_str(line.trim());
+            }
+        }
+    }
+    releases
+}
+
+/// Empty releases for each version tag, newest first
+fn parse_tags(content: &str) -> Vec<ImportedRelease> {
+    let mut releases: Vec<(semver::Version, ImportedRelease)> = content
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let version = normalize_version(words.next()?)?;
+            let date = words
+                .next()
+                .filter(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+            Some((
+                semver::Version::parse(&version).ok()?,
+                ImportedRelease {
+                    version,
+                    date: date.map(String::from),
+                    entries: Vec::new(),
+                },
+            ))
+        })
+        .collect();
+    releases.sort_by(|a, b| b.0.cmp(&a.0));
+    releases.into_iter().map(|(_, release)| release).collect()
+}
+
+/// `version` without a `v` prefix and padded to three numbers, e.g. `v1.2`
+/// becomes `1.2.0`, or `Unreleased`. Anything that isn't a version is `None`.
+fn normalize_version(version: &str) -> Option<String> {
+    if version.eq_ignore_ascii_case("unreleased") {
+        return Some("Unreleased".to_string());
+    }
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let end = version.find(['-', '+']).unwrap_or(version.len());
+    let (core, rest) = version.split_at(end);
+    let mut numbers: Vec<&str> = core.split('.').collect();
+    if numbers.len() > 3 || numbers.iter().any(|n| n.parse::<u64>().is_err()) {
+        return None;
+    }
+    numbers.resize(3, "0");
+    let version = format!("{}{}", numbers.join("."), rest);
+    semver::Version::parse(&version).ok().map(|_| version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_conventional() {
+        let content = r#"# Changelog
+
+All notable changes to this project will be documented in this file. See [standard-version](https://github.com/conventional-changelog/standard-version) for commit guidelines.
+
+### [1.0.1](https://github.com/owner/repo/compare/v1.0.0...v1.0.1) (2024-02-03)
+
+
+### Bug Fixes
+
+* **cli:** crash on startup ([abc1234](https://github.com/owner/repo/commit/abc1234))
+
+## [1.0.0](https://github.com/owner/repo/compare/v0.9.0...v1.0.0) (2024-01-02)
+
+
+### ⚠ BREAKING CHANGES
+
+* config moved to changelog.toml
+
+### Features
+
+* export to csv
+  with headers
+"#;
+        assert_eq!(detect(content), Format::Conventional);
+        assert_eq!(
+            parse(content, Format::Conventional).unwrap(),
+            vec![
+                ImportedRelease {
+                    version: "1.0.1".to_string(),
+                    date: Some("2024-02-03".to_string()),
+                    entries: vec![(
+                        ChangeType::Fixed,
+                        "**cli:** crash on startup ([abc1234](https://github.com/owner/repo/commit/abc1234))".to_string()
+                    )],
+                },
+                ImportedRelease {
+                    version: "1.0.0".to_string(),
+                    date: Some("2024-01-02".to_string()),
+                    entries: vec![
+                        (
+                            ChangeType::Changed,
+                            "**Breaking:** config moved to changelog.toml".to_string()
+                        ),
+                        (
+                            ChangeType::Added,
+                            "export to csv\nwith headers".to_string()
+                        ),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_cliff() {
+        let content = "# Changelog\n\n## [unreleased]\n\n### 🚀 Features\n\n- Dark mode\n\n## [0.2.0] - 2024-03-01\n\n### <!-- 1 -->🐛 Bug Fixes\n\n- Crash on startup\n\n### ⚙️ Miscellaneous Tasks\n\n- Bump deps\n";
+        assert_eq!(detect(content), Format::Cliff);
+        let releases = parse(content, Format::Cliff).unwrap();
+        assert_eq!(releases[0].version, "Unreleased");
+        assert_eq!(
+            releases[0].entries,
+            vec![(ChangeType::Added, "Dark mode".to_string())]
+        );
+        assert_eq!(releases[1].date, Some("2024-03-01".to_string()));
+        assert_eq!(
+            releases[1].entries,
+            vec![
+                (ChangeType::Fixed, "Crash on startup".to_string()),
+                (ChangeType::Changed, "Bump deps".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cliff_groups() {
+        let cliff_toml = r#"
+[git]
+commit_parsers = [
+  { message = "^feat", group = "<!-- 0 -->🚀 New stuff" },
+  { message = "^fix", group = "Squashed bugs" },
+  { message = "^security", group = "Hardening" },
+  { message = "^chore", skip = true },
+  { body = ".*security", group = "🛡️ Security" },
+]
+"#;
+        let groups = CliffGroups::parse(cliff_toml, &SectionsConfig::default()).unwrap();
+        let content = "## [1.0.0] - 2024-03-01\n\n### 🚀 New stuff\n\n- Dark mode\n\n### Squashed bugs\n\n- Crash on startup\n\n### Hardening\n\n- Escape paths\n\n### 🛡️ Security\n\n- Sandbox\n";
+        let releases = parse_cliff(content, &groups).unwrap();
+        assert_eq!(
+            releases[0].entries,
+            vec![
+                (ChangeType::Added, "Dark mode".to_string()),
+                (ChangeType::Fixed, "Crash on startup".to_string()),
+                (ChangeType::Security, "Escape paths".to_string()),
+                (ChangeType::Security, "Sandbox".to_string()),
+            ]
+        );
+        assert!(CliffGroups::parse("[git", &SectionsConfig::default()).is_err());
+        assert!(parse_cliff("nothing here", &groups).is_err());
+    }
+
+    #[test]
+    fn test_import_debian() {
+        let content = "mytool (1.2-2) unstable; urgency=medium\n\n  * Fix crash on startup.\n\n -- Jane Doe <jane@example.com>  Tue, 05 Mar 2024 10:00:00 +0000\n\nmytool (1.2-1) unstable; urgency=medium\n\n  * New upstream release.\n  * Add man page,\n    with examples.\n\n -- Jane Doe <jane@example.com>  Mon, 04 Mar 2024 10:00:00 +0000\n\nmytool (1:1.1~rc1-1) unstable; urgency=low\n\n  * Initial release.\n\n -- Jane Doe <jane@example.com>  Thu, 01 Feb 2024 10:00:00 +0000\n";
+        assert_eq!(detect(content), Format::Debian);
+        assert_eq!(
+            parse(content, Format::Debian).unwrap(),
+            vec![
+                ImportedRelease {
+                    version: "1.2.0".to_string(),
+                    date: Some("2024-03-05".to_string()),
+                    entries: vec![
+                        (ChangeType::Fixed, "Fix crash on startup.".to_string()),
+                        (ChangeType::Changed, "New upstream release.".to_string()),
+                        (
+                            ChangeType::Added,
+                            "Add man page,\nwith examples.".to_string()
+                        ),
+                    ],
+                },
+                ImportedRelease {
+                    version: "1.1.0-rc1".to_string(),
+                    date: Some("2024-02-01".to_string()),
+                    entries: vec![(ChangeType::Changed, "Initial release.".to_string())],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_tags() {
+        let content = "v0.9.0 2023-12-01\nv1.0.0\nnightly\nv0.10.0 2024-01-01\n";
+        assert_eq!(detect(content), Format::Tags);
+        let releases = parse(content, Format::Tags).unwrap();
+        assert_eq!(
+            releases
+                .iter()
+                .map(|r| (r.version.as_str(), r.date.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1.0.0", None),
+                ("0.10.0", Some("2024-01-01")),
+                ("0.9.0", Some("2023-12-01")),
+            ]
+        );
+        assert!(parse("nightly\n", Format::Tags).is_err());
+    }
+}
@@ -1,999 +1,6126 @@
+pub mod audit;
+pub mod config;
+mod document;
+mod encoding;
+mod error;
+pub mod feed;
+pub mod forge;
+pub mod fragments;
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+pub mod hooks;
+mod http;
+pub mod import;
+pub mod lint;
+pub mod manifest;
+mod notes;
+pub mod notify;
+pub mod osv;
+mod render;
+pub mod site;
+pub mod template;
+pub mod version_files;
+pub mod versioning;
+pub mod workspace;
+
+pub use document::{ChangelogDocument, Release};
+pub use encoding::{Encoding, LineEnding};
+pub use error::{Error, Result};
+pub use forge::{Forge, ForgeKind, Remote};
+pub use hooks::Hook;
+pub use notes::{escape_entry, Freeze, Section};
+use template::{EntryFields, Template};
+
 use clap::ValueEnum;
-#[derive(Clone, ValueEnum)]
+/// A section of a release. Besides the six from Keep a Changelog, projects
+/// can configure their own in `changelog.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ChangeType {
     /// New features
-    #[value(name = "added", alias = "a")]
     Added,
     /// Changes in existing functionality
-    #[value(name = "changed", alias = "c")]
     Changed,
     /// Soon-to-be removed features
-    #[value(name = "deprecated", alias = "d")]
     Deprecated,
     /// Removed features
-    #[value(name = "removed", alias = "r")]
     Removed,
     /// Bug fixes
-    #[value(name = "fixed", alias = "f")]
     Fixed,
     /// Security fixes
-    #[value(name = "security", alias = "s")]
     Security,
+    /// A section from `[[sections.custom]]`, by its heading
+    Custom(String),
 }
 
-impl ToString for ChangeType {
-    fn to_string(&self) -> String {
-        match self {
-            ChangeType::Added => "added".to_string(),
-            ChangeType::Changed => "changed".to_string(),
-            ChangeType::Deprecated => "deprecated".to_string(),
-            ChangeType::Removed => "removed".to_string(),
-            ChangeType::Fixed => "fixed".to_string(),
-            ChangeType::Security => "security".to_string(),
-        }
+impl ChangeType {
+    /// The Keep a Changelog sections, in their usual order
+    pub const BUILT_IN: &'static [ChangeType] = &[
+        ChangeType::Added,
+        ChangeType::Changed,
+        ChangeType::Deprecated,
+        ChangeType::Removed,
+        ChangeType::Fixed,
+        ChangeType::Security,
+    ];
+
+    /// Built-in change type for a `### Section` heading, matched
+    /// case-insensitively
+    pub fn from_section(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::BUILT_IN
+            .iter()
+            .find(|t| t.heading().eq_ignore_ascii_case(name))
+            .cloned()
     }
-}
-
-use chrono::Local;
-use colored::Colorize;
-use git2::Repository;
-use indexmap::IndexMap;
-use parse_changelog::{Parser, Release};
-use similar::{ChangeTag, TextDiff};
-use std::fs;
-use std::io::{self, ErrorKind, Write};
-use std::path::Path;
-use std::process::Command;
 
-pub struct Changelog {
-    path: Box<Path>,
-}
+    /// Built-in change type for a name or one letter alias like `a`
+    pub fn built_in(name: &str) -> Option<Self> {
+        Self::from_section(name).or_else(|| {
+            Self::BUILT_IN
+                .iter()
+                .find(|t| name.eq_ignore_ascii_case(&t.to_string()[..1]))
+                .cloned()
+        })
+    }
 
-#[cfg(test)]
-thread_local! {
-    static TEST_GITHUB_REPO: std::cell::RefCell<Option<(String, String)>> = std::cell::RefCell::new(None);
-}
+    /// The `### Heading` the section is written under
+    pub fn heading(&self) -> String {
+        let name = match self {
+            ChangeType::Custom(name) => return name.clone(),
+            _ => self.to_string(),
+        };
+        name[..1].to_uppercase() + &name[1..]
+    }
 
-#[cfg(test)]
-pub fn set_test_github_repo(owner: Option<String>, repo: Option<String>) {
-    TEST_GITHUB_REPO.with(|cell| {
-        *cell.borrow_mut() = owner.zip(repo);
-    });
+    /// What the section is for, shown when picking a type
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            ChangeType::Added => Some("New features"),
+            ChangeType::Changed => Some("Changes in existing functionality"),
+            ChangeType::Deprecated => Some("Soon-to-be removed features"),
+            ChangeType::Removed => Some("Removed features"),
+            ChangeType::Fixed => Some("Bug fixes"),
+            ChangeType::Security => Some("Security fixes"),
+            ChangeType::Custom(_) => None,
+        }
+    }
 }
 
-fn infer_github_repo() -> Option<(String, String)> {
-    #[cfg(test)]
-    {
-        // In tests, return the mock value if set
-        if let Some(repo) = TEST_GITHUB_REPO.with(|cell| cell.borrow().clone()) {
-            return Some(repo);
-        }
+/// Written the way it's passed to `--type`
+impl Serialize for ChangeType {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
+}
 
-    // Production code path
-    if let Ok(repo) = Repository::discover(".") {
-        if let Ok(remote) = repo.find_remote("origin") {
-            if let Some(url) = remote.url() {
-                // Handle both HTTPS and SSH GitHub URLs
-                let parts = if url.starts_with("git@github.com:") {
-                    url.trim_start_matches("git@github.com:")
-                        .trim_end_matches(".git")
-                        .split('/')
-                        .collect::<Vec<_>>()
-                } else if url.contains("github.com") {
-                    url.split("github.com/")
-                        .nth(1)?
-                        .trim_end_matches(".git")
-                        .split('/')
-                        .collect::<Vec<_>>()
-                } else {
-                    return None;
-                };
+/// Built-in types and their one letter aliases parse as themselves, and
+/// anything else as a custom section to look up in the config
+impl FromStr for ChangeType {
+    type Err = String;
 
-                if parts.len() >= 2 {
-                    return Some((parts[0].to_string(), parts[1].to_string()));
-                }
-            }
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("the change type is empty".to_string());
         }
+        Ok(Self::built_in(s).unwrap_or_else(|| ChangeType::Custom(s.to_string())))
     }
-    None
 }
 
-const EDITOR_TEMPLATE: &str = r#"{commits}
-
-# Review commits and add them to the changelog
-# Lines starting with '#' will be ignored
-# Prefix each commit with one of:
-#   added (a), changed (c), deprecated (d), removed (r), fixed (f), security (s)
-# You can also edit the commit message - it will be used as the changelog entry
-#
-# Example:
-# added 1234567 Add new feature
-# changed 89abcde Update existing functionality
-"#;
-
-impl Changelog {
-    fn show_diff(
-        &self,
-        version: Option<&str>,
-        old_content: &str,
-        new_content: &str,
-    ) -> io::Result<()> {
-        // Get the old version content
-        let parser = Parser::new();
-        let old_changelog = parser
-            .parse(old_content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-        let new_changelog = parser
-            .parse(new_content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+impl std::fmt::Display for ChangeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ChangeType::Added => "added",
+            ChangeType::Changed => "changed",
+            ChangeType::Deprecated => "deprecated",
+            ChangeType::Removed => "removed",
+            ChangeType::Fixed => "fixed",
+            ChangeType::Security => "security",
+            // One word, to type back in where types are read from text
+            ChangeType::Custom(name) => return f.pad(&name.to_lowercase().replace(' ', "-")),
+        };
+        f.pad(s)
+    }
+}
 
-        let version_key = version.unwrap_or("Unreleased");
+/// How far a release moves the version, smallest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
 
-        let old_version = old_changelog
-            .get(version_key)
-            .map(|r| format!("## {}\n\n{}", r.title, r.notes.trim()))
-            .unwrap_or_default();
+impl std::fmt::Display for Bump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
 
-        let new_version = new_changelog
-            .get(version_key)
-            .map(|r| format!("## {}\n\n{}", r.title, r.notes.trim()))
-            .unwrap_or_default();
+/// A released version and the date it shipped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub date: Option<String>,
+    pub yanked: bool,
+}
 
-        let diff = TextDiff::from_lines(&old_version, &new_version);
+/// The notes for one version, both raw and split into sections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseEntry {
+    pub version: String,
+    pub title: String,
+    pub date: Option<String>,
+    /// Note after the date, like `re-released 2025-01-03`
+    pub annotation: Option<String>,
+    /// Who cut the release, from its `[release.attribution]` line
+    pub released_by: Option<String>,
+    pub sections: Vec<Section>,
+    /// Issues and pull requests the entries refer to
+    pub references: Vec<u64>,
+    pub link: Option<String>,
+    /// Id of the version's heading in the rendered changelog, for deep links
+    pub anchor: String,
+    #[serde(skip)]
+    pub notes: String,
+}
 
-        for change in diff.iter_all_changes() {
-            match change.tag() {
-                ChangeTag::Delete => {
-                    print!("{}", format!("-{}", change).red());
-                }
-                ChangeTag::Insert => {
-                    print!("{}", format!("+{}", change).green());
-                }
-                ChangeTag::Equal => {
-                    print!(" {}", change);
-                }
-            }
-        }
-        Ok(())
-    }
-    fn get_editor() -> io::Result<String> {
-        // Try VISUAL, then EDITOR, then fall back to vi/vim/nano
-        if let Ok(editor) = std::env::var("VISUAL") {
-            return Ok(editor);
-        }
-        if let Ok(editor) = std::env::var("EDITOR") {
-            return Ok(editor);
-        }
-        for editor in &["vim", "vi", "nano"] {
-            if Command::new(editor).arg("--version").output().is_ok() {
-                return Ok(editor.to_string());
-            }
+impl ReleaseEntry {
+    /// The heading and notes as they appear in the changelog
+    pub fn markdown(&self) -> String {
+        if self.notes.is_empty() {
+            return format!("## {}\n", self.title);
         }
-        Err(io::Error::new(ErrorKind::NotFound, "No editor found"))
+        format!("## {}\n\n{}\n", self.title, self.notes)
     }
-    pub fn new() -> Self {
-        Changelog {
-            path: Path::new("CHANGELOG.md").into(),
-        }
-    }
-
-    pub fn init(&self) -> io::Result<()> {
-        if self.path.exists() {
-            eprintln!("CHANGELOG.md already exists");
-            return Ok(());
-        }
 
-        // Parse empty changelog to get default structure
-        let parser = Parser::new();
-        let changelog = parser
-            .parse("# Changelog\n## [Unreleased]")
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-
-        // Format and write the changelog
-        let content = changelog_to_markdown(&changelog, "# Changelog\n\n", None);
-        fs::write(&self.path, content)?;
-        println!("Created CHANGELOG.md");
-        Ok(())
+    /// Notes without any markup, e.g. for emails
+    pub fn plain(&self) -> String {
+        render::plain(&self.linked_markdown())
     }
 
-    pub fn add(
-        &self,
-        description: &str,
-        r#type: &ChangeType,
-        version: Option<&str>,
-        show_diff: bool,
-    ) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
-        }
-
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let mut changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-
-        // Determine which version to add to
-        let version_key = version.unwrap_or("Unreleased");
+    /// Notes as an HTML fragment, with the heading's id set to its anchor
+    pub fn html(&self) -> String {
+        render::html(&self.linked_markdown()).replacen(
+            "<h2>",
+            &format!("<h2 id=\"{}\">", self.anchor),
+            1,
+        )
+    }
 
-        // Create or get the version entry
-        if !changelog.contains_key(version_key) {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                format!("Version {} not found in changelog", version_key),
-            ));
-        }
+    /// Notes styled for reading in a terminal
+    pub fn terminal(&self) -> String {
+        render::terminal(&self.linked_markdown())
+    }
 
-        // Get the release entry
-        let release = changelog.get_mut(version_key).unwrap();
-
-        // Find the appropriate section
-        let section = r#type.to_string();
-
-        // Add the entry to the appropriate section
-        let section_marker = format!("### {}", section[..1].to_uppercase() + &section[1..]);
-        let mut lines: Vec<String> = release.notes.lines().map(String::from).collect();
-
-        if let Some(section_idx) = lines.iter().position(|line| line.trim() == section_marker) {
-            // Existing section found - insert entry
-            let mut insert_idx = section_idx + 1;
-            while insert_idx < lines.len() {
-                let line = lines[insert_idx].trim();
-                if line.is_empty() {
-                    insert_idx += 1;
-                } else if line.starts_with('-') {
-                    // This is a list item, advance past it and any continuation lines
-                    insert_idx += 1;
-                    // Skip any continuation lines (indented lines that are part of this list item)
-                    while insert_idx < lines.len() {
-                        let next_line = &lines[insert_idx];
-                        // If the line starts with whitespace and isn't a new list item or section,
-                        // it's a continuation of the previous list item
-                        if next_line.starts_with("  ") && !next_line.trim().starts_with('-') && !next_line.trim().starts_with("### ") {
-                            insert_idx += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                } else {
-                    // Not a list item or empty line, we've reached the end of the section
-                    break;
-                }
-            }
-            // Remove any extra blank lines before insertion
-            while insert_idx > section_idx + 1 && lines[insert_idx - 1].trim().is_empty() {
-                lines.remove(insert_idx - 1);
-                insert_idx -= 1;
-            }
-            lines.insert(insert_idx, format!("- {}\n", description));
-        } else {
-            // Section doesn't exist - create it
-            // Find where to insert the new section
-            let mut insert_idx = 0;
+    /// Notes in Slack's mrkdwn format, e.g. for webhooks
+    pub fn slack(&self) -> String {
+        render::slack(&self.linked_markdown())
+    }
 
-            // Skip past the version header
-            while insert_idx < lines.len() && !lines[insert_idx].starts_with("### ") {
-                insert_idx += 1;
+    /// The markdown with the version in the heading as an inline link, since
+    /// the reference definitions at the bottom of the changelog aren't included
+    pub(crate) fn linked_markdown(&self) -> String {
+        let title = self
+            .title
+            .replacen(&format!("[{}]", self.version), &self.version, 1);
+        let title = match &self.link {
+            Some(link) => {
+                title.replacen(&self.version, &format!("[{}]({})", self.version, link), 1)
             }
+            None => title,
+        };
+        format!("## {}\n\n{}\n", title, self.notes)
+    }
+}
 
-            // Insert the new section
-            lines.insert(insert_idx, section_marker);
-            lines.insert(insert_idx + 1, String::new());
-            lines.insert(insert_idx + 2, format!("- {}", description));
-            lines.insert(insert_idx + 3, String::new());
-        }
-
-        let notes = lines.join("\n");
-        release.notes = Box::leak(notes.into_boxed_str());
+/// What lint plugins get on stdin: the changelog as written and as releases
+#[derive(Serialize)]
+struct PluginInput<'a> {
+    path: &'a str,
+    content: &'a str,
+    releases: Vec<ReleaseEntry>,
+}
 
-        // Get old content for diff
-        let old_content = fs::read_to_string(&self.path)?;
+/// A Deprecated or Removed entry and the version it shipped in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    pub version: String,
+    pub date: Option<String>,
+    pub kind: String,
+    pub entry: String,
+}
 
-        // Generate new content
-        let new_content = changelog_to_markdown(&changelog, &old_content, None);
+/// An entry `search` found, with where it is in the changelog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchMatch {
+    pub version: String,
+    pub date: Option<String>,
+    pub section: String,
+    pub entry: String,
+}
 
-        // Write new content
-        fs::write(&self.path, &new_content)?;
+/// The git revision range covered by a version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionRange {
+    pub start: Option<String>,
+    pub end: String,
+    pub range: String,
+}
 
-        if show_diff {
-            self.show_diff(version, &old_content, &new_content)?;
-        }
+/// A user-facing commit with no changelog entry that mentions it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UncoveredCommit {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+}
 
-        Ok(())
-    }
+/// How many feat/fix commits in a version's range made it into its notes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    pub range: String,
+    pub commits: usize,
+    pub uncovered: Vec<UncoveredCommit>,
+}
 
-    pub fn fmt(&self) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                io::ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
-        }
+/// A commit that calls for a release, and how big a one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BumpReason {
+    pub short_id: String,
+    pub summary: String,
+    pub bump: Bump,
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let parsed = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// Every entry released between two versions, grouped by section, e.g. for
+/// writing an upgrade guide
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionDiff {
+    pub from: String,
+    pub to: String,
+    /// Versions whose entries are included, newest first
+    pub versions: Vec<String>,
+    /// Sections in release order, each with its entries newest first
+    pub sections: Vec<Section>,
+}
 
-        fs::write(&self.path, changelog_to_markdown(&parsed, &content, None))?;
-        println!("Formatted CHANGELOG.md");
-        Ok(())
+impl VersionDiff {
+    /// The sections as they would appear in a release's notes
+    pub fn markdown(&self) -> String {
+        self.sections
+            .iter()
+            .map(|section| {
+                let entries: Vec<String> = section
+                    .entries
+                    .iter()
+                    .map(|entry| format!("- {}", entry.replace('\n', "\n  ")))
+                    .collect();
+                format!("### {}\n\n{}\n", section.name, entries.join("\n"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
+}
 
-    fn get_next_version(&self, latest_version: &str, change_type: &str) -> io::Result<String> {
-        let version = semver::Version::parse(latest_version)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-
-        let new_version = match change_type.to_lowercase().as_str() {
-            "major" => semver::Version::new(version.major + 1, 0, 0),
-            "minor" => semver::Version::new(version.major, version.minor + 1, 0),
-            "patch" => semver::Version::new(version.major, version.minor, version.patch + 1),
-            _ => {
-                return Err(io::Error::new(
-                    ErrorKind::InvalidInput,
-                    "Change type must be one of: major, minor, patch",
-                ))
-            }
-        };
+/// A version's notes along with what git knows about it, for release reviews
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    pub entry: ReleaseEntry,
+    pub range: String,
+    pub commits: usize,
+    /// Commit authors, most commits first
+    pub contributors: Vec<Contributor>,
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
 
-        Ok(new_version.to_string())
-    }
+/// Someone who authored commits in a version's range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contributor {
+    pub name: String,
+    pub commits: usize,
+}
 
-    pub fn release(&self, version_or_type: &str, date: Option<&str>) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
+impl Explanation {
+    /// The notes followed by a summary of the range, for pasting into a
+    /// review ticket
+    pub fn report(&self) -> String {
+        let contributors: Vec<String> = self
+            .contributors
+            .iter()
+            .map(|c| format!("{} ({})", c.name, c.commits))
+            .collect();
+        let mut report = self.entry.markdown();
+        report.push_str(&format!("\nRange:         {}\n", self.range));
+        if let Some(link) = &self.entry.link {
+            report.push_str(&format!("Compare:       {}\n", link));
         }
+        report.push_str(&format!("Commits:       {}\n", self.commits));
+        report.push_str(&format!(
+            "Files changed: {} (+{} -{})\n",
+            self.files_changed, self.insertions, self.deletions
+        ));
+        report.push_str(&format!("Contributors:  {}\n", contributors.join(", ")));
+        report
+    }
+}
 
-        // Determine the version to release
-        let version_str = if ["major", "minor", "patch"]
-            .contains(&version_or_type.to_lowercase().as_str())
-        {
-            // Get the latest version and increment it
-            let content = fs::read_to_string(&self.path)?;
-            let parser = Parser::new();
-            let changelog = parser
-                .parse(&content)
-                .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-
-            let latest_version = changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .next()
-                .and_then(|v| v.split_whitespace().next())
-                .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "No previous version found"))?;
+/// A version's notes next to the commits in its range, for spotting
+/// anything that was left out before releasing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntryComparison {
+    pub entry: ReleaseEntry,
+    pub range: String,
+    pub commits: Vec<ComparedCommit>,
+}
 
-            self.get_next_version(latest_version, version_or_type)?
-        } else {
-            // Validate the provided version is a valid semver
-            semver::Version::parse(version_or_type).map_err(|_| {
-                io::Error::new(
-                    ErrorKind::InvalidInput,
-                    "Version must be a valid semver or one of: major, minor, patch",
-                )
-            })?;
-            version_or_type.to_string()
-        };
+/// A commit in a version's range and whether the version's notes mention it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComparedCommit {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    /// An entry mentions its sha, pull request number, or description
+    pub covered: bool,
+    /// A feat or fix, which `coverage` expects an entry for
+    pub user_facing: bool,
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let mut changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-        let unreleased = match changelog.shift_remove("Unreleased") {
-            Some(r) => r,
-            None => {
-                return Err(io::Error::new(
-                    ErrorKind::NotFound,
-                    "No unreleased section found",
-                ))
+impl EntryComparison {
+    /// The notes and the commits in two columns. Commits are checked off
+    /// when an entry mentions them, and user-facing ones that no entry
+    /// mentions are red.
+    pub fn side_by_side(&self) -> String {
+        let notes = self.entry.markdown();
+        let left: Vec<&str> = notes.lines().collect();
+        let mut right = vec![format!("Commits in {}", self.range), String::new()];
+        right.extend(self.commits.iter().map(|commit| {
+            let line = format!(
+                "[{}] {} {}",
+                if commit.covered { 'x' } else { ' ' },
+                commit.short_id,
+                notes::truncate_width(&commit.summary, SUMMARY_WIDTH)
+            );
+            if !commit.covered && commit.user_facing {
+                line.red().to_string()
+            } else {
+                line
             }
-        };
-        let new_title = if let Some(d) = date {
-            format!("[{}] - {}", version_str, d)
-        } else {
-            let today = Local::now().format("%Y-%m-%d").to_string();
-            format!("[{}] - {}", version_str, today)
-        };
-        let new_release_key: &'static str = Box::leak(new_title.clone().into_boxed_str());
-        let mut released = unreleased;
-        released.title = new_release_key;
-        let default_unreleased = {
-            let dummy = r#"# Changelog
-## [Unreleased]
-### Added
+        }));
+
+        let mut output = String::new();
+        for row in 0..left.len().max(right.len()) {
+            let note = notes::truncate_width(left.get(row).copied().unwrap_or(""), NOTES_WIDTH);
+            let padding = " ".repeat(NOTES_WIDTH - note.width());
+            let commit = right.get(row).map_or("", String::as_str);
+            let line = format!("{}{} | {}", note, padding, commit);
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
 
-### Changed
+/// The next version implied by the conventional commits since the latest
+/// release and the breaking changes in Unreleased
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    pub current: String,
+    /// `None` when no commit or entry calls for a release
+    pub bump: Option<Bump>,
+    pub next: Option<String>,
+    pub commits: Vec<BumpReason>,
+    /// Unreleased entries marked as breaking changes, which call for major
+    pub breaking: Vec<String>,
+}
 
-### Deprecated
+/// The section suggested for a change, and what it's based on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeGuess {
+    /// What to pass to `add --type`
+    #[serde(rename = "type")]
+    pub change_type: ChangeType,
+    /// The heading entries of the type go under
+    pub section: String,
+    /// The first line without a conventional commit prefix
+    pub description: String,
+    pub scope: Option<String>,
+    pub reason: GuessReason,
+}
 
-### Removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GuessReason {
+    /// A conventional commit type mapped to a section, like `fix`
+    CommitType,
+    /// The verb the description starts with, like "Remove"
+    Keyword,
+    /// Nothing matched
+    Default,
+}
 
-### Fixed
+/// Everything a release script usually asks the changelog for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Env {
+    pub latest_version: Option<String>,
+    pub next_patch: Option<String>,
+    pub next_minor: Option<String>,
+    pub next_major: Option<String>,
+    pub unreleased_entries: usize,
+    /// Changes since the latest release on the forge
+    pub compare_url: Option<String>,
+    /// `owner/repo` on the forge
+    pub repo: Option<String>,
+}
 
-### Security
-"#;
-            let mut dummy_changelog = Parser::new()
-                .parse(dummy)
-                .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
-            let default_unreleased =
-                dummy_changelog.shift_remove("Unreleased").ok_or_else(|| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to parse default unreleased section",
-                    )
-                })?;
-            default_unreleased
-        };
-        let mut new_changelog = indexmap::IndexMap::new();
-        new_changelog.insert("Unreleased", default_unreleased);
-        let new_release_key: &'static str = Box::leak(new_title.clone().into_boxed_str());
-        new_changelog.insert(new_release_key, released);
-        for (k, v) in changelog.into_iter() {
-            new_changelog.insert(k, v);
-        }
-        fs::write(
-            &self.path,
-            changelog_to_markdown(&new_changelog, &content, None),
-        )?;
-        println!("Released version {}", version_str);
-        Ok(())
-    }
+/// How much has piled up in Unreleased since the last release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Buildup {
+    pub entries: usize,
+    /// Day the oldest committed entry was committed
+    pub oldest: Option<String>,
+    /// Days since then
+    pub age_days: Option<i64>,
+}
 
-    pub fn version_latest(&self) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
+impl Buildup {
+    /// Why it's time to cut a release, if Unreleased has more than
+    /// `max_entries` entries or an entry older than `max_age_days`
+    pub fn overdue(&self, max_age_days: i64, max_entries: usize) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if self.entries > max_entries {
+            reasons.push(format!(
+                "{} entries is more than {}",
+                self.entries, max_entries
+            ));
+        }
+        if let Some(age) = self.age_days.filter(|age| *age > max_age_days) {
+            reasons.push(format!(
+                "the oldest entry is {} days old, more than {}",
+                age, max_age_days
             ));
         }
+        reasons
+    }
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+/// Whether the Unreleased section is keeping up with the commits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnreleasedCheck {
+    pub entries: usize,
+    /// Ref the commits were counted from
+    pub since: Option<String>,
+    pub commits: Option<usize>,
+    /// Label that `[labels]` maps to `skip`, so no entry is needed
+    pub skipped_by: Option<String>,
+}
 
-        // Find first non-Unreleased version
-        if let Some(version) = changelog.keys().filter(|&k| *k != "Unreleased").next() {
-            // Take first part (the version) before any date
-            let version_only = version.split_whitespace().next().unwrap_or("");
-            println!("{}", version_only);
-            Ok(())
-        } else {
-            Err(io::Error::new(
-                ErrorKind::NotFound,
-                "No released versions found",
-            ))
-        }
+impl UnreleasedCheck {
+    /// There are entries, nothing was committed that would need one, or the
+    /// pull request is labeled as not needing one
+    pub fn passed(&self) -> bool {
+        self.entries > 0 || self.commits == Some(0) || self.skipped_by.is_some()
     }
+}
 
-    pub fn version_show(&self, version: &str) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
-        }
+/// A bullet in a release, numbered from 1 across its sections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub index: usize,
+    pub section: String,
+    pub text: String,
+    /// Issues and pull requests the entry refers to, like `(#12, #34)`
+    pub references: Vec<u64>,
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+/// Which entry `remove_entry` or `edit_entry` acts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntrySelector {
+    /// The entry's `Entry::index`
+    Index(usize),
+    /// The only entry containing this text, ignoring case
+    Match(String),
+}
 
-        // Handle special cases
-        let version_to_show = match version.to_lowercase().as_str() {
-            "latest" => changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .next()
-                .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "No released versions found"))?,
-            "unreleased" => "Unreleased",
-            _ => version,
-        };
+/// A version section before and after an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionChange {
+    pub before: String,
+    pub after: String,
+}
 
-        // Find the requested version
-        if let Some(release) = changelog.get(version_to_show) {
-            println!("## {}", release.title);
-            println!("\n{}", release.notes.trim());
-            Ok(())
-        } else {
-            Err(io::Error::new(
-                ErrorKind::NotFound,
-                format!("Version {} not found", version),
-            ))
+impl SectionChange {
+    /// Colored line diff between the two versions of the section
+    pub fn diff(&self) -> String {
+        let mut output = String::new();
+        for change in TextDiff::from_lines(&self.before, &self.after).iter_all_changes() {
+            let line = match change.tag() {
+                ChangeTag::Delete => format!("-{}", change).red().to_string(),
+                ChangeTag::Insert => format!("+{}", change).green().to_string(),
+                ChangeTag::Equal => format!(" {}", change),
+            };
+            output.push_str(&line);
         }
+        output
     }
+}
 
-    pub fn version_list(&self) -> io::Result<()> {
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
-        }
+/// A release's notes after `replace_release`, and whether Unreleased was
+/// released to make it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replaced {
+    pub release: ReleaseEntry,
+    pub released: bool,
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+/// What a release would do, worked out without writing anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRun {
+    pub version: String,
+    /// The date in the new release's heading
+    pub date: Option<String>,
+    /// The whole changelog before and after the release
+    pub change: SectionChange,
+}
 
-        // Print all non-Unreleased versions
-        for version in changelog.keys().filter(|&k| *k != "Unreleased") {
-            // Take first part (the version) before any date
-            let version_only = version.split_whitespace().next().unwrap_or("");
-            println!("{}", version_only);
-        }
-        Ok(())
-    }
+/// A version file before and after a release bumped it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionBump {
+    pub path: PathBuf,
+    pub before: String,
+    pub after: String,
+}
 
-    pub fn range(&self, version: Option<&str>) -> io::Result<()> {
-        // Validate version format if provided
-        if let Some(v) = version {
-            if v.starts_with('v') {
-                return Err(io::Error::new(
-                    ErrorKind::InvalidInput,
-                    "Version should not start with 'v' prefix. Use semantic version format (e.g. '1.0.0')",
-                ));
+impl VersionBump {
+    /// Colored diff of just the lines that changed
+    pub fn diff(&self) -> String {
+        let mut output = String::new();
+        for change in TextDiff::from_lines(&self.before, &self.after).iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Delete => output.push_str(&format!("-{}", change).red().to_string()),
+                ChangeTag::Insert => output.push_str(&format!("+{}", change).green().to_string()),
+                ChangeTag::Equal => {}
             }
         }
+        output
+    }
+}
 
-        if !self.path.exists() {
-            return Err(io::Error::new(
-                ErrorKind::NotFound,
-                "CHANGELOG.md does not exist. Run 'changelog init' first.",
-            ));
-        }
+/// The entry a backport copied and where it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backport {
+    pub source: String,
+    pub change: SectionChange,
+}
 
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+/// The releases a backfill added from git tags, newest first, with how many
+/// entries each got from its commits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Backfill {
+    pub releases: Vec<(String, usize)>,
+}
 
-        // Get the revision range
-        let end = match version {
-            Some(v) => format!("v{}", v),
-            None => "HEAD".to_string(),
-        };
+/// What a sync from git-cliff changed: how many entries each version got,
+/// newest first, and the releases it created
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliffSync {
+    pub added: Vec<(String, usize)>,
+    pub created: Vec<String>,
+}
 
-        // Find the previous version
-        let start = if let Some(version) = version {
-            // For a specific version, find the version after it in changelog
-            changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .skip_while(|&v| *v != version)
-                .nth(1) // Get the next version after the specified one
-                .map(|v| format!("v{}", v))
-        } else {
-            // For HEAD, use the most recent version from changelog
-            changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .next()
-                .map(|v| format!("v{}", v))
-        };
+/// The versions a squash merged, and their sections before and after
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Squash {
+    /// Newest first
+    pub versions: Vec<String>,
+    pub change: SectionChange,
+}
 
-        match start {
-            Some(start) => println!("{}...{}", start, end),
-            None => println!("{}", end),
-        };
+/// What a review added, plus credit lookups that failed along the way
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Review {
+    pub change: Option<SectionChange>,
+    pub warnings: Vec<String>,
+}
 
-        Ok(())
-    }
+/// A version whose git tag and changelog release disagree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCheck {
+    pub version: String,
+    pub tag: String,
+    #[serde(flatten)]
+    pub status: TagStatus,
+    /// Whether `verify_tags` fixed it in the changelog
+    pub fixed: bool,
+}
 
-    pub fn review(&self, version: Option<&str>) -> io::Result<()> {
-        // Find git repository
-        let repo = Repository::discover(".").map_err(|e| {
-            io::Error::new(
-                ErrorKind::NotFound,
-                format!("Git repository not found: {}", e),
-            )
-        })?;
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TagStatus {
+    /// The tag has no release in the changelog
+    MissingRelease,
+    /// The release has no tag
+    MissingTag,
+    /// The release isn't dated the day it was tagged
+    DateMismatch {
+        changelog: Option<String>,
+        tagged: String,
+    },
+}
 
-        // Get the content to determine the revision range
-        let content = fs::read_to_string(&self.path)?;
-        let parser = Parser::new();
-        let changelog = parser
-            .parse(&content)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+/// How a version's forge release compares to its changelog notes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseSync {
+    pub version: String,
+    pub tag: String,
+    #[serde(flatten)]
+    pub status: SyncStatus,
+}
 
-        // Get the revision range
-        let end = match version {
-            Some(v) => format!("v{}", v),
-            None => "HEAD".to_string(),
-        };
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SyncStatus {
+    InSync,
+    /// The release body no longer matches the changelog
+    Drifted {
+        url: String,
+        release: String,
+        changelog: String,
+    },
+    /// The release body was replaced with the changelog notes
+    Updated {
+        url: String,
+    },
+    /// There is no release for the tag
+    Missing,
+}
 
-        // Find the previous version
-        let start = if let Some(version) = version {
-            // For a specific version, find the version after it in changelog
-            changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .skip_while(|&v| *v != version)
-                .nth(1) // Get the next version after the specified one
-                .map(|v| format!("v{}", v))
-        } else {
-            // For HEAD, use the most recent version from changelog
-            changelog
-                .keys()
-                .filter(|&k| *k != "Unreleased")
-                .next()
-                .map(|v| format!("v{}", v))
-        };
+/// Whether `publish` created a new release on the forge or updated one
+#[derive(Debug, Clone)]
+pub enum Published {
+    Created(forge::ReleaseInfo),
+    Updated(forge::ReleaseInfo),
+}
 
-        // Get commits in the range
-        let mut revwalk = repo
-            .revwalk()
-            .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
+use chrono::{Local, NaiveDate};
+use colored::Colorize;
+use document::{Frame, Releases};
+use git2::Repository;
+use indexmap::IndexMap;
+use parse_changelog::Parser;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthStr;
 
-        // Push the end commit
-        if end == "HEAD" {
-            revwalk
-                .push_head()
-                .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-        } else {
-            let obj = repo
-                .revparse_single(&end)
-                .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-            revwalk
-                .push(obj.id())
-                .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-        }
+pub struct Changelog {
+    path: Box<Path>,
+}
 
-        // Hide the start commit if it exists
-        if let Some(start) = start {
-            if let Ok(obj) = repo.revparse_single(&start) {
-                revwalk
-                    .hide(obj.id())
-                    .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-            }
-        }
+thread_local! {
+    /// Lock files this thread holds, so a locked method can call another
+    static LOCKED: std::cell::RefCell<BTreeSet<PathBuf>> = const { std::cell::RefCell::new(BTreeSet::new()) };
+}
 
-        // Collect commits for selection
-        let mut commit_list = Vec::new();
-        for oid in revwalk {
-            let oid = oid.map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-            let commit = repo
-                .find_commit(oid)
-                .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-
-            let short_id = commit.id().to_string()[..7].to_string();
-            let message = commit
-                .message()
-                .unwrap_or("")
-                .lines()
-                .next()
-                .unwrap_or("")
-                .trim();
-            commit_list.push((short_id, message.to_string()));
-        }
+/// An advisory lock on a changelog, held until dropped
+struct Lock {
+    path: PathBuf,
+    file: Option<fs::File>,
+}
 
-        // Parse conventional commits and pre-select feat/fix
-        let mut defaults = vec![false; commit_list.len()];
-        for (idx, (_id, msg)) in commit_list.iter().enumerate() {
-            if let Ok(conv_commit) = git_conventional::Commit::parse(msg) {
-                if conv_commit.type_().to_string() == "feat"
-                    || conv_commit.type_().to_string() == "fix"
-                {
-                    defaults[idx] = true;
-                }
-            }
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            LOCKED.with(|locked| locked.borrow_mut().remove(&self.path));
         }
+    }
+}
 
-        // Let user select commits
-        let selections = dialoguer::MultiSelect::new()
-            .with_prompt("Select commits to include in changelog (press 'a' to select all)")
-            .items(
-                &commit_list
-                    .iter()
-                    .map(|(id, msg)| format!("{} {}", id, msg))
-                    .collect::<Vec<_>>(),
-            )
-            .report(false)
-            .defaults(&defaults)
-            .interact()
-            .map_err(|e| io::Error::new(ErrorKind::Other, e))?;
-
-        if selections.is_empty() {
-            return Ok(());
-        }
+/// The lock file name for the changelog at the canonical `path`. It's an
+/// FNV-1a hash of the path, which unlike `DefaultHasher` comes out the same
+/// in every build, so different versions of the tool lock the same file.
+fn lock_name(path: &Path) -> String {
+    let hash = path
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        });
+    format!("changelog-{:016x}.lock", hash)
+}
 
-        // Build commit list for editor using only selected commits
-        let mut commits = String::new();
-        for &idx in selections.iter() {
-            let (short_id, message) = &commit_list[idx];
-            // Parse commit message to determine type
-            let (type_code, display_message) =
-                if let Ok(conv_commit) = git_conventional::Commit::parse(message) {
-                    let type_str = match conv_commit.type_().to_string().as_str() {
-                        "feat" => "added",
-                        "fix" => "fixed",
-                        _ => "changed",
-                    };
-                    // Remove the type prefix from conventional commits
-                    let msg = conv_commit.description().to_string();
-                    (type_str, msg)
-                } else {
-                    ("changed", message.to_string()) // default to changed for non-conventional commits
-                };
-            commits.push_str(&format!("{} {} {}\n", type_code, short_id, display_message));
-        }
+#[cfg(test)]
+thread_local! {
+    static TEST_FORGE: std::cell::RefCell<Option<Remote>> = const { std::cell::RefCell::new(None) };
+    static TEST_TAGS: std::cell::RefCell<Option<BTreeSet<String>>> = const { std::cell::RefCell::new(None) };
+}
 
-        // Create temporary directory and file with git-rebase-todo name for proper editor highlighting
-        let temp_dir = tempfile::Builder::new().prefix("rebase-merge").tempdir()?;
-        let temp_path = temp_dir.path().join("git-rebase-todo");
-        let mut temp = std::fs::File::create(&temp_path)?;
-        let template = EDITOR_TEMPLATE.replace("{commits}", &commits);
-        temp.write_all(template.as_bytes())?;
-        temp.flush()?;
+/// Have tests see `tags` as the repository's tags, or leave them unknown
+#[cfg(test)]
+fn set_test_tags(tags: Option<&[&str]>) {
+    TEST_TAGS.with(|cell| {
+        *cell.borrow_mut() = tags.map(|tags| tags.iter().map(|tag| tag.to_string()).collect());
+    });
+}
 
-        // Open editor
-        let editor = Self::get_editor()?;
-        let status = Command::new(editor).arg(&temp_path).status()?;
+#[cfg(test)]
+pub fn set_test_github_repo(owner: Option<String>, repo: Option<String>) {
+    TEST_FORGE.with(|cell| {
+        *cell.borrow_mut() = owner.zip(repo).map(|(owner, repo)| Remote {
+            kind: ForgeKind::GitHub,
+            host: "github.com".to_string(),
+            owner,
+            repo,
+        });
+    });
+}
 
-        if !status.success() {
-            return Err(io::Error::new(ErrorKind::Other, "Editor returned error"));
-        }
+/// The repository given with `--repo`, used instead of the git remotes
+static REPO: OnceLock<String> = OnceLock::new();
+
+/// Link to and publish on `repo` instead of the git remotes for the rest
+/// of the process. It's `owner/name` on the public instance of the
+/// configured forge, GitHub by default, or a repository URL.
+pub fn set_repo(repo: &str) -> Result<()> {
+    if Remote::from_spec(repo, None).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "'{}' is not a repository like owner/name or a repository URL",
+            repo
+        )));
+    }
+    REPO.set(repo.to_string())
+        .map_err(|_| Error::InvalidInput("The repository is already set".to_string()))
+}
 
-        // Read edited content
-        let content = fs::read_to_string(&temp_path)?;
+/// The forge hosting the repository's remote, if it's one we know how to link to
+fn infer_forge() -> Option<Box<dyn Forge>> {
+    infer_forge_in(Path::new("."))
+}
 
-        // Get old content before processing
-        let old_content = fs::read_to_string(&self.path)?;
+/// The forge hosting the remote of the repository around `dir`, as
+/// [`repo_remote`] finds it.
+/// Opening the repository and reading the config adds up over the links of
+/// a long changelog and commands that call the forge repeatedly, so each
+/// directory's remote is only looked up once per process.
+#[cfg(not(test))]
+fn infer_forge_in(dir: &Path) -> Option<Box<dyn Forge>> {
+    type Remotes = BTreeMap<PathBuf, Option<(Remote, config::HttpConfig)>>;
+    static REMOTES: std::sync::Mutex<Remotes> = std::sync::Mutex::new(BTreeMap::new());
+
+    let mut remotes = REMOTES.lock().unwrap_or_else(|e| e.into_inner());
+    let (remote, http) = remotes
+        .entry(dir.to_path_buf())
+        .or_insert_with(|| repo_remote(dir))
+        .clone()?;
+    Some(forge::for_remote(remote, &http))
+}
 
-        // Process each line
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
+/// The repository from `--repo` or `[forge] repo`, otherwise the configured
+/// `[forge] remote` or the first of the git remotes on a known forge
+#[cfg(not(test))]
+fn repo_remote(dir: &Path) -> Option<(Remote, config::HttpConfig)> {
+    // Self-hosted instances can name their forge type in the config
+    let config = config::Config::load(dir).unwrap_or_default();
+    let kind = config.forge.kind;
+    let remote = match REPO.get().or(config.forge.repo.as_ref()) {
+        Some(repo) => Remote::from_spec(repo, kind)?,
+        None => {
+            let repo = Repository::discover(dir).ok()?;
+            match &config.forge.remote {
+                Some(name) => Remote::from_url(repo.find_remote(name).ok()?.url()?, kind)?,
+                None => {
+                    let names = repo.remotes().ok()?;
+                    let remotes: Vec<(String, String)> = names
+                        .iter()
+                        .flatten()
+                        .filter_map(|name| {
+                            let url = repo.find_remote(name).ok()?.url()?.to_string();
+                            Some((name.to_string(), url))
+                        })
+                        .collect();
+                    Remote::from_remotes(&remotes, kind)?
+                }
             }
+        }
+    };
+    Some((remote, config.http))
+}
 
-            let parts: Vec<&str> = line.splitn(3, ' ').collect();
-            if parts.len() != 3 {
-                continue;
-            }
+/// In tests, only the mock forge is ever used
+#[cfg(test)]
+fn infer_forge_in(_dir: &Path) -> Option<Box<dyn Forge>> {
+    let http = config::HttpConfig::default();
+    TEST_FORGE.with(|cell| {
+        let remote = cell.borrow().clone()?;
+        Some(forge::for_remote(remote, &http))
+    })
+}
 
-            let type_str = parts[0];
-            let description = parts[2];
-
-            // Normalize single-char types
-            let type_ = match type_str {
-                "a" => "added",
-                "c" => "changed",
-                "d" => "deprecated",
-                "r" => "removed",
-                "f" => "fixed",
-                "s" => "security",
-                _ => type_str,
-            };
+/// What version links can point at: the repository's tags, and the branch
+/// whose history stands in for a compare link before there's a tag to start
+/// from. Outside a repository the tags aren't known, and links are written
+/// as if every release was tagged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RepoRefs {
+    tags: Option<BTreeSet<String>>,
+    branch: String,
+}
 
-            // Add the entry without showing individual diffs
-            self.add(
-                description,
-                &match type_ {
-                    "added" | "a" => ChangeType::Added,
-                    "changed" | "c" => ChangeType::Changed,
-                    "deprecated" | "d" => ChangeType::Deprecated,
-                    "removed" | "r" => ChangeType::Removed,
-                    "fixed" | "f" => ChangeType::Fixed,
-                    "security" | "s" => ChangeType::Security,
-                    _ => ChangeType::Changed,
-                },
-                version,
-                false,
-            )?;
+impl Default for RepoRefs {
+    fn default() -> Self {
+        RepoRefs {
+            tags: None,
+            branch: "main".to_string(),
         }
+    }
+}
 
-        // Show the overall diff
-        let new_content = fs::read_to_string(&self.path)?;
-        self.show_diff(version, &old_content, &new_content)?;
+impl RepoRefs {
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.as_ref().is_none_or(|tags| tags.contains(tag))
+    }
 
-        Ok(())
+    /// These refs with `tag` as well, for a release that's tagged once it's
+    /// written
+    fn with_tag(mut self, tag: String) -> Self {
+        if let Some(tags) = &mut self.tags {
+            tags.insert(tag);
+        }
+        self
     }
 }
 
-fn remove_markdown_links(content: &str, versions: &[String]) -> String {
-    content
-        .lines()
-        .filter(|line| {
-            let line = line.trim_start();
-            if !line.starts_with('[') || !line.contains("]: ") {
-                return true;
-            }
-            // Extract the link text between [ and ]
-            if let Some(link_text) = line.split(']').next() {
-                let link_text = &link_text[1..]; // Remove the leading [
-                                                 // Only remove if it matches a version
-                !versions.iter().any(|v| v == link_text)
-            } else {
-                true
+/// The tags and default branch of the repository around `dir`, looked up
+/// once per process like [`infer_forge_in`]
+#[cfg(not(test))]
+fn repo_refs(dir: &Path) -> RepoRefs {
+    static REFS: std::sync::Mutex<BTreeMap<PathBuf, RepoRefs>> =
+        std::sync::Mutex::new(BTreeMap::new());
+
+    let mut refs = REFS.lock().unwrap_or_else(|e| e.into_inner());
+    refs.entry(dir.to_path_buf())
+        .or_insert_with(|| {
+            let Ok(repo) = Repository::discover(dir) else {
+                return RepoRefs::default();
+            };
+            let tags = repo
+                .tag_names(None)
+                .ok()
+                .map(|names| names.iter().flatten().map(String::from).collect());
+            // The branch the origin remote's HEAD points at, or the one checked
+            // out, even before its first commit
+            let branch = |reference: &str, prefix: &str| {
+                let head = repo.find_reference(reference).ok()?;
+                Some(head.symbolic_target()?.strip_prefix(prefix)?.to_string())
+            };
+            let branch = branch("refs/remotes/origin/HEAD", "refs/remotes/origin/")
+                .or_else(|| branch("HEAD", "refs/heads/"));
+            RepoRefs {
+                tags,
+                branch: branch.unwrap_or_else(|| RepoRefs::default().branch),
             }
         })
-        .collect::<Vec<_>>()
-        .join("\n")
+        .clone()
 }
 
-fn changelog_to_markdown(
-    changelog: &IndexMap<&str, Release>,
-    original: &str,
-    _git_range_url: Option<&str>,
-) -> String {
-    // Extract header (everything before first h2)
-    let header = extract_header(original).unwrap_or_else(|| "# Changelog\n\n".to_string());
-    let mut output = header.trim_end().to_string();
-    output.push_str("\n\n");
+#[cfg(test)]
+fn repo_refs(_dir: &Path) -> RepoRefs {
+    RepoRefs {
+        tags: TEST_TAGS.with(|cell| cell.borrow().clone()),
+        ..Default::default()
+    }
+}
 
-    let mut version_links = Vec::new();
+/// The forge a changelog's own version link definitions point at
+fn linked_forge(content: &str) -> Option<Box<dyn Forge>> {
+    let remote = content.lines().find_map(|line| {
+        let url = match line.strip_prefix("## [") {
+            // Or inline in a heading
+            Some(heading) => heading.split_once("](")?.1.split_once(')')?.0,
+            None => line.trim_start().strip_prefix('[')?.split_once("]:")?.1,
+        };
+        Remote::from_link(url.trim())
+    })?;
+    Some(forge::for_remote(remote, &config::HttpConfig::default()))
+}
 
-    // Generate version sections
-    for (_version, release) in changelog {
-        if !release.notes.contains("# Changelog") {
-            // Remove any existing markdown links from the notes
-            let cleaned_notes = remove_markdown_links(release.notes, &version_links);
-            let mut lines: Vec<_> = cleaned_notes.lines().collect();
-            if let Some(pos) = lines.iter().position(|line| line.trim().starts_with("## ")) {
-                lines.drain(pos..=pos);
-                while pos < lines.len() && lines[pos].trim().is_empty() {
-                    lines.remove(pos);
-                }
-            }
-            if !output.ends_with("\n\n") {
-                output.push_str("\n");
-            }
-            // Determine if we'll have GitHub links
-            #[cfg(test)]
-            let has_github = TEST_GITHUB_REPO.with(|cell| cell.borrow().is_some());
-            #[cfg(not(test))]
-            let has_github = infer_github_repo().is_some();
+/// `content` formatted the way `changelog fmt` writes it under `config`:
+/// section headings are spelled as configured and entries that fit the
+/// entry template are laid out by it again. Version links are for the
+/// current repository's forge, or the one the changelog's own links point at
+/// outside a repository, as when an editor pipes a buffer through.
+pub fn format(content: &str, config: &config::Config) -> Result<String> {
+    let forge = infer_forge().or_else(|| linked_forge(content));
+    formatted(
+        content,
+        config,
+        forge.as_deref(),
+        &config.links,
+        &repo_refs(Path::new(".")),
+        false,
+    )
+}
 
-            let title = if has_github {
-                // Always keep or add brackets when we have GitHub links
-                let version_part = release.title.split(" - ").next().unwrap_or(&release.title);
-                let version_bracketed = if !version_part.starts_with('[') {
-                    format!("[{}]", version_part)
-                } else {
-                    version_part.to_string()
-                };
+/// `content` formatted like [`format`], with releases reordered newest first
+/// the way `changelog fmt --sort` writes them. See [`sort_releases`].
+pub fn format_sorted(content: &str, config: &config::Config) -> Result<String> {
+    let forge = infer_forge().or_else(|| linked_forge(content));
+    formatted(
+        content,
+        config,
+        forge.as_deref(),
+        &config.links,
+        &repo_refs(Path::new(".")),
+        true,
+    )
+}
 
-                if release.title.contains(" - ") {
-                    format!(
-                        "{} - {}",
-                        version_bracketed,
-                        release.title.split(" - ").nth(1).unwrap()
-                    )
-                } else {
-                    version_bracketed
-                }
-            } else {
-                release.title.replace("[", "").replace("]", "")
-            };
-            output.push_str(&format!("## {}\n\n", title));
-            let mut filtered_sections = Vec::new();
-            let mut current_section_header = "";
-            let mut current_section_lines = Vec::new();
-            for line in lines {
-                if line.trim().starts_with("### ") {
-                    if !current_section_header.is_empty() {
-                        let content_exists = current_section_lines
-                            .iter()
-                            .any(|l: &&str| !l.trim().is_empty() && !l.trim().starts_with('#'));
-                        if content_exists {
-                            filtered_sections.push(current_section_header.to_string());
-                            filtered_sections.extend(
-                                current_section_lines
-                                    .clone()
-                                    .into_iter()
-                                    .map(|s| s.to_string()),
-                            );
-                        }
-                    }
-                    current_section_header = line;
-                    current_section_lines.clear();
-                } else {
-                    current_section_lines.push(line);
-                }
-            }
-            if !current_section_header.is_empty() {
-                let content_exists = current_section_lines
-                    .iter()
-                    .any(|l: &&str| !l.trim().is_empty() && !l.trim().starts_with('#'));
-                if content_exists {
-                    filtered_sections.push(current_section_header.to_string());
-                    filtered_sections
-                        .extend(current_section_lines.into_iter().map(|s| s.to_string()));
-                }
-            }
-            if !filtered_sections.is_empty() {
-                output.push_str(&filtered_sections.join("\n"));
-                output.push_str("\n");
-            }
+/// `content` with `description` added to the `r#type` section of `version`,
+/// or of Unreleased, the way `changelog add` adds it under `config`
+pub fn add_entry(
+    content: &str,
+    description: &str,
+    r#type: &ChangeType,
+    version: Option<&str>,
+    config: &config::Config,
+) -> Result<String> {
+    let r#type = config.sections.section(r#type)?;
+    let version = entry_version(&parse_releases(content)?, version)?;
+    let forge = infer_forge().or_else(|| linked_forge(content));
+    let description = match forge.filter(|_| config.links.autolink) {
+        Some(forge) => autolinked(description, forge.as_ref()),
+        None => description.to_string(),
+    };
+    added_content(
+        content,
+        &description,
+        &r#type,
+        version.as_deref(),
+        &config.sections.all(),
+    )
+}
 
-            // Extract version for link
-            if let Some(version) = release.title.split_whitespace().next() {
-                version_links.push(version.trim_matches(|c| c == '[' || c == ']').to_string());
-            }
+/// `content` with `description` nested under a `- **scope:**` bullet in the
+/// `r#type` section, starting the bullet when the section doesn't have one
+pub fn add_grouped_entry(
+    content: &str,
+    description: &str,
+    scope: &str,
+    r#type: &ChangeType,
+    version: Option<&str>,
+    config: &config::Config,
+) -> Result<String> {
+    let group = format!("**{}:**", scope);
+    let section = config.sections.section(r#type)?;
+    let version = entry_version(&parse_releases(content)?, version)?;
+    let mut changelog = parse_releases(content)?;
+    if let Some(release) = changelog.get_mut(version.as_deref().unwrap_or("Unreleased")) {
+        let forge = infer_forge().or_else(|| linked_forge(content));
+        let description = match forge.filter(|_| config.links.autolink) {
+            Some(forge) => autolinked(description, forge.as_ref()),
+            None => description.to_string(),
+        };
+        if let Some(notes) =
+            notes::add_to_group(&release.notes, &section.heading(), &group, &description)
+        {
+            release.notes = notes;
+            return Ok(changelog_to_markdown(&changelog, content, None));
         }
     }
+    add_entry(
+        content,
+        &format!("{}\n  - {}", group, description),
+        r#type,
+        version.as_deref(),
+        config,
+    )
+}
 
-    // Remove any existing version link definitions from the output.
-    {
-        let mut lines: Vec<&str> = output.lines().collect();
-        while let Some(last) = lines.last() {
-            if last.trim().starts_with('[') {
-                lines.pop();
-            } else {
-                break;
-            }
-        }
-        output = lines.join("\n");
-    }
+/// The entry already in the `r#type` section of `version`, or of Unreleased,
+/// that says the same as `description` once markup, links, case, and
+/// punctuation are set aside, e.g. `Fix crash (#12)` for `fix crash ([#12](url))`
+pub fn duplicate_entry(
+    content: &str,
+    description: &str,
+    r#type: &ChangeType,
+    version: Option<&str>,
+    config: &config::Config,
+) -> Result<Option<String>> {
+    let r#type = config.sections.section(r#type)?;
+    let changelog = parse_releases(content)?;
+    let version = entry_version(&changelog, version)?;
+    let Some(release) = changelog.get(version.as_deref().unwrap_or("Unreleased")) else {
+        return Ok(None);
+    };
+    let wanted = entry_words(description);
+    Ok(typed_entries(&release.notes, Some(&r#type))
+        .into_iter()
+        .map(|(entry, _)| entry.text)
+        .find(|text| !wanted.is_empty() && entry_words(text) == wanted))
+}
 
-    // Add version links if we can infer GitHub repo
-    #[cfg(test)]
-    let should_add_links = TEST_GITHUB_REPO.with(|cell| {
-        // Only add links if test repo is Some
-        cell.borrow().is_some()
-    });
-    #[cfg(not(test))]
-    let should_add_links = infer_github_repo().is_some();
+/// What an entry says, without markup, links, case, or punctuation, for
+/// telling entries that repeat each other apart
+fn entry_words(text: &str) -> String {
+    normalize_words(&render::plain(text))
+}
 
-    if should_add_links && !version_links.is_empty() {
-        if output.ends_with("\n") {
-            output.push_str("\n");
-        } else {
-            output.push_str("\n\n");
+/// `content` in canonical form: formatted like [`format`], but depending only
+/// on `content` and `config`, never on the repository it's run in. Version
+/// links are for the forge the changelog's own links point at.
+///
+/// Canonicalizing is idempotent: `canonicalize(canonicalize(x))` is always
+/// `canonicalize(x)`, whichever forge the links are for and however headings,
+/// dates, and bullets were written, so editors can run it on every save.
+pub fn canonicalize(content: &str, config: &config::Config) -> Result<String> {
+    formatted(
+        content,
+        config,
+        linked_forge(content).as_deref(),
+        &config.links,
+        &RepoRefs::default(),
+        false,
+    )
+}
+
+fn formatted(
+    content: &str,
+    config: &config::Config,
+    forge: Option<&dyn Forge>,
+    links: &config::LinksConfig,
+    refs: &RepoRefs,
+    sort: bool,
+) -> Result<String> {
+    let mut parsed = parse_releases(content)?;
+    if sort {
+        sort_releases(&mut parsed, &config.release);
+    }
+    for release in parsed.values_mut() {
+        release.notes = canonical_headings(&release.notes, &config.sections);
+        release.notes = ordered_sections(&release.notes, &config.sections);
+        release.notes = expanded_shortcuts(&release.notes, &config.links.shortcuts);
+        if let Some(forge) = forge.filter(|_| config.links.autolink) {
+            release.notes = autolinked(&release.notes, forge);
         }
-        for (i, version) in version_links.iter().enumerate() {
-            let url = if let Some((owner, repo)) = infer_github_repo() {
-                let base = format!("https://github.com/{}/{}", owner, repo);
-                if i + 1 >= version_links.len() {
-                    // For first release, link to the release tag
-                    format!("{}/releases/tag/v{}", base, version)
-                } else if version == "Unreleased" {
-                    // For unreleased, compare with latest version
-                    format!("{}/compare/v{}...HEAD", base, version_links[i + 1])
-                } else {
-                    // For other versions, compare with previous version
-                    let prev_ver = format!("v{}", version_links[i + 1]);
-                    format!("{}/compare/{}...v{}", base, prev_ver, version)
-                }
-            } else {
-                continue;
-            };
-            output.push_str(&format!("[{}]: {}\n", version, url));
+        release.notes = normalized_references(&release.notes, forge);
+        if let Some(template) = &config.entries.template {
+            release.notes = normalized_entries(&release.notes, template);
         }
     }
-    if !output.ends_with("\n") {
-        output.push_str("\n");
-    }
-    return output;
-    // // Format the markdown using comrak's format_commonmark formatter
-    // let options = ComrakOptions::default();
-    // let arena = comrak::Arena::new();
-    // let root = comrak::parse_document(&arena, &output, &options);
-    // let mut buf = Vec::new();
-    // comrak::format_commonmark(root, &options, &mut buf).unwrap();
-    // String::from_utf8(buf).unwrap()
+    Ok(render_changelog(
+        &parsed,
+        &Frame::parse(content),
+        forge,
+        links,
+        tag_prefix(&config.release),
+        refs,
+    ))
 }
 
-fn extract_header(original: &str) -> Option<String> {
-    // Find the first h2 (##) and take everything before it
-    if let Some(idx) = original.find("\n## ") {
-        Some(original[..idx].trim_end().to_string())
-    } else {
-        Some(original.trim_end().to_string())
+/// Reorder `changelog` newest first by its version scheme, semver pre-releases below
+/// the release they lead up to. Unreleased and queues stay on top. A version
+/// the scheme can't compare goes above the first release dated before it,
+/// or last when it has no date or nothing is older.
+fn sort_releases(changelog: &mut Releases, config: &config::ReleaseConfig) {
+    let scheme = &config.scheme;
+    let day = |release: &Release| {
+        release
+            .date()
+            .and_then(|date| config.date_format.read(date))
+    };
+    let mut sorted = Vec::new();
+    let mut rest = Vec::new();
+    for (key, release) in std::mem::take(changelog) {
+        if is_unreleased(&key) {
+            changelog.insert(key, release);
+        } else if scheme.is_valid(&key) {
+            sorted.push((key, release));
+        } else {
+            rest.push((key, release));
+        }
+    }
+    sorted.sort_by(|(a, _), (b, _)| scheme.compare(b, a).unwrap_or(std::cmp::Ordering::Equal));
+    for (key, release) in rest {
+        let position = day(&release).and_then(|date| {
+            sorted
+                .iter()
+                .position(|(_, other)| day(other).is_some_and(|other| other < date))
+        });
+        sorted.insert(position.unwrap_or(sorted.len()), (key, release));
     }
+    changelog.extend(sorted);
+}
+
+/// `notes` with each section heading that names a known section, by alias or
+/// in another case, spelled the way `add` writes it
+fn canonical_headings(notes: &str, sections: &config::SectionsConfig) -> String {
+    let lines: Vec<String> = notes
+        .lines()
+        .map(|line| {
+            let section = line
+                .strip_prefix("### ")
+                .and_then(|name| sections.resolve(name));
+            match section {
+                Some(section) => format!("### {}", section.heading()),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    // Keep the trailing newline `lines` drops
+    let mut result = lines.join("\n");
+    if notes.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// `notes` with its sections in the configured order, each section heading
+/// that appears more than once merged into the first. Sections that aren't
+/// known follow the rest in the order they're written, and anything above the
+/// first section stays on top. Notes already in order are left as written.
+fn ordered_sections(notes: &str, sections: &config::SectionsConfig) -> String {
+    let order = sections.all();
+    let mut preamble: Vec<&str> = Vec::new();
+    // Each distinct section's heading, rank, and the lines under each of its
+    // headings
+    let mut blocks: Vec<(&str, usize, Vec<Vec<&str>>)> = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut in_order = true;
+    // Link definitions and sections of the changelog's own at the end stay
+    // there, whichever section they follow
+    let lines: Vec<&str> = notes.lines().collect();
+    let end = lines
+        .iter()
+        .position(|line| line.starts_with("# ") || line.starts_with("## "))
+        .unwrap_or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| !line.trim().is_empty() && document::definition(line).is_none())
+                .map_or(0, |i| i + 1)
+        });
+    for &line in &lines[..end] {
+        let Some(name) = line.strip_prefix("### ") else {
+            match current {
+                Some(index) => blocks[index].2.last_mut().unwrap().push(line),
+                None => preamble.push(line),
+            }
+            continue;
+        };
+        let rank = sections
+            .resolve(name)
+            .and_then(|section| order.iter().position(|s| *s == section))
+            .unwrap_or(order.len());
+        let existing = blocks
+            .iter()
+            .position(|(heading, _, _)| heading[4..].trim().eq_ignore_ascii_case(name.trim()));
+        match existing {
+            Some(index) => {
+                in_order = false;
+                blocks[index].2.push(Vec::new());
+                current = Some(index);
+            }
+            None => {
+                if blocks.last().is_some_and(|(_, last, _)| *last > rank) {
+                    in_order = false;
+                }
+                blocks.push((line, rank, vec![Vec::new()]));
+                current = Some(blocks.len() - 1);
+            }
+        }
+    }
+    if in_order {
+        return notes.to_string();
+    }
+
+    blocks.sort_by_key(|(_, rank, _)| *rank);
+    let mut parts: Vec<String> = Vec::new();
+    let preamble = preamble.join("\n");
+    if !preamble.trim().is_empty() {
+        parts.push(preamble.trim_end().to_string());
+    }
+    for (heading, _, bodies) in blocks {
+        // Merged sections run together as one list
+        let entries: Vec<&str> = bodies
+            .iter()
+            .flat_map(|body| {
+                let start = body.iter().position(|line| !line.trim().is_empty());
+                let end = body.iter().rposition(|line| !line.trim().is_empty());
+                match start.zip(end) {
+                    Some((start, end)) => &body[start..=end],
+                    None => &[],
+                }
+            })
+            .copied()
+            .collect();
+        if entries.is_empty() {
+            parts.push(heading.to_string());
+        } else {
+            parts.push(format!("{}\n\n{}", heading, entries.join("\n")));
+        }
+    }
+    let rest = lines[end..].join("\n");
+    if !rest.trim().is_empty() {
+        parts.push(rest.trim().to_string());
+    }
+    // Keep whatever whitespace ended the notes
+    parts.join("\n\n") + &notes[notes.trim_end().len()..]
+}
+
+/// `notes` with bare `#123` references and commit shas linked to `forge`.
+/// Code spans, links, and URLs are left alone, as are runs of hex digits
+/// without both letters and numbers, which are more likely words or numbers.
+fn autolinked(notes: &str, forge: &dyn Forge) -> String {
+    let reference = Regex::new(
+        r"(?m)`[^`]*`|\[[^\]]*\](?:\([^)]*\))?|<[^>]*>|https?://\S+|(?P<pre>^|\\|[^\w#&/\\])(?:#(?P<issue>\d+)|(?P<sha>[0-9a-f]{7,40}))\b",
+    )
+    .unwrap();
+    reference
+        .replace_all(notes, |caps: &regex::Captures| {
+            let pre = caps.name("pre").map_or("", |m| m.as_str());
+            let issue = caps.name("issue").and_then(|m| m.as_str().parse().ok());
+            if let Some(number) = issue {
+                // A link can't be mistaken for a heading, so it needs no escape
+                let pre = if pre == "\\" { "" } else { pre };
+                return format!("{}{}", pre, reference_with(Some(forge), number));
+            }
+            match caps.name("sha").map(|m| m.as_str()) {
+                Some(sha)
+                    if pre != "\\"
+                        && sha.contains(|c: char| c.is_ascii_digit())
+                        && sha.contains(|c: char| c.is_ascii_lowercase()) =>
+                {
+                    format!("{}{}", pre, commit_link(forge, &sha[..7], sha))
+                }
+                _ => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// `notes` with each list of references ending a line, like `(#12 #34)` or
+/// `(#12 and [#34](url))`, written as `(#12, #34)` without repeats. When
+/// some of a list is linked, the rest is linked to `forge` to match.
+fn normalized_references(notes: &str, forge: Option<&dyn Forge>) -> String {
+    let list = Regex::new(
+        r"(?m)\((?P<refs>(?:\[#\d+\]\([^()\s]*\)|#\d+)(?:(?:[ \t]*[,&][ \t]*|[ \t]+and[ \t]+|[ \t]+)(?:\[#\d+\]\([^()\s]*\)|#\d+))*)\)[ \t]*$",
+    )
+    .unwrap();
+    let reference = Regex::new(r"\[#(\d+)\]\([^()\s]*\)|#(\d+)").unwrap();
+    list.replace_all(notes, |caps: &regex::Captures| {
+        let refs: Vec<(u64, Option<&str>)> = reference
+            .captures_iter(&caps["refs"])
+            .filter_map(|r| match (r.get(1), r.get(2)) {
+                (Some(n), _) => Some((n.as_str().parse().ok()?, Some(r.get(0)?.as_str()))),
+                (None, Some(n)) => Some((n.as_str().parse().ok()?, None)),
+                (None, None) => None,
+            })
+            .collect();
+        let link = forge.filter(|_| refs.iter().any(|(_, linked)| linked.is_some()));
+        let mut seen = Vec::new();
+        let mut written = Vec::new();
+        for (number, linked) in refs {
+            if seen.contains(&number) {
+                continue;
+            }
+            seen.push(number);
+            written.push(match (linked, link) {
+                (Some(linked), _) => linked.to_string(),
+                (None, Some(forge)) => reference_with(Some(forge), number),
+                (None, None) => format!("#{}", number),
+            });
+        }
+        format!("({})", written.join(", "))
+    })
+    .into_owned()
+}
+
+/// `notes` with each configured link shortcut like `{rfc:123}` written out
+/// as a link. Shortcuts that aren't configured are left alone.
+fn expanded_shortcuts(notes: &str, shortcuts: &BTreeMap<String, String>) -> String {
+    if shortcuts.is_empty() {
+        return notes.to_string();
+    }
+    let shortcut = Regex::new(r"\{([\w-]+):([^{}\s]+)\}").unwrap();
+    shortcut
+        .replace_all(notes, |caps: &regex::Captures| {
+            match shortcuts.get(&caps[1]) {
+                Some(url) => format!(
+                    "[{}:{}]({})",
+                    &caps[1],
+                    &caps[2],
+                    url.replace("{id}", &caps[2])
+                ),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// `notes` with each entry that fits `template` laid out by it again
+fn normalized_entries(notes: &str, template: &Template) -> String {
+    let texts = notes::parse_sections(notes)
+        .into_iter()
+        .flat_map(|section| section.entries);
+    let entries: Vec<_> = notes::entry_lines(notes).into_iter().zip(texts).collect();
+
+    let mut lines: Vec<String> = notes.lines().map(String::from).collect();
+    // Work from the end so the line ranges of earlier entries stay put
+    for ((_, range), text) in entries.into_iter().rev() {
+        let Some(fields) = template.parse(&text) else {
+            continue;
+        };
+        let entry = fields.render(Some(template));
+        if entry != text {
+            lines.splice(range, [format!("- {}", entry.replace('\n', "\n  "))]);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Lint `content` and check that `changelog fmt` would leave it unchanged,
+/// without needing it to be the current repository's changelog. Version
+/// links are checked against the forge of the repository around `dir`, or
+/// failing that the one the changelog's own links point at.
+pub fn validate(content: &str, dir: Option<&Path>) -> Vec<lint::Violation> {
+    // Like the forge, a broken config falls back to the defaults here
+    let config = config::Config::load(dir.unwrap_or(Path::new("."))).unwrap_or_default();
+    let mut violations = lint::lint(content, &config);
+    match parse_releases(content) {
+        Ok(releases) => {
+            let forge = dir
+                .and_then(infer_forge_in)
+                .or_else(|| linked_forge(content));
+            let rendered = render_changelog(
+                &releases,
+                &Frame::parse(content),
+                forge.as_deref(),
+                &config.links,
+                tag_prefix(&config.release),
+                &dir.map(repo_refs).unwrap_or_default(),
+            );
+            if rendered != content {
+                violations.push(lint::Violation::new(
+                    "unformatted",
+                    lint::Severity::Warning,
+                    None,
+                    "file differs from `changelog fmt` output",
+                ));
+            }
+        }
+        Err(e) => violations.push(lint::Violation::new(
+            "unparseable",
+            lint::Severity::Error,
+            None,
+            e.to_string(),
+        )),
+    }
+    violations
+}
+
+/// The section for a change described by a commit message or free text.
+/// Only the first line counts: a conventional commit type mapped to a
+/// section decides, then the verb the description starts with, and
+/// anything else is a change to existing functionality.
+pub fn guess_type(text: &str, sections: &config::SectionsConfig) -> TypeGuess {
+    let summary = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let conventional = git_conventional::Commit::parse(summary).ok();
+    let description = conventional
+        .as_ref()
+        .map_or(summary, |c| c.description())
+        .to_string();
+    let scope = conventional
+        .as_ref()
+        .and_then(|c| c.scope())
+        .map(|scope| scope.to_string());
+    let commit_type = conventional
+        .as_ref()
+        .and_then(|c| sections.commit_type(c.type_().as_str()));
+    let (change_type, reason) = match (commit_type, keyword_type(&description)) {
+        (Some(change_type), _) => (change_type, GuessReason::CommitType),
+        (None, Some(change_type)) => (change_type, GuessReason::Keyword),
+        (None, None) => (ChangeType::Changed, GuessReason::Default),
+    };
+    TypeGuess {
+        section: change_type.heading(),
+        change_type,
+        description,
+        scope,
+        reason,
+    }
+}
+
+/// Section for free-form text, from the verb it starts with
+fn keyword_type(text: &str) -> Option<ChangeType> {
+    let lower = text.trim().to_lowercase();
+    let first = lower.split_whitespace().next().unwrap_or_default();
+    if lower.contains("cve-") || first.starts_with("security") {
+        Some(ChangeType::Security)
+    } else if ["add", "added", "adds", "introduce", "introduced", "support"].contains(&first) {
+        Some(ChangeType::Added)
+    } else if ["fix", "fixed", "fixes", "correct", "corrected"].contains(&first) {
+        Some(ChangeType::Fixed)
+    } else if ["remove", "removed", "removes", "drop", "dropped", "drops"].contains(&first) {
+        Some(ChangeType::Removed)
+    } else if first.starts_with("deprecate") {
+        Some(ChangeType::Deprecated)
+    } else {
+        None
+    }
+}
+
+/// Heading for a named release train queue, e.g. `Unreleased (1.x)`
+pub fn queue_title(queue: &str) -> String {
+    format!("Unreleased ({})", queue)
+}
+
+/// Where `to` and `from` are in `changelog`, which lists the newest release
+/// first, refusing a `from` released after `to`
+fn version_span(changelog: &Releases, from: &str, to: &str) -> Result<(usize, usize)> {
+    let (from_key, to_key) = (version_key(changelog, from)?, version_key(changelog, to)?);
+    let newest = changelog.get_index_of(to_key).unwrap_or_default();
+    let oldest = changelog.get_index_of(from_key).unwrap_or_default();
+    if newest > oldest {
+        return Err(Error::InvalidInput(format!(
+            "{} was released before {}, swap them around",
+            to, from
+        )));
+    }
+    Ok((newest, oldest))
+}
+
+/// The release `version` names: `latest`, `unreleased`, an exact version
+/// with or without a `v`, or a partial one like `1.2` or `1` for the newest
+/// release in that line, preferring stable releases to pre-releases
+fn version_key<'a>(changelog: &'a Releases, version: &'a str) -> Result<&'a str> {
+    let key = match version.to_lowercase().as_str() {
+        "latest" => changelog
+            .keys()
+            .find(|&k| !is_unreleased(k))
+            .ok_or(Error::NoReleases)?,
+        "unreleased" => "Unreleased",
+        _ => version,
+    };
+    if let Some((key, _)) = changelog.get_key_value(key) {
+        return Ok(key);
+    }
+    let stripped = version
+        .strip_prefix(['v', 'V'])
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(version);
+    if let Some((key, _)) = changelog.get_key_value(stripped) {
+        return Ok(key);
+    }
+    if !stripped.is_empty() && stripped.split('.').all(|part| part.parse::<u64>().is_ok()) {
+        let newest = changelog
+            .keys()
+            .filter(|key| !is_unreleased(key) && version_in_queue(key, stripped))
+            // Keys in changelog order break ties between versions semver can't compare
+            .min_by_key(|key| {
+                std::cmp::Reverse(
+                    semver::Version::parse(key)
+                        .ok()
+                        .map(|v| (v.pre.is_empty(), v)),
+                )
+            });
+        if let Some(key) = newest {
+            return Ok(key);
+        }
+    }
+    Err(Error::VersionNotFound(version.to_string()))
+}
+
+/// True for the default Unreleased section and for named release train queues
+fn is_unreleased(key: &str) -> bool {
+    key == "Unreleased" || is_queue(key)
+}
+
+fn is_queue(key: &str) -> bool {
+    key.starts_with("Unreleased (") && key.ends_with(')')
+}
+
+/// Whether `version` belongs to the release line a queue like `1.x` or `2.0` tracks
+fn version_in_queue(version: &str, queue: &str) -> bool {
+    let prefix = queue.trim_end_matches(".x");
+    version == prefix || version.starts_with(&format!("{}.", prefix))
+}
+
+/// Parse a changelog into releases keyed by version.
+///
+/// parse_changelog keys releases by the first word of their heading, which
+/// would make every `Unreleased (...)` queue collide, so those are keyed by
+/// their full title instead.
+fn parse_releases(content: &str) -> Result<Releases> {
+    let mut changelog = IndexMap::new();
+    for release in parser().parse_iter(content) {
+        let key = release_key(release.version, release.title).to_string();
+        if changelog.contains_key(&key) {
+            return Err(Error::Parse(format!(
+                "multiple release notes for '{}'",
+                key
+            )));
+        }
+        changelog.insert(key, Release::from(release));
+    }
+    if changelog.is_empty() {
+        return Err(Error::Parse("no release note was found".to_string()));
+    }
+    Ok(changelog)
+}
+
+/// Release heading parser. Versions are dotted numbers with an optional
+/// pre-release and build, so calendar versions with leading zeros like
+/// `2025.05.0` count as releases as well as semver ones; whether they follow
+/// the project's scheme is for `lint` to say.
+fn parser() -> Parser {
+    let mut parser = Parser::new();
+    parser
+        .version_format(VERSION_FORMAT)
+        .expect("version format is a valid regex");
+    parser
+}
+
+/// What [`parser`] takes for a version in a release heading
+const VERSION_FORMAT: &str = r"^\d+(\.\d+)*(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$|^Unreleased$";
+
+fn release_key<'a>(version: &'a str, title: &'a str) -> &'a str {
+    if version != "Unreleased" {
+        return version;
+    }
+    let title = title.strip_prefix('[').unwrap_or(title);
+    let title = title.split(']').next().unwrap_or(title).trim();
+    if is_unreleased(title) {
+        title
+    } else {
+        version
+    }
+}
+
+/// Changelogs `init --template` can start from by name, the default first
+pub const INIT_TEMPLATES: [(&str, &str); 3] = [
+    ("minimal", "# Changelog\n\n## [Unreleased]\n"),
+    (
+        "keepachangelog",
+        r#"# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+
+## [Unreleased]
+"#,
+    ),
+    (
+        "detailed",
+        r#"# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+
+<!--
+How to write an entry:
+
+- Add it to Unreleased in the same pull request as the change, with
+  `changelog add "description" --type fixed`.
+- Write for the people using the project, not for reviewers: say what
+  changed for them, not how it was implemented.
+- File it under Added, Changed, Deprecated, Removed, Fixed, or Security.
+- Link the pull request or issue, like (#123).
+- Call out anything that needs action on upgrade under Changed.
+-->
+
+## [Unreleased]
+"#,
+    ),
+];
+
+const EDITOR_TEMPLATE: &str = r#"{commits}
+
+# Review commits and add them to the changelog
+# Lines starting with '#' will be ignored
+# Prefix each commit with one of:
+#   {types}
+# You can also edit the commit message - it will be used as the changelog entry
+#
+# Example:
+# added 1234567 Add new feature
+# changed 89abcde Update existing functionality
+"#;
+
+/// A command for an editor setting that can carry arguments, like
+/// `code --wait`, split the way a shell would so quoted paths with spaces
+/// stay whole
+fn editor_command(editor: &str) -> Result<Command> {
+    // An unquoted path to the editor, like on Windows, is taken whole
+    if Path::new(editor).is_file() {
+        return Ok(Command::new(editor));
+    }
+    let words = shell_words::split(editor)
+        .map_err(|e| Error::InvalidInput(format!("Can't run editor '{}': {}", editor, e)))?;
+    let (program, args) = words.split_first().ok_or(Error::NoEditor)?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+/// Columns a commit subject may take up in the review picker
+const SUMMARY_WIDTH: usize = 72;
+
+/// Columns for the notes beside the commits in `entry --compare`
+const NOTES_WIDTH: usize = 48;
+
+const EDIT_ENTRY_TEMPLATE: &str = r#"
+# Edit the changelog entry above
+# Lines starting with '#' will be ignored
+"#;
+
+impl Default for Changelog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Changelog {
+    fn section_change(
+        &self,
+        version: Option<&str>,
+        old_content: &str,
+        new_content: &str,
+    ) -> Result<SectionChange> {
+        // Get the old version content
+        let old_changelog = parse_releases(old_content)?;
+        let new_changelog = parse_releases(new_content)?;
+
+        let version_key = version.unwrap_or("Unreleased");
+
+        let before = old_changelog
+            .get(version_key)
+            .map(|r| format!("## {}\n\n{}", r.title, r.notes.trim()))
+            .unwrap_or_default();
+
+        let after = new_changelog
+            .get(version_key)
+            .map(|r| format!("## {}\n\n{}", r.title, r.notes.trim()))
+            .unwrap_or_default();
+
+        Ok(SectionChange { before, after })
+    }
+
+    /// The editor to open, looked up the way git does: `GIT_EDITOR`, then
+    /// `core.editor`, then `VISUAL` and `EDITOR`. Without any of those it's
+    /// notepad on Windows and the first of vim, vi, and nano elsewhere.
+    fn get_editor() -> Result<String> {
+        if let Ok(editor) = std::env::var("GIT_EDITOR") {
+            return Ok(editor);
+        }
+        let config = match Repository::discover(".") {
+            Ok(repo) => repo.config(),
+            Err(_) => git2::Config::open_default(),
+        };
+        if let Ok(editor) = config.and_then(|config| config.get_string("core.editor")) {
+            return Ok(editor);
+        }
+        for var in ["VISUAL", "EDITOR"] {
+            if let Ok(editor) = std::env::var(var) {
+                return Ok(editor);
+            }
+        }
+        if cfg!(windows) {
+            return Ok("notepad".to_string());
+        }
+        for editor in &["vim", "vi", "nano"] {
+            if Command::new(editor).arg("--version").output().is_ok() {
+                return Ok(editor.to_string());
+            }
+        }
+        Err(Error::NoEditor)
+    }
+
+    /// Let the user edit `text` in a temporary file and return the result
+    fn edit_in_editor(dir_prefix: &str, file_name: &str, text: &str) -> Result<String> {
+        Self::edit_with(&Self::get_editor()?, dir_prefix, file_name, text)
+    }
+
+    fn edit_with(editor: &str, dir_prefix: &str, file_name: &str, text: &str) -> Result<String> {
+        let temp_dir = tempfile::Builder::new().prefix(dir_prefix).tempdir()?;
+        let temp_path = temp_dir.path().join(file_name);
+        let mut temp = std::fs::File::create(&temp_path)?;
+        temp.write_all(text.as_bytes())?;
+        temp.flush()?;
+
+        let status = editor_command(editor)?.arg(&temp_path).status()?;
+
+        if !status.success() {
+            return Err(std::io::Error::other("Editor returned error").into());
+        }
+
+        Ok(fs::read_to_string(&temp_path)?)
+    }
+    pub fn new() -> Self {
+        Changelog {
+            path: Path::new("CHANGELOG.md").into(),
+        }
+    }
+
+    /// A changelog at `path` instead of `CHANGELOG.md` in the current directory
+    pub fn at(path: &Path) -> Self {
+        Changelog { path: path.into() }
+    }
+
+    pub fn init(&self) -> Result<()> {
+        self.init_with(None, false).map(|_| ())
+    }
+
+    /// Start the changelog from `template`, the name of one of
+    /// [`INIT_TEMPLATES`] or a file, keeping its header and Unreleased but
+    /// none of its releases. With `from_tags` the newest version tag becomes
+    /// the first release, dated from the tag, and its version is returned.
+    pub fn init_with(&self, template: Option<&str>, from_tags: bool) -> Result<Option<String>> {
+        let _lock = self.lock()?;
+        if self.path.exists() {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        let template = match template {
+            None => INIT_TEMPLATES[0].1.to_string(),
+            Some(name) => match INIT_TEMPLATES.iter().find(|(n, _)| *n == name) {
+                Some((_, builtin)) => builtin.to_string(),
+                None => fs::read_to_string(name).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => Error::NotFound(format!(
+                        "No template '{}'. Use a file or one of: {}",
+                        name,
+                        INIT_TEMPLATES.map(|(n, _)| n).join(", ")
+                    )),
+                    _ => e.into(),
+                })?,
+            },
+        };
+
+        let mut changelog = parse_releases(&template)?;
+        changelog.retain(|key, _| is_unreleased(key));
+        if !changelog.contains_key("Unreleased") {
+            let empty = parse_releases("# Changelog\n## [Unreleased]")?;
+            changelog.shift_insert(0, "Unreleased".to_string(), empty["Unreleased"].clone());
+        }
+
+        let mut seeded = None;
+        if from_tags {
+            let repo = Repository::discover(self.dir())?;
+            let prefix = self.tag_prefix()?;
+            let version = latest_version_tag(&repo, &prefix)
+                .ok_or_else(|| Error::NotFound(format!("No version tags like {}1.0.0", prefix)))?;
+            let version = version.to_string();
+            let date_format = self.config()?.release.date_format;
+            let title = match tag_date(&repo, &prefix, &version) {
+                Some(date) => format!("[{}] - {}", version, date_format.format(date)),
+                None => format!("[{}]", version),
+            };
+            let release =
+                parse_releases(&format!("## {}\n\n### Added\n\n- Initial release\n", title))?
+                    .shift_remove(&version)
+                    .ok_or_else(|| Error::Parse(format!("Failed to create release {}", version)))?;
+            changelog.insert(version.clone(), release);
+            seeded = Some(version);
+        }
+
+        self.write(changelog_to_markdown(&changelog, &template, None))?;
+        Ok(seeded)
+    }
+
+    /// Add a release for every version tag that isn't in the changelog yet,
+    /// dated from the tag, with entries from the commits since the tag
+    /// before it. Conventional commits go in the section their type maps to
+    /// and other commits are left out, unless `all` files them by the verb
+    /// they start with. Starts the changelog when there isn't one.
+    pub fn backfill(&self, all: bool) -> Result<Backfill> {
+        let _lock = self.lock()?;
+        let repo = Repository::discover(self.dir())?;
+        if !self.path.exists() {
+            self.init()?;
+        }
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+        let config = self.config()?;
+        let prefix = self.tag_prefix()?;
+        let forge = infer_forge().or_else(|| linked_forge(&content));
+        let forge = forge.filter(|_| config.links.autolink);
+
+        let mut backfill = Backfill::default();
+        let mut previous: Option<String> = None;
+        for version in version_tags(&repo, &prefix) {
+            let tag = format!("{}{}", prefix, version);
+            let range = VersionRange {
+                start: previous.replace(tag.clone()),
+                end: tag,
+                range: String::new(),
+            };
+            let version = version.to_string();
+            if changelog.contains_key(&version) {
+                continue;
+            }
+
+            let mut entries: Vec<(ChangeType, String)> = Vec::new();
+            for commit in range_commits(&repo, &range)? {
+                let guess = guess_type(&commit.message, &config.sections);
+                // Conventional types without a section, like chore, stay out
+                let conventional = git_conventional::Commit::parse(&commit.summary).is_ok();
+                let merge = commit.summary.starts_with("Merge ");
+                if guess.reason != GuessReason::CommitType && (!all || conventional || merge) {
+                    continue;
+                }
+                let fields = EntryFields {
+                    description: guess.description,
+                    scope: guess.scope,
+                    links: Vec::new(),
+                    breaking: commit_bump(&commit.message) == Some(Bump::Major),
+                };
+                let entry = fields.render(config.entries.template.as_ref());
+                let entry = match &forge {
+                    Some(forge) => autolinked(&entry, forge.as_ref()),
+                    None => entry,
+                };
+                entries.push((guess.change_type, entry));
+            }
+
+            let mut markdown = match tag_date(&repo, &prefix, &version) {
+                Some(date) => format!(
+                    "## [{}] - {}\n\n",
+                    version,
+                    config.release.date_format.format(date)
+                ),
+                None => format!("## [{}]\n\n", version),
+            };
+            for change_type in config.sections.all() {
+                let section: Vec<_> = entries.iter().filter(|(t, _)| *t == change_type).collect();
+                if section.is_empty() {
+                    continue;
+                }
+                markdown.push_str(&format!("### {}\n\n", change_type.heading()));
+                for (_, entry) in section {
+                    markdown.push_str(&format!("- {}\n", entry));
+                }
+                markdown.push('\n');
+            }
+            let release = parse_releases(&markdown)?
+                .shift_remove(&version)
+                .ok_or_else(|| Error::Parse(format!("Failed to create release {}", version)))?;
+
+            // Releases are ordered by semver, newest first
+            let target = semver::Version::parse(&version).ok();
+            let index = changelog
+                .keys()
+                .position(|k| {
+                    !is_unreleased(k)
+                        && semver::Version::parse(k)
+                            .ok()
+                            .zip(target.as_ref())
+                            .is_some_and(|(v, target)| v < *target)
+                })
+                .unwrap_or(changelog.len());
+            changelog.shift_insert(index, version.clone(), release);
+            backfill.releases.insert(0, (version, entries.len()));
+        }
+
+        if !backfill.releases.is_empty() {
+            self.write(changelog_to_markdown(&changelog, &content, None))?;
+        }
+        Ok(backfill)
+    }
+
+    /// Start the changelog, and `changelog.toml` if there isn't one, from the
+    /// templates in `repo`, an `owner/repo` or URL, so every project in an
+    /// organization starts with the same header and conventions. Released
+    /// versions in the template are left out. Returns the files written.
+    pub fn init_from_repo(&self, repo: &str) -> Result<Vec<PathBuf>> {
+        let remote = Remote::from_spec(repo, None)
+            .ok_or_else(|| Error::InvalidInput(format!("Can't tell which forge hosts {}", repo)))?;
+        let forge = forge::for_remote(remote, &self.config()?.http);
+        self.init_from(forge.as_ref())
+    }
+
+    fn init_from(&self, templates: &dyn Forge) -> Result<Vec<PathBuf>> {
+        let _lock = self.lock()?;
+        if self.path.exists() {
+            return Err(Error::AlreadyInitialized);
+        }
+        let slug = templates.remote().slug();
+        let template = templates.file("CHANGELOG.md")?.ok_or_else(|| {
+            Error::NotFound(format!(
+                "No CHANGELOG.md in the template repository {}",
+                slug
+            ))
+        })?;
+        let config = templates.file(config::FILENAME)?;
+
+        let mut changelog = parse_releases(&template)?;
+        changelog.retain(|key, _| is_unreleased(key));
+        if !changelog.contains_key("Unreleased") {
+            let empty = parse_releases("# Changelog\n## [Unreleased]")?;
+            changelog.shift_insert(0, "Unreleased".to_string(), empty["Unreleased"].clone());
+        }
+
+        // A config of our own is kept, and a broken template one refused
+        // before anything is written
+        let config_path = self.dir().join(config::FILENAME);
+        let config = config.filter(|_| !config_path.exists());
+        if let Some(config) = &config {
+            config::Config::parse(config, &Path::new(&slug).join(config::FILENAME))?;
+        }
+
+        let mut written = vec![self.path.to_path_buf()];
+        self.write(changelog_to_markdown(&changelog, &template, None))?;
+        if let Some(config) = config {
+            fs::write(&config_path, config)?;
+            written.push(config_path);
+        }
+        Ok(written)
+    }
+
+    /// Replace the changelog with one converted from another tool's
+    /// `content`, detecting its format unless given. Releases without a date
+    /// take the date of their `vX.Y.Z` tag. Returns the number of releases.
+    pub fn import(
+        &self,
+        content: &str,
+        format: Option<import::Format>,
+        force: bool,
+    ) -> Result<usize> {
+        let _lock = self.lock()?;
+        if self.path.exists() && !force {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        let format = format.unwrap_or_else(|| import::detect(content));
+        let mut releases = import::parse(content, format)?;
+        let date_format = self.config()?.release.date_format;
+        if let Ok(repo) = Repository::discover(self.dir()) {
+            let prefix = self.tag_prefix()?;
+            for release in releases.iter_mut().filter(|r| r.date.is_none()) {
+                release.date =
+                    tag_date(&repo, &prefix, &release.version).map(|date| date_format.format(date));
+            }
+        }
+        self.write(imported_content(&releases, &date_format)?)?;
+        Ok(releases
+            .iter()
+            .filter(|r| !is_unreleased(&r.version))
+            .count())
+    }
+
+    /// Merge releases from git-cliff's markdown output into the changelog,
+    /// filing each group into a section by the `commit_parsers` in
+    /// `cliff_config`, or in the `cliff.toml` next to the changelog.
+    /// Entries already in a release, by their words or the commit or pull
+    /// request they refer to, are left alone, so edits made since stay.
+    pub fn sync_from_cliff(
+        &self,
+        content: &str,
+        cliff_config: Option<&Path>,
+        dry_run: bool,
+    ) -> Result<CliffSync> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let config = self.config()?;
+        let cliff_config = cliff_config
+            .map(Path::to_path_buf)
+            .or_else(|| Some(self.dir().join("cliff.toml")).filter(|path| path.exists()));
+        let groups = match cliff_config {
+            Some(path) => import::CliffGroups::parse(&fs::read_to_string(path)?, &config.sections)?,
+            None => import::CliffGroups::default(),
+        };
+        let releases = import::parse_cliff(content, &groups)?;
+
+        let old_content = self.read()?;
+        let mut changelog = parse_releases(&old_content)?;
+        let mut sync = CliffSync::default();
+        for release in releases.iter().rev() {
+            let version = if is_unreleased(&release.version) {
+                entry_version(&changelog, None)?
+            } else {
+                if !changelog.contains_key(&release.version) {
+                    let title = match release
+                        .date
+                        .as_deref()
+                        .and_then(|date| config.release.date_format.read(date))
+                    {
+                        Some(date) => format!(
+                            "[{}] - {}",
+                            release.version,
+                            config.release.date_format.format(date)
+                        ),
+                        None => format!("[{}]", release.version),
+                    };
+                    let created = parse_releases(&format!("## {}\n", title))?
+                        .shift_remove(&release.version)
+                        .ok_or_else(|| {
+                            Error::Parse(format!("Failed to create release {}", release.version))
+                        })?;
+
+                    // Releases are ordered by semver, newest first
+                    let target = semver::Version::parse(&release.version).ok();
+                    let index = changelog
+                        .keys()
+                        .position(|k| {
+                            !is_unreleased(k)
+                                && semver::Version::parse(k)
+                                    .ok()
+                                    .zip(target.as_ref())
+                                    .is_some_and(|(v, target)| v < *target)
+                        })
+                        .unwrap_or(changelog.len());
+                    changelog.shift_insert(index, release.version.clone(), created);
+                    sync.created.insert(0, release.version.clone());
+                }
+                Some(release.version.clone())
+            };
+
+            let key = version.clone().unwrap_or_else(|| "Unreleased".to_string());
+            let mut added = 0;
+            for (change_type, text) in &release.entries {
+                let existing = changelog
+                    .get(&key)
+                    .map(|release| typed_entries(&release.notes, None))
+                    .unwrap_or_default();
+                if existing
+                    .iter()
+                    .any(|(entry, _)| same_change(&entry.text, text))
+                {
+                    continue;
+                }
+                insert_entry(
+                    &mut changelog,
+                    &text.replace('\n', "\n  "),
+                    change_type,
+                    version.as_deref(),
+                    &config.sections.all(),
+                )?;
+                added += 1;
+            }
+            if added > 0 {
+                sync.added.insert(0, (key, added));
+            }
+        }
+
+        if !dry_run && (!sync.added.is_empty() || !sync.created.is_empty()) {
+            self.write(changelog_to_markdown(&changelog, &old_content, None))?;
+        }
+        Ok(sync)
+    }
+
+    pub fn add(
+        &self,
+        description: &str,
+        r#type: &ChangeType,
+        version: Option<&str>,
+    ) -> Result<SectionChange> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let lock = self.lock()?;
+
+        let old_content = self.read()?;
+        let version = entry_version(&parse_releases(&old_content)?, version)?;
+        let new_content = add_entry(
+            &old_content,
+            description,
+            r#type,
+            version.as_deref(),
+            &self.config()?,
+        )?;
+        self.write(&new_content)?;
+        // Let the hook run commands that change the changelog
+        drop(lock);
+        let version_key = version.as_deref().unwrap_or("Unreleased");
+        self.hook(Hook::PostAdd, &new_content, version_key)?;
+
+        self.section_change(version.as_deref(), &old_content, &new_content)
+    }
+
+    /// The entry `add` would be repeating, as [`duplicate_entry`] finds it
+    pub fn duplicate_entry(
+        &self,
+        description: &str,
+        r#type: &ChangeType,
+        version: Option<&str>,
+    ) -> Result<Option<String>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        duplicate_entry(&self.read()?, description, r#type, version, &self.config()?)
+    }
+
+    /// Record an entry in a fragment under `.changelog/unreleased/` instead
+    /// of the changelog itself. `name` defaults to the current branch, and
+    /// adding to an existing fragment appends to it.
+    pub fn fragment_add(
+        &self,
+        description: &str,
+        r#type: &ChangeType,
+        name: Option<&str>,
+    ) -> Result<PathBuf> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => self.current_branch().ok_or_else(|| {
+                Error::InvalidInput("Not on a branch; pass --name to name the fragment".to_string())
+            })?,
+        };
+        let file_name = fragments::file_name(&name)
+            .ok_or_else(|| Error::InvalidInput(format!("Invalid fragment name '{}'", name)))?;
+        let r#type = self.config()?.sections.section(r#type)?;
+
+        let dir = self.fragments_dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(file_name);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        fs::write(&path, fragments::add_entry(&content, &r#type, description))?;
+        Ok(path)
+    }
+
+    /// Fragments waiting to be collected into the changelog
+    pub fn fragments(&self) -> Result<Vec<fragments::Fragment>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        fragments::load(&self.fragments_dir())
+    }
+
+    /// Move every pending fragment into the Unreleased section and delete
+    /// the fragment files
+    pub fn collect_fragments(&self) -> Result<Option<SectionChange>> {
+        let _lock = self.lock()?;
+        let fragments = self.fragments()?;
+        if fragments.is_empty() {
+            return Ok(None);
+        }
+
+        let old_content = self.read()?;
+        let new_content = with_fragments(&old_content, &fragments, &self.config()?.sections)?;
+        self.write(&new_content)?;
+        for fragment in &fragments {
+            fs::remove_file(&fragment.path)?;
+        }
+        Ok(Some(self.section_change(
+            None,
+            &old_content,
+            &new_content,
+        )?))
+    }
+
+    fn fragments_dir(&self) -> PathBuf {
+        match self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            Some(dir) => dir.join(fragments::DIR),
+            None => PathBuf::from(fragments::DIR),
+        }
+    }
+
+    fn current_branch(&self) -> Option<String> {
+        let repo = Repository::discover(self.dir()).ok()?;
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        head.shorthand().map(String::from)
+    }
+
+    /// Format the changelog, reordering releases newest first when `sort`
+    /// is set and switching its line endings to `line_ending` when given
+    pub fn fmt(&self, sort: bool, line_ending: Option<LineEnding>) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let content = self.read()?;
+        let config = self.config()?;
+        let formatted = if sort {
+            format_sorted(&content, &config)?
+        } else {
+            format(&content, &config)?
+        };
+        let encoding = self.encoding().with_line_ending(line_ending);
+        self.write_as(&formatted, encoding)?;
+        Ok(())
+    }
+
+    /// A unified diff of what `fmt` would change, or `None` when the
+    /// changelog is already formatted and uses `line_ending` if given
+    pub fn fmt_check(&self, sort: bool, line_ending: Option<LineEnding>) -> Result<Option<String>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let config = self.config()?;
+        let formatted = if sort {
+            format_sorted(&content, &config)?
+        } else {
+            format(&content, &config)?
+        };
+        let name = self.path.display().to_string();
+        if formatted == content {
+            let current = self.encoding().line_ending;
+            return Ok(line_ending
+                .filter(|line_ending| *line_ending != current)
+                .map(|line_ending| {
+                    format!(
+                        "{} has {} line endings rather than {}\n",
+                        name, current, line_ending
+                    )
+                }));
+        }
+        Ok(Some(
+            TextDiff::from_lines(&content, &formatted)
+                .unified_diff()
+                .header(&name, &name)
+                .to_string(),
+        ))
+    }
+
+    /// Merge the `base` and `theirs` versions of this changelog into it, the
+    /// way git's merge drivers update the current branch's file in place
+    pub fn merge(&self, base: &Path, theirs: &Path) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let ours = self.read()?;
+        let base = Encoding::decode(&fs::read_to_string(base)?);
+        let theirs = Encoding::decode(&fs::read_to_string(theirs)?);
+        self.write(merge_changelogs(&ours, &base, &theirs)?)?;
+        Ok(())
+    }
+
+    /// Release Unreleased (or a release train `queue`) as a version or bump.
+    /// A bump with a `pre` label releases a pre-release like `1.3.0-rc.1`,
+    /// and `stable` finalizes the latest pre-release. `released_by` credits
+    /// someone in the release's attribution line, which is otherwise only
+    /// written when `[release.attribution]` is configured.
+    pub fn release(
+        &self,
+        version_or_type: &str,
+        date: Option<&str>,
+        queue: Option<&str>,
+        released_by: Option<&str>,
+        pre: Option<&str>,
+    ) -> Result<String> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        // Pending fragments are collected into Unreleased on the way out
+        let fragments = match queue {
+            Some(_) => Vec::new(),
+            None => self.fragments()?,
+        };
+        let content = with_fragments(&self.read()?, &fragments, &self.config()?.sections)?;
+        let (version_str, new_content) =
+            self.released_content(&content, version_or_type, date, queue, released_by, pre)?;
+        self.hook(Hook::PreRelease, &new_content, &version_str)?;
+        self.write(new_content)?;
+        for fragment in &fragments {
+            fs::remove_file(&fragment.path)?;
+        }
+        Ok(version_str)
+    }
+
+    /// Everything `release` would change in the changelog, without touching
+    /// it or the fragments it would collect
+    pub fn release_dry_run(
+        &self,
+        version_or_type: &str,
+        date: Option<&str>,
+        queue: Option<&str>,
+        released_by: Option<&str>,
+        pre: Option<&str>,
+    ) -> Result<DryRun> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let before = self.read()?;
+        let content = match queue {
+            Some(_) => before.clone(),
+            None => with_fragments(&before, &self.fragments()?, &self.config()?.sections)?,
+        };
+        let (version, after) =
+            self.released_content(&content, version_or_type, date, queue, released_by, pre)?;
+        let date = parse_releases(&after)?
+            .get(&version)
+            .and_then(|release| notes::release_date(&release.title))
+            .map(String::from);
+        Ok(DryRun {
+            version,
+            date,
+            change: SectionChange { before, after },
+        })
+    }
+
+    /// Set `version`'s notes to `notes`, for release tools that run this as a
+    /// hook. Unreleased is released as `version` first when the changelog
+    /// doesn't have it yet, so running it again changes nothing more.
+    pub fn replace_release(
+        &self,
+        version: &str,
+        notes: Option<&str>,
+        dry_run: bool,
+    ) -> Result<Replaced> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+        if notes.is_some_and(|notes| notes.trim().is_empty()) {
+            return Err(Error::InvalidInput("The notes are empty".to_string()));
+        }
+
+        let version = version
+            .strip_prefix(['v', 'V'])
+            .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(version);
+        let before = self.read()?;
+        let released = !parse_releases(&before)?.contains_key(version);
+        let fragments = if released {
+            self.fragments()?
+        } else {
+            Vec::new()
+        };
+        let mut content = with_fragments(&before, &fragments, &self.config()?.sections)?;
+        let mut version = version.to_string();
+        if released {
+            (version, content) =
+                self.released_content(&content, &version, None, None, None, None)?;
+        }
+        let mut changelog = parse_releases(&content)?;
+        if let Some(notes) = notes {
+            if let Some(release) = changelog.get_mut(&version) {
+                release.notes = notes.trim().to_string();
+            }
+            content = changelog_to_markdown(&changelog, &content, None);
+            changelog = parse_releases(&content)?;
+        }
+
+        if !dry_run && content != before {
+            if released {
+                self.hook(Hook::PreRelease, &content, &version)?;
+            }
+            self.write(&content)?;
+            for fragment in &fragments {
+                fs::remove_file(&fragment.path)?;
+            }
+        }
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        Ok(Replaced {
+            release: release_entry(&version, &changelog[&version], &versions),
+            released,
+        })
+    }
+
+    /// Fold Unreleased into the latest release and date it `date`, or today,
+    /// for a fix found just after cutting a release but before tagging it.
+    /// Pending fragments come along like they do for `release`. Returns the
+    /// amended version.
+    pub fn amend_release(&self, date: Option<&str>) -> Result<String> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let fragments = self.fragments()?;
+        let content = with_fragments(&self.read()?, &fragments, &self.config()?.sections)?;
+        let (version, new_content) = self.amended_content(&content, date)?;
+        self.hook(Hook::PreRelease, &new_content, &version)?;
+        self.write(new_content)?;
+        for fragment in &fragments {
+            fs::remove_file(&fragment.path)?;
+        }
+        Ok(version)
+    }
+
+    /// Run the command configured for `hook`, if any, for `version` as the
+    /// changelog has it now, e.g. `Unreleased` after `add`
+    pub fn run_hook(&self, hook: Hook, version: &str) -> Result<()> {
+        self.hook(hook, &self.read()?, version)
+    }
+
+    /// Run the command configured for `hook` with `version`'s notes in
+    /// `content`, which may not be written yet
+    fn hook(&self, hook: Hook, content: &str, version: &str) -> Result<()> {
+        let config = self.config()?;
+        let Some(command) = config.hooks.command(hook) else {
+            return Ok(());
+        };
+        let changelog = parse_releases(content)?;
+        let notes = changelog
+            .get(version)
+            .map_or("", |release| release.notes.as_str());
+        hooks::run(hook, command, self.dir(), &self.path, version, notes)
+    }
+
+    /// Everything `amend_release` would change, without touching the
+    /// changelog or fragments
+    pub fn amend_release_dry_run(&self, date: Option<&str>) -> Result<DryRun> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let before = self.read()?;
+        let content = with_fragments(&before, &self.fragments()?, &self.config()?.sections)?;
+        let (version, after) = self.amended_content(&content, date)?;
+        let date = parse_releases(&after)?
+            .get(&version)
+            .and_then(|release| notes::release_date(&release.title))
+            .map(String::from);
+        Ok(DryRun {
+            version,
+            date,
+            change: SectionChange { before, after },
+        })
+    }
+
+    /// The section `release` would produce, without touching the file
+    pub fn preview_release(
+        &self,
+        version_or_type: &str,
+        date: Option<&str>,
+        queue: Option<&str>,
+        released_by: Option<&str>,
+        pre: Option<&str>,
+    ) -> Result<String> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let mut content = self.read()?;
+        if queue.is_none() {
+            content = with_fragments(&content, &self.fragments()?, &self.config()?.sections)?;
+        }
+        let (version_str, new_content) =
+            self.released_content(&content, version_or_type, date, queue, released_by, pre)?;
+        release_preview(&new_content, &version_str)
+    }
+
+    pub fn version_latest(&self) -> Result<VersionInfo> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+
+        // Find first non-Unreleased version
+        let (version, release) = changelog
+            .iter()
+            .find(|(k, _)| !is_unreleased(k))
+            .ok_or(Error::NoReleases)?;
+        Ok(version_info(version, release))
+    }
+
+    pub fn version_show(&self, version: &str) -> Result<ReleaseEntry> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let version_to_show = version_key(&changelog, version)?;
+        let release = &changelog[version_to_show];
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let attribution = self.config()?.release.attribution.unwrap_or_default();
+        let mut entry = release_entry(version_to_show, release, &versions);
+        entry.released_by = attribution.template.parse(&release.notes);
+        Ok(entry)
+    }
+
+    /// The notes of each version after `from` up to and including `to`,
+    /// newest first, e.g. for showing someone what changed since the version
+    /// they're running. Unreleased is left out when it has no notes.
+    pub fn version_entries(&self, from: &str, to: &str) -> Result<Vec<ReleaseEntry>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let changelog = parse_releases(&self.read()?)?;
+        let (newest, oldest) = version_span(&changelog, from, to)?;
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let attribution = self.config()?.release.attribution.unwrap_or_default();
+        Ok(changelog
+            .iter()
+            .take(oldest)
+            .skip(newest)
+            .filter(|(key, release)| !is_unreleased(key) || !release.notes.trim().is_empty())
+            .map(|(key, release)| {
+                let mut entry = release_entry(key, release, &versions);
+                entry.released_by = attribution.template.parse(&release.notes);
+                entry
+            })
+            .collect())
+    }
+
+    /// Entries from the releases after `from` up to and including `to`,
+    /// merged by section. `include_from` and `exclude_to` move either end.
+    pub fn diff(
+        &self,
+        from: &str,
+        to: &str,
+        include_from: bool,
+        exclude_to: bool,
+    ) -> Result<VersionDiff> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let changelog = parse_releases(&self.read()?)?;
+        let (newest, oldest) = version_span(&changelog, from, to)?;
+        let start = newest + usize::from(exclude_to);
+        let end = oldest + usize::from(include_from);
+
+        let config = self.config()?;
+        let order = config.sections.all();
+        let mut sections: Vec<Section> = Vec::new();
+        let mut versions = Vec::new();
+        for (version, release) in changelog.iter().take(end).skip(start) {
+            versions.push(version.clone());
+            for section in notes::parse_sections(&release.notes) {
+                let name = config
+                    .sections
+                    .resolve(&section.name)
+                    .map(|t| t.heading())
+                    .unwrap_or(section.name);
+                match sections.iter_mut().find(|s| s.name == name) {
+                    Some(existing) => existing.entries.extend(section.entries),
+                    None => sections.push(Section {
+                        name,
+                        entries: section.entries,
+                    }),
+                }
+            }
+        }
+        sections.retain(|section| !section.entries.is_empty());
+        // Sections the config doesn't know keep their place after the rest
+        sections.sort_by_key(|section| {
+            order
+                .iter()
+                .position(|t| t.heading() == section.name)
+                .unwrap_or(order.len())
+        });
+
+        Ok(VersionDiff {
+            from: changelog
+                .get_index(oldest)
+                .map_or("", |(key, _)| key)
+                .to_string(),
+            to: changelog
+                .get_index(newest)
+                .map_or("", |(key, _)| key)
+                .to_string(),
+            versions,
+            sections,
+        })
+    }
+
+    /// Merge the releases from `from` through `to` into one `target` release,
+    /// e.g. a run of release candidates into the final version, listing
+    /// entries they repeat once. The target takes the place and date of the
+    /// newest release merged; it can be one of them or a new version.
+    pub fn squash(&self, from: &str, to: &str, target: &str) -> Result<Squash> {
+        let _lock = self.lock()?;
+        let mut merged = self.diff(from, to, true, false)?;
+        if merged.versions.iter().any(|v| is_unreleased(v)) || is_unreleased(target) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be squashed".to_string(),
+            ));
+        }
+        if merged.versions.len() < 2 {
+            return Err(Error::InvalidInput(format!(
+                "{}..{} is a single version, nothing to squash",
+                from, to
+            )));
+        }
+        if target.is_empty() || target.contains(|c: char| c.is_whitespace() || c == '[' || c == ']')
+        {
+            return Err(Error::InvalidInput(format!(
+                "Invalid version: {:?}",
+                target
+            )));
+        }
+
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+        if changelog.contains_key(target) && !merged.versions.iter().any(|v| v == target) {
+            return Err(Error::InvalidInput(format!(
+                "Version {} already exists, include it in the range to squash into it",
+                target
+            )));
+        }
+
+        for section in &mut merged.sections {
+            let mut seen = std::collections::HashSet::new();
+            section
+                .entries
+                .retain(|entry| seen.insert(entry_words(entry)));
+        }
+        let notes = merged.markdown();
+
+        let section =
+            |release: &Release| format!("## {}\n\n{}", release.title, release.notes.trim());
+        let before: Vec<String> = merged
+            .versions
+            .iter()
+            .map(|version| section(&changelog[version.as_str()]))
+            .collect();
+
+        let newest = &merged.versions[0];
+        let index = changelog.get_index_of(newest.as_str()).unwrap_or_default();
+        let mut release = changelog[newest.as_str()].clone();
+        // Text above the first section, like an attribution line, is kept
+        let preamble: Vec<&str> = release
+            .notes
+            .lines()
+            .take_while(|line| !line.trim_start().starts_with("### "))
+            .collect();
+        let preamble = preamble.join("\n");
+        release.notes = match preamble.trim() {
+            "" => format!("\n{}", notes),
+            preamble => format!("\n{}\n\n{}", preamble, notes),
+        };
+        let (version, rest) = notes::split_title(&release.title);
+        release.title = format!("{}{}", version.replacen(newest.as_str(), target, 1), rest);
+        release.version = target.to_string();
+        for version in &merged.versions {
+            changelog.shift_remove(version.as_str());
+        }
+        let after = section(&release);
+        changelog.shift_insert(index, target.to_string(), release);
+
+        // Links are kept for the forge they point at, even outside a repository
+        let forge = infer_forge().or_else(|| linked_forge(&content));
+        let new_content = render_changelog(
+            &changelog,
+            &Frame::parse(&content),
+            forge.as_deref(),
+            &links_config(Path::new(".")),
+            &tag_prefix_in(Path::new(".")),
+            &repo_refs(Path::new(".")),
+        );
+        self.write(new_content)?;
+        Ok(Squash {
+            versions: merged.versions,
+            change: SectionChange {
+                before: before.join("\n\n"),
+                after,
+            },
+        })
+    }
+
+    /// Released versions, newest first. Yanked releases are left out unless
+    /// `include_yanked` is set.
+    pub fn version_list(&self, include_yanked: bool) -> Result<Vec<VersionInfo>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+
+        Ok(changelog
+            .iter()
+            .filter(|(k, _)| !is_unreleased(k))
+            .map(|(version, release)| version_info(version, release))
+            .filter(|info| include_yanked || !info.yanked)
+            .collect())
+    }
+
+    /// Mark a release as yanked, e.g. `## [1.2.3] - 2025-01-01 [YANKED]`
+    pub fn yank(&self, version: &str) -> Result<()> {
+        self.set_yanked(version, true)
+    }
+
+    /// Remove the yanked marker from a release
+    pub fn unyank(&self, version: &str) -> Result<()> {
+        self.set_yanked(version, false)
+    }
+
+    /// Freeze Unreleased for a release, so new entries are refused or, with
+    /// `redirect`, go to that release train queue until it thaws. Freezing
+    /// again replaces the redirect.
+    pub fn freeze(&self, redirect: Option<&str>) -> Result<()> {
+        self.set_freeze(Some(Freeze {
+            redirect: redirect.map(String::from),
+        }))
+    }
+
+    /// Lift a freeze, which releasing Unreleased also does
+    pub fn thaw(&self) -> Result<()> {
+        self.set_freeze(None)
+    }
+
+    pub fn frozen(&self) -> Result<Option<Freeze>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let changelog = parse_releases(&self.read()?)?;
+        Ok(changelog
+            .get("Unreleased")
+            .and_then(|release| notes::freeze(&release.notes)))
+    }
+
+    fn set_freeze(&self, freeze: Option<Freeze>) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+        let unreleased = changelog
+            .get_mut("Unreleased")
+            .ok_or_else(|| Error::NotFound("No Unreleased section found".to_string()))?;
+        if freeze.is_none() && notes::freeze(&unreleased.notes).is_none() {
+            return Err(Error::InvalidInput("Unreleased is not frozen".to_string()));
+        }
+
+        unreleased.notes = notes::set_freeze(&unreleased.notes, freeze.as_ref());
+        self.write(changelog_to_markdown(&changelog, &content, None))?;
+        Ok(())
+    }
+
+    fn set_yanked(&self, version: &str, yanked: bool) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+
+        let release = changelog
+            .get_mut(version)
+            .ok_or_else(|| Error::VersionNotFound(version.to_string()))?;
+        if is_unreleased(version) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be yanked".to_string(),
+            ));
+        }
+        if notes::is_yanked(&release.title) == yanked {
+            let state = if yanked { "already" } else { "not" };
+            return Err(Error::InvalidInput(format!(
+                "Version {} is {} yanked",
+                version, state
+            )));
+        }
+
+        release.title = notes::set_yanked(&release.title, yanked);
+        self.write(changelog_to_markdown(&changelog, &content, None))?;
+        Ok(())
+    }
+
+    /// Note something about a release after its date, like a re-release or
+    /// hotfix, or remove the note when `annotation` is `None`
+    pub fn annotate(&self, version: &str, annotation: Option<&str>) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+        if is_unreleased(version) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be annotated".to_string(),
+            ));
+        }
+        let annotation = annotation.map(str::trim);
+        if annotation.is_some_and(|a| a.is_empty() || a.contains('\n')) {
+            return Err(Error::InvalidInput(
+                "The annotation must be a single line of text".to_string(),
+            ));
+        }
+
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+        let release = changelog
+            .get_mut(version)
+            .ok_or_else(|| Error::VersionNotFound(version.to_string()))?;
+        release.title = notes::set_annotation(&release.title, annotation);
+        self.write(changelog_to_markdown(&changelog, &content, None))?;
+        Ok(())
+    }
+
+    /// Correct a released version's number, e.g. `1.2` to `1.2.0`. The
+    /// heading and every version link are rewritten, so the neighbouring
+    /// releases compare against the new tag. Tags themselves are left alone.
+    pub fn rename_version(&self, from: &str, to: &str) -> Result<()> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+        if is_unreleased(from) || is_unreleased(to) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be renamed".to_string(),
+            ));
+        }
+        if to.is_empty() || to.contains(|c: char| c.is_whitespace() || c == '[' || c == ']') {
+            return Err(Error::InvalidInput(format!("Invalid version: {:?}", to)));
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        if !changelog.contains_key(from) {
+            return Err(Error::VersionNotFound(from.to_string()));
+        }
+        if from != to && changelog.contains_key(to) {
+            return Err(Error::InvalidInput(format!(
+                "Version {} already exists",
+                to
+            )));
+        }
+
+        let renamed: Releases = changelog
+            .into_iter()
+            .map(|(key, mut release)| {
+                if key != from {
+                    return (key, release);
+                }
+                let (version, rest) = notes::split_title(&release.title);
+                release.title = format!("{}{}", version.replacen(from, to, 1), rest);
+                release.version = to.to_string();
+                (to.to_string(), release)
+            })
+            .collect();
+        // Links are kept for the forge they point at, even outside a repository
+        let forge = infer_forge().or_else(|| linked_forge(&content));
+        self.write(render_changelog(
+            &renamed,
+            &Frame::parse(&content),
+            forge.as_deref(),
+            &links_config(Path::new(".")),
+            &tag_prefix_in(Path::new(".")),
+            &repo_refs(Path::new(".")),
+        ))?;
+        Ok(())
+    }
+
+    pub fn range(&self, version: Option<&str>) -> Result<VersionRange> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        // Resolve `v1.2.0` and `1.2` like `entry` does, leaving versions
+        // that aren't in the changelog to name a tag as they are
+        let version = version
+            .map(|v| version_key(&changelog, v).unwrap_or(v))
+            .filter(|v| !is_unreleased(v));
+
+        // Get the revision range
+        let prefix = self.tag_prefix()?;
+        let end = match version {
+            Some(v) => format!("{}{}", prefix, v),
+            None => "HEAD".to_string(),
+        };
+
+        // Find the previous version
+        let start = if let Some(version) = version {
+            // For a specific version, find the version after it in changelog
+            changelog
+                .keys()
+                .filter(|&k| !is_unreleased(k))
+                .skip_while(|&v| *v != version)
+                .nth(1) // Get the next version after the specified one
+                .map(|v| format!("{}{}", prefix, v))
+        } else {
+            // For HEAD, use the most recent version from changelog
+            changelog
+                .keys()
+                .find(|&k| !is_unreleased(k))
+                .map(|v| format!("{}{}", prefix, v))
+        };
+
+        let range = match &start {
+            Some(start) => format!("{}...{}", start, end),
+            None => end.clone(),
+        };
+
+        Ok(VersionRange { start, end, range })
+    }
+
+    /// Check that the feat/fix commits in a version's range are mentioned
+    /// by its entries, or those in Unreleased when `version` is `None`
+    pub fn coverage(&self, version: Option<&str>) -> Result<Coverage> {
+        let range = self.range(version)?;
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let notes = match version {
+            Some(v) => changelog
+                .get(v)
+                .ok_or_else(|| Error::VersionNotFound(v.to_string()))?
+                .notes
+                .as_str(),
+            None => changelog.get("Unreleased").map_or("", |r| r.notes.as_str()),
+        };
+        let entries: Vec<String> = notes::parse_sections(notes)
+            .into_iter()
+            .flat_map(|section| section.entries)
+            .collect();
+
+        let repo = Repository::discover(".")?;
+        let commits: Vec<RangeCommit> = range_commits(&repo, &range)?
+            .into_iter()
+            .filter(|commit| is_user_facing(&commit.summary))
+            .collect();
+
+        Ok(Coverage {
+            range: range.range,
+            commits: commits.len(),
+            uncovered: uncovered_commits(&commits, &entries),
+        })
+    }
+
+    /// A version's notes, defaulting to Unreleased, next to every commit in
+    /// its range and whether an entry mentions it
+    pub fn compare_entry(&self, version: &str) -> Result<EntryComparison> {
+        let entry = self.version_show(version)?;
+        let released = (!is_unreleased(&entry.version)).then_some(entry.version.as_str());
+        let range = self.range(released)?;
+        let entries: Vec<String> = entry
+            .sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .map(|text| normalize_words(text))
+            .collect();
+
+        let repo = Repository::discover(self.dir())?;
+        let commits = range_commits(&repo, &range)?
+            .into_iter()
+            .map(|commit| ComparedCommit {
+                covered: entries
+                    .iter()
+                    .any(|entry| entry_mentions_commit(entry, &commit)),
+                user_facing: is_user_facing(&commit.summary),
+                id: commit.id,
+                short_id: commit.short_id,
+                summary: commit.summary,
+            })
+            .collect();
+
+        Ok(EntryComparison {
+            entry,
+            range: range.range,
+            commits,
+        })
+    }
+
+    /// A version's notes, defaulting to Unreleased, with the commits,
+    /// contributors, and files changed in its range
+    pub fn explain(&self, version: &str) -> Result<Explanation> {
+        let entry = self.version_show(version)?;
+        let released = (!is_unreleased(&entry.version)).then_some(entry.version.as_str());
+        let range = self.range(released)?;
+
+        let repo = Repository::discover(self.dir())?;
+        let commits = range_commits(&repo, &range)?;
+        let mut contributors: Vec<Contributor> = Vec::new();
+        for commit in &commits {
+            match contributors.iter_mut().find(|c| c.name == commit.author) {
+                Some(contributor) => contributor.commits += 1,
+                None => contributors.push(Contributor {
+                    name: commit.author.clone(),
+                    commits: 1,
+                }),
+            }
+        }
+        // Stable, so ties stay in order of their latest commit
+        contributors.sort_by_key(|c| std::cmp::Reverse(c.commits));
+
+        let end = repo.revparse_single(&range.end)?.peel_to_tree()?;
+        let start = match &range.start {
+            Some(start) => repo
+                .revparse_single(start)
+                .ok()
+                .and_then(|obj| obj.peel_to_tree().ok()),
+            None => None,
+        };
+        let stats = repo
+            .diff_tree_to_tree(start.as_ref(), Some(&end), None)?
+            .stats()?;
+
+        Ok(Explanation {
+            entry,
+            range: range.range,
+            commits: commits.len(),
+            contributors,
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
+    /// Resolve the latest and next versions, the unreleased entry count, and
+    /// the forge repository in one go
+    pub fn env(&self) -> Result<Env> {
+        let latest = match self.version_latest() {
+            Ok(latest) => Some(latest.version),
+            Err(Error::NoReleases) => None,
+            Err(e) => return Err(e),
+        };
+        let release = self.config()?.release;
+        let (scheme, today) = (&release.scheme, today(&release));
+        let next = |bump: Bump| {
+            latest
+                .as_deref()
+                .map(|v| scheme.next(Some(v), bump, today))
+                .transpose()
+        };
+        let forge = infer_forge();
+        let prefix = self.tag_prefix()?;
+
+        Ok(Env {
+            next_patch: next(Bump::Patch)?,
+            next_minor: next(Bump::Minor)?,
+            next_major: next(Bump::Major)?,
+            unreleased_entries: self.check_unreleased(None, &[])?.entries,
+            compare_url: forge
+                .as_ref()
+                .zip(latest.as_deref())
+                .map(|(forge, v)| forge.compare_url(&format!("{}{}", prefix, v), "HEAD")),
+            repo: forge.map(|forge| forge.remote().slug()),
+            latest_version: latest,
+        })
+    }
+
+    /// Work out the next version from the conventional commits since the
+    /// latest release: breaking changes are major, features minor, and
+    /// fixes patch. Entries in Unreleased marked as breaking, as by
+    /// `add --breaking`, call for major too.
+    pub fn suggest(&self) -> Result<Suggestion> {
+        let range = self.range(None)?;
+        let current = range
+            .start
+            .as_deref()
+            .and_then(|tag| tag.strip_prefix('v'))
+            .ok_or_else(|| Error::NotFound("No previous version found".to_string()))?
+            .to_string();
+
+        let repo = Repository::discover(self.dir())?;
+        let commits: Vec<BumpReason> = range_commits(&repo, &range)?
+            .into_iter()
+            .filter_map(|commit| {
+                Some(BumpReason {
+                    bump: commit_bump(&commit.message)?,
+                    short_id: commit.short_id,
+                    summary: commit.summary,
+                })
+            })
+            .collect();
+
+        let mut breaking = Vec::new();
+        if self.exists() {
+            let changelog = parse_releases(&self.read()?)?;
+            if let Some(release) = changelog.get("Unreleased") {
+                breaking = notes::parse_sections(&release.notes)
+                    .into_iter()
+                    .flat_map(|section| section.entries)
+                    .filter(|entry| notes::is_breaking(entry))
+                    .collect();
+            }
+        }
+
+        let bump = commits
+            .iter()
+            .map(|c| c.bump)
+            .chain(breaking.iter().map(|_| Bump::Major))
+            .max();
+        let release = self.config()?.release;
+        let next = bump
+            .map(|bump| release.scheme.next(Some(&current), bump, today(&release)))
+            .transpose()?;
+        Ok(Suggestion {
+            current,
+            bump,
+            next,
+            commits,
+            breaking,
+        })
+    }
+
+    /// How many entries Unreleased has and how long the oldest has waited,
+    /// going by when git blame says its line was committed
+    pub fn buildup(&self) -> Result<Buildup> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        // Outside a repository, or before the changelog is committed, there's
+        // no telling how old anything is
+        let dates = self.line_dates(&content).unwrap_or_default();
+        let mut entries = 0;
+        let mut oldest: Option<NaiveDate> = None;
+        let mut in_unreleased = false;
+        for (i, line) in content.lines().enumerate() {
+            if let Some(title) = line.strip_prefix("## ") {
+                let (version, _) = notes::split_title(title);
+                in_unreleased = notes::bare_version(version).eq_ignore_ascii_case("Unreleased");
+                continue;
+            }
+            let trimmed = line.trim();
+            if in_unreleased && (trimmed.starts_with("- ") || trimmed.starts_with("* ")) {
+                entries += 1;
+                if let Some(date) = dates.get(i).copied().flatten() {
+                    oldest = Some(oldest.map_or(date, |oldest| oldest.min(date)));
+                }
+            }
+        }
+
+        let today = today(&self.config()?.release);
+        Ok(Buildup {
+            entries,
+            oldest: oldest.map(|date| date.format("%Y-%m-%d").to_string()),
+            age_days: oldest.map(|date| (today - date).num_days()),
+        })
+    }
+
+    /// The day each line of `content` was committed, going by git blame
+    /// against the changelog's last commit. Lines that aren't committed yet
+    /// have no date.
+    fn line_dates(&self, content: &str) -> Result<Vec<Option<NaiveDate>>> {
+        let repo = Repository::discover(self.dir())?;
+        let committed = repo.blame_file(&self.repo_path(&repo)?, None)?;
+        let blame = committed.blame_buffer(content.as_bytes())?;
+        Ok((1..=content.lines().count())
+            .map(|line| {
+                // Uncommitted lines have no commit, and no signature either
+                let id = blame.get_line(line)?.final_commit_id();
+                let seconds = repo.find_commit(id).ok()?.time().seconds();
+                chrono::DateTime::from_timestamp(seconds, 0).map(|time| time.date_naive())
+            })
+            .collect())
+    }
+
+    /// Count the entries in Unreleased and, with `since`, the commits made
+    /// on top of that git ref. A pull request with any of `labels` that
+    /// `[labels]` maps to `skip` doesn't need an entry.
+    pub fn check_unreleased(
+        &self,
+        since: Option<&str>,
+        labels: &[String],
+    ) -> Result<UnreleasedCheck> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let config = self.config()?;
+        let skipped_by = labels
+            .iter()
+            .find(|label| !config.entry_required([label.as_str()]))
+            .cloned();
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let entries = changelog.get("Unreleased").map_or(0, |release| {
+            notes::parse_sections(&release.notes)
+                .iter()
+                .map(|section| section.entries.len())
+                .sum()
+        });
+
+        let commits = match since {
+            Some(since) => {
+                let repo = Repository::discover(self.dir())?;
+                // Unlike a changelog version, a ref given by the user has to exist
+                repo.revparse_single(since)?;
+                let range = VersionRange {
+                    start: Some(since.to_string()),
+                    end: "HEAD".to_string(),
+                    range: format!("{}...HEAD", since),
+                };
+                Some(range_commits(&repo, &range)?.len())
+            }
+            None => None,
+        };
+
+        Ok(UnreleasedCheck {
+            entries,
+            since: since.map(String::from),
+            commits,
+            skipped_by,
+        })
+    }
+
+    /// Entries in a version (Unreleased by default), optionally only those
+    /// of one type
+    pub fn entries(
+        &self,
+        version: Option<&str>,
+        r#type: Option<&ChangeType>,
+    ) -> Result<Vec<Entry>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let r#type = self.section_filter(r#type)?;
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let version_key = version.unwrap_or("Unreleased");
+        let release = changelog
+            .get(version_key)
+            .ok_or_else(|| Error::VersionNotFound(version_key.to_string()))?;
+        Ok(typed_entries(&release.notes, r#type.as_ref())
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect())
+    }
+
+    /// Delete one entry from a version
+    pub fn remove_entry(
+        &self,
+        version: Option<&str>,
+        r#type: Option<&ChangeType>,
+        selector: &EntrySelector,
+    ) -> Result<SectionChange> {
+        self.replace_entry(version, r#type, selector, |_| Ok(None))
+    }
+
+    /// Rewrite one entry in a version. Without a new `description`, the
+    /// entry is opened in your editor.
+    pub fn edit_entry(
+        &self,
+        version: Option<&str>,
+        r#type: Option<&ChangeType>,
+        selector: &EntrySelector,
+        description: Option<&str>,
+    ) -> Result<SectionChange> {
+        self.replace_entry(version, r#type, selector, |entry| {
+            let text = match description {
+                Some(description) => description.to_string(),
+                None => {
+                    let template = format!("{}\n{}", entry.text, EDIT_ENTRY_TEMPLATE);
+                    let edited = Self::edit_in_editor("changelog-edit", "ENTRY.md", &template)?;
+                    edited
+                        .lines()
+                        .filter(|line| !line.starts_with('#'))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            };
+            let text = text.trim();
+            if text.is_empty() {
+                return Err(Error::InvalidInput(
+                    "The new entry is empty; use 'changelog remove' to delete it".to_string(),
+                ));
+            }
+            Ok(Some(text.to_string()))
+        })
+    }
+
+    /// Swap the selected entry's bullet for what `replace` returns, or drop
+    /// it when that is `None`
+    fn replace_entry(
+        &self,
+        version: Option<&str>,
+        r#type: Option<&ChangeType>,
+        selector: &EntrySelector,
+        replace: impl FnOnce(&Entry) -> Result<Option<String>>,
+    ) -> Result<SectionChange> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let r#type = self.section_filter(r#type)?;
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+        let version_key = version.unwrap_or("Unreleased");
+        let release = changelog
+            .get_mut(version_key)
+            .ok_or_else(|| Error::VersionNotFound(version_key.to_string()))?;
+
+        let entries = typed_entries(&release.notes, r#type.as_ref());
+        let (entry, lines) = select_entry(entries, selector)?;
+        let replacement = replace(&entry)?;
+
+        let mut notes: Vec<String> = release.notes.lines().map(String::from).collect();
+        let bullet = replacement.map(|text| format!("- {}", text.replace('\n', "\n  ")));
+        notes.splice(lines, bullet);
+        release.notes = notes.join("\n");
+
+        let new_content = changelog_to_markdown(&changelog, &content, None);
+        self.write(&new_content)?;
+        self.section_change(version, &content, &new_content)
+    }
+
+    pub fn backport(
+        &self,
+        pattern: &str,
+        to: &str,
+        from: Option<&str>,
+        date: Option<&str>,
+    ) -> Result<Backport> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let target = semver::Version::parse(to).map_err(|_| {
+            Error::InvalidVersion("Backport target must be a valid semver version".to_string())
+        })?;
+
+        let release = self.config()?.release;
+        let date = release.date_format.format(release_day(date, &release)?);
+        let target_markdown = format!("## [{}] - {}\n", to, date);
+
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+
+        // Look for the entry in Unreleased and every release newer than the target
+        let needle = pattern.to_lowercase();
+        let mut matches = Vec::new();
+        for (version, release) in changelog.iter() {
+            if from.is_some_and(|f| f != *version) {
+                continue;
+            }
+            let newer =
+                is_unreleased(version) || semver::Version::parse(version).is_ok_and(|v| v > target);
+            if !newer {
+                continue;
+            }
+            for section in notes::parse_sections(&release.notes) {
+                for entry in section.entries {
+                    if entry.to_lowercase().contains(&needle) {
+                        matches.push((version.to_string(), section.name.clone(), entry));
+                    }
+                }
+            }
+        }
+
+        let (source, section, entry) = match matches.len() {
+            0 => {
+                return Err(Error::NotFound(format!(
+                    "No entry matching '{}' found",
+                    pattern
+                )))
+            }
+            1 => matches.remove(0),
+            _ => {
+                let found = matches
+                    .iter()
+                    .map(|(version, _, entry)| format!("  {}: {}", version, entry))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                return Err(Error::InvalidInput(format!(
+                    "Multiple entries match '{}', be more specific or use --from:\n{}",
+                    pattern, found
+                )));
+            }
+        };
+
+        let change_type = ChangeType::from_section(&section).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Cannot backport entries from the '{}' section",
+                section
+            ))
+        })?;
+
+        // Create the maintenance release if needed, ordered by semver among the releases
+        if !changelog.contains_key(to) {
+            let release = parse_releases(&target_markdown)?
+                .shift_remove(to)
+                .ok_or_else(|| Error::Parse("Failed to create release section".to_string()))?;
+            let index = changelog
+                .keys()
+                .position(|k| {
+                    !is_unreleased(k) && semver::Version::parse(k).is_ok_and(|v| v < target)
+                })
+                .unwrap_or(changelog.len());
+            changelog.shift_insert(index, to.to_string(), release);
+            self.write(changelog_to_markdown(&changelog, &content, None))?;
+        } else {
+            let existing = notes::parse_sections(&changelog[to].notes);
+            if existing.iter().any(|s| s.entries.contains(&entry)) {
+                return Err(Error::InvalidInput(format!(
+                    "Entry is already present in {}",
+                    to
+                )));
+            }
+        }
+
+        let description = entry.replace('\n', "\n  ");
+        let change = self.add(&description, &change_type, Some(to))?;
+        Ok(Backport { source, change })
+    }
+
+    /// [`validate`] this file, wherever it lives
+    pub fn validate(&self) -> Result<Vec<lint::Violation>> {
+        let content = self.read()?;
+        Ok(validate(&content, Some(self.dir())))
+    }
+
+    /// Lint the changelog, then hand it to each of the `[lint] plugins` for
+    /// checks of their own
+    pub fn lint(&self) -> Result<Vec<lint::Violation>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let config = self.config()?;
+        let mut violations = lint::lint(&content, &config);
+        // Links are only checked against tags when there's a repository to ask
+        if let Ok(repo) = Repository::discover(self.dir()) {
+            let tags = repo.tag_names(None)?;
+            let tags = tags.iter().flatten().map(String::from).collect();
+            violations.extend(lint::missing_tags(&content, &tags, &config));
+        }
+        if config.lint.plugins.is_empty() {
+            return Ok(violations);
+        }
+
+        let changelog = parse_releases(&content)?;
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let model = serde_json::to_string(&PluginInput {
+            path: &self.path.to_string_lossy(),
+            content: &content,
+            releases: changelog
+                .iter()
+                .map(|(key, release)| release_entry(key, release, &versions))
+                .collect(),
+        })?;
+        for plugin in &config.lint.plugins {
+            violations.extend(lint::run_plugin(plugin, self.dir(), &model)?);
+        }
+        Ok(violations)
+    }
+
+    /// What `notify` would post about `version`, the latest release by
+    /// default, and where: to each of `targets`, given as a URL or by name
+    /// from `[notify] webhooks`, or else to every configured webhook. `kind`
+    /// overrides what each webhook is taken to expect.
+    pub fn notifications(
+        &self,
+        version: Option<&str>,
+        targets: &[String],
+        kind: Option<notify::Kind>,
+    ) -> Result<Vec<(String, serde_json::Value)>> {
+        let webhooks = self.config()?.notify.webhooks;
+        let webhooks: Vec<notify::Webhook> = if targets.is_empty() {
+            webhooks.into_values().collect()
+        } else {
+            targets
+                .iter()
+                .map(|target| match webhooks.get(target) {
+                    Some(webhook) => Ok(webhook.clone()),
+                    None if target.contains("://") => Ok(notify::Webhook::Url(target.clone())),
+                    None => Err(Error::InvalidInput(format!(
+                        "Unknown webhook '{}', expected a URL or a name from [notify] webhooks",
+                        target
+                    ))),
+                })
+                .collect::<Result<_>>()?
+        };
+        if webhooks.is_empty() {
+            return Err(Error::InvalidInput(
+                "No webhooks to notify. Pass --webhook or add some under [notify] webhooks in changelog.toml".to_string(),
+            ));
+        }
+
+        let entry = self.version_show(version.unwrap_or("latest"))?;
+        if is_unreleased(&entry.version) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be announced".to_string(),
+            ));
+        }
+        let project = self.package_name()?;
+        Ok(webhooks
+            .iter()
+            .map(|webhook| {
+                let kind = kind.unwrap_or_else(|| webhook.kind());
+                (
+                    webhook.url().to_string(),
+                    notify::payload(kind, &project, &entry),
+                )
+            })
+            .collect())
+    }
+
+    /// Post `version`'s notes to webhooks as [`Changelog::notifications`]
+    /// lays them out, returning the URLs posted to
+    pub fn notify(
+        &self,
+        version: Option<&str>,
+        targets: &[String],
+        kind: Option<notify::Kind>,
+    ) -> Result<Vec<String>> {
+        let http = self.config()?.http;
+        let mut posted = Vec::new();
+        for (url, payload) in self.notifications(version, targets, kind)? {
+            let client = http::Client::webhook(&url, &http);
+            client.send_json(client.request("POST", &url), payload)?;
+            posted.push(url);
+        }
+        Ok(posted)
+    }
+
+    /// Create or update the release for a version on the origin remote's forge
+    pub fn publish(
+        &self,
+        version: Option<&str>,
+        draft: bool,
+        prerelease: bool,
+    ) -> Result<Published> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+
+        let (version, release) = match version {
+            Some(v) => changelog
+                .get_key_value(v)
+                .ok_or_else(|| Error::VersionNotFound(v.to_string()))?,
+            None => changelog
+                .iter()
+                .find(|(k, _)| !is_unreleased(k))
+                .ok_or(Error::NoReleases)?,
+        };
+        if is_unreleased(version) {
+            return Err(Error::InvalidInput(
+                "Unreleased changes cannot be published".to_string(),
+            ));
+        }
+
+        let payload = release_payload(
+            version,
+            &release.notes,
+            draft,
+            prerelease,
+            &self.tag_prefix()?,
+        );
+        let forge = infer_forge().ok_or(Error::UnknownForge)?;
+        match forge.release_by_tag(&payload.tag_name)? {
+            Some(existing) => Ok(Published::Updated(
+                forge.update_release(&existing, &payload)?,
+            )),
+            None => Ok(Published::Created(forge.create_release(&payload)?)),
+        }
+    }
+
+    /// Compare every released version's notes with its forge release body.
+    /// With `push`, drifted release bodies are rewritten from the changelog.
+    pub fn sync_check(&self, push: bool) -> Result<Vec<ReleaseSync>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let forge = infer_forge().ok_or(Error::UnknownForge)?;
+        let releases = forge.releases()?;
+
+        let mut results = release_sync(&changelog, &releases, &self.tag_prefix()?);
+        if push {
+            for result in &mut results {
+                let published = releases.iter().find(|r| r.tag_name == result.tag);
+                if let (SyncStatus::Drifted { changelog, .. }, Some(published)) =
+                    (&result.status, published)
+                {
+                    let updated = forge.update_release_body(published, changelog)?;
+                    result.status = SyncStatus::Updated {
+                        url: updated.html_url,
+                    };
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Compare the version tags in git with the releases in the changelog:
+    /// tags without a release, releases without a tag, and releases dated
+    /// other than the day they were tagged. Versions that agree are left out.
+    /// With `fix`, releases are backfilled for missing tags and dates are set
+    /// from the tags; untagged releases are only reported.
+    pub fn verify_tags(&self, fix: bool) -> Result<Vec<TagCheck>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let _lock = self.lock()?;
+
+        let repo = Repository::discover(self.dir())?;
+        let prefix = self.tag_prefix()?;
+        let release = self.config()?.release;
+        let content = self.read()?;
+        let mut changelog = parse_releases(&content)?;
+
+        // Tags without a release, newest first
+        let mut missing: Vec<(Option<NaiveDate>, String)> = repo
+            .tag_names(None)?
+            .iter()
+            .flatten()
+            .filter_map(|tag| tag.strip_prefix(prefix.as_str()))
+            .filter(|version| release.scheme.is_valid(version) && !changelog.contains_key(*version))
+            .map(|version| (tag_date(&repo, &prefix, version), version.to_string()))
+            .collect();
+        missing.sort_by(|a, b| b.cmp(a));
+        let mut checks: Vec<TagCheck> = missing
+            .into_iter()
+            .map(|(_, version)| TagCheck {
+                tag: format!("{}{}", prefix, version),
+                version,
+                status: TagStatus::MissingRelease,
+                fixed: false,
+            })
+            .collect();
+
+        let mut redated = false;
+        for (version, entry) in changelog.iter_mut().filter(|(k, _)| !is_unreleased(k)) {
+            let tag = [format!("{}{}", prefix, version), version.clone()]
+                .into_iter()
+                .find(|name| repo.find_reference(&format!("refs/tags/{}", name)).is_ok());
+            let Some(tag) = tag else {
+                checks.push(TagCheck {
+                    version: version.clone(),
+                    tag: format!("{}{}", prefix, version),
+                    status: TagStatus::MissingTag,
+                    fixed: false,
+                });
+                continue;
+            };
+            let Some(tagged) = tag_date(&repo, &prefix, version) else {
+                continue;
+            };
+            let written = notes::release_date(&entry.title).map(String::from);
+            if written
+                .as_deref()
+                .and_then(|date| release.date_format.read(date))
+                == Some(tagged)
+            {
+                continue;
+            }
+            let tagged = release.date_format.format(tagged);
+            if fix {
+                entry.title = dated_title(&entry.title, &tagged);
+                redated = true;
+            }
+            checks.push(TagCheck {
+                version: version.clone(),
+                tag,
+                status: TagStatus::DateMismatch {
+                    changelog: written,
+                    tagged,
+                },
+                fixed: fix,
+            });
+        }
+
+        if redated {
+            self.write(changelog_to_markdown(&changelog, &content, None))?;
+        }
+        if fix && checks.iter().any(|c| c.status == TagStatus::MissingRelease) {
+            let backfill = self.backfill(false)?;
+            for check in &mut checks {
+                check.fixed |= backfill.releases.iter().any(|(v, _)| *v == check.version);
+            }
+        }
+        Ok(checks)
+    }
+
+    /// Lint the changelog and its links, check that every release is tagged
+    /// and every user-facing commit since the latest has an entry, and
+    /// compare the notes with the forge's releases, for one scorecard.
+    /// Checks that can't run here, like comparing releases without a forge,
+    /// are skipped instead of failing the audit.
+    pub fn audit(&self) -> Result<audit::Audit> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let tag_prefix = self.tag_prefix()?;
+
+        let (links, lint): (Vec<_>, Vec<_>) = self
+            .validate()?
+            .into_iter()
+            .partition(|v| matches!(v.rule.as_str(), "undefined-link" | "stale-link"));
+        let mut checks = vec![
+            audit::Check::violations("lint", &lint),
+            audit::Check::violations("links", &links),
+        ];
+
+        checks.push(match Repository::discover(self.dir()) {
+            Ok(repo) => {
+                let untagged = self
+                    .version_list(true)?
+                    .into_iter()
+                    .map(|v| format!("{}{}", tag_prefix, v.version))
+                    .filter(|tag| repo.find_reference(&format!("refs/tags/{}", tag)).is_err())
+                    .map(|tag| format!("no {} tag", tag))
+                    .collect();
+                audit::Check::new("tags", audit::Status::Fail, untagged)
+            }
+            Err(e) => audit::Check::skipped("tags", e),
+        });
+
+        checks.push(match self.coverage(None) {
+            Ok(coverage) => audit::Check::new(
+                "coverage",
+                audit::Status::Fail,
+                coverage
+                    .uncovered
+                    .iter()
+                    .map(|commit| format!("{} {} has no entry", commit.short_id, commit.summary))
+                    .collect(),
+            ),
+            Err(e) => audit::Check::skipped("coverage", e),
+        });
+
+        checks.push(match self.sync_check(false) {
+            // Projects that don't publish forge releases have nothing to drift
+            Ok(results) if results.iter().all(|r| r.status == SyncStatus::Missing) => {
+                audit::Check::skipped("releases", "no releases published on the forge")
+            }
+            Ok(results) => {
+                let drifted = results
+                    .iter()
+                    .any(|r| matches!(r.status, SyncStatus::Drifted { .. }));
+                let problems = results
+                    .iter()
+                    .filter_map(|r| match &r.status {
+                        SyncStatus::Drifted { url, .. } => {
+                            Some(format!("{}: {} differs from the changelog", r.version, url))
+                        }
+                        SyncStatus::Missing => {
+                            Some(format!("{}: no release for {}", r.version, r.tag))
+                        }
+                        SyncStatus::InSync | SyncStatus::Updated { .. } => None,
+                    })
+                    .collect();
+                let status = if drifted {
+                    audit::Status::Fail
+                } else {
+                    audit::Status::Warn
+                };
+                audit::Check::new("releases", status, problems)
+            }
+            Err(e) => audit::Check::skipped("releases", e),
+        });
+
+        Ok(audit::Audit::new(self.path.display().to_string(), checks))
+    }
+
+    pub fn deprecations(&self) -> Result<Vec<Deprecation>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+
+        Ok(deprecation_timeline(&changelog))
+    }
+
+    /// Entries containing `pattern`, ignoring case, or matching it as a
+    /// regular expression with `regex`, newest first. `section` limits the
+    /// search to one type of change and `since` to the versions after it.
+    pub fn search(
+        &self,
+        pattern: &str,
+        regex: bool,
+        section: Option<&ChangeType>,
+        since: Option<&str>,
+    ) -> Result<Vec<SearchMatch>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let section = self.section_filter(section)?;
+        let matcher = if regex {
+            Regex::new(pattern)
+        } else {
+            Regex::new(&format!("(?i){}", regex::escape(pattern)))
+        }
+        .map_err(|e| Error::InvalidInput(format!("Invalid pattern '{}': {}", pattern, e)))?;
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        if let Some(since) = since {
+            if !changelog.contains_key(since) {
+                return Err(Error::VersionNotFound(since.to_string()));
+            }
+        }
+
+        let mut matches = Vec::new();
+        for (version, release) in changelog
+            .iter()
+            .take_while(|(k, _)| Some(k.as_str()) != since)
+        {
+            for found in notes::parse_sections(&release.notes) {
+                if section
+                    .as_ref()
+                    .is_some_and(|t| !t.heading().eq_ignore_ascii_case(&found.name))
+                {
+                    continue;
+                }
+                for entry in found.entries {
+                    if matcher.is_match(&entry) {
+                        matches.push(SearchMatch {
+                            version: version.to_string(),
+                            date: notes::release_date(&release.title).map(String::from),
+                            section: found.name.clone(),
+                            entry,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Link crediting the pull request `pr`, or the one that contains
+    /// `commit`, and its author, e.g. `[#104](url); thanks @author`
+    pub fn credit(&self, pr: Option<u64>, commit: Option<&str>) -> Result<Option<String>> {
+        if pr.is_none() && commit.is_none() {
+            return Ok(None);
+        }
+        let forge = infer_forge().ok_or(Error::UnknownForge)?;
+        credit_with(forge.as_ref(), pr, commit, &[]).map(Some)
+    }
+
+    /// Link to issue or pull request `number`, or a plain `#number` when the
+    /// forge isn't known
+    pub fn reference(&self, number: u64) -> String {
+        reference_with(infer_forge().as_deref(), number)
+    }
+
+    /// An entry laid out by the `[entries] template` in `changelog.toml`
+    pub fn render_entry(&self, fields: &EntryFields) -> Result<String> {
+        Ok(fields.render(self.config()?.entries.template.as_ref()))
+    }
+
+    pub fn export_osv(
+        &self,
+        version: Option<&str>,
+        package: Option<&str>,
+        ecosystem: &str,
+    ) -> Result<Vec<osv::Advisory>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        if let Some(v) = version {
+            if !changelog.contains_key(v) {
+                return Err(Error::VersionNotFound(v.to_string()));
+            }
+        }
+
+        let name = match package {
+            Some(name) => name.to_string(),
+            None => self.package_name()?,
+        };
+        let package = osv::Package {
+            ecosystem: ecosystem.to_string(),
+            name,
+        };
+
+        // Only released versions have a fixed version to report
+        let date_format = self.config()?.release.date_format;
+        let mut advisories = Vec::new();
+        for (key, release) in changelog.iter().filter(|(k, _)| !is_unreleased(k)) {
+            if version.is_some_and(|v| v != *key) {
+                continue;
+            }
+            // OSV timestamps are ISO whatever the changelog uses
+            let date = notes::release_date(&release.title)
+                .and_then(|date| date_format.read(date))
+                .map(|date| date.format("%Y-%m-%d").to_string());
+            for section in notes::parse_sections(&release.notes) {
+                if !matches!(
+                    ChangeType::from_section(&section.name),
+                    Some(ChangeType::Security)
+                ) {
+                    continue;
+                }
+                for (i, entry) in section.entries.iter().enumerate() {
+                    advisories.push(osv::advisory(entry, key, date.as_deref(), &package, i));
+                }
+            }
+        }
+
+        Ok(advisories)
+    }
+
+    /// Released versions as an Atom or RSS feed, leaving out yanked and
+    /// undated releases. Items link to `link#anchor` when the changelog is
+    /// published at `link`, or else to each release on the forge.
+    pub fn feed(&self, title: Option<&str>, link: Option<&str>) -> Result<feed::Feed> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let title = match title {
+            Some(title) => title.to_string(),
+            None => format!("{} releases", self.package_name()?),
+        };
+        let date_format = self.config()?.release.date_format;
+
+        let mut items = Vec::new();
+        for (key, release) in changelog.iter().filter(|(k, _)| !is_unreleased(k)) {
+            let date = notes::release_date(&release.title).and_then(|date| date_format.read(date));
+            let Some(date) = date.filter(|_| !notes::is_yanked(&release.title)) else {
+                continue;
+            };
+            let entry = release_entry(key, release, &versions);
+            let item_link = match link {
+                Some(link) => Some(format!("{}#{}", link, entry.anchor)),
+                None => entry.link.clone(),
+            };
+            items.push(feed::Item {
+                id: item_link
+                    .clone()
+                    .unwrap_or_else(|| feed::urn(&title, Some(key))),
+                title: key.to_string(),
+                link: item_link,
+                date,
+                html: render::html(&entry.notes),
+            });
+        }
+
+        Ok(feed::Feed {
+            link: link
+                .map(String::from)
+                .or_else(|| infer_forge().map(|forge| forge.base_url())),
+            title,
+            items,
+        })
+    }
+
+    /// Everything in the changelog as data, for `export json`
+    pub fn manifest(&self) -> Result<manifest::Manifest> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let attribution = self.config()?.release.attribution.unwrap_or_default();
+        let releases = changelog
+            .iter()
+            .map(|(key, release)| {
+                let mut entry = release_entry(key, release, &versions);
+                entry.released_by = attribution.template.parse(&release.notes);
+                manifest::Release {
+                    yanked: notes::is_yanked(&release.title),
+                    markdown: entry.markdown(),
+                    entry,
+                }
+            })
+            .collect();
+        Ok(manifest::Manifest {
+            header: Frame::parse(&content).header,
+            releases,
+        })
+    }
+
+    /// The changelog as a page for a website: its header and `versions`,
+    /// or every release and Unreleased when it has entries, with links like
+    /// `entry --format html`
+    pub fn page(&self, versions: &[String]) -> Result<site::Page> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let content = self.read()?;
+        let changelog = parse_releases(&content)?;
+        let keys: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        for version in versions {
+            if !changelog.contains_key(version) {
+                return Err(Error::VersionNotFound(version.clone()));
+            }
+        }
+
+        let releases = changelog
+            .iter()
+            .filter(|(key, release)| {
+                if versions.is_empty() {
+                    !is_unreleased(key) || !release.notes.trim().is_empty()
+                } else {
+                    versions.contains(key)
+                }
+            })
+            .map(|(key, release)| {
+                let entry = release_entry(key, release, &keys);
+                site::PageRelease {
+                    html: entry.html(),
+                    anchor: entry.anchor,
+                }
+            })
+            .collect();
+
+        let header = Frame::parse(&content).header;
+        let title = header
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(|title| render::plain(title).trim().to_string())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "Changelog".to_string());
+        Ok(site::Page {
+            title,
+            header: render::html(&header),
+            releases,
+        })
+    }
+
+    /// Name of the directory holding the changelog, the default package name
+    fn package_name(&self) -> Result<String> {
+        Ok(fs::canonicalize(&self.path)?
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default())
+    }
+
+    fn workspace_root(&self) -> &Path {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Changelogs below this one's directory, relative to it
+    pub fn workspace_list(&self) -> Vec<PathBuf> {
+        let root = self.workspace_root();
+        workspace::discover_changelogs(root)
+            .into_iter()
+            .map(|path| {
+                path.strip_prefix(root)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path)
+            })
+            .collect()
+    }
+
+    /// Run `op` on every changelog in the workspace in parallel. Results come
+    /// back in [`Changelog::workspace_list`] order however the work was
+    /// scheduled, so output stays the same from run to run.
+    pub fn workspace_map<T, F>(&self, op: F) -> Vec<(PathBuf, Result<T>)>
+    where
+        T: Send,
+        F: Fn(&Changelog) -> Result<T> + Sync,
+    {
+        let root = self.workspace_root();
+        self.workspace_list()
+            .into_par_iter()
+            .map(|path| {
+                let result = op(&Changelog::at(&root.join(&path)));
+                (path, result)
+            })
+            .collect()
+    }
+
+    /// Propose an entry for a pull request from its title and labels, and add
+    /// it once the user has confirmed it in their editor. Returns `None` when
+    /// the user removes the entry.
+    pub fn pr_notes(&self, number: u64, version: Option<&str>) -> Result<Option<SectionChange>> {
+        if !self.exists() {
+            return Err(Error::NotInitialized);
+        }
+
+        let target = entry_version(&parse_releases(&self.read()?)?, version)?;
+        let config = self.config()?;
+        let pull = infer_forge()
+            .ok_or(Error::UnknownForge)?
+            .pull_request(number)?;
+        let template = pr_notes_template(&pull, &config);
+        let edited = Self::edit_in_editor("changelog-pr", "PR_NOTES.md", &template)?;
+        let entries = parse_typed_entries(&edited, &config.sections)?;
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        let old_content = self.read()?;
+        for (change_type, description) in &entries {
+            self.add(description, change_type, target.as_deref())?;
+        }
+        let new_content = self.read()?;
+        Ok(Some(self.section_change(
+            target.as_deref(),
+            &old_content,
+            &new_content,
+        )?))
+    }
+
+    /// The section for a commit message or free text, with this
+    /// changelog's custom sections; see [`guess_type`]
+    pub fn guess_type(&self, text: &str) -> Result<TypeGuess> {
+        Ok(guess_type(text, &self.config()?.sections))
+    }
+
+    /// The configured section a `--type` filter names
+    fn section_filter(&self, r#type: Option<&ChangeType>) -> Result<Option<ChangeType>> {
+        r#type
+            .map(|t| self.config()?.sections.section(t))
+            .transpose()
+    }
+
+    /// What comes before the version in this repository's release tags:
+    /// `--tag-prefix`, `[release] tag_prefix`, or `v`
+    pub fn tag_prefix(&self) -> Result<String> {
+        Ok(tag_prefix(&self.config()?.release).to_string())
+    }
+
+    /// Settings from the `changelog.toml` next to this changelog
+    pub fn config(&self) -> Result<config::Config> {
+        config::Config::load(self.dir())
+    }
+
+    /// Whether the changelog exists. At a `--worktree` ref that's left for
+    /// [`read`](Self::read) to report, since it knows which ref to name.
+    fn exists(&self) -> bool {
+        WORKTREE.get().is_some() || self.path.exists()
+    }
+
+    /// The changelog's content, as it is at the `--worktree` ref if one is set
+    fn read(&self) -> Result<String> {
+        match WORKTREE.get() {
+            Some(reference) => self.read_at(reference),
+            None => Ok(Encoding::decode(&fs::read_to_string(&self.path)?)),
+        }
+    }
+
+    /// The line endings and byte order mark the changelog is written with
+    fn encoding(&self) -> Encoding {
+        fs::read_to_string(&self.path)
+            .map(|content| Encoding::detect(&content))
+            .unwrap_or_default()
+    }
+
+    /// Replace the changelog's content, keeping its line endings and byte
+    /// order mark. See [`write_as`](Self::write_as).
+    fn write(&self, content: impl AsRef<str>) -> Result<()> {
+        self.write_as(content.as_ref(), self.encoding())
+    }
+
+    /// Replace the changelog's content, written with `encoding`. It's written
+    /// to a temporary file next to the changelog and renamed over it, so a
+    /// reader never sees half a changelog.
+    fn write_as(&self, content: &str, encoding: Encoding) -> Result<()> {
+        let content = encoding.encode(content);
+        // Write through a symlink rather than replacing it
+        let path = fs::canonicalize(&self.path).unwrap_or_else(|_| self.path.to_path_buf());
+        let permissions = match fs::metadata(&path) {
+            Ok(metadata) => metadata.permissions(),
+            Err(_) => return Ok(fs::write(&path, content)?),
+        };
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+        let mut file = tempfile::NamedTempFile::new_in(dir.unwrap_or(Path::new(".")))?;
+        file.write_all(content.as_bytes())?;
+        file.as_file().set_permissions(permissions)?;
+        file.persist(&path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Wait for other processes changing this changelog to finish, and keep
+    /// them waiting until the returned lock is dropped. The lock file lives in
+    /// the temporary directory rather than next to the changelog, since the
+    /// changelog itself is replaced on every write.
+    fn lock(&self) -> Result<Lock> {
+        let dir = fs::canonicalize(self.dir())?;
+        let name = self.path.file_name().unwrap_or_default();
+        let path = std::env::temp_dir().join(lock_name(&dir.join(name)));
+
+        if !LOCKED.with(|locked| locked.borrow_mut().insert(path.clone())) {
+            return Ok(Lock { path, file: None });
+        }
+        let locked = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.lock().map(|()| file));
+        match locked {
+            Ok(file) => Ok(Lock {
+                path,
+                file: Some(file),
+            }),
+            Err(e) => {
+                LOCKED.with(|locked| locked.borrow_mut().remove(&path));
+                Err(e.into())
+            }
+        }
+    }
+
+    /// The changelog's content as committed at `reference`, like
+    /// `git show <reference>:CHANGELOG.md`
+    fn read_at(&self, reference: &str) -> Result<String> {
+        let repo = Repository::discover(self.dir())?;
+        let path = self.repo_path(&repo)?;
+        let path = path.as_path();
+
+        let tree = repo.revparse_single(reference)?.peel_to_tree()?;
+        let entry = tree.get_path(path).map_err(|_| {
+            Error::NotFound(format!(
+                "{} does not exist at {}",
+                path.display(),
+                reference
+            ))
+        })?;
+        let blob = entry.to_object(&repo)?.peel_to_blob()?;
+        let content = String::from_utf8(blob.content().to_vec()).map_err(|_| {
+            Error::InvalidInput(format!("{} at {} is not UTF-8", path.display(), reference))
+        })?;
+        Ok(Encoding::decode(&content))
+    }
+
+    /// Where the changelog is relative to the top of `repo`
+    fn repo_path(&self, repo: &Repository) -> Result<PathBuf> {
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| Error::InvalidInput("The repository has no working tree".to_string()))?;
+        let file_name = self.path.file_name().unwrap_or_default();
+        let full_path = fs::canonicalize(self.dir())?.join(file_name);
+        Ok(full_path
+            .strip_prefix(fs::canonicalize(workdir)?)
+            .map(Path::to_path_buf)
+            .unwrap_or(full_path))
+    }
+
+    /// Directory holding the changelog
+    fn dir(&self) -> &Path {
+        let dir = self.path.parent().filter(|p| !p.as_os_str().is_empty());
+        dir.unwrap_or(Path::new("."))
+    }
+
+    /// Set the version in each `[release] bump_files` entry, or in the
+    /// manifests found next to the changelog when none are configured
+    pub fn bump_version_files(&self, version: &str) -> Result<Vec<VersionBump>> {
+        let bumps = self.version_bumps(version)?;
+        for bump in &bumps {
+            fs::write(self.dir().join(&bump.path), &bump.after)?;
+        }
+        Ok(bumps)
+    }
+
+    /// The changes `bump_version_files` would make, without writing them.
+    /// Every change is worked out up front so one bad file doesn't leave the
+    /// project half bumped.
+    pub fn version_bumps(&self, version: &str) -> Result<Vec<VersionBump>> {
+        let configured = self.config()?.release.bump_files;
+        let files = if configured.is_empty() {
+            version_files::DEFAULT_FILES
+                .iter()
+                .map(|name| version_files::VersionFile::Manifest(name.into()))
+                .filter(|file| self.dir().join(file.path()).exists())
+                .collect()
+        } else {
+            configured
+        };
+
+        let mut bumps = Vec::new();
+        for file in &files {
+            let path = self.dir().join(file.path());
+            if !path.exists() {
+                return Err(Error::NotFound(format!(
+                    "{} does not exist",
+                    file.path().display()
+                )));
+            }
+            let before = fs::read_to_string(&path)?;
+            let after = file.bump(&before, version)?;
+            bumps.push(VersionBump {
+                path: file.path().to_path_buf(),
+                before,
+                after,
+            });
+        }
+        Ok(bumps)
+    }
+
+    /// Commit the changelog, and any `files` relative to it, as
+    /// "chore: release v{version}" and return the new commit's id,
+    /// abbreviated the way git would show it. Like
+    /// `git add CHANGELOG.md && git commit`, anything already staged goes
+    /// into the commit too.
+    pub fn commit_release(&self, version: &str, files: &[PathBuf]) -> Result<String> {
+        let repo = Repository::discover(self.dir())?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| Error::InvalidInput("Cannot commit in a bare repository".to_string()))?;
+        let workdir = fs::canonicalize(workdir)?;
+
+        let mut index = repo.index()?;
+        let extra = files.iter().map(|file| self.dir().join(file));
+        for path in std::iter::once(self.path.to_path_buf()).chain(extra) {
+            let path = fs::canonicalize(&path)?;
+            let relative = path.strip_prefix(&workdir).map_err(|_| {
+                Error::InvalidInput(format!("{} is outside the git repository", path.display()))
+            })?;
+            index.add_path(relative)?;
+        }
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        let signature = repo.signature()?;
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &format!("chore: release {}{}", self.tag_prefix()?, version),
+            &tree,
+            &parents,
+        )?;
+        let short_id = repo.find_object(oid, None)?.short_id()?;
+        Ok(short_id.as_str().unwrap_or_default().to_string())
+    }
+
+    /// The version being released and the changelog as it reads afterwards
+    fn released_content(
+        &self,
+        content: &str,
+        version_or_type: &str,
+        date: Option<&str>,
+        queue: Option<&str>,
+        released_by: Option<&str>,
+        pre: Option<&str>,
+    ) -> Result<(String, String)> {
+        let mut changelog = parse_releases(content)?;
+        let release = self.config()?.release;
+        let version = cut_release(&mut changelog, version_or_type, date, queue, pre, &release)?;
+
+        let attribution = release.attribution;
+        if attribution.is_some() || released_by.is_some() {
+            let attribution = attribution.unwrap_or_default();
+            let name = match released_by.or(attribution.name.as_deref()) {
+                Some(name) => name.to_string(),
+                None => self.releaser(&version)?,
+            };
+            if let Some(release) = changelog.get_mut(&version) {
+                release.notes = format!(
+                    "{}\n\n{}",
+                    attribution.template.render(&name),
+                    release.notes.trim_start()
+                );
+            }
+        }
+        // Links are written as they'll be once the release is tagged
+        let tag_prefix = tag_prefix_in(Path::new("."));
+        let refs = repo_refs(Path::new(".")).with_tag(format!("{}{}", tag_prefix, version));
+        let new_content = render_changelog(
+            &changelog,
+            &Frame::parse(content),
+            infer_forge().as_deref(),
+            &links_config(Path::new(".")),
+            &tag_prefix,
+            &refs,
+        );
+        Ok((version, new_content))
+    }
+
+    /// The amended version and the changelog with Unreleased folded into it
+    fn amended_content(&self, content: &str, date: Option<&str>) -> Result<(String, String)> {
+        let mut changelog = parse_releases(content)?;
+        let version = changelog
+            .keys()
+            .find(|key| !is_unreleased(key))
+            .cloned()
+            .ok_or(Error::NoReleases)?;
+        let tag = format!("{}{}", self.tag_prefix()?, version);
+        let tagged = Repository::discover(self.dir())
+            .is_ok_and(|repo| repo.find_reference(&format!("refs/tags/{}", tag)).is_ok());
+        if tagged {
+            return Err(Error::InvalidInput(format!(
+                "{} is already tagged, release a new version instead",
+                tag
+            )));
+        }
+        let config = self.config()?;
+        let date = config
+            .release
+            .date_format
+            .format(release_day(date, &config.release)?);
+
+        // Each bullet moves with any nested under it, like a scope's entries
+        let sections = config.sections;
+        let unreleased = changelog
+            .get_mut("Unreleased")
+            .ok_or_else(|| Error::NotFound("No Unreleased section found".to_string()))?;
+        let lines: Vec<&str> = unreleased.notes.lines().collect();
+        let mut bullets: Vec<(String, std::ops::Range<usize>)> = Vec::new();
+        for (section, range) in notes::entry_lines(&unreleased.notes) {
+            match bullets.last_mut() {
+                Some((name, bullet))
+                    if *name == section
+                        && bullet.end == range.start
+                        && lines[range.start].starts_with(char::is_whitespace) =>
+                {
+                    bullet.end = range.end
+                }
+                _ => bullets.push((section, range)),
+            }
+        }
+        let entries: Vec<(ChangeType, String)> = bullets
+            .into_iter()
+            .map(|(section, range)| {
+                let r#type = sections
+                    .resolve(&section)
+                    .unwrap_or(ChangeType::Custom(section));
+                let text = lines[range].join("\n");
+                let text = text.trim_start();
+                let text = text
+                    .strip_prefix("- ")
+                    .or_else(|| text.strip_prefix("* "))
+                    .unwrap_or(text);
+                (r#type, text.to_string())
+            })
+            .collect();
+        // A freeze or other text above the sections stays in Unreleased
+        let preamble: Vec<&str> = lines
+            .iter()
+            .copied()
+            .take_while(|line| !line.trim_start().starts_with("### "))
+            .collect();
+        unreleased.notes = preamble.join("\n");
+
+        for (r#type, text) in &entries {
+            insert_entry(
+                &mut changelog,
+                text,
+                r#type,
+                Some(&version),
+                &sections.all(),
+            )?;
+        }
+        let release = &changelog[version.as_str()];
+        let (written, _) = notes::split_title(&release.title);
+        let mut title = format!("{} - {}", written, date);
+        if let Some(annotation) = release.annotation() {
+            title.push_str(&format!(" ({})", annotation));
+        }
+        let title = notes::set_yanked(&title, release.is_yanked());
+        changelog[version.as_str()].title = title;
+        Ok((version, changelog_to_markdown(&changelog, content, None)))
+    }
+
+    /// Who to credit for releasing `version`: the tagger of its tag when
+    /// it's already tagged, or else whoever would tag it now
+    fn releaser(&self, version: &str) -> Result<String> {
+        let repo = Repository::discover(self.dir())?;
+        let tagger = repo
+            .find_reference(&format!("refs/tags/{}{}", self.tag_prefix()?, version))
+            .and_then(|tag| tag.peel_to_tag())
+            .ok()
+            .and_then(|tag| tag.tagger().and_then(|t| t.name().map(String::from)));
+        match tagger {
+            Some(name) => Ok(name),
+            None => Ok(repo
+                .signature()?
+                .name()
+                .ok_or_else(|| Error::InvalidInput("git user.name is not valid UTF-8".to_string()))?
+                .to_string()),
+        }
+    }
+
+    /// Create an annotated tag for `version` at HEAD, `v{version}` unless
+    /// another tag prefix is set, whose message is the version's release
+    /// notes, and return the tag name
+    pub fn tag_release(&self, version: &str) -> Result<String> {
+        let message = self.tag_message(version)?;
+        let repo = Repository::discover(self.dir())?;
+        let name = format!("{}{}", self.tag_prefix()?, version);
+        let target = repo.head()?.peel(git2::ObjectType::Commit)?;
+        repo.tag(&name, &target, &repo.signature()?, &message, false)?;
+        Ok(name)
+    }
+
+    /// The message `tag_release` gives a version's tag: its notes, or
+    /// `Release vX.Y.Z` when it has none
+    pub fn tag_message(&self, version: &str) -> Result<String> {
+        let notes = self.version_show(version)?.notes;
+        if notes.is_empty() {
+            Ok(format!("Release {}{}", self.tag_prefix()?, version))
+        } else {
+            Ok(notes)
+        }
+    }
+
+    /// Push HEAD and, when given, the release `tag` to `remote`. This runs
+    /// `git push` so the remote is reached with the user's own credentials.
+    pub fn push_release(&self, remote: &str, tag: Option<&str>) -> Result<()> {
+        let mut command = Command::new("git");
+        command
+            .current_dir(self.dir())
+            .args(["push", remote, "HEAD"]);
+        if let Some(tag) = tag {
+            command.arg(format!("refs/tags/{}", tag));
+        }
+        let status = command.status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::InvalidInput(format!(
+                "git push to {} failed ({})",
+                remote, status
+            )))
+        }
+    }
+
+    pub fn review(
+        &self,
+        version: Option<&str>,
+        credit: bool,
+        commit_links: bool,
+        group_scopes: bool,
+    ) -> Result<Review> {
+        // Find git repository
+        let repo = Repository::discover(".")?;
+        let range = self.range(version)?;
+        // Refuse a frozen Unreleased before anyone picks commits
+        let target = entry_version(&parse_releases(&self.read()?)?, version)?;
+
+        // Collect commits for selection
+        let mut commit_list = Vec::new();
+        let mut full_ids = std::collections::HashMap::new();
+        let mut scopes = std::collections::HashMap::new();
+        let mut co_authors = std::collections::HashMap::new();
+        let mut breaking = std::collections::HashSet::new();
+        for commit in range_commits(&repo, &range)? {
+            full_ids.insert(commit.short_id.clone(), commit.id);
+            // `!` or a BREAKING CHANGE footer marks the entry
+            if commit_bump(&commit.message) == Some(Bump::Major) {
+                breaking.insert(commit.short_id.clone());
+            }
+            co_authors.insert(commit.short_id.clone(), commit_co_authors(&commit.message));
+            commit_list.push((commit.short_id, commit.summary));
+        }
+
+        // Parse conventional commits and pre-select those with a section,
+        // like feat and fix
+        let config = self.config()?;
+        let sections = &config.sections;
+        let mut defaults = vec![false; commit_list.len()];
+        for (idx, (_id, msg)) in commit_list.iter().enumerate() {
+            if let Ok(conv_commit) = git_conventional::Commit::parse(msg) {
+                if sections.commit_type(conv_commit.type_().as_str()).is_some() {
+                    defaults[idx] = true;
+                }
+            }
+        }
+
+        // Let user select commits
+        let selections = dialoguer::MultiSelect::new()
+            .with_prompt("Select commits to include in changelog (press 'a' to select all)")
+            .items(
+                &commit_list
+                    .iter()
+                    .map(|(id, msg)| {
+                        format!("{} {}", id, notes::truncate_width(msg, SUMMARY_WIDTH))
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .report(false)
+            .defaults(&defaults)
+            .interact()
+            .map_err(|dialoguer::Error::IO(e)| e)?;
+
+        if selections.is_empty() {
+            return Ok(Review::default());
+        }
+
+        // Build commit list for editor using only selected commits
+        let mut commits = String::new();
+        for &idx in selections.iter() {
+            let (short_id, message) = &commit_list[idx];
+            // Guess the type, dropping the prefix from conventional commits
+            let guess = guess_type(message, sections);
+            if let Some(scope) = guess.scope {
+                scopes.insert(short_id.clone(), scope);
+            }
+            commits.push_str(&format!(
+                "{} {} {}\n",
+                guess.change_type, short_id, guess.description
+            ));
+        }
+
+        // Use the git-rebase-todo name for proper editor highlighting
+        let template = EDITOR_TEMPLATE
+            .replace("{commits}", &commits)
+            .replace("{types}", &sections.codes().join(", "));
+        let content = Self::edit_in_editor("rebase-merge", "git-rebase-todo", &template)?;
+
+        // Get old content before processing
+        let old_content = self.read()?;
+
+        let commit_links = commit_links || config.review.commit_links;
+        let group_scopes = group_scopes || config.review.group_scopes;
+        let forge = if credit || commit_links {
+            Some(infer_forge().ok_or(Error::UnknownForge)?)
+        } else {
+            None
+        };
+        let client = forge.as_deref().filter(|_| credit);
+        let commit_forge = forge.as_deref().filter(|_| commit_links);
+
+        // Process each line
+        let mut warnings = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.splitn(3, ' ').collect();
+            if parts.len() != 3 {
+                continue;
+            }
+
+            let type_str = parts[0];
+            let scope = scopes.get(parts[1]).cloned();
+            // A grouped scope heads the bullet, so the entry leaves it out
+            let group = scope.clone().filter(|_| group_scopes);
+            let mut fields = EntryFields {
+                description: parts[2].to_string(),
+                scope: scope.filter(|_| !group_scopes),
+                links: Vec::new(),
+                breaking: breaking.contains(parts[1]),
+            };
+
+            if let Some(client) = client {
+                if let Some(sha) = full_ids.get(parts[1]) {
+                    let others = co_authors.get(parts[1]).map_or(&[][..], Vec::as_slice);
+                    match credit_with(client, None, Some(sha), others) {
+                        Ok(credit) => fields.links.push(credit),
+                        Err(e) => {
+                            warnings.push(format!("Skipping credit for {}: {}", parts[1], e));
+                            // Co-authors are in the commit, so they're credited anyway
+                            if !others.is_empty() {
+                                fields.links.push(format!("thanks {}", join_names(others)));
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(forge) = commit_forge {
+                if let Some(sha) = full_ids.get(parts[1]) {
+                    fields.links.push(commit_link(forge, parts[1], sha));
+                }
+            }
+            let description = fields.render(config.entries.template.as_ref());
+            let change_type = sections.resolve(type_str).unwrap_or(ChangeType::Changed);
+
+            // Picking the same commits again shouldn't list them twice
+            if let Some(existing) =
+                self.duplicate_entry(&description, &change_type, target.as_deref())?
+            {
+                warnings.push(format!(
+                    "Skipping {}: already in the changelog as \"{}\"",
+                    parts[1], existing
+                ));
+                continue;
+            }
+
+            // Add the entry without showing individual diffs
+            match &group {
+                Some(scope) => {
+                    let _lock = self.lock()?;
+                    let content = add_grouped_entry(
+                        &self.read()?,
+                        &description,
+                        scope,
+                        &change_type,
+                        target.as_deref(),
+                        &config,
+                    )?;
+                    self.write(content)?;
+                }
+                None => {
+                    self.add(&description, &change_type, target.as_deref())?;
+                }
+            }
+        }
+
+        // Report the overall change rather than one per entry
+        let new_content = self.read()?;
+        Ok(Review {
+            change: Some(self.section_change(target.as_deref(), &old_content, &new_content)?),
+            warnings,
+        })
+    }
+}
+
+/// `content` with the entries of every fragment added to Unreleased
+fn with_fragments(
+    content: &str,
+    fragments: &[fragments::Fragment],
+    sections: &config::SectionsConfig,
+) -> Result<String> {
+    let mut content = content.to_string();
+    for fragment in fragments {
+        for (change_type, entry) in fragment.entries(sections)? {
+            // Continuation lines of multiline entries stay indented under their bullet
+            let entry = entry.replace('\n', "\n  ");
+            content = added_content(&content, &entry, &change_type, None, &sections.all())?;
+        }
+    }
+    Ok(content)
+}
+
+/// Move the entries of Unreleased (or a release train `queue`) into a new
+/// release below it, leaving an empty Unreleased behind. Returns the version
+/// released, which may be a bump like `minor` from the latest release.
+fn cut_release(
+    changelog: &mut Releases,
+    version_or_type: &str,
+    date: Option<&str>,
+    queue: Option<&str>,
+    pre: Option<&str>,
+    release: &config::ReleaseConfig,
+) -> Result<String> {
+    let scheme = &release.scheme;
+    let day = release_day(date, release)?;
+    let unreleased_key = queue.map_or_else(|| "Unreleased".to_string(), queue_title);
+
+    // A release train bumps from the latest version on its own line when there is one
+    let released = || changelog.keys().filter(|k| !is_unreleased(k));
+    let latest_version = queue
+        .and_then(|q| released().find(|v| version_in_queue(v, q)))
+        .or_else(|| released().next())
+        .and_then(|v| v.split_whitespace().next());
+
+    // Determine the version to release
+    let version_str = match (Bump::from_str(version_or_type, true), pre) {
+        (Ok(bump), Some(label)) => scheme.pre_release(latest_version, bump, label)?,
+        // Calendar versions are for the day of the release
+        (Ok(bump), None) => scheme.next(latest_version, bump, day)?,
+        (Err(_), Some(_)) => {
+            return Err(Error::InvalidInput(
+                "A pre-release label needs major, minor, or patch".to_string(),
+            ))
+        }
+        (Err(_), None) if version_or_type.eq_ignore_ascii_case("stable") => {
+            scheme.stable(latest_version)?
+        }
+        (Err(_), None) => {
+            // Validate the provided version against the versioning scheme
+            if !scheme.is_valid(version_or_type) {
+                return Err(Error::InvalidVersion(match scheme {
+                    versioning::Scheme::Semver => {
+                        "Version must be a valid semver or one of: major, minor, patch, stable"
+                            .to_string()
+                    }
+                    calver => format!(
+                        "Version must match {} or be one of: major, minor, patch",
+                        calver
+                    ),
+                }));
+            }
+            version_or_type.to_string()
+        }
+    };
+    if changelog.contains_key(&version_str) {
+        return Err(Error::InvalidVersion(format!(
+            "{} has already been released",
+            version_str
+        )));
+    }
+    // A queue named for a release line only releases versions on it
+    if let Some(queue) = queue.filter(|q| q.starts_with(|c: char| c.is_ascii_digit())) {
+        if !version_in_queue(&version_str, queue) {
+            return Err(Error::InvalidVersion(format!(
+                "{} is not on the {} release line",
+                version_str, queue
+            )));
+        }
+    }
+
+    let dummy = format!(
+        r#"# Changelog
+## [{}]
+### Added
+
+### Changed
+
+### Deprecated
+
+### Removed
+
+### Fixed
+
+### Security
+"#,
+        unreleased_key
+    );
+
+    let (index, key, unreleased) = match changelog.shift_remove_full(unreleased_key.as_str()) {
+        Some(r) => r,
+        None => {
+            return Err(Error::NotFound(format!(
+                "No {} section found",
+                unreleased_key
+            )))
+        }
+    };
+    let new_title = format!("[{}] - {}", version_str, release.date_format.format(day));
+    // Releasing ends a code freeze
+    let mut released = unreleased;
+    if notes::freeze(&released.notes).is_some() {
+        released.notes = notes::set_freeze(&released.notes, None);
+    }
+    released.version = version_str.clone();
+    released.title = new_title;
+    let default_unreleased = {
+        let mut dummy_changelog = parse_releases(&dummy)?;
+        dummy_changelog
+            .shift_remove(unreleased_key.as_str())
+            .ok_or_else(|| Error::Parse("Failed to parse default unreleased section".to_string()))?
+    };
+    changelog.shift_insert(index, key, default_unreleased);
+
+    // Every Unreleased queue stays on top, and the new release goes above
+    // the first one that isn't newer, so a maintenance release lands among
+    // its own line like a backport
+    let pending = changelog.keys().take_while(|k| is_unreleased(k)).count();
+    let index = changelog
+        .keys()
+        .skip(pending)
+        .position(|k| scheme.compare(k, &version_str) != Some(std::cmp::Ordering::Greater))
+        .map_or(changelog.len(), |i| pending + i);
+    changelog.shift_insert(index, version_str.clone(), released);
+    Ok(version_str)
+}
+
+/// Convert another tool's changelog into this one's format, detecting which
+/// tool wrote it unless `format` is given
+pub fn import(content: &str, format: Option<import::Format>) -> Result<String> {
+    let format = format.unwrap_or_else(|| import::detect(content));
+    imported_content(
+        &import::parse(content, format)?,
+        &config::DateFormat::default(),
+    )
+}
+
+/// A changelog holding `releases`, with an empty Unreleased when they have
+/// none, and their dates written in `date_format`
+fn imported_content(
+    releases: &[import::ImportedRelease],
+    date_format: &config::DateFormat,
+) -> Result<String> {
+    let mut content = String::from("# Changelog\n\n");
+    if !releases.iter().any(|r| is_unreleased(&r.version)) {
+        content.push_str("## [Unreleased]\n\n");
+    }
+    for release in releases {
+        let date = release.date.as_deref().map(|date| {
+            date_format
+                .read(date)
+                .map_or_else(|| date.to_string(), |date| date_format.format(date))
+        });
+        match date {
+            Some(date) => content.push_str(&format!("## [{}] - {}\n\n", release.version, date)),
+            None => content.push_str(&format!("## [{}]\n\n", release.version)),
+        }
+        for change_type in ChangeType::BUILT_IN {
+            let entries: Vec<_> = release
+                .entries
+                .iter()
+                .filter(|(t, _)| t == change_type)
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            content.push_str(&format!("### {}\n\n", change_type.heading()));
+            for (_, entry) in entries {
+                content.push_str(&format!("- {}\n", entry.replace('\n', "\n  ")));
+            }
+            content.push('\n');
+        }
+    }
+    let changelog = parse_releases(&content)?;
+    Ok(changelog_to_markdown(&changelog, &content, None))
+}
+
+/// Versions tagged as `prefix` and a semantic version, oldest first
+fn version_tags(repo: &Repository, prefix: &str) -> Vec<semver::Version> {
+    let Ok(tags) = repo.tag_names(None) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<_> = tags
+        .iter()
+        .flatten()
+        .filter_map(|tag| semver::Version::parse(tag.strip_prefix(prefix)?).ok())
+        .collect();
+    versions.sort();
+    versions
+}
+
+/// Newest version tagged as `prefix` and a semantic version
+fn latest_version_tag(repo: &Repository, prefix: &str) -> Option<semver::Version> {
+    version_tags(repo, prefix).pop()
+}
+
+/// Day `version` was tagged with `prefix` or as a bare `X.Y.Z`, from the
+/// tagged commit
+fn tag_date(repo: &Repository, prefix: &str, version: &str) -> Option<NaiveDate> {
+    let commit = [format!("{}{}", prefix, version), version.to_string()]
+        .iter()
+        .find_map(|name| {
+            repo.find_reference(&format!("refs/tags/{}", name))
+                .and_then(|tag| tag.peel_to_commit())
+                .ok()
+        })?;
+    let time = commit.time();
+    let offset = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)?;
+    let date = chrono::DateTime::from_timestamp(time.seconds(), 0)?.with_timezone(&offset);
+    Some(date.date_naive())
+}
+
+/// `title` dated `date` in place of the date it has, if any
+fn dated_title(title: &str, date: &str) -> String {
+    let (written, rest) = notes::split_title(title);
+    match notes::release_date(title) {
+        Some(old) => format!("{}{}", written, rest.replacen(old, date, 1)),
+        None => format!("{} - {}{}", written, date, rest.trim_start_matches(" -")),
+    }
+}
+
+/// `content` with `description` added to the `r#type` section of `version`,
+/// or of Unreleased
+fn added_content(
+    content: &str,
+    description: &str,
+    r#type: &ChangeType,
+    version: Option<&str>,
+    order: &[ChangeType],
+) -> Result<String> {
+    let mut changelog = parse_releases(content)?;
+    insert_entry(&mut changelog, description, r#type, version, order)?;
+    Ok(changelog_to_markdown(&changelog, content, None))
+}
+
+/// Where a new entry goes: `version` when given, otherwise Unreleased or,
+/// while it's frozen, the queue it redirects to
+fn entry_version(changelog: &Releases, version: Option<&str>) -> Result<Option<String>> {
+    if version.is_some() {
+        return Ok(version.map(String::from));
+    }
+    match changelog
+        .get("Unreleased")
+        .and_then(|release| notes::freeze(&release.notes))
+    {
+        Some(Freeze {
+            redirect: Some(queue),
+        }) => Ok(Some(queue_title(&queue))),
+        Some(Freeze { redirect: None }) => Err(Error::Frozen),
+        None => Ok(None),
+    }
+}
+
+/// Add `description` to the `r#type` section of `version`, or of Unreleased,
+/// starting a release train queue if `version` names one that doesn't exist.
+/// A new section goes before the first one that comes after it in `order`.
+fn insert_entry(
+    changelog: &mut Releases,
+    description: &str,
+    r#type: &ChangeType,
+    version: Option<&str>,
+    order: &[ChangeType],
+) -> Result<()> {
+    // Determine which version to add to
+    let version_key = version.unwrap_or("Unreleased");
+    let queue_markdown = format!("## [{}]\n", version_key);
+
+    // Create or get the version entry
+    if !changelog.contains_key(version_key) {
+        if !is_unreleased(version_key) {
+            return Err(Error::VersionNotFound(version_key.to_string()));
+        }
+
+        // Start a new release train queue after the existing Unreleased sections
+        let queue = parse_releases(&queue_markdown)?
+            .shift_remove(version_key)
+            .ok_or_else(|| Error::Parse("Failed to create unreleased queue".to_string()))?;
+        let index = changelog.keys().take_while(|k| is_unreleased(k)).count();
+        changelog.shift_insert(index, version_key.to_string(), queue);
+    }
+
+    // Get the release entry
+    let release = changelog.get_mut(version_key).unwrap();
+
+    // Add the entry to the appropriate section
+    let section_marker = format!("### {}", r#type.heading());
+    let mut lines: Vec<String> = release.notes.lines().map(String::from).collect();
+
+    if let Some(section_idx) = lines.iter().position(|line| line.trim() == section_marker) {
+        // Existing section found - insert entry
+        let mut insert_idx = section_idx + 1;
+        while insert_idx < lines.len() {
+            let line = lines[insert_idx].trim();
+            if line.is_empty() {
+                insert_idx += 1;
+            } else if line.starts_with('-') {
+                // This is a list item, advance past it and any continuation lines
+                insert_idx += 1;
+                // Skip any continuation lines (indented lines that are part of this list item)
+                while insert_idx < lines.len() {
+                    let next_line = &lines[insert_idx];
+                    // If the line starts with whitespace and isn't a new list item or section,
+                    // it's a continuation of the previous list item
+                    if next_line.starts_with("  ")
+                        && !next_line.trim().starts_with('-')
+                        && !next_line.trim().starts_with("### ")
+                    {
+                        insert_idx += 1;
+                    } else {
+                        break;
+                    }
+                }
+            } else {
+                // Not a list item or empty line, we've reached the end of the section
+                break;
+            }
+        }
+        // Remove any extra blank lines before insertion
+        while insert_idx > section_idx + 1 && lines[insert_idx - 1].trim().is_empty() {
+            lines.remove(insert_idx - 1);
+            insert_idx -= 1;
+        }
+        lines.insert(insert_idx, format!("- {}", description));
+        // One blank line between the section and whatever follows it
+        if lines
+            .get(insert_idx + 1)
+            .is_some_and(|line| !line.trim().is_empty())
+        {
+            lines.insert(insert_idx + 1, String::new());
+        }
+    } else {
+        // Section doesn't exist - create it ahead of the sections ordered after it
+        let rank = |name: &str| {
+            order
+                .iter()
+                .position(|t| t.heading().eq_ignore_ascii_case(name.trim()))
+                .unwrap_or(order.len())
+        };
+        let new_rank = rank(&r#type.heading());
+        let later = lines.iter().position(|line| {
+            line.strip_prefix("### ")
+                .is_some_and(|name| rank(name) > new_rank)
+        });
+        let mut section = vec![section_marker, String::new(), format!("- {}", description)];
+        let mut insert_idx = match later {
+            Some(idx) => {
+                section.push(String::new());
+                idx
+            }
+            None => {
+                // Last section: append after the notes
+                while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                    lines.pop();
+                }
+                lines.len()
+            }
+        };
+        // One blank line apart from what comes before
+        while insert_idx > 0 && lines[insert_idx - 1].trim().is_empty() {
+            insert_idx -= 1;
+            lines.remove(insert_idx);
+        }
+        if insert_idx > 0 {
+            section.insert(0, String::new());
+        }
+        lines.splice(insert_idx..insert_idx, section);
+    }
+
+    release.notes = lines.join("\n");
+    Ok(())
+}
+
+fn remove_markdown_links(content: &str, versions: &[String]) -> String {
+    content
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            if !line.starts_with('[') || !line.contains("]: ") {
+                return true;
+            }
+            // Extract the link text between [ and ]
+            if let Some(link_text) = line.split(']').next() {
+                let link_text = &link_text[1..]; // Remove the leading [
+                                                 // Only remove if it matches a version
+                !versions.iter().any(|v| v.eq_ignore_ascii_case(link_text))
+            } else {
+                true
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn changelog_to_markdown(
+    changelog: &Releases,
+    original: &str,
+    _git_range_url: Option<&str>,
+) -> String {
+    render_changelog(
+        changelog,
+        &Frame::parse(original),
+        infer_forge().as_deref(),
+        &links_config(Path::new(".")),
+        &tag_prefix_in(Path::new(".")),
+        &repo_refs(Path::new(".")),
+    )
+}
+
+/// How version links are written, from the `changelog.toml` in `dir`. Like
+/// the forge, a broken config falls back to the defaults here; commands that
+/// read the config themselves report it.
+fn links_config(dir: &Path) -> config::LinksConfig {
+    config::Config::load(dir).unwrap_or_default().links
+}
+
+/// Set by `--worktree`, to read changelogs as they are at a git ref
+static WORKTREE: OnceLock<String> = OnceLock::new();
+
+/// Read changelogs as they are committed at `reference`, a branch, tag, or
+/// commit, instead of from the working tree for the rest of the process
+pub fn set_worktree(reference: &str) -> Result<()> {
+    WORKTREE
+        .set(reference.to_string())
+        .map_err(|_| Error::InvalidInput("The worktree ref is already set".to_string()))
+}
+
+/// Set by `--tag-prefix`, in place of `[release] tag_prefix`
+static TAG_PREFIX: OnceLock<String> = OnceLock::new();
+
+/// Name release tags with `prefix` before the version instead of the
+/// configured one, `v` by default, for the rest of the process
+pub fn set_tag_prefix(prefix: &str) -> Result<()> {
+    TAG_PREFIX
+        .set(prefix.to_string())
+        .map_err(|_| Error::InvalidInput("The tag prefix is already set".to_string()))
+}
+
+/// Set by `--utc`, in place of `[release] utc`
+static UTC: OnceLock<()> = OnceLock::new();
+
+/// Date releases by the day in UTC instead of the local timezone for the
+/// rest of the process
+pub fn set_utc() {
+    let _ = UTC.set(());
+}
+
+/// Today, in UTC when `--utc` or `[release] utc` asks for it
+fn today(release: &config::ReleaseConfig) -> NaiveDate {
+    if release.utc || UTC.get().is_some() {
+        chrono::Utc::now().date_naive()
+    } else {
+        Local::now().date_naive()
+    }
+}
+
+/// The day a release is dated: `date` as given to `--date`, in the
+/// configured format or ISO, or else today
+fn release_day(date: Option<&str>, release: &config::ReleaseConfig) -> Result<NaiveDate> {
+    match date {
+        Some(date) => release.date_format.read(date).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Date '{}' is not in {} format",
+                date, release.date_format
+            ))
+        }),
+        None => Ok(today(release)),
+    }
+}
+
+/// What comes before the version in release tags, like the `v` in `v1.2.3`
+fn tag_prefix(release: &config::ReleaseConfig) -> &str {
+    TAG_PREFIX
+        .get()
+        .map_or(release.tag_prefix.as_str(), String::as_str)
+}
+
+/// The tag prefix for the repository around `dir`, falling back to the
+/// defaults like [`links_config`]
+fn tag_prefix_in(dir: &Path) -> String {
+    tag_prefix(&config::Config::load(dir).unwrap_or_default().release).to_string()
+}
+
+/// `changelog` as markdown, with version links for `forge` when there is one
+fn render_changelog(
+    changelog: &Releases,
+    frame: &Frame,
+    forge: Option<&dyn Forge>,
+    links: &config::LinksConfig,
+    tag_prefix: &str,
+    refs: &RepoRefs,
+) -> String {
+    let mut output = frame.header.trim_end().to_string();
+    // A file can start right at its first release
+    if !output.is_empty() {
+        output.push_str("\n\n");
+    }
+
+    // Every release that gets a link, in order; release train queues don't
+    let version_links: Vec<String> = changelog
+        .values()
+        .filter(|release| !release.notes.contains("# Changelog"))
+        .filter(|release| !is_queue(release_key(&release.version, &release.title)))
+        .map(|release| notes::bare_version(notes::split_title(&release.title).0).to_string())
+        .collect();
+    let style = match forge {
+        Some(_) => links.link_style,
+        None => config::LinkStyle::None,
+    };
+
+    // Sections with nothing but link definitions are as good as empty
+    let has_content = |line: &str| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with('#') && document::definition(line).is_none()
+    };
+
+    // Generate version sections
+    for (_version, release) in changelog {
+        if !release.notes.contains("# Changelog") {
+            // What follows the link definitions is written after the new ones
+            let notes = match release.notes.trim_end().strip_suffix(&frame.trailer) {
+                Some(notes) if !frame.trailer.is_empty() => notes,
+                _ => &release.notes,
+            };
+            // Remove any existing markdown links from the notes, and the
+            // frame's definitions, wherever this release used to be
+            let cleaned_notes = remove_markdown_links(notes, &version_links);
+            let mut lines: Vec<_> = cleaned_notes
+                .lines()
+                .filter(|line| !frame.definitions.iter().any(|d| d == line))
+                .collect();
+            if let Some(pos) = lines.iter().position(|line| line.trim().starts_with("## ")) {
+                lines.drain(pos..=pos);
+                while pos < lines.len() && lines[pos].trim().is_empty() {
+                    lines.remove(pos);
+                }
+            }
+            if !output.is_empty() && !output.ends_with("\n\n") {
+                output.push('\n');
+            }
+            // The yanked marker is set aside so it keeps its brackets either way
+            let base_title = notes::set_yanked(&release.title, false);
+            // The date and any annotation after it are kept as written
+            let (version, rest) = notes::split_title(&base_title);
+            let version = notes::bare_version(version);
+            let url = forge.zip(version_links.iter().position(|v| v == version));
+            let mut title = match (style, url) {
+                (config::LinkStyle::Inline, Some((forge, i))) => {
+                    let url = version_link_url(forge, &version_links, i, links, tag_prefix, refs);
+                    format!("[{}]({}){}", version, url, rest)
+                }
+                // Always keep or add brackets when we have version links
+                (config::LinkStyle::Reference | config::LinkStyle::Inline, _) => {
+                    format!("[{}]{}", version, rest)
+                }
+                (config::LinkStyle::None, _) => format!("{}{}", version, rest),
+            };
+            if notes::is_yanked(&release.title) {
+                title = notes::set_yanked(&title, true);
+            }
+            output.push_str(&format!("## {}\n\n", title));
+
+            // Text above the first section, like an attribution line, is kept
+            let first_section = lines
+                .iter()
+                .position(|line| line.trim().starts_with("### "))
+                .unwrap_or(lines.len());
+            let preamble = lines.drain(..first_section).collect::<Vec<_>>().join("\n");
+            if !preamble.trim().is_empty() {
+                output.push_str(preamble.trim());
+                output.push_str("\n\n");
+            }
+
+            let mut filtered_sections = Vec::new();
+            let mut current_section_header = "";
+            let mut current_section_lines = Vec::new();
+            for line in lines {
+                if line.trim().starts_with("### ") {
+                    if !current_section_header.is_empty() {
+                        let content_exists =
+                            current_section_lines.iter().any(|l: &&str| has_content(l));
+                        if content_exists {
+                            filtered_sections.push(current_section_header.to_string());
+                            filtered_sections.extend(
+                                current_section_lines
+                                    .clone()
+                                    .into_iter()
+                                    .map(|s| s.to_string()),
+                            );
+                        }
+                    }
+                    current_section_header = line;
+                    current_section_lines.clear();
+                } else {
+                    current_section_lines.push(line);
+                }
+            }
+            if !current_section_header.is_empty() {
+                let content_exists = current_section_lines.iter().any(|l: &&str| has_content(l));
+                if content_exists {
+                    filtered_sections.push(current_section_header.to_string());
+                    filtered_sections
+                        .extend(current_section_lines.into_iter().map(|s| s.to_string()));
+                }
+            }
+            // Trailing whitespace is dropped when the changelog is parsed again,
+            // and an empty section dropped from the end leaves its blank lines
+            for line in filtered_sections.iter_mut() {
+                line.truncate(line.trim_end().len());
+            }
+            while filtered_sections.last().is_some_and(|line| line.is_empty()) {
+                filtered_sections.pop();
+            }
+            if !filtered_sections.is_empty() {
+                output.push_str(&filtered_sections.join("\n"));
+                output.push('\n');
+            }
+        }
+    }
+
+    // Remove any existing link definitions from the output; the ones that
+    // aren't for versions are in the frame
+    {
+        let mut lines: Vec<&str> = output.lines().collect();
+        while let Some(last) = lines.last() {
+            // Blank lines too, or one between the definitions would hide the rest
+            let last = last.trim();
+            if last.is_empty() || (last.starts_with('[') && last.contains("]:")) {
+                lines.pop();
+            } else {
+                break;
+            }
+        }
+        output = lines.join("\n");
+    }
+    if !frame.footer.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&frame.footer);
+    }
+
+    // Add version links if we can infer the forge and they go at the bottom
+    let mut definitions = Vec::new();
+    if let Some(forge) = forge.filter(|_| style == config::LinkStyle::Reference) {
+        for (i, version) in version_links.iter().enumerate() {
+            let url = version_link_url(forge, &version_links, i, links, tag_prefix, refs);
+            definitions.push(format!("[{}]: {}", version, url));
+        }
+    }
+    definitions.extend(frame.definitions.iter().cloned());
+    if !definitions.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&definitions.join("\n"));
+    }
+    if !frame.trailer.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&frame.trailer);
+    }
+    output.push('\n');
+    output
+    // // Format the markdown using comrak's format_commonmark formatter
+    // let options = ComrakOptions::default();
+    // let arena = comrak::Arena::new();
+    // let root = comrak::parse_document(&arena, &output, &options);
+    // let mut buf = Vec::new();
+    // comrak::format_commonmark(root, &options, &mut buf).unwrap();
+    // String::from_utf8(buf).unwrap()
+}
+
+/// Compare (or tag) URL for `versions[i]`, where `versions` lists every
+/// release in changelog order, newest first. A compare link needs a tag to
+/// start from, so without one in `refs` Unreleased links to the branch's
+/// history and a release to its own tag.
+fn version_link_url<S: AsRef<str>>(
+    forge: &dyn Forge,
+    versions: &[S],
+    i: usize,
+    links: &config::LinksConfig,
+    tag_prefix: &str,
+    refs: &RepoRefs,
+) -> String {
+    let version = versions[i].as_ref();
+    let tag = |version: &str| format!("{}{}", tag_prefix, version);
+    if version == "Unreleased" {
+        // For unreleased, compare with latest version
+        match versions.get(i + 1).map(|latest| tag(latest.as_ref())) {
+            Some(latest) if refs.has_tag(&latest) => forge.compare_url(&latest, "HEAD"),
+            _ => forge.commits_url(&refs.branch),
+        }
+    } else if i + 1 >= versions.len() {
+        // For first release, link to the release tag
+        forge.tag_url(&tag(version))
+    } else {
+        // For other versions, compare with the release it follows on from
+        let older = &versions[i + 1..];
+        let prev_ver =
+            previous_version(version, older, links.final_compare).unwrap_or(older[0].as_ref());
+        if refs.has_tag(&tag(prev_ver)) {
+            forge.compare_url(&tag(prev_ver), &tag(version))
+        } else {
+            forge.tag_url(&tag(version))
+        }
+    }
+}
+
+/// The release `version` follows on from: the highest of the `older`
+/// releases below it, so a patch to an earlier release train isn't compared
+/// against a newer major, and `rc.2` compares against `rc.1`. A stable
+/// release compares against its last pre-release, unless `final_compare`
+/// asks for the last stable release instead. `None` for versions that
+/// aren't semver.
+fn previous_version<'a, S: AsRef<str>>(
+    version: &str,
+    older: &'a [S],
+    final_compare: config::FinalCompare,
+) -> Option<&'a str> {
+    let current = semver::Version::parse(version).ok()?;
+    let skip_prereleases = current.pre.is_empty() && final_compare == config::FinalCompare::Stable;
+    older
+        .iter()
+        .filter_map(|v| Some((v.as_ref(), semver::Version::parse(v.as_ref()).ok()?)))
+        .filter(|(_, v)| *v < current && (v.pre.is_empty() || !skip_prereleases))
+        .max_by(|a, b| a.1.cmp(&b.1))
+        .map(|(v, _)| v)
+}
+
+/// Three-way merge of changelog contents. Releases are merged entry by entry
+/// with [`notes::merge_notes`]; a release only one side has is kept unless
+/// the other side removed it, and a title only they changed takes theirs.
+fn merge_changelogs<'a>(ours: &'a str, base: &str, theirs: &'a str) -> Result<String> {
+    let our_releases = parse_releases(ours)?;
+    let their_releases = parse_releases(theirs)?;
+    // A changelog added on both branches has no common ancestor
+    let base_releases = parse_releases(base).unwrap_or_default();
+    let unchanged = |release: &Release, key: &str| {
+        base_releases
+            .get(key)
+            .is_some_and(|b| b.title == release.title && b.notes == release.notes)
+    };
+
+    let mut merged = Releases::new();
+    for (key, release) in &our_releases {
+        let Some(their_release) = their_releases.get(key) else {
+            if !unchanged(release, key) {
+                merged.insert(key.clone(), release.clone());
+            }
+            continue;
+        };
+
+        let base_release = base_releases.get(key);
+        let mut release = release.clone();
+        if base_release.is_some_and(|b| b.title == release.title) {
+            release.title = their_release.title.clone();
+        }
+        let base_notes = base_release.map_or("", |b| b.notes.as_str());
+        release.notes = notes::merge_notes(&release.notes, base_notes, &their_release.notes);
+        merged.insert(key.clone(), release);
+    }
+
+    // Releases only they have go after the release they follow on their side
+    let their_keys: Vec<&str> = their_releases.keys().map(String::as_str).collect();
+    for (i, (key, release)) in their_releases.iter().enumerate() {
+        if our_releases.contains_key(key) || unchanged(release, key) {
+            continue;
+        }
+        let index = their_keys[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| merged.get_index_of(*prev))
+            .map_or(0, |j| j + 1);
+        merged.shift_insert(index, key.clone(), release.clone());
+    }
+
+    Ok(changelog_to_markdown(&merged, ours, None))
+}
+
+/// A single release rendered as it appears in `content`, followed by its
+/// link definition when there is one
+fn release_preview(content: &str, version: &str) -> Result<String> {
+    let changelog = parse_releases(content)?;
+    let release = changelog
+        .get(version)
+        .ok_or_else(|| Error::VersionNotFound(version.to_string()))?;
+
+    // The last release in a file also carries the link definitions
+    let notes: Vec<&str> = release
+        .notes
+        .lines()
+        .take_while(|line| !(line.starts_with('[') && line.contains("]: ")))
+        .collect();
+    let mut output = format!("## {}\n\n{}\n", release.title, notes.join("\n").trim());
+    let label = format!("[{}]:", version);
+    if let Some(link) = content.lines().find(|line| line.starts_with(&label)) {
+        output.push('\n');
+        output.push_str(link);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn version_info(key: &str, release: &Release) -> VersionInfo {
+    VersionInfo {
+        version: key.to_string(),
+        date: notes::release_date(&release.title).map(String::from),
+        yanked: notes::is_yanked(&release.title),
+    }
+}
+
+fn release_entry(key: &str, release: &Release, versions: &[&str]) -> ReleaseEntry {
+    let link = versions
+        .iter()
+        .position(|v| *v == key)
+        .zip(infer_forge())
+        .map(|(i, forge)| {
+            version_link_url(
+                forge.as_ref(),
+                versions,
+                i,
+                &links_config(Path::new(".")),
+                &tag_prefix_in(Path::new(".")),
+                &repo_refs(Path::new(".")),
+            )
+        });
+
+    ReleaseEntry {
+        version: key.to_string(),
+        title: release.title.to_string(),
+        date: notes::release_date(&release.title).map(String::from),
+        annotation: notes::release_annotation(&release.title).map(String::from),
+        released_by: None,
+        sections: notes::parse_sections(&release.notes),
+        references: notes::references(&release.notes),
+        link,
+        anchor: render::anchor(&release.title),
+        notes: document::without_version_links(&release.notes),
+    }
+}
+
+/// Entries in `notes`, numbered in order, with the lines each one spans
+fn typed_entries(notes: &str, r#type: Option<&ChangeType>) -> Vec<(Entry, std::ops::Range<usize>)> {
+    let texts = notes::parse_sections(notes)
+        .into_iter()
+        .flat_map(|section| section.entries);
+    notes::entry_lines(notes)
+        .into_iter()
+        .zip(texts)
+        .filter(|((section, _), _)| {
+            r#type.is_none_or(|t| t.heading().eq_ignore_ascii_case(section.trim()))
+        })
+        .enumerate()
+        .map(|(i, ((section, lines), text))| {
+            let entry = Entry {
+                index: i + 1,
+                section,
+                references: notes::references(&text),
+                text,
+            };
+            (entry, lines)
+        })
+        .collect()
+}
+
+fn select_entry(
+    mut entries: Vec<(Entry, std::ops::Range<usize>)>,
+    selector: &EntrySelector,
+) -> Result<(Entry, std::ops::Range<usize>)> {
+    match selector {
+        EntrySelector::Index(index) => entries
+            .into_iter()
+            .find(|(entry, _)| entry.index == *index)
+            .ok_or_else(|| Error::NotFound(format!("No entry number {}", index))),
+        EntrySelector::Match(pattern) => {
+            let needle = pattern.to_lowercase();
+            entries.retain(|(entry, _)| entry.text.to_lowercase().contains(&needle));
+            match entries.len() {
+                0 => Err(Error::NotFound(format!(
+                    "No entry matching '{}' found",
+                    pattern
+                ))),
+                1 => Ok(entries.remove(0)),
+                _ => {
+                    let found = entries
+                        .iter()
+                        .map(|(entry, _)| format!("  {}. {}", entry.index, entry.text))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Err(Error::InvalidInput(format!(
+                        "Multiple entries match '{}', be more specific or use --index:\n{}",
+                        pattern, found
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// A commit in a version's range
+struct RangeCommit {
+    id: String,
+    /// Abbreviated by git, so it respects `core.abbrev` and stays unique
+    short_id: String,
+    /// First line of the message
+    summary: String,
+    message: String,
+    author: String,
+}
+
+/// The commits in a version's range, newest first
+fn range_commits(repo: &Repository, range: &VersionRange) -> Result<Vec<RangeCommit>> {
+    let mut revwalk = repo.revwalk()?;
+
+    // Push the end commit
+    if range.end == "HEAD" {
+        revwalk.push_head()?;
+    } else {
+        let obj = repo.revparse_single(&range.end)?;
+        revwalk.push(obj.id())?;
+    }
+
+    // Hide the start commit if it exists
+    if let Some(start) = &range.start {
+        if let Ok(obj) = repo.revparse_single(start) {
+            revwalk.hide(obj.id())?;
+        }
+    }
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        // Messages in legacy encodings are decoded lossily rather than dropped
+        let message = String::from_utf8_lossy(commit.message_bytes());
+        let short_id = commit.as_object().short_id()?;
+        commits.push(RangeCommit {
+            id: commit.id().to_string(),
+            short_id: short_id.as_str().unwrap_or_default().to_string(),
+            summary: message.lines().next().unwrap_or("").trim().to_string(),
+            message: message.to_string(),
+            author: String::from_utf8_lossy(commit.author().name_bytes()).into_owned(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Release a conventional commit message calls for, including `!` and
+/// `BREAKING CHANGE:` footers
+fn commit_bump(message: &str) -> Option<Bump> {
+    let commit = git_conventional::Commit::parse(message.trim()).ok()?;
+    if commit.breaking() {
+        return Some(Bump::Major);
+    }
+    match commit.type_().as_str() {
+        "feat" => Some(Bump::Minor),
+        "fix" | "perf" => Some(Bump::Patch),
+        _ => None,
+    }
+}
+
+/// Conventional feat and fix commits are the ones users would notice
+fn is_user_facing(summary: &str) -> bool {
+    git_conventional::Commit::parse(summary)
+        .is_ok_and(|commit| commit.type_() == "feat" || commit.type_() == "fix")
+}
+
+/// Commits that no entry mentions by sha, pull request number, or description
+fn uncovered_commits(commits: &[RangeCommit], entries: &[String]) -> Vec<UncoveredCommit> {
+    let entries: Vec<String> = entries.iter().map(|e| normalize_words(e)).collect();
+    commits
+        .iter()
+        .filter(|commit| {
+            !entries
+                .iter()
+                .any(|entry| entry_mentions_commit(entry, commit))
+        })
+        .map(|commit| UncoveredCommit {
+            id: commit.id.clone(),
+            short_id: commit.short_id.clone(),
+            summary: commit.summary.clone(),
+        })
+        .collect()
+}
+
+/// `entry` must already be normalized with `normalize_words`
+fn entry_mentions_commit(entry: &str, commit: &RangeCommit) -> bool {
+    // Ids as short as the repository abbreviates them count, up to git's default of 7
+    let min_len = commit.short_id.len().min(7);
+    if entry
+        .split(' ')
+        .any(|word| word.len() >= min_len && commit.id.starts_with(word))
+    {
+        return true;
+    }
+    let summary = commit.summary.as_str();
+
+    // Squash merges carry the pull request number, e.g. "fix: crash (#12)"
+    let pull = summary
+        .rsplit_once("(#")
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if let Some(number) = pull {
+        if entry.split(' ').any(|word| word == number) {
+            return true;
+        }
+    }
+
+    let description = git_conventional::Commit::parse(summary)
+        .map(|commit| commit.description().to_string())
+        .unwrap_or_else(|_| summary.to_string());
+    let description = normalize_words(description.split(" (#").next().unwrap_or_default());
+    let contains =
+        |haystack: &str, needle: &str| format!(" {} ", haystack).contains(&format!(" {} ", needle));
+    // Entries are often a reworded commit, so a shortened one counts too
+    !description.is_empty()
+        && (contains(entry, &description)
+            || (entry.split(' ').count() >= 2 && contains(&description, entry)))
+}
+
+/// Whether two entries describe the same change: they say the same thing,
+/// or refer to the same commit or pull request
+fn same_change(entry: &str, other: &str) -> bool {
+    let words = entry_words(entry);
+    if !words.is_empty() && words == entry_words(other) {
+        return true;
+    }
+    let references = notes::references(entry);
+    if notes::references(other)
+        .iter()
+        .any(|number| references.contains(number))
+    {
+        return true;
+    }
+    static HASH: OnceLock<Regex> = OnceLock::new();
+    let hash = HASH.get_or_init(|| Regex::new(r"\b[0-9a-f]{7,40}\b").expect("valid regex"));
+    let commits: Vec<&str> = hash
+        .find_iter(entry)
+        .map(|m| m.as_str())
+        .filter(|id| id.chars().any(|c| c.is_ascii_digit()))
+        .collect();
+    hash.find_iter(other).any(|m| {
+        commits
+            .iter()
+            .any(|id| id.starts_with(m.as_str()) || m.as_str().starts_with(id))
+    })
+}
+
+/// Lowercase alphanumeric words separated by single spaces
+fn normalize_words(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Deprecated and Removed entries across all versions, oldest first
+fn deprecation_timeline(changelog: &Releases) -> Vec<Deprecation> {
+    let mut timeline = Vec::new();
+    for (version, release) in changelog.iter().rev() {
+        for section in notes::parse_sections(&release.notes) {
+            let kind = match ChangeType::from_section(&section.name) {
+                Some(ChangeType::Deprecated) => ChangeType::Deprecated,
+                Some(ChangeType::Removed) => ChangeType::Removed,
+                _ => continue,
+            };
+            for entry in section.entries {
+                timeline.push(Deprecation {
+                    version: version.to_string(),
+                    date: notes::release_date(&release.title).map(String::from),
+                    kind: kind.to_string(),
+                    entry,
+                });
+            }
+        }
+    }
+    timeline
+}
+
+/// Credit for a pull request, also thanking `co_authors` besides its author
+fn credit_with(
+    client: &dyn Forge,
+    pr: Option<u64>,
+    commit: Option<&str>,
+    co_authors: &[String],
+) -> Result<String> {
+    let pull = match (pr, commit) {
+        (Some(number), _) => client.pull_request(number)?,
+        (None, Some(sha)) => client
+            .pulls_for_commit(sha)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NotFound(format!("No pull request found for commit {}", sha)))?,
+        (None, None) => {
+            return Err(Error::InvalidInput(
+                "A pull request or commit is needed to credit".to_string(),
+            ))
+        }
+    };
+    Ok(credit_link(&pull, co_authors))
+}
+
+fn reference_with(forge: Option<&dyn Forge>, number: u64) -> String {
+    match forge {
+        Some(forge) => format!("[#{}]({})", number, forge.issue_url(number)),
+        None => format!("#{}", number),
+    }
+}
+
+/// Attribution for an entry, e.g. `[#104](url); thanks @author and @co-author`
+fn credit_link(pull: &forge::PullRequest, co_authors: &[String]) -> String {
+    let author = format!("@{}", pull.user.login);
+    let mut names = vec![author.clone()];
+    names.extend(
+        co_authors
+            .iter()
+            .filter(|name| !name.eq_ignore_ascii_case(&author))
+            .cloned(),
+    );
+    format!(
+        "[#{}]({}); thanks {}",
+        pull.number,
+        pull.html_url,
+        join_names(&names)
+    )
+}
+
+/// People named in a commit's `Co-authored-by:` trailers, as `@login` for
+/// GitHub noreply addresses and by name otherwise
+fn commit_co_authors(message: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for line in message.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+            continue;
+        }
+        let (name, email) = match value.split_once('<') {
+            Some((name, email)) => (name.trim(), email.trim_end().trim_end_matches('>')),
+            None => (value.trim(), ""),
+        };
+        // e.g. 1234+octocat@users.noreply.github.com
+        let login = email
+            .strip_suffix("@users.noreply.github.com")
+            .map(|user| user.split_once('+').map_or(user, |(_, login)| login));
+        let handle = match login {
+            Some(login) if !login.is_empty() => format!("@{}", login),
+            _ if !name.is_empty() => name.to_string(),
+            _ => continue,
+        };
+        if !names.contains(&handle) {
+            names.push(handle);
+        }
+    }
+    names
+}
+
+/// `a`, `a and b`, or `a, b and c`
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// The change type and entry text suggested for a pull request. Labels
+/// decide the type first, then the title as [`guess_type`] reads it.
+fn pr_entry(pull: &forge::PullRequest, config: &config::Config) -> (ChangeType, String) {
+    let guess = guess_type(&pull.title, &config.sections);
+    let change_type = pull
+        .labels
+        .iter()
+        .find_map(|label| config.label_type(&label.name))
+        .unwrap_or(guess.change_type);
+    let fields = EntryFields {
+        description: guess.description,
+        scope: guess.scope,
+        links: vec![credit_link(pull, &[])],
+        breaking: config.labels_bump(pull.labels.iter().map(|l| l.name.as_str()))
+            == Some(Bump::Major),
+    };
+    (change_type, fields.render(config.entries.template.as_ref()))
+}
+
+fn pr_notes_template(pull: &forge::PullRequest, config: &config::Config) -> String {
+    let (change_type, entry) = pr_entry(pull, config);
+    let labels: Vec<&str> = pull.labels.iter().map(|l| l.name.as_str()).collect();
+
+    // Pull requests labelled as not needing an entry get a commented out suggestion
+    let mut notes = String::new();
+    let comment = if config.entry_required(labels.iter().copied()) {
+        ""
+    } else {
+        notes.push_str("# Labels say this pull request doesn't need an entry\n");
+        "# "
+    };
+    if let Some(bump) = config.labels_bump(labels.iter().copied()) {
+        notes.push_str(&format!("# Labels call for a {} release\n", bump));
+    }
+
+    let mut template = format!(
+        r#"{}{} {}
+
+# Changelog entry for #{}: {}
+# Labels: {}
+{}#
+# Lines starting with '#' will be ignored
+# Each line is a change type followed by the entry, where the type is one of:
+#   {}
+# Remove every line to skip this pull request
+"#,
+        comment,
+        change_type,
+        entry,
+        pull.number,
+        pull.title,
+        if labels.is_empty() {
+            "none".to_string()
+        } else {
+            labels.join(", ")
+        },
+        notes,
+        config.sections.codes().join(", ")
+    );
+
+    if let Some(body) = pull.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        template.push_str("#\n# Pull request description:\n#\n");
+        for line in body.trim().lines() {
+            template.push_str(&format!("#   {}\n", line.trim_end()));
+        }
+    }
+    template
+}
+
+/// Entries written as `<type> <description>` lines, ignoring comments
+fn parse_typed_entries(
+    text: &str,
+    sections: &config::SectionsConfig,
+) -> Result<Vec<(ChangeType, String)>> {
+    let mut entries = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (type_str, description) = line.split_once(' ').unwrap_or((line, ""));
+        let change_type = sections
+            .resolve(type_str)
+            .ok_or_else(|| Error::InvalidInput(format!("Unknown change type '{}'", type_str)))?;
+        if description.trim().is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Missing description after '{}'",
+                type_str
+            )));
+        }
+        entries.push((change_type, description.trim().to_string()));
+    }
+    Ok(entries)
+}
+
+/// Markdown link to a commit, labelled with its short sha
+fn commit_link(forge: &dyn Forge, short_id: &str, sha: &str) -> String {
+    format!("[{}]({})", short_id, forge.commit_url(sha))
+}
+
+fn release_payload(
+    version: &str,
+    notes: &str,
+    draft: bool,
+    prerelease: bool,
+    tag_prefix: &str,
+) -> forge::NewRelease {
+    let tag_name = format!("{}{}", tag_prefix, version);
+    // Semver pre-releases (1.0.0-rc.1) are always published as pre-releases
+    let is_pre = semver::Version::parse(version).is_ok_and(|v| !v.pre.is_empty());
+    forge::NewRelease {
+        name: tag_name.clone(),
+        tag_name,
+        // The oldest release's notes end with the file's link definitions
+        body: document::without_version_links(notes),
+        draft,
+        prerelease: prerelease || is_pre,
+    }
+}
+
+/// Match released versions to forge releases by tag and compare their notes
+fn release_sync(
+    changelog: &Releases,
+    releases: &[forge::ReleaseInfo],
+    tag_prefix: &str,
+) -> Vec<ReleaseSync> {
+    // Forges store notes edited in the browser with CRLF line endings
+    let normalize = |notes: &str| notes.replace("\r\n", "\n").trim().to_string();
+
+    changelog
+        .iter()
+        .filter(|(key, _)| !is_unreleased(key))
+        .map(|(version, release)| {
+            let tag = format!("{}{}", tag_prefix, version);
+            let notes = normalize(&document::without_version_links(&release.notes));
+            let status = match releases.iter().find(|r| r.tag_name == tag) {
+                None => SyncStatus::Missing,
+                Some(published) => {
+                    let body = normalize(published.body.as_deref().unwrap_or_default());
+                    if body == notes {
+                        SyncStatus::InSync
+                    } else {
+                        SyncStatus::Drifted {
+                            url: published.html_url.clone(),
+                            release: body,
+                            changelog: notes,
+                        }
+                    }
+                }
+            };
+            ReleaseSync {
+                version: version.to_string(),
+                tag,
+                status,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -1004,259 +6131,3196 @@ mod tests {
     use tempfile::TempDir;
 
     #[test]
-    fn test_changelog_with_github_urls() {
+    fn test_changelog_with_github_urls() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+
+        let input = r#"# Changelog
+
+## Unreleased
+
+### Added
+- New feature
+
+## 1.0.0 - 2025-01-01
+
+### Added
+- Initial release"#;
+
+        let expected = r#"# Changelog
+
+## [Unreleased]
+
+### Added
+- New feature
+
+## [1.0.0] - 2025-01-01
+
+### Added
+- Initial release
+
+[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD
+[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0
+"#;
+
+        let changelog = parse_releases(input).unwrap();
+        let markdown = changelog_to_markdown(&changelog, input, None);
+
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_release_json_includes_sections_and_link() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let input = r#"# Changelog
+
+## [Unreleased]
+
+## [1.1.0] - 2025-02-01
+
+### Added
+- New feature
+
+### Fixed
+- A bug
+
+## [1.0.0] - 2025-01-01
+
+### Added
+- Initial release"#;
+
+        let changelog = parse_releases(input).unwrap();
+        let versions: Vec<&str> = changelog.keys().map(String::as_str).collect();
+        let json =
+            serde_json::to_value(release_entry("1.1.0", &changelog["1.1.0"], &versions)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": "1.1.0",
+                "title": "[1.1.0] - 2025-02-01",
+                "date": "2025-02-01",
+                "annotation": null,
+                "released_by": null,
+                "sections": [
+                    { "name": "Added", "entries": ["New feature"] },
+                    { "name": "Fixed", "entries": ["A bug"] }
+                ],
+                "references": [],
+                "link": "https://github.com/owner/repo/compare/v1.0.0...v1.1.0",
+                "anchor": "110---2025-02-01"
+            })
+        );
+    }
+
+    #[test]
+    fn test_release_entry_formats() {
+        let entry = ReleaseEntry {
+            version: "1.0.0".to_string(),
+            title: "[1.0.0] - 2025-01-01".to_string(),
+            date: Some("2025-01-01".to_string()),
+            annotation: None,
+            released_by: None,
+            sections: Vec::new(),
+            references: Vec::new(),
+            link: Some("https://github.com/owner/repo/releases/tag/v1.0.0".to_string()),
+            anchor: "100---2025-01-01".to_string(),
+            notes: "### Added\n\n- Initial release".to_string(),
+        };
+        assert_eq!(
+            entry.markdown(),
+            "## [1.0.0] - 2025-01-01\n\n### Added\n\n- Initial release\n"
+        );
+        assert_eq!(
+            entry.plain(),
+            "1.0.0 - 2025-01-01\n\nAdded\n\n- Initial release\n"
+        );
+        assert!(entry.slack().starts_with(
+            "*<https://github.com/owner/repo/releases/tag/v1.0.0|1.0.0> - 2025-01-01*\n"
+        ));
+
+        let unlinked = ReleaseEntry {
+            link: None,
+            ..entry
+        };
+        assert!(unlinked
+            .html()
+            .starts_with("<h2 id=\"100---2025-01-01\">1.0.0 - 2025-01-01</h2>\n"));
+    }
+
+    #[test]
+    fn test_release_payload() {
+        let payload = release_payload("1.2.0", "\n### Added\n- Feature\n", true, false, "v");
+        assert_eq!(
+            payload,
+            forge::NewRelease {
+                tag_name: "v1.2.0".to_string(),
+                name: "v1.2.0".to_string(),
+                body: "### Added\n- Feature".to_string(),
+                draft: true,
+                prerelease: false,
+            }
+        );
+
+        let payload = release_payload("2.0.0-rc.1", "", false, false, "release-");
+        assert_eq!(payload.tag_name, "release-2.0.0-rc.1");
+        assert!(payload.prerelease);
+
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- [first] thing\n\n[first]: https://example.com/first\n[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n";
+        let changelog = parse_releases(content).unwrap();
+        let payload = release_payload("1.0.0", &changelog["1.0.0"].notes, false, false, "v");
+        assert_eq!(
+            payload.body,
+            "### Added\n\n- [first] thing\n\n[first]: https://example.com/first"
+        );
+    }
+
+    #[test]
+    fn test_release_sync() {
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.2.0] - 2025-03-01\n\n### Fixed\n\n- crash\n\n## [1.1.0] - 2025-02-01\n\n### Added\n\n- export\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n";
+        let changelog = parse_releases(content).unwrap();
+        let release = |tag: &str, body: &str| forge::ReleaseInfo {
+            id: 1,
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/owner/repo/releases/tag/{}", tag),
+            body: Some(body.to_string()),
+        };
+        let releases = vec![
+            release("v1.2.0", "### Fixed\r\n\r\n- crash\r\n"),
+            release("v1.1.0", "### Added\n\n- export (edited on github)"),
+        ];
+
+        let statuses: Vec<(String, SyncStatus)> = release_sync(&changelog, &releases, "v")
+            .into_iter()
+            .map(|r| (r.version, r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("1.2.0".to_string(), SyncStatus::InSync),
+                (
+                    "1.1.0".to_string(),
+                    SyncStatus::Drifted {
+                        url: "https://github.com/owner/repo/releases/tag/v1.1.0".to_string(),
+                        release: "### Added\n\n- export (edited on github)".to_string(),
+                        changelog: "### Added\n\n- export".to_string(),
+                    }
+                ),
+                ("1.0.0".to_string(), SyncStatus::Missing),
+            ]
+        );
+
+        // The next release rewrites the definitions at the end of the oldest
+        // release's notes, which leaves what was published in sync
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        changelog.add("first", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("1.0.0", Some("2025-01-01"), None, None, None)
+            .unwrap();
+        let released = parse_releases(&changelog.read().unwrap()).unwrap();
+        let published = release_payload("1.0.0", &released["1.0.0"].notes, false, false, "v");
+        let releases = vec![release("v1.0.0", &published.body)];
+
+        changelog.add("second", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("1.1.0", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        let released = parse_releases(&changelog.read().unwrap()).unwrap();
+        let statuses: Vec<(String, SyncStatus)> = release_sync(&released, &releases, "v")
+            .into_iter()
+            .map(|r| (r.version, r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("1.1.0".to_string(), SyncStatus::Missing),
+                ("1.0.0".to_string(), SyncStatus::InSync),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- Export crash on empty rows\n\n## [1.1.0] - 2025-02-01\n\n### Added\n\n- export to CSV\n\n### Changed\n\n- faster export (#12)\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first export\n",
+        )
+        .unwrap();
+        let found =
+            |pattern: &str, regex: bool, section: Option<ChangeType>, since: Option<&str>| {
+                changelog
+                    .search(pattern, regex, section.as_ref(), since)
+                    .unwrap()
+                    .into_iter()
+                    .map(|m| format!("{} {}: {}", m.version, m.section, m.entry))
+                    .collect::<Vec<_>>()
+            };
+
+        assert_eq!(
+            found("EXPORT", false, None, Some("1.0.0")),
+            [
+                "Unreleased Fixed: Export crash on empty rows",
+                "1.1.0 Added: export to CSV",
+                "1.1.0 Changed: faster export (#12)"
+            ]
+        );
+        assert_eq!(
+            found("export", false, Some(ChangeType::Added), None),
+            ["1.1.0 Added: export to CSV", "1.0.0 Added: first export"]
+        );
+        assert_eq!(
+            found(r"#\d+", true, None, None),
+            ["1.1.0 Changed: faster export (#12)"]
+        );
+        assert!(found("(#12", false, None, None).len() == 1);
+        assert!(matches!(
+            changelog.search("(", true, None, None),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.search("x", false, None, Some("0.1.0")),
+            Err(Error::VersionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_deprecation_timeline() {
+        let input = r#"# Changelog
+
+## [Unreleased]
+
+### Removed
+- the old api
+
+## [1.1.0] - 2025-02-01
+
+### Added
+- Feature
+
+### Deprecated
+- the old api
+"#;
+        let changelog = parse_releases(input).unwrap();
+        let timeline: Vec<_> = deprecation_timeline(&changelog)
+            .into_iter()
+            .map(|d| (d.version, d.kind, d.entry))
+            .collect();
+
+        assert_eq!(
+            timeline,
+            vec![
+                (
+                    "1.1.0".to_string(),
+                    "deprecated".to_string(),
+                    "the old api".to_string()
+                ),
+                (
+                    "Unreleased".to_string(),
+                    "removed".to_string(),
+                    "the old api".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let command = editor_command("code --wait").unwrap();
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--wait"]);
+        assert_eq!(editor_command("vim").unwrap().get_args().count(), 0);
+        assert!(matches!(editor_command("  "), Err(Error::NoEditor)));
+
+        let command = editor_command(r#"'/opt/my editor/bin/ed' -f "a b""#).unwrap();
+        assert_eq!(command.get_program(), "/opt/my editor/bin/ed");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["-f", "a b"]);
+        assert!(matches!(
+            editor_command("vim 'unclosed"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_review_with_multi_argument_editors() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("my editor");
+        fs::create_dir(&dir).unwrap();
+        let script = dir.join("edit.sh");
+        // Files the first commit as fixed, like someone retyping the todo
+        fs::write(
+            &script,
+            "#!/bin/sh\n[ \"$1\" = --wait ] || exit 1\nsed 's/^added 1234567/fixed 1234567/' \"$2\" > \"$2.new\" && mv \"$2.new\" \"$2\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let todo = EDITOR_TEMPLATE
+            .replace("{commits}", "added 1234567 crash on start\n")
+            .replace("{types}", "added, fixed");
+        for editor in [
+            format!("'{}' --wait", script.display()),
+            format!("sh \"{}\" --wait", script.display()),
+        ] {
+            let edited =
+                Changelog::edit_with(&editor, "rebase-merge", "git-rebase-todo", &todo).unwrap();
+            assert!(edited.starts_with("fixed 1234567 crash on start\n"));
+        }
+        assert!(
+            Changelog::edit_with(&script.display().to_string(), "rebase-merge", "todo", &todo)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_credit_link() {
+        let pull: forge::PullRequest = serde_json::from_value(serde_json::json!({
+            "number": 104,
+            "title": "Fix login",
+            "html_url": "https://github.com/owner/repo/pull/104",
+            "user": { "login": "octocat" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            credit_link(&pull, &[]),
+            "[#104](https://github.com/owner/repo/pull/104); thanks @octocat"
+        );
+
+        let co_authors = commit_co_authors(
+            "fix: login\n\nSee #104\n\nCo-authored-by: Mona <1234+monalisa@users.noreply.github.com>\nco-authored-by: Jane Doe <jane@example.com>\nCo-authored-by: The Octocat <octocat@users.noreply.github.com>\nCo-authored-by: Jane Doe <jane@example.com>\n",
+        );
+        assert_eq!(co_authors, ["@monalisa", "Jane Doe", "@octocat"]);
+        assert_eq!(
+            credit_link(&pull, &co_authors),
+            "[#104](https://github.com/owner/repo/pull/104); thanks @octocat, @monalisa and Jane Doe"
+        );
+    }
+
+    #[test]
+    fn test_reference_with() {
+        let github = forge::for_remote(
+            Remote::from_url("https://github.com/owner/repo", None).unwrap(),
+            &config::HttpConfig::default(),
+        );
+        assert_eq!(
+            reference_with(Some(github.as_ref()), 12),
+            "[#12](https://github.com/owner/repo/issues/12)"
+        );
+        assert_eq!(reference_with(None, 12), "#12");
+    }
+
+    #[test]
+    fn test_pr_entry_from_labels_and_title() {
+        let pull: forge::PullRequest = serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "title": "fix: crash when the config is empty",
+            "html_url": "https://github.com/owner/repo/pull/7",
+            "user": { "login": "octocat" },
+            "labels": [{ "name": "ui" }, { "name": "Enhancement" }]
+        }))
+        .unwrap();
+        let config = config::Config::default();
+
+        assert_eq!(
+            pr_entry(&pull, &config),
+            (
+                ChangeType::Added,
+                "crash when the config is empty ([#7](https://github.com/owner/repo/pull/7); thanks @octocat)".to_string()
+            )
+        );
+
+        let unlabelled = forge::PullRequest {
+            labels: Vec::new(),
+            ..pull
+        };
+        assert_eq!(pr_entry(&unlabelled, &config).0, ChangeType::Fixed);
+
+        let template = pr_notes_template(&unlabelled, &config);
+        assert_eq!(
+            parse_typed_entries(&template, &config.sections).unwrap(),
+            vec![(
+                ChangeType::Fixed,
+                "crash when the config is empty ([#7](https://github.com/owner/repo/pull/7); thanks @octocat)".to_string()
+            )]
+        );
+        assert!(parse_typed_entries("oops something", &config.sections).is_err());
+
+        let skipped = forge::PullRequest {
+            labels: vec![forge::Label {
+                name: "no-changelog".to_string(),
+            }],
+            ..unlabelled
+        };
+        assert!(
+            parse_typed_entries(&pr_notes_template(&skipped, &config), &config.sections)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_uncovered_commits() {
+        let commit = |id: &str, summary: &str| RangeCommit {
+            id: id.to_string(),
+            short_id: id[..7].to_string(),
+            summary: summary.to_string(),
+            message: summary.to_string(),
+            author: "Alice".to_string(),
+        };
+        let commits = vec![
+            commit("abc1234def", "feat: add dark mode"),
+            commit("bcd2345efa", "fix(cli): crash on empty config (#12)"),
+            commit("cde3456fab", "fix: handle unicode paths"),
+            commit("def4567abc", "feat: export to csv"),
+        ];
+        let entries = vec![
+            "**Dark mode**!".to_string(),
+            "no more crashes ([#12](https://github.com/owner/repo/pull/12))".to_string(),
+            "paths with emoji work now ([cde3456](https://github.com/owner/repo/commit/cde3456fab))"
+                .to_string(),
+        ];
+
+        assert_eq!(
+            uncovered_commits(&commits, &entries),
+            vec![UncoveredCommit {
+                id: "def4567abc".to_string(),
+                short_id: "def4567".to_string(),
+                summary: "feat: export to csv".to_string(),
+            }]
+        );
+        assert!(is_user_facing("fix!: drop old flag"));
+        assert!(!is_user_facing("chore: bump deps"));
+    }
+
+    #[test]
+    fn test_tag_prefix() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - 2025-02-01\n\n### Fixed\n\n- crash\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n";
+        fs::write(&changelog.path, content).unwrap();
+        assert_eq!(
+            changelog.range(Some("1.1.0")).unwrap().range,
+            "v1.0.0...v1.1.0"
+        );
+
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[release]\ntag_prefix = \"mypkg-v\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            changelog.range(Some("1.1.0")).unwrap().range,
+            "mypkg-v1.0.0...mypkg-v1.1.0"
+        );
+        assert_eq!(changelog.range(None).unwrap().range, "mypkg-v1.1.0...HEAD");
+
+        // Bare version tags link without any prefix
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let mut config = config::Config::default();
+        config.release.tag_prefix = String::new();
+        let formatted = format(content, &config).unwrap();
+        assert!(formatted
+            .contains("[Unreleased]: https://github.com/owner/repo/compare/1.1.0...HEAD\n"));
+        assert!(
+            formatted.contains("[1.1.0]: https://github.com/owner/repo/compare/1.0.0...1.1.0\n")
+        );
+        assert!(formatted.contains("[1.0.0]: https://github.com/owner/repo/releases/tag/1.0.0\n"));
+    }
+
+    #[test]
+    fn test_range_commits_with_multibyte_subjects() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "修复: 启动时崩溃 🚀\n\n详细说明",
+                &tree,
+                &[],
+            )
+            .unwrap();
+
+        let range = VersionRange {
+            start: None,
+            end: "HEAD".to_string(),
+            range: "HEAD".to_string(),
+        };
+        let commits = range_commits(&repo, &range).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].id, oid.to_string());
+        assert!(
+            commits[0].short_id.len() >= 7 && oid.to_string().starts_with(&commits[0].short_id)
+        );
+
+        // Large repositories raise core.abbrev to keep ids unambiguous
+        repo.config().unwrap().set_i32("core.abbrev", 12).unwrap();
+        let commits = range_commits(&repo, &range).unwrap();
+        assert_eq!(commits[0].short_id, oid.to_string()[..12]);
+        assert_eq!(commits[0].summary, "修复: 启动时崩溃 🚀");
+    }
+
+    #[test]
+    fn test_commit_bump() {
+        assert_eq!(commit_bump("fix: crash"), Some(Bump::Patch));
+        assert_eq!(commit_bump("perf(parser): faster"), Some(Bump::Patch));
+        assert_eq!(commit_bump("feat: dark mode\n"), Some(Bump::Minor));
+        assert_eq!(commit_bump("feat!: drop node 16"), Some(Bump::Major));
+        assert_eq!(
+            commit_bump("fix: config path\n\nBREAKING CHANGE: config moved to ~/.config"),
+            Some(Bump::Major)
+        );
+        assert_eq!(commit_bump("chore: bump deps"), None);
+        assert_eq!(commit_bump("Merge branch 'main'"), None);
+    }
+
+    #[test]
+    fn test_suggest_next_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = |message: &str| {
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        };
+        let released = commit("feat: first");
+        repo.tag_lightweight("v1.2.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.2.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        commit("docs: readme");
+        let suggestion = changelog.suggest().unwrap();
+        assert_eq!(suggestion.current, "1.2.0");
+        assert_eq!(suggestion.bump, None);
+        assert_eq!(suggestion.next, None);
+
+        commit("fix: crash");
+        commit("feat(cli): dark mode");
+        let suggestion = changelog.suggest().unwrap();
+        assert_eq!(suggestion.bump, Some(Bump::Minor));
+        assert_eq!(suggestion.next.as_deref(), Some("1.3.0"));
+        assert_eq!(suggestion.commits.len(), 2);
+        assert_eq!(suggestion.commits[0].summary, "feat(cli): dark mode");
+
+        // A breaking entry in Unreleased calls for major whatever the commits say
+        changelog
+            .add("**Breaking:** drop node 16", &ChangeType::Removed, None)
+            .unwrap();
+        let suggestion = changelog.suggest().unwrap();
+        assert_eq!(suggestion.bump, Some(Bump::Major));
+        assert_eq!(suggestion.next.as_deref(), Some("2.0.0"));
+        assert_eq!(suggestion.breaking, ["**Breaking:** drop node 16"]);
+    }
+
+    #[test]
+    fn test_explain() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let commit = |author: &str, file: &str, content: &str, message: &str| {
+            fs::write(temp_dir.path().join(file), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(file)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now(author, "dev@example.com").unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        };
+        let released = commit("Alice", "a.txt", "a\n", "feat: first");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        commit("Bob", "a.txt", "b\n", "fix: crash");
+        commit("Carol", "c.txt", "c\nc\n", "feat: export");
+        commit("Bob", "a.txt", "b\nb\n", "fix: typo");
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n### Fixed\n\n- crash\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        let explanation = changelog.explain("unreleased").unwrap();
+        assert_eq!(explanation.range, "v1.0.0...HEAD");
+        assert_eq!(explanation.commits, 3);
+        assert_eq!(
+            explanation
+                .contributors
+                .iter()
+                .map(|c| (c.name.as_str(), c.commits))
+                .collect::<Vec<_>>(),
+            [("Bob", 2), ("Carol", 1)]
+        );
+        assert_eq!(
+            (
+                explanation.files_changed,
+                explanation.insertions,
+                explanation.deletions
+            ),
+            (2, 4, 1)
+        );
+        assert!(explanation.report().ends_with(
+            "Commits:       3\nFiles changed: 2 (+4 -1)\nContributors:  Bob (2), Carol (1)\n"
+        ));
+
+        let first = changelog.explain("1.0.0").unwrap();
+        assert_eq!((first.commits, first.files_changed), (1, 1));
+    }
+
+    #[test]
+    fn test_read_at() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let package = temp_dir.path().join("packages/api");
+        fs::create_dir_all(&package).unwrap();
+        let changelog = Changelog {
+            path: package.join("CHANGELOG.md").into(),
+        };
+        let released = "# Changelog\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        fs::write(&changelog.path, released).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_path(Path::new("packages/api/CHANGELOG.md"))
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let id = repo
+            .commit(Some("HEAD"), &signature, &signature, "release", &tree, &[])
+            .unwrap();
+        repo.tag_lightweight("v1.0.0", &repo.find_object(id, None).unwrap(), false)
+            .unwrap();
+        fs::write(&changelog.path, "# Changelog\n\n## Unreleased\n").unwrap();
+
+        assert_eq!(changelog.read_at("v1.0.0").unwrap(), released);
+        let missing = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        assert!(matches!(
+            missing.read_at("HEAD"),
+            Err(Error::NotFound(message)) if message == "CHANGELOG.md does not exist at HEAD"
+        ));
+    }
+
+    #[test]
+    fn test_buildup() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let released = "# Changelog\n\n## Unreleased\n\n### Added\n\n- old\n\n## 1.0.0 - 2019-12-01\n\n### Added\n\n- first\n";
+        fs::write(&changelog.path, released).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("CHANGELOG.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        // 2020-01-02T03:00:00Z
+        let time = git2::Time::new(1577934000, 0);
+        let signature = git2::Signature::new("Test", "test@example.com", &time).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "add", &tree, &[])
+            .unwrap();
+        fs::write(
+            &changelog.path,
+            released.replace(
+                "- old\n",
+                "- old\n- new\n  continued\n\n### Fixed\n\n- fix\n",
+            ),
+        )
+        .unwrap();
+
+        let buildup = changelog.buildup().unwrap();
+        assert_eq!(buildup.entries, 3);
+        assert_eq!(buildup.oldest.as_deref(), Some("2020-01-02"));
+        let age =
+            (Local::now().date_naive() - NaiveDate::from_ymd_opt(2020, 1, 2).unwrap()).num_days();
+        assert_eq!(buildup.age_days, Some(age));
+        assert_eq!(buildup.overdue(age, 3), Vec::<String>::new());
+        assert_eq!(
+            buildup.overdue(30, 2),
+            [
+                "3 entries is more than 2".to_string(),
+                format!("the oldest entry is {} days old, more than 30", age)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_entry() {
+        colored::control::set_override(false);
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = |message: &str| {
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        };
+        let released = commit("feat: first");
+        repo.tag_lightweight("v1.2.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        commit("feat: export to csv");
+        commit("fix: crash on startup");
+        commit("chore: bump deps");
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- Export to CSV\n\n## [1.2.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        let comparison = changelog.compare_entry("unreleased").unwrap();
+        assert_eq!(comparison.range, "v1.2.0...HEAD");
+        let marks: Vec<_> = comparison
+            .commits
+            .iter()
+            .map(|c| (c.summary.as_str(), c.covered, c.user_facing))
+            .collect();
+        assert_eq!(
+            marks,
+            [
+                ("chore: bump deps", false, false),
+                ("fix: crash on startup", false, true),
+                ("feat: export to csv", true, true),
+            ]
+        );
+
+        let columns = comparison.side_by_side();
+        let lines: Vec<&str> = columns.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("{:<48} | Commits in v1.2.0...HEAD", "## [Unreleased]")
+        );
+        assert!(lines[4].starts_with(&format!("{:<48} | [x] ", "- Export to CSV")));
+        assert!(lines[4].ends_with(" feat: export to csv"));
+        assert!(lines[3].ends_with(" fix: crash on startup"));
+        assert!(lines[3].contains("| [ ] "));
+    }
+
+    #[test]
+    fn test_commit_link() {
+        assert_eq!(
+            commit_link(
+                forge::for_remote(
+                    Remote::from_url("https://github.com/owner/repo", None).unwrap(),
+                    &config::HttpConfig::default()
+                )
+                .as_ref(),
+                "abc1234",
+                "abc1234def5678"
+            ),
+            "[abc1234](https://github.com/owner/repo/commit/abc1234def5678)"
+        );
+    }
+
+    #[test]
+    fn test_init_creates_changelog() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        // First initialization should succeed
+        changelog.init().unwrap();
+        assert!(changelog.path.exists());
+
+        // Content should match expected template
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("# Changelog"));
+        assert!(content.contains("## Unreleased"));
+
+        // Parse the content to verify structure
+        let parser = Parser::new();
+        let parsed = parser.parse(&content).unwrap();
+        assert!(parsed.contains_key("Unreleased"));
+
+        // Second initialization reports the existing file and leaves it alone
+        assert!(matches!(changelog.init(), Err(Error::AlreadyInitialized)));
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_init_with_template_and_tags() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+
+        assert_eq!(
+            changelog.init_with(Some("keepachangelog"), false).unwrap(),
+            None
+        );
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.starts_with("# Changelog\n\nAll notable changes"));
+        assert!(content.ends_with("\n## Unreleased\n"));
+        fs::remove_file(&changelog.path).unwrap();
+
+        assert!(matches!(
+            changelog.init_with(Some("fancy"), false),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            changelog.init_with(None, true),
+            Err(Error::NotFound(_))
+        ));
+
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let signature =
+            git2::Signature::new("dev", "dev@example.com", &git2::Time::new(1735732800, 0))
+                .unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "first", &tree, &[])
+            .unwrap();
+        let commit = repo.find_object(commit, None).unwrap();
+        for tag in ["v0.9.0", "v0.10.0", "nightly"] {
+            repo.tag_lightweight(tag, &commit, false).unwrap();
+        }
+        let template = temp_dir.path().join("template.md");
+        fs::write(
+            &template,
+            "# Release notes\n\nOur notes.\n\n## [Unreleased]\n\n## [0.1.0] - 2020-01-01\n\n### Added\n\n- old\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            changelog
+                .init_with(template.to_str(), true)
+                .unwrap()
+                .as_deref(),
+            Some("0.10.0")
+        );
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Release notes\n\nOur notes.\n\n## Unreleased\n\n## 0.10.0 - 2025-01-01\n\n### Added\n\n- Initial release\n"
+        );
+    }
+
+    #[test]
+    fn test_backfill() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let mut day = 0;
+        let mut commit = |message: &str| {
+            day += 1;
+            let time = git2::Time::new(1735689600 + day * 86400, 0);
+            let signature = git2::Signature::new("dev", "dev@example.com", &time).unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let id = repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            repo.find_object(id, None).unwrap()
+        };
+        commit("feat: export to CSV");
+        let tagged = commit("chore: release");
+        repo.tag_lightweight("v1.0.0", &tagged, false).unwrap();
+        commit("fix(api): crash on empty body");
+        let tagged = commit("Remove the legacy flag");
+        repo.tag_lightweight("v1.1.0", &tagged, false).unwrap();
+        let tagged = commit("feat: dark mode");
+        repo.tag_lightweight("v2.0.0", &tagged, false).unwrap();
+        commit("feat: unreleased");
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-01-05\n\n### Fixed\n\n- written by hand\n",
+        )
+        .unwrap();
+
+        let backfill = changelog.backfill(false).unwrap();
+        assert_eq!(
+            backfill.releases,
+            [("2.0.0".to_string(), 1), ("1.0.0".to_string(), 1)]
+        );
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n## 2.0.0 - 2025-01-06\n\n### Added\n\n- dark mode\n\n## 1.1.0 - 2025-01-05\n\n### Fixed\n\n- written by hand\n\n## 1.0.0 - 2025-01-03\n\n### Added\n\n- export to CSV\n"
+        );
+        assert!(changelog.backfill(false).unwrap().releases.is_empty());
+
+        fs::remove_file(&changelog.path).unwrap();
+        changelog.backfill(true).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains(
+            "## 1.1.0 - 2025-01-05\n\n### Removed\n\n- Remove the legacy flag\n\n### Fixed\n\n- crash on empty body\n"
+        ));
+        assert!(!content.contains("release\n"));
+    }
+
+    #[test]
+    fn test_version_queries_return_data() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+        fs::write(
+            &temp_path,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        let latest = changelog.version_latest().unwrap();
+        assert_eq!(latest.version, "1.0.0");
+        assert_eq!(latest.date.as_deref(), Some("2025-01-01"));
+        assert_eq!(changelog.range(None).unwrap().range, "v1.0.0...HEAD");
+        assert_eq!(
+            changelog.version_show("latest").unwrap().notes,
+            "### Added\n\n- first"
+        );
+        assert!(matches!(
+            changelog.version_show("2.0.0"),
+            Err(Error::VersionNotFound(v)) if v == "2.0.0"
+        ));
+    }
+
+    #[test]
+    fn test_partial_versions() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 1.3.0-rc.1 - 2025-04-01\n\n## 1.2.10 - 2025-03-01\n\n\
+             ## 1.2.9 - 2025-02-01\n\n## 1.12.0 - 2025-01-15\n\n## 0.9.0 - 2025-01-01\n",
+        )
+        .unwrap();
+
+        let show = |version| changelog.version_show(version).unwrap().version;
+        assert_eq!(show("v1.2.9"), "1.2.9");
+        assert_eq!(show("1.2"), "1.2.10");
+        assert_eq!(show("1"), "1.12.0");
+        assert_eq!(show("1.3"), "1.3.0-rc.1");
+        assert_eq!(show("0"), "0.9.0");
+        assert!(matches!(
+            changelog.version_show("1.4"),
+            Err(Error::VersionNotFound(v)) if v == "1.4"
+        ));
+        assert!(changelog.version_show("1.").is_err());
+
+        assert_eq!(
+            changelog.range(Some("v1.2")).unwrap().range,
+            "v1.2.9...v1.2.10"
+        );
+        assert_eq!(changelog.range(Some("2.0.0")).unwrap().range, "v2.0.0");
+    }
+
+    #[test]
+    fn test_concurrent_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let changelog = &changelog;
+                scope.spawn(move || {
+                    for entry in 0..10 {
+                        changelog
+                            .add(
+                                &format!("entry {} from {}", entry, thread),
+                                &ChangeType::Added,
+                                None,
+                            )
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        let content = changelog.read().unwrap();
+        assert_eq!(content.matches("- entry ").count(), 40);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let target = temp_dir.path().join("docs.md");
+            fs::rename(&changelog.path, &target).unwrap();
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+            std::os::unix::fs::symlink(&target, &changelog.path).unwrap();
+            changelog
+                .add("through a link", &ChangeType::Fixed, None)
+                .unwrap();
+            assert!(fs::read_to_string(&target)
+                .unwrap()
+                .contains("through a link"));
+            assert!(fs::symlink_metadata(&changelog.path)
+                .unwrap()
+                .file_type()
+                .is_symlink());
+            assert_eq!(
+                fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+                0o640
+            );
+        }
+    }
+
+    #[test]
+    fn test_lock_name() {
+        // Pinned, so a change that moves the lock between versions shows up
+        assert_eq!(
+            lock_name(Path::new("/work/CHANGELOG.md")),
+            "changelog-71eb68612e9dd5f1.lock"
+        );
+        assert_ne!(
+            lock_name(Path::new("/work/CHANGELOG.md")),
+            lock_name(Path::new("/work/docs/CHANGELOG.md"))
+        );
+    }
+
+    #[test]
+    fn test_commit_and_tag_release() {
+        // Linked, so the release's notes end with the version definitions
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("other.txt"), "not part of the release").unwrap();
+
+        changelog
+            .release("1.0.0", Some("2025-01-01"), None, None, None)
+            .unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let bumps = changelog.bump_version_files("1.0.0").unwrap();
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].path, PathBuf::from("Cargo.toml"));
+        assert!(bumps[0].after.contains("version = \"1.0.0\""));
+
+        let files: Vec<PathBuf> = bumps.into_iter().map(|bump| bump.path).collect();
+        let id = changelog.commit_release("1.0.0", &files).unwrap();
+        assert_eq!(changelog.tag_release("1.0.0").unwrap(), "v1.0.0");
+
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        assert!(commit.id().to_string().starts_with(&id));
+        assert_eq!(commit.message(), Some("chore: release v1.0.0"));
+        let tree = commit.tree().unwrap();
+        assert!(tree.get_name("CHANGELOG.md").is_some());
+        assert!(tree.get_name("Cargo.toml").is_some());
+        assert!(tree.get_name("other.txt").is_none());
+
+        let tag = repo
+            .revparse_single("v1.0.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.target_id(), commit.id());
+        assert_eq!(tag.message(), Some("### Added\n\n- first"));
+        assert_eq!(
+            changelog.tag_message("1.0.0").unwrap(),
+            "### Added\n\n- first"
+        );
+        assert!(changelog.tag_release("1.0.0").is_err());
+
+        let remote = TempDir::new().unwrap();
+        let bare = Repository::init_bare(remote.path()).unwrap();
+        repo.remote("origin", remote.path().to_str().unwrap())
+            .unwrap();
+        changelog.push_release("origin", Some("v1.0.0")).unwrap();
+        assert!(bare.revparse_single("v1.0.0").is_ok());
+        assert!(changelog.push_release("upstream", None).is_err());
+    }
+
+    #[test]
+    fn test_notes_out_for_oldest_release() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        changelog.add("first", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("1.0.0", Some("2025-01-01"), None, None, None)
+            .unwrap();
+        assert!(changelog.read().unwrap().contains("[1.0.0]: "));
+
+        // What `release --notes-out` and `entry --output` write
+        assert_eq!(
+            changelog.tag_message("1.0.0").unwrap(),
+            "### Added\n\n- first"
+        );
+        assert_eq!(
+            changelog.version_show("1.0.0").unwrap().markdown(),
+            "## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n"
+        );
+    }
+
+    #[test]
+    fn test_check_unreleased() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = |message: &str| {
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        };
+        let first = commit("first");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        let check = changelog.check_unreleased(None, &[]).unwrap();
+        assert_eq!(check.entries, 0);
+        assert!(!check.passed());
+        assert!(changelog
+            .check_unreleased(Some("v1.0.0"), &[])
+            .unwrap()
+            .passed());
+        assert!(changelog.check_unreleased(Some("v9.9.9"), &[]).is_err());
+
+        commit("second");
+        let check = changelog.check_unreleased(Some("v1.0.0"), &[]).unwrap();
+        assert_eq!(check.commits, Some(1));
+        assert!(!check.passed());
+
+        // Pull requests labeled to skip the changelog don't need an entry
+        let labels = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let check = changelog
+            .check_unreleased(Some("v1.0.0"), &labels(&["bug", "No-Changelog"]))
+            .unwrap();
+        assert_eq!(check.skipped_by.as_deref(), Some("No-Changelog"));
+        assert!(check.passed());
+        assert!(!changelog
+            .check_unreleased(Some("v1.0.0"), &labels(&["dependencies"]))
+            .unwrap()
+            .passed());
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[labels]\ndependencies = \"skip\"\nno-changelog = \"fixed\"\n",
+        )
+        .unwrap();
+        assert!(changelog
+            .check_unreleased(Some("v1.0.0"), &labels(&["dependencies"]))
+            .unwrap()
+            .passed());
+        assert!(!changelog
+            .check_unreleased(Some("v1.0.0"), &labels(&["no-changelog"]))
+            .unwrap()
+            .passed());
+
+        changelog.add("second", &ChangeType::Added, None).unwrap();
+        assert!(changelog
+            .check_unreleased(Some("v1.0.0"), &[])
+            .unwrap()
+            .passed());
+    }
+
+    #[test]
+    fn test_remove_and_edit_entries() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- dark mode\n- export to csv\n  and tsv\n\n### Fixed\n\n- crahs on startup\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        let fixed = changelog.entries(None, Some(&ChangeType::Fixed)).unwrap();
+        assert_eq!(
+            fixed,
+            vec![Entry {
+                index: 1,
+                section: "Fixed".to_string(),
+                text: "crahs on startup".to_string(),
+                references: Vec::new(),
+            }]
+        );
+        assert_eq!(changelog.entries(None, None).unwrap().len(), 3);
+
+        changelog
+            .edit_entry(
+                None,
+                Some(&ChangeType::Fixed),
+                &EntrySelector::Index(1),
+                Some("crash on startup"),
+            )
+            .unwrap();
+        assert!(matches!(
+            changelog.remove_entry(None, None, &EntrySelector::Match("s".to_string())),
+            Err(Error::InvalidInput(_))
+        ));
+        changelog
+            .remove_entry(None, None, &EntrySelector::Match("CSV".to_string()))
+            .unwrap();
+        changelog
+            .remove_entry(Some("1.0.0"), None, &EntrySelector::Index(1))
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- dark mode\n\n### Fixed\n\n- crash on startup\n\n## 1.0.0 - 2025-01-01\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_changelogs() {
+        set_test_github_repo(None, None);
+        let base = "# Changelog\n\n## Unreleased\n\n### Added\n\n- search\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        // We added an entry while they released what was pending
+        let ours = "# Changelog\n\n## Unreleased\n\n### Added\n\n- search\n\n### Fixed\n\n- crash\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        let theirs = "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-02-01\n\n### Added\n\n- search\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n- export\n";
+
+        assert_eq!(
+            merge_changelogs(ours, base, theirs).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n### Fixed\n\n- crash\n\n## 1.1.0 - 2025-02-01\n\n### Added\n\n- search\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n- export\n"
+        );
+
+        // Both sides adding the same entry without a common ancestor
+        let merged = merge_changelogs(ours, "", ours).unwrap();
+        assert_eq!(merged.matches("- crash").count(), 1);
+    }
+
+    #[test]
+    fn test_fragments() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- search\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        let path = changelog
+            .fragment_add("crash", &ChangeType::Fixed, Some("fix/crash"))
+            .unwrap();
+        assert_eq!(
+            path,
+            temp_dir.path().join(".changelog/unreleased/fix-crash.md")
+        );
+        changelog
+            .fragment_add("export", &ChangeType::Added, Some("export"))
+            .unwrap();
+        assert_eq!(changelog.fragments().unwrap().len(), 2);
+
+        let preview = changelog
+            .preview_release("minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        assert!(preview.contains("- search\n- export"));
+        assert!(preview.contains("### Fixed\n\n- crash"));
+        assert_eq!(changelog.fragments().unwrap().len(), 2);
+
+        changelog
+            .release("minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        let entry = changelog.version_show("1.1.0").unwrap();
+        let section = |name: &str| {
+            let section = entry.sections.iter().find(|s| s.name == name).unwrap();
+            section.entries.clone()
+        };
+        assert_eq!(section("Added"), vec!["search", "export"]);
+        assert_eq!(section("Fixed"), vec!["crash"]);
+        assert!(changelog.fragments().unwrap().is_empty());
+
+        changelog
+            .fragment_add("typo", &ChangeType::Fixed, Some("typo"))
+            .unwrap();
+        let change = changelog.collect_fragments().unwrap().unwrap();
+        assert!(change.after.contains("- typo"));
+        assert!(changelog.collect_fragments().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_env() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- crash\n- typo\n\n## [1.2.3] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            changelog.env().unwrap(),
+            Env {
+                latest_version: Some("1.2.3".to_string()),
+                next_patch: Some("1.2.4".to_string()),
+                next_minor: Some("1.3.0".to_string()),
+                next_major: Some("2.0.0".to_string()),
+                unreleased_entries: 2,
+                compare_url: Some(
+                    "https://github.com/owner/repo/compare/v1.2.3...HEAD".to_string()
+                ),
+                repo: Some("owner/repo".to_string()),
+            }
+        );
+
+        set_test_github_repo(None, None);
+        changelog.init().ok();
+        fs::write(&changelog.path, "# Changelog\n\n## [Unreleased]\n").unwrap();
+        let env = changelog.env().unwrap();
+        assert_eq!(env.latest_version, None);
+        assert_eq!(env.next_minor, None);
+        assert_eq!(env.repo, None);
+    }
+
+    #[test]
+    fn test_format() {
+        set_test_github_repo(None, None);
+        let formatted = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://gitlab.com/group/app/-/compare/v1.0.0...HEAD\n[1.0.0]: https://gitlab.com/group/app/-/tags/v1.0.0\n";
+        let content = formatted.replace("## [1.0.0]", "## 1.0.0");
+        let config = config::Config::default();
+        assert_eq!(format(&content, &config).unwrap(), formatted);
+        assert_eq!(format(formatted, &config).unwrap(), formatted);
+        assert!(matches!(
+            format("no releases\n", &config),
+            Err(Error::Parse(_))
+        ));
+
+        // Headings are spelled the way `add` writes them
+        let lowercase = formatted.replace("### Added", "### added");
+        assert_eq!(format(&lowercase, &config).unwrap(), formatted);
+
+        // Sections go in the configured order, repeated ones merged
+        let scrambled = formatted.replace(
+            "### Added\n\n- first\n",
+            "### Fixed\n\n- crash\n\n### Added\n\n- first\n\n### fixed\n\n- hang\n",
+        );
+        let ordered = formatted.replace("- first\n", "- first\n\n### Fixed\n\n- crash\n- hang\n");
+        assert_eq!(format(&scrambled, &config).unwrap(), ordered);
+        assert_eq!(format(&ordered, &config).unwrap(), ordered);
+        let mut config = config::Config::default();
+        config.sections.order = vec!["fixed".to_string()];
+        assert_eq!(
+            format(&ordered, &config).unwrap(),
+            formatted.replace(
+                "### Added\n\n- first\n",
+                "### Fixed\n\n- crash\n- hang\n\n### Added\n\n- first\n"
+            )
+        );
+
+        // Entries matching the template are laid out by it
+        let config = config::Config {
+            entries: config::EntriesConfig {
+                template: Some(
+                    Template::try_from("**{scope}**: {description}".to_string()).unwrap(),
+                ),
+            },
+            ..Default::default()
+        };
+        let loose = formatted.replace("- first", "- **cli**:first");
+        assert_eq!(
+            format(&loose, &config).unwrap(),
+            formatted.replace("- first", "- **cli**: first")
+        );
+
+        // Configured link shortcuts are written out, others are left alone
+        let mut config = config::Config::default();
+        config.links.shortcuts.insert(
+            "rfc".to_string(),
+            "https://docs.example.com/rfc/{id}".to_string(),
+        );
+        let terse = formatted.replace("- first", "- first ({rfc:12}, {adr:3})");
+        let expanded = formatted.replace(
+            "- first",
+            "- first ([rfc:12](https://docs.example.com/rfc/12), {adr:3})",
+        );
+        assert_eq!(format(&terse, &config).unwrap(), expanded);
+        assert_eq!(format(&expanded, &config).unwrap(), expanded);
+
+        // With autolink, bare references and shas link to the forge
+        let mut config = config::Config::default();
+        let bare = formatted.replace(
+            "- first",
+            "- first (#12, 1a2b3c4d5e, `#7`, [#8](https://example.com), decade1s, 1234567)",
+        );
+        assert_eq!(format(&bare, &config).unwrap(), bare);
+        config.links.autolink = true;
+        let linked = formatted.replace(
+            "- first",
+            "- first ([#12](https://gitlab.com/group/app/-/issues/12), [1a2b3c4](https://gitlab.com/group/app/-/commit/1a2b3c4d5e), `#7`, [#8](https://example.com), decade1s, 1234567)",
+        );
+        assert_eq!(format(&bare, &config).unwrap(), linked);
+        assert_eq!(format(&linked, &config).unwrap(), linked);
+
+        // Version links move into the headings, or go away, and back again
+        let mut config = config::Config::default();
+        config.links.link_style = config::LinkStyle::Inline;
+        let inline = "# Changelog\n\n## [Unreleased](https://gitlab.com/group/app/-/compare/v1.0.0...HEAD)\n\n## [1.0.0](https://gitlab.com/group/app/-/tags/v1.0.0) - 2025-01-01\n\n### Added\n\n- first\n";
+        assert_eq!(format(formatted, &config).unwrap(), inline);
+        assert_eq!(format(inline, &config).unwrap(), inline);
+        config.links.link_style = config::LinkStyle::None;
+        let unlinked =
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        assert_eq!(format(inline, &config).unwrap(), unlinked);
+        assert_eq!(
+            format(inline, &config::Config::default()).unwrap(),
+            formatted
+        );
+        assert!(lint::lint(inline, &config::Config::default()).is_empty());
+
+        // Lists of references are tidied, and linked to match when partly linked
+        let config = config::Config::default();
+        let listed = |refs: &str| formatted.replace("- first", &format!("- first ({})", refs));
+        let tidy = listed("#12, #34");
+        assert_eq!(format(&listed("#12 #34 & #12"), &config).unwrap(), tidy);
+        assert_eq!(format(&listed("#12,#34"), &config).unwrap(), tidy);
+        assert_eq!(format(&tidy, &config).unwrap(), tidy);
+        let linked = listed("[#12](https://gitlab.com/group/app/-/issues/12), [#34](https://gitlab.com/group/app/-/issues/34)");
+        assert_eq!(
+            format(
+                &listed("[#12](https://gitlab.com/group/app/-/issues/12) and #34"),
+                &config
+            )
+            .unwrap(),
+            linked
+        );
+        let mentioned = formatted.replace("- first", "- first (see #12 #34)");
+        assert_eq!(format(&mentioned, &config).unwrap(), mentioned);
+    }
+
+    #[test]
+    fn test_import() {
+        set_test_github_repo(None, None);
+        let cliff = "# Changelog\n\n## [0.2.0] - 2024-03-01\n\n### Bug Fixes\n\n- Crash on startup\n  in safe mode\n\n### Features\n\n- Dark mode\n";
+        assert_eq!(
+            import(cliff, None).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n## 0.2.0 - 2024-03-01\n\n### Added\n\n- Dark mode\n\n### Fixed\n\n- Crash on startup\n  in safe mode\n"
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_704_067_200, 0),
+        )
+        .unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+            .unwrap();
+        repo.tag_lightweight("v0.1.0", &repo.find_object(commit, None).unwrap(), false)
+            .unwrap();
+
+        assert_eq!(
+            changelog
+                .import(
+                    "v0.1.0\nv0.2.0 2024-02-01\n",
+                    Some(import::Format::Tags),
+                    false
+                )
+                .unwrap(),
+            2
+        );
+        let versions = changelog.version_list(false).unwrap();
+        assert_eq!(versions[0].date, Some("2024-02-01".to_string()));
+        assert_eq!(versions[1].date, Some("2024-01-01".to_string()));
+        assert!(matches!(
+            changelog.import(cliff, None, false),
+            Err(Error::AlreadyInitialized)
+        ));
+        assert_eq!(changelog.import(cliff, None, true).unwrap(), 1);
+
+        // Dates from tags and from the imported file follow the configured format
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[release]\ndate_format = \"%d.%m.%Y\"\n",
+        )
+        .unwrap();
+        changelog
+            .import(
+                "v0.1.0\nv0.2.0 2024-02-01\n",
+                Some(import::Format::Tags),
+                true,
+            )
+            .unwrap();
+        let versions = changelog.version_list(false).unwrap();
+        assert_eq!(versions[0].date, Some("01.02.2024".to_string()));
+        assert_eq!(versions[1].date, Some("01.01.2024".to_string()));
+        assert!(changelog
+            .lint()
+            .unwrap()
+            .iter()
+            .all(|violation| violation.rule != "date-format"));
+    }
+
+    #[test]
+    fn test_sync_from_cliff() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- Dark mode, reworded by hand ([a1b2c3d](https://example.com/commit/a1b2c3d))\n\n## [0.1.0] - 2024-01-01\n\n### Added\n\n- First release\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("cliff.toml"),
+            "[git]\ncommit_parsers = [{ message = \"^fix\", group = \"Squashed bugs\" }]\n",
+        )
+        .unwrap();
+        let cliff = "# Changelog\n\n## [unreleased]\n\n### Features\n\n- Dark mode (a1b2c3d)\n- Light mode\n\n## [0.2.0] - 2024-02-01\n\n### Squashed bugs\n\n- Crash on startup (#12)\n\n## [0.1.0] - 2024-01-01\n\n### Features\n\n- First release\n";
+
+        let sync = changelog.sync_from_cliff(cliff, None, true).unwrap();
+        assert_eq!(
+            sync,
+            CliffSync {
+                added: vec![("Unreleased".to_string(), 1), ("0.2.0".to_string(), 1)],
+                created: vec!["0.2.0".to_string()],
+            }
+        );
+        assert!(!changelog.read().unwrap().contains("Light mode"));
+
+        changelog.sync_from_cliff(cliff, None, false).unwrap();
+        assert_eq!(
+            changelog.read().unwrap(),
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- Dark mode, reworded by hand ([a1b2c3d](https://example.com/commit/a1b2c3d))\n- Light mode\n\n## 0.2.0 - 2024-02-01\n\n### Fixed\n\n- Crash on startup (#12)\n\n## 0.1.0 - 2024-01-01\n\n### Added\n\n- First release\n"
+        );
+        assert_eq!(
+            changelog.sync_from_cliff(cliff, None, false).unwrap(),
+            CliffSync::default()
+        );
+    }
+
+    #[test]
+    fn test_replace_release() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let content = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- Dark mode\n\n## [0.1.0] - 2024-01-01\n\n### Added\n\n- First\n";
+        fs::write(&changelog.path, content).unwrap();
+
+        let replaced = changelog.replace_release("0.2.0", None, true).unwrap();
+        assert!(replaced.released);
+        assert_eq!(replaced.release.notes, "### Added\n\n- Dark mode");
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), content);
+
+        assert!(
+            changelog
+                .replace_release("v0.2.0", None, false)
+                .unwrap()
+                .released
+        );
+        let again = changelog.replace_release("0.2.0", None, false).unwrap();
+        assert!(!again.released);
+        assert_eq!(again.release.notes, "### Added\n\n- Dark mode");
+
+        let replaced = changelog
+            .replace_release("0.1.0", Some("### Fixed\n\n- Crash\n"), false)
+            .unwrap();
+        assert!(!replaced.released);
+        assert_eq!(replaced.release.notes, "### Fixed\n\n- Crash");
+        let versions = changelog.version_list(false).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(
+            changelog.version_show("0.1.0").unwrap().notes,
+            "### Fixed\n\n- Crash"
+        );
+
+        assert!(matches!(
+            changelog.replace_release("0.1.0", Some(" \n"), false),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.replace_release("nope", None, false),
+            Err(Error::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_tags() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let mut day = 0;
+        let mut commit = |message: &str| {
+            day += 1;
+            let time = git2::Time::new(1735689600 + day * 86400, 0);
+            let signature = git2::Signature::new("dev", "dev@example.com", &time).unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let id = repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            repo.find_object(id, None).unwrap()
+        };
+        let tagged = commit("feat: export to CSV");
+        repo.tag_lightweight("v1.0.0", &tagged, false).unwrap();
+        let tagged = commit("fix: crash on empty body");
+        repo.tag_lightweight("v1.1.0", &tagged, false).unwrap();
+        let tagged = commit("feat: dark mode");
+        repo.tag_lightweight("v1.2.0", &tagged, false).unwrap();
+        repo.tag_lightweight("not-a-version", &tagged, false)
+            .unwrap();
+
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 2.0.0\n\n### Added\n\n- Untagged\n\n## 1.1.0 - 2024-12-31 [YANKED]\n\n### Fixed\n\n- Crash\n\n## 1.0.0 - 2025-01-02\n\n### Added\n\n- Export\n",
+        )
+        .unwrap();
+
+        let checks = changelog.verify_tags(false).unwrap();
+        assert_eq!(
+            checks,
+            vec![
+                TagCheck {
+                    version: "1.2.0".to_string(),
+                    tag: "v1.2.0".to_string(),
+                    status: TagStatus::MissingRelease,
+                    fixed: false,
+                },
+                TagCheck {
+                    version: "2.0.0".to_string(),
+                    tag: "v2.0.0".to_string(),
+                    status: TagStatus::MissingTag,
+                    fixed: false,
+                },
+                TagCheck {
+                    version: "1.1.0".to_string(),
+                    tag: "v1.1.0".to_string(),
+                    status: TagStatus::DateMismatch {
+                        changelog: Some("2024-12-31".to_string()),
+                        tagged: "2025-01-03".to_string(),
+                    },
+                    fixed: false,
+                },
+            ]
+        );
+
+        let checks = changelog.verify_tags(true).unwrap();
+        assert_eq!(
+            checks.iter().map(|c| c.fixed).collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert_eq!(
+            changelog.read().unwrap(),
+            "# Changelog\n\n## Unreleased\n\n## 2.0.0\n\n### Added\n\n- Untagged\n\n## 1.2.0 - 2025-01-04\n\n### Added\n\n- dark mode\n\n## 1.1.0 - 2025-01-03 [YANKED]\n\n### Fixed\n\n- Crash\n\n## 1.0.0 - 2025-01-02\n\n### Added\n\n- Export\n"
+        );
+        assert_eq!(
+            changelog.verify_tags(false).unwrap(),
+            vec![checks[1].clone()]
+        );
+    }
+
+    #[test]
+    fn test_notifications() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-02-01\n\n### Added\n\n- export\n\n\
+             ## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n\n\
+             [1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0\n\
+             [1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            changelog.notifications(None, &[], None),
+            Err(Error::InvalidInput(_))
+        ));
+
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[notify.webhooks]\nteam = \"https://hooks.slack.com/services/x\"\n",
+        )
+        .unwrap();
+        let notifications = changelog.notifications(None, &[], None).unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].0, "https://hooks.slack.com/services/x");
+        assert!(notifications[0].1["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("*Added*\n\n• export"));
+
+        let targets = ["https://ci.example.com/hooks".to_string()];
+        let notifications = changelog
+            .notifications(Some("1.0.0"), &targets, None)
+            .unwrap();
+        assert_eq!(notifications[0].1["version"], "1.0.0");
+        // The oldest release's notes leave the link definitions behind
+        assert!(notifications[0].1["markdown"]
+            .as_str()
+            .unwrap()
+            .ends_with("### Added\n\n- first\n"));
+        let notifications = changelog
+            .notifications(Some("1.0.0"), &targets, Some(notify::Kind::Discord))
+            .unwrap();
+        assert!(notifications[0].1["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("### Added\n\n- first"));
+
+        assert!(matches!(
+            changelog.notifications(None, &["ops".to_string()], None),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.notifications(Some("unreleased"), &[], None),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let content =
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        fs::write(&changelog.path, content).unwrap();
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[hooks]\n\
+             post_add = 'echo \"$CHANGELOG_VERSION\" > added'\n\
+             pre_release = 'test ! -e blocked && cp \"$CHANGELOG_NOTES_FILE\" notes'\n",
+        )
+        .unwrap();
+
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("added")).unwrap(),
+            "Unreleased\n"
+        );
+
+        // A failing pre_release hook leaves the changelog as it was
+        fs::write(temp_dir.path().join("blocked"), "").unwrap();
+        let before = changelog.read().unwrap();
+        assert!(matches!(
+            changelog.release("1.1.0", Some("2025-02-01"), None, None, None),
+            Err(Error::Hook {
+                hook: Hook::PreRelease,
+                ..
+            })
+        ));
+        assert_eq!(changelog.read().unwrap(), before);
+
+        fs::remove_file(temp_dir.path().join("blocked")).unwrap();
+        changelog
+            .release("1.1.0", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("notes")).unwrap(),
+            "### Added\n\n- export"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lint_plugins() {
+        use std::os::unix::fs::PermissionsExt;
+
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first TODO\n",
+        )
+        .unwrap();
+        let script = |name: &str, body: &str| {
+            let path = temp_dir.path().join("scripts").join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, format!("#!/bin/sh\n{}", body)).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        };
+        // Finds entries mentioning TODO in the releases it's handed
+        script(
+            "check-entries",
+            r#"if grep -q '"entries":\["first TODO"\]'; then
+  echo '[{"rule": "no-todo", "severity": "error", "version": "1.0.0", "message": "entry mentions TODO"}]'
+  exit 1
+fi
+"#,
+        );
+        script("quiet", "exit 0\n");
+        script("broken", "echo 'no such rule' >&2\nexit 2\n");
+        let config = |plugins: &str| {
+            fs::write(
+                temp_dir.path().join(config::FILENAME),
+                format!("[lint]\nplugins = [{}]\n", plugins),
+            )
+            .unwrap();
+        };
+
+        config(r#""./scripts/check-entries", "scripts/quiet""#);
+        let violations = changelog.lint().unwrap();
+        assert_eq!(
+            violations.last().unwrap().to_string(),
+            "error[no-todo] 1.0.0: entry mentions TODO"
+        );
+        assert_eq!(
+            violations.len(),
+            lint::lint(&changelog.read().unwrap(), &config::Config::default()).len() + 1
+        );
+
+        config(r#""./scripts/broken""#);
+        let error = changelog.lint().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Lint plugin ./scripts/broken failed: no such rule"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        set_test_github_repo(None, None);
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://gitlab.com/group/app/-/compare/v1.0.0...HEAD\n[1.0.0]: https://gitlab.com/group/app/-/tags/v1.0.0\n";
+        // Links are checked against the forge they point at, not the current repo's
+        assert!(validate(content, None).is_empty());
+
+        let rules = |content: &str| -> Vec<String> {
+            validate(content, None)
+                .into_iter()
+                .map(|v| v.rule)
+                .collect()
+        };
+        assert_eq!(
+            rules(&content.replace("## [1.0.0]", "## 1.0.0")),
+            vec!["unformatted"]
+        );
+        assert_eq!(
+            rules("no releases here\n"),
+            vec!["missing-unreleased", "unparseable"]
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog::at(&temp_dir.path().join("HISTORY.md"));
+        assert!(matches!(changelog.validate(), Err(Error::Io(_))));
+        fs::write(&changelog.path, content).unwrap();
+        assert!(changelog.validate().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_calver_release() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[release]\nscheme = \"calver\"\n",
+        )
+        .unwrap();
+
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        let version = changelog
+            .release("patch", Some("2025-05-02"), None, None, None)
+            .unwrap();
+        assert_eq!(version, "2025.05.0");
+        changelog.add("crash", &ChangeType::Fixed, None).unwrap();
+        let version = changelog
+            .release("minor", Some("2025-05-20"), None, None, None)
+            .unwrap();
+        assert_eq!(version, "2025.05.1");
+        assert_eq!(changelog.version_latest().unwrap().version, "2025.05.1");
+        assert!(changelog.lint().unwrap().is_empty());
+
+        assert!(matches!(
+            changelog.release("2.0.0", None, None, None, None),
+            Err(Error::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_release_date_format() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[release]\ndate_format = \"%d.%m.%Y\"\nutc = true\n",
+        )
+        .unwrap();
+
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("1.0.0", Some("2025-05-02"), None, None, None)
+            .unwrap();
+        changelog.add("crash", &ChangeType::Fixed, None).unwrap();
+        changelog
+            .release("patch", Some("20.05.2025"), None, None, None)
+            .unwrap();
+        assert_eq!(
+            changelog.version_show("1.0.1").unwrap().date.as_deref(),
+            Some("20.05.2025")
+        );
+        assert_eq!(
+            changelog.version_show("1.0.0").unwrap().date.as_deref(),
+            Some("02.05.2025")
+        );
+        assert!(changelog.lint().unwrap().is_empty());
+
+        changelog.add("today", &ChangeType::Fixed, None).unwrap();
+        changelog.release("patch", None, None, None, None).unwrap();
+        assert_eq!(
+            changelog.version_show("1.0.2").unwrap().date,
+            Some(chrono::Utc::now().format("%d.%m.%Y").to_string())
+        );
+
+        assert!(matches!(
+            changelog.release("patch", Some("May 2"), None, None, None),
+            Err(Error::InvalidInput(message)) if message == "Date 'May 2' is not in %d.%m.%Y format"
+        ));
+    }
+
+    #[test]
+    fn test_pre_release() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("1.2.0", Some("2025-01-01"), None, None, None)
+            .unwrap();
+
+        let release = |version_or_type, pre| {
+            changelog.add("change", &ChangeType::Changed, None).unwrap();
+            changelog.release(version_or_type, Some("2025-02-01"), None, None, pre)
+        };
+        assert_eq!(release("minor", Some("rc")).unwrap(), "1.3.0-rc.1");
+        assert_eq!(release("minor", Some("rc")).unwrap(), "1.3.0-rc.2");
+        assert!(release("1.4.0", Some("rc")).is_err());
+        assert_eq!(release("stable", None).unwrap(), "1.3.0");
+        assert!(release("stable", None).is_err());
+        assert_eq!(release("patch", None).unwrap(), "1.3.1");
+        assert!(changelog.lint().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_guess_type() {
+        let sections = config::SectionsConfig {
+            custom: vec![config::CustomSection {
+                name: "Performance".to_string(),
+                commit_types: vec!["perf".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let guess = guess_type("fix(parser): crash on empty input\n\nCloses #4", &sections);
+        assert_eq!(guess.change_type, ChangeType::Fixed);
+        assert_eq!(guess.description, "crash on empty input");
+        assert_eq!(guess.scope.as_deref(), Some("parser"));
+        assert_eq!(guess.reason, GuessReason::CommitType);
+
+        let guess = guess_type("perf: cache lookups", &sections);
+        assert_eq!(guess.section, "Performance");
+        assert_eq!(guess.change_type.to_string(), "performance");
+
+        // Unmapped commit types and plain text fall back to the leading verb
+        let guess = guess_type("chore: drop node 16", &sections);
+        assert_eq!(guess.change_type, ChangeType::Removed);
+        assert_eq!(guess.reason, GuessReason::Keyword);
+        assert_eq!(
+            guess_type("Deprecate the v1 api", &sections).change_type,
+            ChangeType::Deprecated
+        );
+        let guess = guess_type("Update dependencies", &sections);
+        assert_eq!(
+            (guess.change_type, guess.reason),
+            (ChangeType::Changed, GuessReason::Default)
+        );
+    }
+
+    #[test]
+    fn test_custom_sections() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[sections]\norder = [\"perf\", \"fixed\"]\n\n[[sections.custom]]\nname = \"Performance\"\naliases = [\"perf\"]\ncommit_types = [\"perf\"]\n\n[[sections.custom]]\nname = \"Documentation\"\n",
+        )
+        .unwrap();
+
+        // New sections go in the configured order, whatever order they're added in
+        changelog
+            .add("typo", &"documentation".parse().unwrap(), None)
+            .unwrap();
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        changelog.add("crash", &ChangeType::Fixed, None).unwrap();
+        changelog
+            .add("faster", &"PERF".parse().unwrap(), None)
+            .unwrap();
+        assert_eq!(
+            changelog.version_show("Unreleased").unwrap().notes,
+            "### Performance\n\n- faster\n\n### Fixed\n\n- crash\n\n### Added\n\n- export\n\n### Documentation\n\n- typo"
+        );
+        assert!(matches!(
+            changelog.add("oops", &"internal".parse().unwrap(), None),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let perf = changelog
+            .entries(None, Some(&ChangeType::Custom("perf".to_string())))
+            .unwrap();
+        assert_eq!(perf.len(), 1);
+        assert_eq!(perf[0].section, "Performance");
+        assert!(changelog.lint().unwrap().is_empty());
+
+        // fmt spells aliased headings out
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        fs::write(
+            &changelog.path,
+            content.replace("### Performance", "### perf"),
+        )
+        .unwrap();
+        changelog.fmt(false, None).unwrap();
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_freeze_and_thaw() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        changelog.init().unwrap();
+        changelog.add("export", &ChangeType::Added, None).unwrap();
+        assert!(matches!(changelog.thaw(), Err(Error::InvalidInput(_))));
+
+        changelog.freeze(None).unwrap();
+        assert_eq!(changelog.frozen().unwrap(), Some(Freeze { redirect: None }));
+        assert!(matches!(
+            changelog.add("import", &ChangeType::Added, None),
+            Err(Error::Frozen)
+        ));
+        // Releasing ends the freeze
+        changelog
+            .release("1.0.0", Some("2025-01-01"), None, None, None)
+            .unwrap();
+        assert_eq!(changelog.frozen().unwrap(), None);
+        assert_eq!(
+            changelog.version_show("1.0.0").unwrap().notes,
+            "### Added\n\n- export"
+        );
+
+        changelog.freeze(Some("next")).unwrap();
+        let change = changelog.add("import", &ChangeType::Added, None).unwrap();
+        assert!(change.after.starts_with("## Unreleased (next)"));
+        let mut document =
+            ChangelogDocument::parse(&fs::read_to_string(&changelog.path).unwrap()).unwrap();
+        document.add_entry(&ChangeType::Fixed, "crash").unwrap();
+        assert!(document.unreleased().unwrap().sections().is_empty());
+        assert_eq!(
+            document.get("Unreleased (next)").unwrap().sections().len(),
+            2
+        );
+
+        changelog.thaw().unwrap();
+        changelog.add("search", &ChangeType::Added, None).unwrap();
+        assert_eq!(
+            changelog.version_show("unreleased").unwrap().notes,
+            "### Added\n\n- search"
+        );
+    }
+
+    #[test]
+    fn test_yank_and_unyank() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+        fs::write(
+            &temp_path,
+            "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-02-01\n\n### Fixed\n\n- oops\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        changelog.yank("1.1.0").unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## 1.1.0 - 2025-02-01 [YANKED]\n"));
+        assert!(changelog.yank("1.1.0").is_err());
+
+        let listed = |include_yanked| -> Vec<String> {
+            changelog
+                .version_list(include_yanked)
+                .unwrap()
+                .into_iter()
+                .map(|v| v.version)
+                .collect()
+        };
+        assert_eq!(listed(false), vec!["1.0.0"]);
+        assert_eq!(listed(true), vec!["1.1.0", "1.0.0"]);
+
+        // Link generation keeps the marker and still links the yanked version
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        changelog.fmt(false, None).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## [1.1.0] - 2025-02-01 [YANKED]\n"));
+        assert!(
+            content.contains("[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0\n")
+        );
+
+        changelog.unyank("1.1.0").unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## [1.1.0] - 2025-02-01\n"));
+        assert!(changelog.unyank("1.1.0").is_err());
+    }
+
+    /// A template repository serving files from memory
+    struct Templates {
+        remote: Remote,
+        files: BTreeMap<&'static str, &'static str>,
+    }
+
+    impl Forge for Templates {
+        fn remote(&self) -> &Remote {
+            &self.remote
+        }
+
+        fn file(&self, path: &str) -> Result<Option<String>> {
+            Ok(self.files.get(path).map(|content| content.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_init_from_templates() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let mut templates = Templates {
+            remote: Remote::from_spec("acme/templates", None).unwrap(),
+            files: BTreeMap::from([(
+                "CHANGELOG.md",
+                "# Changelog\n\nAll notable changes to this service are documented here.\nSee the [acme guide](https://acme.example.com/changelogs).\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- template\n\n[Unreleased]: https://github.com/acme/templates/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/acme/templates/releases/tag/v1.0.0\n",
+            )]),
+        };
+        templates
+            .files
+            .insert(config::FILENAME, "[sections]\nextra = \"wat\"\n");
+        assert!(matches!(
+            changelog.init_from(&templates),
+            Err(Error::Config { .. })
+        ));
+        assert!(!changelog.path.exists());
+
+        templates
+            .files
+            .insert(config::FILENAME, "[links]\nautolink = true\n");
+        let written = changelog.init_from(&templates).unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\nAll notable changes to this service are documented here.\nSee the [acme guide](https://acme.example.com/changelogs).\n\n## Unreleased\n"
+        );
+        assert!(changelog.config().unwrap().links.autolink);
+        assert!(matches!(
+            changelog.init_from(&templates),
+            Err(Error::AlreadyInitialized)
+        ));
+
+        templates.files.remove("CHANGELOG.md");
+        fs::remove_file(&changelog.path).unwrap();
+        assert!(matches!(
+            changelog.init_from(&templates),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_releases_outlive_their_content() {
+        set_test_github_repo(None, None);
+        // Releases are edited after the text they were parsed from is gone
+        let mut changelog = {
+            let content = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n".to_string();
+            parse_releases(&content).unwrap()
+        };
+        for yanked in [true, false, true] {
+            let release = changelog.get_mut("1.0.0").unwrap();
+            release.title = notes::set_yanked(&release.title, yanked);
+        }
+        let release = changelog.get_mut("1.0.0").unwrap();
+        release.notes = release.notes.replace("- first", "- first, reworded");
+
+        assert_eq!(
+            changelog_to_markdown(&changelog, "# Changelog\n", None),
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01 [YANKED]\n\n### Added\n\n- first, reworded\n"
+        );
+    }
+
+    #[test]
+    fn test_squash() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n## [2.0.0-rc.3] - 2025-03-01\n\n### Fixed\n\n- crash in export\n\n## [2.0.0-rc.2] - 2025-02-15\n\n### Added\n\n- export to CSV\n\n### Fixed\n\n- Crash in export.\n\n## [2.0.0-rc.1] - 2025-02-01\n\n### Changed\n\n- new config format\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/repo/compare/v2.0.0-rc.3...HEAD\n[2.0.0-rc.3]: https://github.com/owner/repo/compare/v2.0.0-rc.2...v2.0.0-rc.3\n[2.0.0-rc.2]: https://github.com/owner/repo/compare/v2.0.0-rc.1...v2.0.0-rc.2\n[2.0.0-rc.1]: https://github.com/owner/repo/compare/v1.0.0...v2.0.0-rc.1\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            changelog.squash("2.0.0-rc.1", "2.0.0-rc.3", "1.0.0"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.squash("2.0.0-rc.3", "2.0.0-rc.3", "2.0.0"),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let squash = changelog
+            .squash("2.0.0-rc.1", "2.0.0-rc.3", "2.0.0")
+            .unwrap();
+        assert_eq!(squash.versions, ["2.0.0-rc.3", "2.0.0-rc.2", "2.0.0-rc.1"]);
+        assert_eq!(
+            squash.change.after,
+            "## [2.0.0] - 2025-03-01\n\n### Added\n\n- export to CSV\n\n### Changed\n\n- new config format\n\n### Fixed\n\n- crash in export"
+        );
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\n## [Unreleased]\n\n## [2.0.0] - 2025-03-01\n\n### Added\n\n- export to CSV\n\n### Changed\n\n- new config format\n\n### Fixed\n\n- crash in export\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/repo/compare/v2.0.0...HEAD\n[2.0.0]: https://github.com/owner/repo/compare/v1.0.0...v2.0.0\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_version() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.3.0] - 2025-03-01\n\n### Added\n\n- export\n\n## [1.2] - 2025-02-01 [YANKED]\n\n### Fixed\n\n- oops\n\n## [1.1.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.3.0...HEAD\n[1.3.0]: https://github.com/owner/repo/compare/v1.2...v1.3.0\n[1.2]: https://github.com/owner/repo/compare/v1.1.0...v1.2\n[1.1.0]: https://github.com/owner/repo/releases/tag/v1.1.0\n",
+        )
+        .unwrap();
+
+        // Outside a repository the links stay on the forge they point at
+        changelog.rename_version("1.2", "1.2.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\n## [Unreleased]\n\n## [1.3.0] - 2025-03-01\n\n### Added\n\n- export\n\n## [1.2.0] - 2025-02-01 [YANKED]\n\n### Fixed\n\n- oops\n\n## [1.1.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.3.0...HEAD\n[1.3.0]: https://github.com/owner/repo/compare/v1.2.0...v1.3.0\n[1.2.0]: https://github.com/owner/repo/compare/v1.1.0...v1.2.0\n[1.1.0]: https://github.com/owner/repo/releases/tag/v1.1.0\n"
+        );
+
+        assert!(matches!(
+            changelog.rename_version("1.2", "1.2.1"),
+            Err(Error::VersionNotFound(_))
+        ));
+        assert!(matches!(
+            changelog.rename_version("1.2.0", "1.1.0"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.rename_version("Unreleased", "2.0.0"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_annotate_release() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+        fs::write(
+            &temp_path,
+            "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-02-01 - hotfix 2025-02-02\n\n### Fixed\n\n- oops\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        // fmt keeps notes after the date when it adds brackets and links
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        changelog.fmt(false, None).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## [1.1.0] - 2025-02-01 - hotfix 2025-02-02\n"));
+
+        changelog
+            .annotate("1.0.0", Some("re-released 2025-01-03"))
+            .unwrap();
+        changelog.yank("1.0.0").unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## [1.0.0] - 2025-01-01 (re-released 2025-01-03) [YANKED]\n"));
+
+        let release = changelog.version_show("1.0.0").unwrap();
+        assert_eq!(release.date.as_deref(), Some("2025-01-01"));
+        assert_eq!(
+            release.annotation.as_deref(),
+            Some("re-released 2025-01-03")
+        );
+
+        changelog.annotate("1.1.0", None).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## [1.1.0] - 2025-02-01\n"));
+        assert!(changelog.annotate("Unreleased", Some("note")).is_err());
+    }
+
+    #[test]
+    fn test_changelog_to_markdown() {
+        set_test_github_repo(None, None);
+        let content = r#"# Changelog
+All notable changes to this project will be documented in this file.
+
+## [Unreleased]
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+- First release
+- Cool new feature
+"#;
+        let changelog = parse_releases(content).unwrap();
+
+        let markdown = changelog_to_markdown(&changelog, content, None);
+
+        let expected = r#"# Changelog
+All notable changes to this project will be documented in this file.
+
+## Unreleased
+
+## 1.0.0 - 2025-01-01
+
+### Added
+
+- First release
+- Cool new feature
+"#;
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_fmt_is_idempotent() {
+        set_test_github_repo(None, None);
+        let initial_content = r#"# Changelog
+
+## [Unreleased]
+
+### Added
+- Feature A
+
+## [1.0.0] - 2025-01-01
+
+### Added
+- Initial release"#;
+
+        // First format without GitHub links
+        let first_parse = parse_releases(initial_content).unwrap();
+        let first_format = changelog_to_markdown(&first_parse, initial_content, None);
+
+        // Second format without GitHub links
+        let second_parse = parse_releases(&first_format).unwrap();
+        let second_format = changelog_to_markdown(&second_parse, &first_format, None);
+
+        // Formats should be identical without GitHub links (ignoring trailing whitespace)
+        assert_eq!(first_format.trim_end(), second_format.trim_end());
+
+        // Now test with GitHub links
         set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
 
+        // First format with GitHub links
+        let github_parse = parse_releases(initial_content).unwrap();
+        let github_format = changelog_to_markdown(&github_parse, initial_content, None);
+
+        // Second format with GitHub links
+        let github_second_parse = parse_releases(&github_format).unwrap();
+        let github_second_format =
+            changelog_to_markdown(&github_second_parse, &github_format, None);
+
+        // Formats should be identical with GitHub links (ignoring trailing whitespace)
+        assert_eq!(github_format.trim_end(), github_second_format.trim_end());
+
+        // Verify GitHub links are present
+        assert!(github_format.contains("//github.com/owner/repo"));
+        assert!(github_format
+            .contains("[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD"));
+        assert!(
+            github_format.contains("[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0")
+        );
+    }
+
+    #[test]
+    fn test_changelog_format_exact() {
+        set_test_github_repo(None, None);
+        let input = r#"# Changelog
+
+## [Unreleased]
+
+### Added
+
+- stuff
+
+### Changed
+
+### Deprecated
+
+### Removed
+
+### Fixed
+
+### Security
+
+## [1.0.0]
+
+### Added
+
+- things"#;
+
+        let expected = r#"# Changelog
+
+## Unreleased
+
+### Added
+
+- stuff
+
+## 1.0.0
+
+### Added
+
+- things
+"#;
+
+        let changelog = parse_releases(input).unwrap();
+        let markdown = changelog_to_markdown(&changelog, input, None);
+
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_changelog_format_with_date() {
+        set_test_github_repo(None, None);
         let input = r#"# Changelog
 
+## [1.0.0] - 2025-02-06
+
+### Added
+- Initial release"#;
+
+        let expected = r#"# Changelog
+
+## 1.0.0 - 2025-02-06
+
+### Added
+- Initial release
+"#;
+
+        let changelog = parse_releases(input).unwrap();
+        let markdown = changelog_to_markdown(&changelog, input, None);
+
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_add_entry_to_section() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+
+        // Create initial changelog
+        fs::write(
+            &temp_path,
+            r#"# Changelog
+
+## [Unreleased]
+
+### Added
+
+- one
+- two
+
+### Changed
+
+- changed
+
+## [1.0.0] - 2000-01-01
+
+### Added
+
+- something
+"#,
+        )
+        .unwrap();
+
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        // Add new entry
+        changelog.add("three", &ChangeType::Added, None).unwrap();
+
+        // Verify result
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        let expected = r#"# Changelog
+
 ## Unreleased
 
-### Added
-- New feature
+### Added
+
+- one
+- two
+- three
+
+### Changed
+
+- changed
+
+## 1.0.0 - 2000-01-01
+
+### Added
+
+- something
+"#;
+        assert_eq!(content, expected);
+    }
+
+    #[test]
+    fn test_preserve_original_header_custom() {
+        let input = r#"Custom Header Line 1
+Custom Header Line 2
+
+## [Unreleased]
+
+### Added
+
+- entry
+"#;
+        let changelog = parse_releases(input).unwrap();
+        let markdown = changelog_to_markdown(&changelog, input, None);
+        assert!(markdown.contains("Custom Header Line 1"));
+        assert!(markdown.contains("Custom Header Line 2"));
+    }
+
+    #[test]
+    fn test_add_entry_creates_missing_section() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+
+        // Create initial changelog without Added section
+        fs::write(
+            &temp_path,
+            r#"# Changelog
+
+## [Unreleased]
+
+### Changed
+
+- something changed
+
+## [1.0.0] - 2000-01-01
+
+### Added
+
+- something
+"#,
+        )
+        .unwrap();
+
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        // Add new entry that requires Added section
+        changelog
+            .add("new feature", &ChangeType::Added, None)
+            .unwrap();
+
+        // Verify result
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        let expected = r#"# Changelog
 
-## 1.0.0 - 2025-01-01
+## Unreleased
 
 ### Added
-- Initial release"#;
 
-        let expected = r#"# Changelog
+- new feature
 
-## [Unreleased]
+### Changed
 
-### Added
-- New feature
+- something changed
 
-## [1.0.0] - 2025-01-01
+## 1.0.0 - 2000-01-01
 
 ### Added
-- Initial release
 
-[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD
-[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0
+- something
 "#;
-
-        let parser = Parser::new();
-        let changelog = parser.parse(input).unwrap();
-        let markdown = changelog_to_markdown(&changelog, input, None);
-
-        assert_eq!(markdown, expected);
+        assert_eq!(content, expected);
     }
 
     #[test]
-    fn test_init_creates_changelog() {
+    fn test_release_train_queue() {
+        set_test_github_repo(None, None);
         let temp_dir = TempDir::new().unwrap();
         let temp_path = temp_dir.path().join("CHANGELOG.md");
 
-        let changelog = Changelog {
-            path: temp_path.into(),
-        };
+        fs::write(
+            &temp_path,
+            r#"# Changelog
 
-        // First initialization should succeed
-        changelog.init().unwrap();
-        assert!(changelog.path.exists());
+## [Unreleased]
 
-        // Content should match expected template
-        let content = fs::read_to_string(&changelog.path).unwrap();
-        assert!(content.contains("# Changelog"));
-        assert!(content.contains("## Unreleased"));
+### Added
 
-        // Parse the content to verify structure
-        let parser = Parser::new();
-        let parsed = parser.parse(&content).unwrap();
-        assert!(parsed.contains_key("Unreleased"));
+- next major feature
 
-        // Second initialization should not error but should warn
-        changelog.init().unwrap();
-    }
+## [2.0.0] - 2025-03-01
 
-    #[test]
-    fn test_changelog_to_markdown() {
-        set_test_github_repo(None, None);
-        let content = r#"# Changelog
-All notable changes to this project will be documented in this file.
+### Added
 
-## [Unreleased]
+- big
 
-## [1.0.0] - 2025-01-01
+## [1.8.2] - 2025-02-01
 
-### Added
+### Fixed
 
-- First release
-- Cool new feature
-"#;
-        let parser = Parser::new();
-        let changelog = parser.parse(content).unwrap();
+- old
+"#,
+        )
+        .unwrap();
 
-        let markdown = changelog_to_markdown(&changelog, content, None);
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+
+        let queue = queue_title("1.x");
+        changelog
+            .add("stable fix", &ChangeType::Fixed, Some(&queue))
+            .unwrap();
+        changelog
+            .release("patch", Some("2025-04-01"), Some("1.x"), None, None)
+            .unwrap();
 
+        let content = fs::read_to_string(&changelog.path).unwrap();
         let expected = r#"# Changelog
-All notable changes to this project will be documented in this file.
 
 ## Unreleased
 
-## 1.0.0 - 2025-01-01
-
 ### Added
 
-- First release
-- Cool new feature
-"#;
-        assert_eq!(markdown, expected);
-    }
-
-    #[test]
-    fn test_fmt_is_idempotent() {
-        set_test_github_repo(None, None);
-        let initial_content = r#"# Changelog
-
-## [Unreleased]
+- next major feature
 
-### Added
-- Feature A
+## Unreleased (1.x)
 
-## [1.0.0] - 2025-01-01
+## 2.0.0 - 2025-03-01
 
 ### Added
-- Initial release"#;
 
-        let parser = Parser::new();
-
-        // First format without GitHub links
-        let first_parse = parser.parse(initial_content).unwrap();
-        let first_format = changelog_to_markdown(&first_parse, initial_content, None);
+- big
 
-        // Second format without GitHub links
-        let second_parse = parser.parse(&first_format).unwrap();
-        let second_format = changelog_to_markdown(&second_parse, &first_format, None);
+## 1.8.3 - 2025-04-01
 
-        // Formats should be identical without GitHub links (ignoring trailing whitespace)
-        assert_eq!(first_format.trim_end(), second_format.trim_end());
+### Fixed
 
-        // Now test with GitHub links
-        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+- stable fix
 
-        // First format with GitHub links
-        let github_parse = parser.parse(initial_content).unwrap();
-        let github_format = changelog_to_markdown(&github_parse, initial_content, None);
+## 1.8.2 - 2025-02-01
 
-        // Second format with GitHub links
-        let github_second_parse = parser.parse(&github_format).unwrap();
-        let github_second_format =
-            changelog_to_markdown(&github_second_parse, &github_format, None);
+### Fixed
 
-        // Formats should be identical with GitHub links (ignoring trailing whitespace)
-        assert_eq!(github_format.trim_end(), github_second_format.trim_end());
+- old
+"#;
+        assert_eq!(content, expected);
 
-        // Verify GitHub links are present
-        assert!(github_format.contains("//github.com/owner/repo"));
-        assert!(github_format
-            .contains("[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD"));
-        assert!(
-            github_format.contains("[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0")
-        );
+        // A line with no release on it doesn't borrow the latest release's
+        changelog
+            .add("another", &ChangeType::Added, Some(&queue_title("2.1")))
+            .unwrap();
+        assert!(matches!(
+            changelog.release("patch", Some("2025-04-02"), Some("2.1"), None, None),
+            Err(Error::InvalidVersion(_))
+        ));
+        changelog
+            .release("minor", Some("2025-04-02"), Some("2.1"), None, None)
+            .unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains("## 2.1.0 - 2025-04-02\n\n### Added\n\n- another\n\n## 2.0.0"));
     }
 
     #[test]
-    fn test_changelog_format_exact() {
-        set_test_github_repo(None, None);
-        let input = r#"# Changelog
+    fn test_preview_release_leaves_file_untouched() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("CHANGELOG.md");
+        let original = r#"# Changelog
 
 ## [Unreleased]
 
 ### Added
 
-- stuff
+- new thing
 
-### Changed
+## [1.0.0] - 2025-01-01
 
-### Deprecated
+### Fixed
 
-### Removed
+- bug
 
-### Fixed
+[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD
+[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0
+"#;
+        fs::write(&temp_path, original).unwrap();
 
-### Security
+        let changelog = Changelog {
+            path: temp_path.into(),
+        };
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        let (version, new_content) = changelog
+            .released_content(&content, "minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
 
-## [1.0.0]
+        assert_eq!(version, "1.1.0");
+        assert_eq!(
+            release_preview(&new_content, &version).unwrap(),
+            r#"## [1.1.0] - 2025-02-01
 
 ### Added
 
-- things"#;
-
-        let expected = r#"# Changelog
+- new thing
 
-## Unreleased
+[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0
+"#
+        );
 
-### Added
+        changelog
+            .preview_release("minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), original);
 
-- stuff
+        let dry_run = changelog
+            .release_dry_run("minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        assert_eq!(dry_run.version, "1.1.0");
+        assert_eq!(dry_run.date.as_deref(), Some("2025-02-01"));
+        assert_eq!(dry_run.change.before, original);
+        assert_eq!(dry_run.change.after, new_content);
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), original);
+    }
 
-## 1.0.0
+    #[test]
+    fn test_release_attribution() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- export\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
 
-### Added
+        changelog
+            .release(
+                "minor",
+                Some("2025-02-01"),
+                None,
+                Some("Release Team"),
+                None,
+            )
+            .unwrap();
+        let entry = changelog.version_show("1.1.0").unwrap();
+        assert_eq!(entry.released_by, Some("Release Team".to_string()));
+        assert_eq!(
+            entry.notes,
+            "Released by Release Team\n\n### Added\n\n- export"
+        );
+        assert_eq!(changelog.version_show("1.0.0").unwrap().released_by, None);
 
-- things
-"#;
+        // Without a name the release is credited to the tagger
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("user.name", "Jane Doe")
+            .unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("user.email", "jane@example.com")
+            .unwrap();
+        fs::write(
+            temp_dir.path().join(config::FILENAME),
+            "[release.attribution]\ntemplate = \"_Released by {name}_\"\n",
+        )
+        .unwrap();
+        changelog.add("import", &ChangeType::Added, None).unwrap();
+        changelog
+            .release("patch", Some("2025-03-01"), None, None, None)
+            .unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.contains(
+            "## 1.1.1 - 2025-03-01\n\n_Released by Jane Doe_\n\n### Added\n\n- import\n\n## 1.1.0 - 2025-02-01\n\nReleased by Release Team\n\n### Added"
+        ));
+        assert_eq!(
+            changelog.version_show("1.1.1").unwrap().released_by,
+            Some("Jane Doe".to_string())
+        );
+    }
 
-        let parser = Parser::new();
-        let changelog = parser.parse(input).unwrap();
-        let markdown = changelog_to_markdown(&changelog, input, None);
+    #[test]
+    fn test_version_links_stay_out_of_notes() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        // As `init` writes it, with the definition at the end of Unreleased
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n[unreleased]: https://github.com/owner/repo/commits/main\n",
+        )
+        .unwrap();
 
-        assert_eq!(markdown, expected);
+        changelog.add("thing", &ChangeType::Added, None).unwrap();
+        changelog.add("other", &ChangeType::Added, None).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert_eq!(content.to_lowercase().matches("[unreleased]:").count(), 1);
+        assert!(content.starts_with(
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- thing\n- other\n\n[Unreleased]: "
+        ));
+
+        // A section left with nothing but a definition goes in the same pass
+        let content = "# Changelog\n\n## 1.1.0\n\n### Added\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD\n";
+        let once = changelog_to_markdown(&parse_releases(content).unwrap(), content, None);
+        assert!(!once.contains("### Added"));
+        let twice = changelog_to_markdown(&parse_releases(&once).unwrap(), &once, None);
+        assert_eq!(twice, once);
     }
 
     #[test]
-    fn test_changelog_format_with_date() {
+    fn test_amend_release() {
         set_test_github_repo(None, None);
-        let input = r#"# Changelog
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- **api:**\n  - pagination\n\n### Fixed\n\n- crash on exit\n\n## [1.1.0] - 2025-02-01\n\n### Fixed\n\n- crash on start\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
 
-## [1.0.0] - 2025-02-06
+        let dry_run = changelog.amend_release_dry_run(Some("2025-02-03")).unwrap();
+        assert_eq!(dry_run.version, "1.1.0");
+        assert_eq!(dry_run.date.as_deref(), Some("2025-02-03"));
+        assert!(fs::read_to_string(&changelog.path)
+            .unwrap()
+            .contains("- crash on exit\n\n## [1.1.0] - 2025-02-01"));
+
+        assert!(matches!(
+            changelog.amend_release(Some("02/03/2025")),
+            Err(Error::InvalidInput(_))
+        ));
+        assert_eq!(
+            changelog.amend_release(Some("2025-02-03")).unwrap(),
+            "1.1.0"
+        );
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n## 1.1.0 - 2025-02-03\n\n### Added\n\n- **api:**\n  - pagination\n\n### Fixed\n\n- crash on start\n- crash on exit\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n"
+        );
 
-### Added
-- Initial release"#;
+        // Once the release is tagged it's too late to amend
+        let signature = repo.signature().unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "release", &tree, &[])
+            .unwrap();
+        repo.tag_lightweight("v1.1.0", &repo.find_object(commit, None).unwrap(), false)
+            .unwrap();
+        assert!(matches!(
+            changelog.amend_release(None),
+            Err(Error::InvalidInput(_))
+        ));
+    }
 
-        let expected = r#"# Changelog
+    #[test]
+    fn test_feed() {
+        set_test_github_repo(Some("owner".to_string()), Some("app".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.2.0]\n\n## [1.1.0] - 2025-02-01 [YANKED]\n\n## [1.0.1] - 2025-01-15\n\n### Fixed\n\n- crash\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
 
-## 1.0.0 - 2025-02-06
+        let feed = changelog.feed(Some("App"), None).unwrap();
+        assert_eq!(feed.link, Some("https://github.com/owner/app".to_string()));
+        assert_eq!(
+            feed.items.iter().map(|i| &i.title).collect::<Vec<_>>(),
+            vec!["1.0.1", "1.0.0"]
+        );
+        assert_eq!(
+            feed.items[0].link,
+            Some("https://github.com/owner/app/compare/v1.0.0...v1.0.1".to_string())
+        );
+        assert_eq!(
+            feed.items[0].html,
+            "<h3>Fixed</h3>\n<ul>\n<li>crash</li>\n</ul>\n"
+        );
 
-### Added
-- Initial release
-"#;
+        let feed = changelog
+            .feed(None, Some("https://example.com/changelog"))
+            .unwrap();
+        assert!(feed.title.ends_with(" releases"));
+        assert_eq!(
+            feed.items[1].id,
+            "https://example.com/changelog#100---2025-01-01"
+        );
+    }
 
-        let parser = Parser::new();
-        let changelog = parser.parse(input).unwrap();
-        let markdown = changelog_to_markdown(&changelog, input, None);
+    #[test]
+    fn test_page() {
+        set_test_github_repo(Some("owner".to_string()), Some("app".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# App *changes*\n\nEverything new.\n\n## [Unreleased]\n\n## [1.1.0] - 2025-02-01\n\n### Added\n\n- export\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/app/compare/v1.1.0...HEAD\n[1.1.0]: https://github.com/owner/app/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/owner/app/releases/tag/v1.0.0\n",
+        )
+        .unwrap();
+
+        let page = changelog.page(&[]).unwrap();
+        assert_eq!(page.title, "App changes");
+        assert_eq!(
+            page.header,
+            "<h1>App <em>changes</em></h1>\n<p>Everything new.</p>\n"
+        );
+        assert_eq!(
+            page.releases
+                .iter()
+                .map(|r| r.anchor.as_str())
+                .collect::<Vec<_>>(),
+            ["110---2025-02-01", "100---2025-01-01"]
+        );
+        assert!(page.releases[0].html.starts_with(
+            "<h2 id=\"110---2025-02-01\"><a href=\"https://github.com/owner/app/compare/v1.0.0...v1.1.0\">1.1.0</a>"
+        ));
 
-        assert_eq!(markdown, expected);
+        let manifest = changelog.manifest().unwrap();
+        assert_eq!(manifest.header, "# App *changes*\n\nEverything new.");
+        assert_eq!(
+            manifest
+                .releases
+                .iter()
+                .map(|r| r.markdown.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "## [Unreleased]\n",
+                "## [1.1.0] - 2025-02-01\n\n### Added\n\n- export\n",
+                "## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n"
+            ]
+        );
+        // The oldest release's notes leave the file's link definitions behind
+        assert_eq!(manifest.releases[2].entry.notes, "### Added\n\n- first");
+
+        let page = changelog.page(&["1.0.0".to_string()]).unwrap();
+        assert_eq!(page.releases.len(), 1);
+        assert!(matches!(
+            changelog.page(&["2.0.0".to_string()]),
+            Err(Error::VersionNotFound(_))
+        ));
     }
 
     #[test]
-    fn test_add_entry_to_section() {
+    fn test_backport_creates_maintenance_release() {
         set_test_github_repo(None, None);
         let temp_dir = TempDir::new().unwrap();
         let temp_path = temp_dir.path().join("CHANGELOG.md");
 
-        // Create initial changelog
         fs::write(
             &temp_path,
             r#"# Changelog
 
 ## [Unreleased]
 
-### Added
+### Fixed
 
-- one
-- two
+- crash when config is missing
 
-### Changed
+## [2.0.0] - 2025-03-01
 
-- changed
+### Added
 
-## [1.0.0] - 2000-01-01
+- big
 
-### Added
+## [1.8.2] - 2025-02-01
 
-- something
+### Fixed
+
+- old
 "#,
         )
         .unwrap();
@@ -1265,110 +9329,479 @@ All notable changes to this project will be documented in this file.
             path: temp_path.into(),
         };
 
-        // Add new entry
         changelog
-            .add("three", &ChangeType::Added, None, false)
+            .backport("CONFIG", "1.8.3", None, Some("2025-04-02"))
             .unwrap();
 
-        // Verify result
         let content = fs::read_to_string(&changelog.path).unwrap();
         let expected = r#"# Changelog
 
 ## Unreleased
 
+### Fixed
+
+- crash when config is missing
+
+## 2.0.0 - 2025-03-01
+
 ### Added
 
-- one
-- two
-- three
+- big
 
-### Changed
+## 1.8.3 - 2025-04-02
 
-- changed
+### Fixed
 
-## 1.0.0 - 2000-01-01
+- crash when config is missing
 
-### Added
+## 1.8.2 - 2025-02-01
 
-- something
+### Fixed
+
+- old
 "#;
         assert_eq!(content, expected);
+
+        // Backporting the same entry again is refused
+        assert!(changelog.backport("config", "1.8.3", None, None).is_err());
     }
 
-    #[test]
-    fn test_preserve_original_header_custom() {
-        let input = r#"Custom Header Line 1
-Custom Header Line 2
+    /// A changelog written any of the ways people write them: headings with
+    /// or without brackets, dates, and markers, sections in any case, either
+    /// bullet, and links for any forge or none
+    fn any_changelog() -> impl proptest::strategy::Strategy<Value = String> {
+        use proptest::prelude::*;
 
-## [Unreleased]
+        let entry = (
+            "[-*]",
+            "(\\*\\*[a-z]{1,5}\\*\\*: ?)?[a-zA-Z`{][a-z {}:0-9`]{0,16}",
+            prop::bool::ANY,
+        )
+            .prop_map(|(bullet, text, wrapped)| match wrapped {
+                true => format!("{} {}\n  continued", bullet, text),
+                false => format!("{} {}", bullet, text),
+            });
+        let section = (
+            prop::sample::select(vec![
+                "Added", "added", "Fixed", "FIXED", "Security", "Changed", "Removed", "Mystery",
+            ]),
+            prop::collection::vec(entry, 0..3),
+        )
+            .prop_map(|(name, entries)| format!("### {}\n\n{}", name, entries.join("\n")));
+        let release = (
+            prop::bool::ANY,
+            prop::option::of("20[0-9]{2}-0[1-9]-[12][0-9]"),
+            prop::bool::ANY,
+            prop::option::of("re-released [a-z]{1,8}"),
+            prop::collection::vec(section, 0..3),
+        );
+        let forge = prop::sample::select(vec![
+            "",
+            "https://github.com/owner/app/compare/v1.0.0...HEAD",
+            "https://gitlab.com/group/app/-/compare/v1.0.0...HEAD",
+            "https://codeberg.org/owner/app/compare/v1.0.0...HEAD",
+        ]);
+
+        (
+            prop::option::of("[A-Z][a-z ]{0,20}"),
+            prop::option::of(prop::bool::ANY),
+            prop::collection::vec(release, 0..4),
+            forge,
+            "\n{0,3}",
+        )
+            .prop_map(|(intro, unreleased, releases, link, gap)| {
+                let mut content = String::from("# Changelog\n\n");
+                if let Some(intro) = intro {
+                    content.push_str(&format!("{}\n\n", intro));
+                }
+                if let Some(bracketed) = unreleased {
+                    let title = if bracketed {
+                        "[Unreleased]"
+                    } else {
+                        "Unreleased"
+                    };
+                    content.push_str(&format!("## {}\n\n", title));
+                }
+                let count = releases.len();
+                for (i, (bracketed, date, yanked, note, sections)) in
+                    releases.into_iter().enumerate()
+                {
+                    let version = format!("1.{}.0", count - i);
+                    let mut title = if bracketed {
+                        format!("[{}]", version)
+                    } else {
+                        version
+                    };
+                    if let Some(date) = date {
+                        title.push_str(&format!(" - {}", date));
+                    }
+                    title = notes::set_annotation(&title, note.as_deref());
+                    title = notes::set_yanked(&title, yanked);
+                    content.push_str(&format!("## {}\n{}", title, gap));
+                    for section in sections {
+                        content.push_str(&format!("\n{}\n{}", section, gap));
+                    }
+                    content.push('\n');
+                }
+                if !link.is_empty() {
+                    content.push_str(&format!("\n[Unreleased]: {}\n", link));
+                }
+                content
+            })
+    }
 
-### Added
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_canonicalize_is_idempotent(
+            content in any_changelog(),
+            shortcuts in proptest::prelude::any::<bool>(),
+            template in proptest::prelude::any::<bool>(),
+        ) {
+            let mut config = config::Config::default();
+            if template {
+                config.entries.template =
+                    Some(Template::try_from("**{scope}**: {description}".to_string()).unwrap());
+            }
+            if shortcuts {
+                config.links.shortcuts.insert(
+                    "rfc".to_string(),
+                    "https://www.rfc-editor.org/rfc/rfc{id}".to_string(),
+                );
+            }
+            if let Ok(once) = canonicalize(&content, &config) {
+                let twice = canonicalize(&once, &config).unwrap();
+                proptest::prop_assert_eq!(twice, once);
+            }
+        }
+    }
 
-- entry
-"#;
-        let parser = Parser::new();
-        let changelog = parser.parse(input).unwrap();
-        let markdown = changelog_to_markdown(&changelog, input, None);
-        assert!(markdown.contains("Custom Header Line 1"));
-        assert!(markdown.contains("Custom Header Line 2"));
+    #[test]
+    fn test_duplicate_entry() {
+        set_test_github_repo(None, None);
+        let config = config::Config::default();
+        let content = "# Changelog\n\n## Unreleased\n\n### Fixed\n\n- Crash on startup ([#12](https://github.com/owner/repo/pull/12))\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- export to CSV\n";
+        let duplicate = |description: &str, r#type: &ChangeType, version: Option<&str>| {
+            duplicate_entry(content, description, r#type, version, &config).unwrap()
+        };
+
+        assert_eq!(
+            duplicate("crash on startup (#12)", &ChangeType::Fixed, None).as_deref(),
+            Some("Crash on startup ([#12](https://github.com/owner/repo/pull/12))")
+        );
+        assert_eq!(
+            duplicate("crash on startup", &ChangeType::Fixed, None),
+            None
+        );
+        assert_eq!(
+            duplicate("crash on startup (#12)", &ChangeType::Added, None),
+            None
+        );
+        assert_eq!(
+            duplicate("Export to `csv`", &ChangeType::Added, Some("1.0.0")).as_deref(),
+            Some("export to CSV")
+        );
+        assert_eq!(duplicate("export to CSV", &ChangeType::Added, None), None);
     }
 
     #[test]
-    fn test_add_entry_creates_missing_section() {
+    fn test_add_grouped_entry() {
         set_test_github_repo(None, None);
-        let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path().join("CHANGELOG.md");
+        let config = config::Config::default();
+        let content =
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        let add = |content: &str, description: &str, scope: &str| {
+            add_grouped_entry(
+                content,
+                description,
+                scope,
+                &ChangeType::Added,
+                None,
+                &config,
+            )
+            .unwrap()
+        };
 
-        // Create initial changelog without Added section
-        fs::write(
-            &temp_path,
-            r#"# Changelog
+        let content = add(content, "pagination", "api");
+        let content = add(&content, "--json flag", "cli");
+        let content = add(&content, "rate limits", "api");
+        assert_eq!(
+            content,
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- **api:**\n  - pagination\n  - rate limits\n- **cli:**\n  - --json flag\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n"
+        );
+    }
 
-## [Unreleased]
+    #[test]
+    fn test_add_entry_to_content() {
+        let content =
+            "# Changelog\n\n## Unreleased\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n";
+        let config = config::Config::default();
+        assert_eq!(
+            add_entry(content, "crash", &ChangeType::Fixed, None, &config).unwrap(),
+            "# Changelog\n\n## Unreleased\n\n### Fixed\n\n- crash\n\n## 1.0.0 - 2025-01-01\n\n### Added\n\n- first\n"
+        );
+        assert!(matches!(
+            add_entry(
+                content,
+                "crash",
+                &ChangeType::Custom("nope".to_string()),
+                None,
+                &config
+            ),
+            Err(Error::InvalidInput(_))
+        ));
 
-### Changed
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let mut config = config::Config::default();
+        config.links.autolink = true;
+        let added = add_entry(
+            content,
+            &escape_entry("#12 crash"),
+            &ChangeType::Fixed,
+            None,
+            &config,
+        )
+        .unwrap();
+        assert!(added.contains("\n- [#12](https://github.com/owner/repo/issues/12) crash\n"));
+    }
 
-- something changed
+    #[test]
+    fn test_insert_entry_spacing() {
+        set_test_github_repo(None, None);
+        let content =
+            "# Changelog\n\n## Unreleased\n\n### Added\n\n- first\n\n\n### Removed\n\n- old\n";
+        let mut changelog = parse_releases(content).unwrap();
+        let order = ChangeType::BUILT_IN;
+        // New sections are one blank line apart from their neighbours, however
+        // far apart those were
+        insert_entry(&mut changelog, "tweak", &ChangeType::Changed, None, order).unwrap();
+        assert!(changelog["Unreleased"]
+            .notes
+            .starts_with("### Added\n\n- first\n\n### Changed\n"));
+        insert_entry(&mut changelog, "second", &ChangeType::Added, None, order).unwrap();
+        insert_entry(&mut changelog, "third", &ChangeType::Removed, None, order).unwrap();
+        insert_entry(&mut changelog, "crash", &ChangeType::Fixed, None, order).unwrap();
+        assert_eq!(
+            changelog["Unreleased"].notes,
+            "### Added\n\n- first\n- second\n\n### Changed\n\n- tweak\n\n### Removed\n\n- old\n- third\n\n### Fixed\n\n- crash"
+        );
+    }
 
-## [1.0.0] - 2000-01-01
+    #[test]
+    fn test_fmt_check() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        let formatted = "# Changelog\n\n## Unreleased\n\n### Added\n\n- first\n";
+        fs::write(&changelog.path, formatted.replace("- first", "- first  ")).unwrap();
 
-### Added
+        let diff = changelog.fmt_check(false, None).unwrap().unwrap();
+        assert!(diff.contains("\n-- first  \n+- first\n"));
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            formatted.replace("- first", "- first  ")
+        );
 
-- something
-"#,
+        changelog.fmt(false, None).unwrap();
+        assert_eq!(fs::read_to_string(&changelog.path).unwrap(), formatted);
+        assert_eq!(changelog.fmt_check(false, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fmt_sort() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n- next\n\n\
+             ## [1.0.0] - 2025-01-01\n\n- first\n\n\
+             ## [1.1.0] - 2025-03-01\n\n- second\n\n\
+             ## [1.1.0-rc.1] - 2025-02-01\n\n- candidate\n\n\
+             ## [2.0] - 2025-04-01\n\n- odd one\n\n\
+             [unreleased]: https://github.com/owner/repo/compare/v1.1.0...HEAD\n\
+             [1.1.0]: https://github.com/owner/repo/compare/v1.1.0-rc.1...v1.1.0\n",
         )
         .unwrap();
 
+        assert!(changelog.fmt_check(true, None).unwrap().is_some());
+        changelog.fmt(true, None).unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        let versions: Vec<String> = parse_releases(&content).unwrap().into_keys().collect();
+        assert_eq!(
+            versions,
+            ["Unreleased", "2.0", "1.1.0", "1.1.0-rc.1", "1.0.0"]
+        );
+        assert!(content.ends_with(
+            "- first\n\n\
+             [Unreleased]: https://github.com/owner/repo/compare/v2.0...HEAD\n\
+             [2.0]: https://github.com/owner/repo/compare/v1.1.0...v2.0\n\
+             [1.1.0]: https://github.com/owner/repo/compare/v1.1.0-rc.1...v1.1.0\n\
+             [1.1.0-rc.1]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0-rc.1\n\
+             [1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n"
+        ));
+        assert_eq!(changelog.fmt_check(true, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_line_endings() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
         let changelog = Changelog {
-            path: temp_path.into(),
+            path: temp_dir.path().join("CHANGELOG.md").into(),
         };
+        fs::write(
+            &changelog.path,
+            "\u{feff}# Changelog\r\n\r\n## Unreleased\r\n\r\n### Added\r\n\r\n- first\r\n",
+        )
+        .unwrap();
 
-        // Add new entry that requires Added section
-        changelog
-            .add("new feature", &ChangeType::Added, None, false)
-            .unwrap();
+        changelog.add("second", &ChangeType::Added, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "\u{feff}# Changelog\r\n\r\n## Unreleased\r\n\r\n### Added\r\n\r\n- first\r\n- second\r\n"
+        );
+        assert_eq!(changelog.fmt_check(false, None).unwrap(), None);
+        assert!(changelog
+            .fmt_check(false, Some(LineEnding::Lf))
+            .unwrap()
+            .unwrap()
+            .contains("CRLF line endings rather than LF"));
+
+        changelog.fmt(false, Some(LineEnding::Lf)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&changelog.path).unwrap(),
+            "\u{feff}# Changelog\n\n## Unreleased\n\n### Added\n\n- first\n- second\n"
+        );
+        assert_eq!(
+            changelog.fmt_check(false, Some(LineEnding::Lf)).unwrap(),
+            None
+        );
+    }
 
-        // Verify result
-        let content = fs::read_to_string(&changelog.path).unwrap();
-        let expected = r#"# Changelog
+    #[test]
+    fn test_diff() {
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- next\n\n\
+             ## [2.0.0] - 2025-03-01\n\n### Removed\n\n- old api\n\n### fixed\n\n- crash\n\n\
+             ## [1.3.0] - 2025-02-01\n\n### Fixed\n\n- typo\n\n### Added\n\n- new api\n  with details\n\n\
+             ## [1.2.0] - 2025-01-01\n\n### Added\n\n- first\n",
+        )
+        .unwrap();
 
-## Unreleased
+        let diff = changelog.diff("1.2.0", "2.0.0", false, false).unwrap();
+        assert_eq!(diff.versions, ["2.0.0", "1.3.0"]);
+        assert_eq!(
+            diff.markdown(),
+            "### Added\n\n- new api\n  with details\n\n### Removed\n\n- old api\n\n### Fixed\n\n- crash\n- typo\n"
+        );
 
-### Added
+        let diff = changelog.diff("1.2.0", "latest", true, true).unwrap();
+        assert_eq!(diff.versions, ["1.3.0", "1.2.0"]);
+        assert_eq!(diff.sections[0].entries, ["new api\nwith details", "first"]);
 
-- new feature
+        assert_eq!(
+            changelog
+                .diff("2.0.0", "unreleased", false, false)
+                .unwrap()
+                .versions,
+            ["Unreleased"]
+        );
+        assert!(matches!(
+            changelog.diff("2.0.0", "1.2.0", false, false),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            changelog.diff("1.0.0", "2.0.0", false, false),
+            Err(Error::VersionNotFound(_))
+        ));
+    }
 
-### Changed
+    #[test]
+    fn test_version_entries() {
+        set_test_github_repo(None, None);
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## Unreleased\n\n## 1.3.0 - 2025-02-01\n\n### Added\n\n- export\n\n\
+             ## 1.2.1 - 2025-01-15\n\n### Fixed\n\n- crash\n\n## 1.2.0 - 2025-01-01\n\n- first\n",
+        )
+        .unwrap();
 
-- something changed
+        let versions = |from, to| -> Vec<String> {
+            changelog
+                .version_entries(from, to)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.version)
+                .collect()
+        };
+        assert_eq!(versions("v1.2.0", "unreleased"), ["1.3.0", "1.2.1"]);
+        assert_eq!(versions("1.2.1", "latest"), ["1.3.0"]);
+        assert!(versions("latest", "unreleased").is_empty());
 
-## 1.0.0 - 2000-01-01
+        changelog.add("import", &ChangeType::Added, None).unwrap();
+        let entries = changelog.version_entries("1.2.1", "unreleased").unwrap();
+        assert_eq!(
+            entries[0].markdown(),
+            "## Unreleased\n\n### Added\n\n- import\n"
+        );
+        assert_eq!(entries[1].version, "1.3.0");
+        assert!(matches!(
+            changelog.version_entries("latest", "1.2.0"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
 
-### Added
+    #[test]
+    fn test_workspace_map() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        root.init().unwrap();
+        for i in 0..12 {
+            let package = Changelog::at(
+                &temp_dir
+                    .path()
+                    .join(format!("packages/p{:02}/CHANGELOG.md", i)),
+            );
+            fs::create_dir_all(package.dir()).unwrap();
+            package.init().unwrap();
+        }
+        fs::write(
+            temp_dir.path().join("packages/p07/CHANGELOG.md"),
+            "# Changelog\n\n## [1.0.0] - 2025-01-01\n\n### Stuff\n\n- first\n",
+        )
+        .unwrap();
 
-- something
-"#;
-        assert_eq!(content, expected);
+        let results = root.workspace_map(Changelog::lint);
+        assert_eq!(
+            results
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            root.workspace_list()
+        );
+        let dirty: Vec<_> = results
+            .iter()
+            .filter(|(_, violations)| !violations.as_ref().unwrap().is_empty())
+            .map(|(path, _)| path.to_str().unwrap())
+            .collect();
+        assert_eq!(dirty, ["packages/p07/CHANGELOG.md"]);
     }
 
     #[test]
@@ -1411,8 +9844,7 @@ Custom Header Line 2
 [1.0.0]: //incorrect/link
 [0.9.0]: //incorrect/link
 "#;
-        let parser = parse_changelog::Parser::new();
-        let changelog = parser.parse(input).unwrap();
+        let changelog = parse_releases(input).unwrap();
         let markdown = changelog_to_markdown(&changelog, input, None);
 
         // Verify the markdown link definitions are removed and regenerated correctly
@@ -1424,6 +9856,163 @@ Custom Header Line 2
         assert!(!markdown.contains("[0.9.0]:")); // Versions not in changelog should be removed
     }
 
+    #[test]
+    fn test_prerelease_links() {
+        let github = forge::for_remote(
+            Remote::from_url("https://github.com/owner/repo", None).unwrap(),
+            &config::HttpConfig::default(),
+        );
+        let versions = [
+            "Unreleased",
+            "2.0.0",
+            "1.9.1",
+            "2.0.0-rc.2",
+            "2.0.0-rc.1",
+            "1.9.0",
+        ];
+        let links = |config: &config::LinksConfig| {
+            (0..versions.len())
+                .map(|i| {
+                    version_link_url(
+                        github.as_ref(),
+                        &versions,
+                        i,
+                        config,
+                        "v",
+                        &RepoRefs::default(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            links(&config::LinksConfig::default()),
+            vec![
+                "https://github.com/owner/repo/compare/v2.0.0...HEAD",
+                "https://github.com/owner/repo/compare/v2.0.0-rc.2...v2.0.0",
+                "https://github.com/owner/repo/compare/v1.9.0...v1.9.1",
+                "https://github.com/owner/repo/compare/v2.0.0-rc.1...v2.0.0-rc.2",
+                "https://github.com/owner/repo/compare/v1.9.0...v2.0.0-rc.1",
+                "https://github.com/owner/repo/releases/tag/v1.9.0",
+            ]
+        );
+        let stable = config::LinksConfig {
+            final_compare: config::FinalCompare::Stable,
+            ..Default::default()
+        };
+        assert_eq!(
+            links(&stable)[1],
+            "https://github.com/owner/repo/compare/v1.9.1...v2.0.0"
+        );
+    }
+
+    #[test]
+    fn test_footer_round_trip() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let content = "# Changelog\n\n<!-- badges -->\n[![ci](https://example.com/ci.svg)](https://example.com/ci)\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n## Older releases\n\nSee the [archive].\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n[archive]: https://example.com/archive\n\n<!-- generated by a script -->\n";
+        let config = config::Config::default();
+        assert_eq!(format(content, &config).unwrap(), content);
+
+        let added = add_entry(content, "crash", &ChangeType::Fixed, None, &config).unwrap();
+        assert_eq!(
+            added,
+            content.replace(
+                "## [Unreleased]\n",
+                "## [Unreleased]\n\n### Fixed\n\n- crash\n"
+            )
+        );
+
+        // A trailer after the definitions stays last when a release is cut
+        let mut releases = parse_releases(content).unwrap();
+        cut_release(
+            &mut releases,
+            "1.1.0",
+            Some("2025-02-01"),
+            None,
+            None,
+            &config::ReleaseConfig::default(),
+        )
+        .unwrap();
+        let released = changelog_to_markdown(&releases, content, None);
+        assert!(released.ends_with(
+            "## Older releases\n\nSee the [archive].\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.1.0...HEAD\n[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n[archive]: https://example.com/archive\n\n<!-- generated by a script -->\n"
+        ));
+        set_test_github_repo(None, None);
+    }
+
+    #[test]
+    fn test_links_without_tags() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - 2025-02-01\n\n### Added\n\n- second\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n";
+        let links = |content: &str| {
+            let releases = parse_releases(content).unwrap();
+            let markdown = changelog_to_markdown(&releases, content, None);
+            markdown
+                .lines()
+                .filter(|line| line.contains("]: "))
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        set_test_tags(Some(&["v1.0.0"]));
+        assert_eq!(
+            links(content),
+            [
+                "[Unreleased]: https://github.com/owner/repo/commits/main",
+                "[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0",
+                "[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0",
+            ]
+        );
+        set_test_tags(Some(&[]));
+        assert_eq!(
+            links(content)[1],
+            "[1.1.0]: https://github.com/owner/repo/releases/tag/v1.1.0"
+        );
+        assert_eq!(
+            links("# Changelog\n\n## [Unreleased]\n"),
+            ["[Unreleased]: https://github.com/owner/repo/commits/main"]
+        );
+
+        // Unknown tags are taken to exist
+        set_test_tags(None);
+        assert_eq!(
+            links(content)[0],
+            "[Unreleased]: https://github.com/owner/repo/compare/v1.1.0...HEAD"
+        );
+        set_test_github_repo(None, None);
+    }
+
+    #[test]
+    fn test_release_links_to_its_tag() {
+        set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
+        set_test_tags(Some(&["v1.0.0"]));
+        let temp_dir = TempDir::new().unwrap();
+        let changelog = Changelog {
+            path: temp_dir.path().join("CHANGELOG.md").into(),
+        };
+        fs::write(
+            &changelog.path,
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- second\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- first\n\n[Unreleased]: https://github.com/owner/repo/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n",
+        )
+        .unwrap();
+
+        changelog
+            .release("minor", Some("2025-02-01"), None, None, None)
+            .unwrap();
+        let content = fs::read_to_string(&changelog.path).unwrap();
+        assert!(content.ends_with(
+            "[Unreleased]: https://github.com/owner/repo/compare/v1.1.0...HEAD\n[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0\n"
+        ));
+
+        // Once it's tagged, the links are already what fmt writes
+        set_test_tags(Some(&["v1.0.0", "v1.1.0"]));
+        assert_eq!(changelog.fmt_check(false, None).unwrap(), None);
+        let tags = ["v1.0.0".to_string(), "v1.1.0".to_string()].into();
+        assert!(lint::missing_tags(&content, &tags, &config::Config::default()).is_empty());
+        set_test_tags(None);
+        set_test_github_repo(None, None);
+    }
+
     #[test]
     fn test_update_incorrect_links() {
         set_test_github_repo(Some("owner".to_string()), Some("repo".to_string()));
@@ -1442,8 +10031,7 @@ Custom Header Line 2
 [Unreleased]: //incorrect/link
 [1.0.0]: //incorrect/link
 "#;
-        let parser = parse_changelog::Parser::new();
-        let changelog = parser.parse(input).unwrap();
+        let changelog = parse_releases(input).unwrap();
         let markdown = changelog_to_markdown(&changelog, input, None);
         let expected = r#"# Changelog
 
@@ -1493,16 +10081,16 @@ Custom Header Line 2
 
         // Add new entry - this should not break multiline entries
         changelog
-            .add("new single line entry", &ChangeType::Added, None, false)
+            .add("new single line entry", &ChangeType::Added, None)
             .unwrap();
 
         // Verify result - multiline entries should be preserved
         let content = fs::read_to_string(&changelog.path).unwrap();
-        
+
         // The multiline entry should still exist with proper indentation
         assert!(content.contains("- this entry\n  has multiple lines"));
         assert!(content.contains("- new single line entry"));
-        
+
         // Verify the structure is still intact
         let parser = Parser::new();
         let parsed = parser.parse(&content).unwrap();
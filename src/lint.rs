@@ -0,0 +1,434 @@
+use crate::config::Config;
+use crate::versioning::Scheme;
+use crate::{is_unreleased, notes, parser, release_key, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A single problem found while linting a changelog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    pub rule: String,
+    pub severity: Severity,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub message: String,
+}
+
+impl Violation {
+    pub(crate) fn new(
+        rule: &'static str,
+        severity: Severity,
+        version: Option<&str>,
+        message: impl Into<String>,
+    ) -> Self {
+        Violation {
+            rule: rule.to_string(),
+            severity,
+            version: version.map(String::from),
+            message: message.into(),
+        }
+    }
+}
+
+/// The violations found in one of several files checked at once
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub path: String,
+    pub violations: Vec<Violation>,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.severity, self.rule)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Check a changelog against the Keep a Changelog conventions, allowing the
+/// custom sections and version scheme in `config`
+pub fn lint(content: &str, config: &Config) -> Vec<Violation> {
+    let scheme = &config.release.scheme;
+    let date_format = &config.release.date_format;
+    let mut violations = Vec::new();
+    let releases: Vec<_> = parser().parse_iter(content).collect();
+
+    if !releases
+        .iter()
+        .any(|r| release_key(r.version, r.title) == "Unreleased")
+    {
+        violations.push(Violation::new(
+            "missing-unreleased",
+            Severity::Warning,
+            None,
+            "no Unreleased section found",
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut previous: Option<&str> = None;
+    for release in &releases {
+        let key = release_key(release.version, release.title);
+        if !seen.insert(key) {
+            violations.push(Violation::new(
+                "duplicate-version",
+                Severity::Error,
+                Some(key),
+                "version appears more than once",
+            ));
+        }
+
+        for section in notes::parse_sections(release.notes) {
+            if config.sections.resolve(&section.name).is_none() {
+                violations.push(Violation::new(
+                    "unknown-section",
+                    Severity::Warning,
+                    Some(key),
+                    format!("unknown section '{}'", section.name),
+                ));
+            }
+        }
+
+        if is_unreleased(key) {
+            continue;
+        }
+
+        if scheme.is_valid(key) {
+            if let Some(prev_key) = previous {
+                if scheme.compare(key, prev_key) != Some(std::cmp::Ordering::Less) {
+                    violations.push(Violation::new(
+                        "version-order",
+                        Severity::Error,
+                        Some(key),
+                        format!(
+                            "listed after {} but is not older, `changelog fmt --sort` reorders releases",
+                            prev_key
+                        ),
+                    ));
+                }
+            }
+            previous = Some(key);
+        } else {
+            violations.push(Violation::new(
+                "invalid-version",
+                Severity::Error,
+                Some(key),
+                match scheme {
+                    Scheme::Semver => "version is not valid semver".to_string(),
+                    calver => format!("version does not match {}", calver),
+                },
+            ));
+        }
+
+        match notes::release_date(release.title) {
+            Some(date) if date_format.parse(date).is_none() => violations.push(Violation::new(
+                "date-format",
+                Severity::Error,
+                Some(key),
+                format!("date '{}' is not in {} format", date, date_format),
+            )),
+            Some(_) => {}
+            None => violations.push(Violation::new(
+                "missing-date",
+                Severity::Warning,
+                Some(key),
+                "release has no date",
+            )),
+        }
+
+        if notes::parse_sections(release.notes)
+            .iter()
+            .all(|s| s.entries.is_empty())
+        {
+            violations.push(Violation::new(
+                "empty-release",
+                Severity::Warning,
+                Some(key),
+                "release has no entries",
+            ));
+        }
+    }
+
+    violations.extend(lint_links(content, &releases, scheme));
+    violations
+}
+
+fn lint_links(
+    content: &str,
+    releases: &[parse_changelog::Release],
+    scheme: &Scheme,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let definitions: Vec<&str> = content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start().strip_prefix('[')?;
+            let (label, rest) = line.split_once("]:")?;
+            (!rest.trim().is_empty()).then_some(label)
+        })
+        .collect();
+    let keys: HashSet<&str> = releases
+        .iter()
+        .map(|r| release_key(r.version, r.title))
+        .collect();
+
+    for release in releases {
+        let key = release_key(release.version, release.title);
+        // Inline links like `[1.2.0](url)` need no definition
+        let (written, _) = notes::split_title(release.title);
+        if written.starts_with('[')
+            && written.ends_with(']')
+            && !definitions.iter().any(|d| d.eq_ignore_ascii_case(key))
+        {
+            violations.push(Violation::new(
+                "undefined-link",
+                Severity::Warning,
+                Some(key),
+                "heading is a link reference without a definition",
+            ));
+        }
+    }
+
+    for label in definitions {
+        let looks_like_version = label == "Unreleased" || scheme.is_valid(label);
+        if looks_like_version && !keys.contains(label) {
+            violations.push(Violation::new(
+                "stale-link",
+                Severity::Warning,
+                Some(label),
+                "link definition for a version that is not in the changelog",
+            ));
+        }
+    }
+
+    violations
+}
+
+/// Version links in `content` that point at tags missing from `tags`, the
+/// repository's, like a compare link from a release that was never tagged
+pub fn missing_tags(content: &str, tags: &BTreeSet<String>, config: &Config) -> Vec<Violation> {
+    let prefix = crate::tag_prefix(&config.release);
+    let links = content.lines().filter_map(|line| {
+        let (label, url) = match line.strip_prefix("## [") {
+            Some(heading) => {
+                let (label, rest) = heading.split_once("](")?;
+                (label, rest.split_once(')')?.0)
+            }
+            None => line.trim_start().strip_prefix('[')?.split_once("]:")?,
+        };
+        let looks_like_version = label == "Unreleased" || config.release.scheme.is_valid(label);
+        looks_like_version.then_some((label, url.trim()))
+    });
+
+    let mut violations = Vec::new();
+    for (label, url) in links {
+        // Refs are in the path, alone or on either side of a compare
+        let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let refs = path
+            .split('/')
+            .skip(1)
+            .flat_map(|segment| segment.split("..."))
+            .flat_map(|segment| segment.split("%0D"));
+        for tag in refs {
+            let is_tag = tag
+                .strip_prefix(prefix)
+                .is_some_and(|version| config.release.scheme.is_valid(version));
+            if is_tag && !tags.contains(tag) {
+                violations.push(Violation::new(
+                    "missing-tag",
+                    Severity::Warning,
+                    Some(label),
+                    format!(
+                        "link points at {}, which is not a tag in the repository",
+                        tag
+                    ),
+                ));
+            }
+        }
+    }
+    violations
+}
+
+/// Run a lint plugin from `dir`, handing it `model` as JSON on stdin and
+/// reading its findings back from stdout as a JSON list of violations.
+/// Plugins given as a path are found relative to `dir`, others on `PATH`.
+/// A plugin may exit non-zero when it has findings; only output that isn't
+/// a list of violations counts as the plugin failing.
+pub(crate) fn run_plugin(plugin: &str, dir: &Path, model: &str) -> Result<Vec<Violation>> {
+    let failed = |message: String| Error::Plugin {
+        plugin: plugin.to_string(),
+        message,
+    };
+    let program = if plugin.contains(['/', '\\']) {
+        dir.join(plugin)
+    } else {
+        plugin.into()
+    };
+    let mut child = Command::new(program)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+
+    // Write from another thread so a plugin that answers before reading
+    // everything can't deadlock with us on full pipes
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let model = model.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(model.as_bytes()));
+    let output = child.wait_with_output()?;
+    match writer.join() {
+        // Plugins that don't need the model may exit without reading it
+        Ok(Err(e)) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+        _ => {}
+    }
+
+    if output.stdout.iter().all(u8::is_ascii_whitespace) && output.status.success() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || stderr.trim().is_empty() {
+            failed(format!("{} ({})", e, output.status))
+        } else {
+            failed(stderr.trim().to_string())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(content: &str) -> Vec<String> {
+        lint(content, &Config::default())
+            .into_iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_clean_changelog() {
+        let content = r#"# Changelog
+
+## [Unreleased]
+
+## [1.1.0] - 2025-02-01
+
+### Added
+- Feature
+
+## [1.0.0] - 2025-01-01
+
+### Fixed
+- Bug
+
+[Unreleased]: https://github.com/owner/repo/compare/v1.1.0...HEAD
+[1.1.0]: https://github.com/owner/repo/compare/v1.0.0...v1.1.0
+[1.0.0]: https://github.com/owner/repo/releases/tag/v1.0.0
+"#;
+        assert!(lint(content, &Config::default()).is_empty());
+
+        let mut config = Config::default();
+        config.release.date_format = "%d.%m.%Y".to_string().try_into().unwrap();
+        let violations = lint(content, &config);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            violations[0].message,
+            "date '2025-02-01' is not in %d.%m.%Y format"
+        );
+        let content = content
+            .replace("2025-02-01", "01.02.2025")
+            .replace("2025-01-01", "01.01.2025");
+        assert!(lint(&content, &config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_reports_violations() {
+        let content = r#"# Changelog
+
+## 1.0.0 - 2025-01-01
+
+### Added
+- Feature
+
+## 1.1.0 - 01/02/2025
+
+### Misc
+- Something
+
+## 0.9.0 - 2024-12-01
+
+## 0.9.0 - 2024-12-01
+
+### Fixed
+- Bug
+
+[0.8.0]: https://example.com
+"#;
+        assert_eq!(
+            rules(content),
+            vec![
+                "missing-unreleased",
+                "unknown-section",
+                "version-order",
+                "date-format",
+                "empty-release",
+                "duplicate-version",
+                "version-order",
+                "stale-link",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_tags() {
+        let content = r#"# Changelog
+
+## [Unreleased]
+
+## [1.1.0](https://gitlab.com/owner/repo/-/compare/v1.0.0...v1.1.0) - 2025-02-01
+
+## [1.0.0] - 2025-01-01
+
+[Unreleased]: https://gitlab.com/owner/repo/-/commits/main
+[1.0.0]: https://gitlab.com/owner/repo/-/tags/v1.0.0
+[docs]: https://example.com/v2.0.0
+"#;
+        let tags: BTreeSet<String> = ["v1.0.0".to_string()].into();
+        let violations = missing_tags(content, &tags, &Config::default());
+        assert_eq!(
+            violations
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>(),
+            ["warning[missing-tag] 1.1.0: link points at v1.1.0, which is not a tag in the repository"]
+        );
+        assert_eq!(
+            missing_tags(content, &BTreeSet::new(), &Config::default()).len(),
+            3
+        );
+    }
+}
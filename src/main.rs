@@ -1,11 +1,108 @@
-use changelog::{ChangeType, Changelog};
-use clap::{CommandFactory, Parser, Subcommand};
+use changelog::config::WorkflowStep;
+use changelog::lint::{Report, Severity};
+use changelog::notify;
+use changelog::template::EntryFields;
+use changelog::{
+    escape_entry, queue_title, Bump, ChangeType, Changelog, Encoding, EntrySelector, Error, Hook,
+    LineEnding, Published, SectionChange, Suggestion, SyncStatus, TagStatus,
+};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Forge repository to link to and publish on instead of the git
+    /// remotes: owner/name on GitHub (or the forge in changelog.toml), or a URL
+    #[arg(long, global = true, alias = "repo-url", value_name = "OWNER/NAME")]
+    repo: Option<String>,
+    /// What release tags start with before the version, instead of the
+    /// `[release] tag_prefix` in changelog.toml or `v`
+    #[arg(long, global = true, value_name = "PREFIX")]
+    tag_prefix: Option<String>,
+    /// Read the changelog as it is at this branch, tag, or commit instead of
+    /// from the working tree. Only works with commands that don't change files.
+    #[arg(long, global = true, value_name = "REF")]
+    worktree: Option<String>,
+    /// Date releases by today's date in UTC instead of the local timezone,
+    /// like `[release] utc` in changelog.toml
+    #[arg(long, global = true)]
+    utc: bool,
+    /// When to color output: auto colors it for a terminal
+    #[arg(long, global = true, value_enum, default_value_t)]
+    color: ColorChoice,
+}
+
+/// Whether output is colored, like git's `--color`
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color output to a terminal, unless NO_COLOR is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Output format for commands that print changelog information
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum OutputFormat {
+    /// Human readable text (markdown for entries)
+    #[default]
+    #[value(name = "text", alias = "markdown")]
+    Text,
+    /// Structured JSON
+    #[value(name = "json")]
+    Json,
+}
+
+/// Output format for `env`
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum EnvFormat {
+    /// `export NAME='value'` lines for `eval`
+    #[default]
+    #[value(name = "shell")]
+    Shell,
+    /// Structured JSON
+    #[value(name = "json")]
+    Json,
+}
+
+/// Output format for a version's notes
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum EntryFormat {
+    /// Markdown, as written in the changelog
+    #[default]
+    #[value(name = "markdown", alias = "text")]
+    Markdown,
+    /// Plain text with markup and links stripped
+    #[value(name = "plain")]
+    Plain,
+    /// An HTML fragment
+    #[value(name = "html")]
+    Html,
+    /// Slack mrkdwn
+    #[value(name = "slack")]
+    Slack,
+    /// Structured JSON
+    #[value(name = "json")]
+    Json,
+}
+
+/// Syndication format for `export feed`
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum FeedFormat {
+    /// Atom 1.0
+    #[default]
+    #[value(name = "atom")]
+    Atom,
+    /// RSS 2.0
+    #[value(name = "rss")]
+    Rss,
 }
 
 #[derive(Subcommand)]
@@ -17,38 +114,546 @@ enum Commands {
     },
     /// Show changelog entry
     Entry {
-        /// Version to show (latest, unreleased, or specific version)
+        /// Version to show: latest, unreleased, a specific version, or a
+        /// partial one like 1.2 for the newest 1.2.x
+        #[arg(default_value = "unreleased")]
+        version: String,
+        /// Show the notes beside the commits in the version's range, marking
+        /// commits no entry mentions. With `--format json`, print them as JSON.
+        #[arg(long, conflicts_with = "anchor")]
+        compare: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: EntryFormat,
+        /// Print only the id of the version's heading, for linking to it
+        #[arg(long, conflicts_with = "format")]
+        anchor: bool,
+        /// Show every version after FROM up to and including TO, each under
+        /// its own heading, e.g. `1.2.0..latest` or `latest..unreleased`
+        #[arg(
+            long,
+            value_name = "FROM..TO",
+            conflicts_with_all = ["version", "compare", "anchor"]
+        )]
+        range: Option<String>,
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Show a version's notes with its commits, contributors, files changed,
+    /// and compare link
+    Explain {
+        /// Version to explain (latest, unreleased, or specific version)
         version: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Show every entry between two versions, grouped by section
+    Diff {
+        /// Version to start after, e.g. the one being upgraded from
+        from: String,
+        /// Version to end with (latest, unreleased, or specific version)
+        to: String,
+        /// Include the entries of `from` itself
+        #[arg(long)]
+        include_from: bool,
+        /// Leave out the entries of `to` itself
+        #[arg(long)]
+        exclude_to: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
     },
     /// Append a change to the unreleased section or specific version
+    ///
+    /// Prompts for the type, description, and an issue to link when they
+    /// aren't given, or with --interactive
     Add {
         /// Description of the change
-        description: String,
-        /// Type of change
-        #[arg(short, long, required = true)]
-        r#type: ChangeType,
+        description: Option<String>,
+        /// Type of change: added, changed, deprecated, removed, fixed, security,
+        /// their first letters, or a section from changelog.toml
+        #[arg(short, long)]
+        r#type: Option<ChangeType>,
+        /// Prompt for the entry, starting from any description and type given
+        #[arg(short, long)]
+        interactive: bool,
         /// Version to add the change to (defaults to unreleased)
         #[arg(short, long)]
         version: Option<String>,
+        /// Named unreleased queue to add the change to, e.g. "1.x"
+        #[arg(short, long, conflicts_with = "version")]
+        queue: Option<String>,
+        /// Add the description as markdown as-is instead of escaping it
+        #[arg(long)]
+        raw: bool,
+        /// Part of the project the change is in, for entry templates using {scope}
+        #[arg(long)]
+        scope: Option<String>,
+        /// Mark the entry as a breaking change, so `suggest` calls for a major release
+        #[arg(long)]
+        breaking: bool,
+        /// Credit the author of this pull request number
+        #[arg(long, value_name = "PR_NUMBER")]
+        credit: Option<u64>,
+        /// Credit the author of the pull request that contains this commit
+        #[arg(long, value_name = "SHA", conflicts_with = "credit")]
+        commit: Option<String>,
+        /// Add to markdown read from stdin and print the result, leaving files
+        /// alone
+        #[arg(long, requires_all = ["description", "type"], conflicts_with = "interactive")]
+        stdin: bool,
+        /// Add the entry even when the section already has one that says the same
+        #[arg(long)]
+        allow_duplicate: bool,
+    },
+    /// Delete an entry, picked by text, by --index, or interactively
+    Remove {
+        /// Text of the entry to remove
+        entry_match: Option<String>,
+        /// Number of the entry as listed by the interactive picker
+        #[arg(long, conflicts_with = "entry_match")]
+        index: Option<usize>,
+        /// Only consider entries of this type
+        #[arg(short, long)]
+        r#type: Option<ChangeType>,
+        /// Version the entry is in (defaults to unreleased)
+        #[arg(short, long)]
+        version: Option<String>,
+    },
+    /// Rewrite an entry, picked by text, by --index, or interactively
+    Edit {
+        /// Text of the entry to edit
+        entry_match: Option<String>,
+        /// Number of the entry as listed by the interactive picker
+        #[arg(long, conflicts_with = "entry_match")]
+        index: Option<usize>,
+        /// Only consider entries of this type
+        #[arg(short, long)]
+        r#type: Option<ChangeType>,
+        /// Version the entry is in (defaults to unreleased)
+        #[arg(short, long)]
+        version: Option<String>,
+        /// New description (opens your editor when omitted)
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Use the new description as markdown as-is instead of escaping it
+        #[arg(long, requires = "description")]
+        raw: bool,
     },
     /// Release a new version
     Release {
-        /// Version or change type (major, minor, patch) to release
+        /// Version or change type (major, minor, patch) to release, auto to
+        /// pick one from conventional commits, or stable to finalize the latest
+        /// pre-release. With a calendar version scheme, any change type
+        /// releases the next version for the release date.
+        #[arg(required_unless_present_any = ["amend", "workflow"])]
+        version_or_type: Option<String>,
+        /// Fold Unreleased into the latest release and re-date it, for a fix
+        /// found after cutting a release but before tagging it
+        #[arg(
+            long,
+            conflicts_with_all = ["version_or_type", "pre", "apply", "queue", "released_by"]
+        )]
+        amend: bool,
+        /// Release a pre-release with this label, e.g. `minor --pre rc` for
+        /// 1.3.0-rc.1, counting up on later calls
+        #[arg(long, value_name = "LABEL")]
+        pre: Option<String>,
+        /// With auto, release the suggested version instead of only showing it
+        #[arg(long)]
+        apply: bool,
+        /// Release date (defaults to today)
+        #[arg(short, long)]
+        date: Option<String>,
+        /// Named unreleased queue to release instead of the default Unreleased section
+        #[arg(short, long)]
+        queue: Option<String>,
+        /// Update the version in Cargo.toml, package.json, pyproject.toml, or the
+        /// files listed under [release] bump_files in changelog.toml
+        #[arg(long)]
+        bump_files: bool,
+        /// Commit CHANGELOG.md (and bumped files) as "chore: release vX.Y.Z"
+        #[arg(long)]
+        commit: bool,
+        /// Create an annotated vX.Y.Z tag with the release notes as its message
+        #[arg(long)]
+        tag: bool,
+        /// Credit this name in the release's attribution line instead of the
+        /// one from changelog.toml or the tagger
+        #[arg(long, value_name = "NAME")]
+        released_by: Option<String>,
+        /// Show the version, date, and changes the release would make without
+        /// writing, committing, or tagging anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Write the released version's notes, as used for the tag message,
+        /// to this file for release tooling to pick up
+        #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+        notes_out: Option<PathBuf>,
+        /// Release from start to finish: pick the version from conventional
+        /// commits unless one is given, bump version files, commit, tag, push,
+        /// and create the forge release
+        #[arg(long)]
+        workflow: bool,
+        /// Leave a step out of --workflow, on top of [release.workflow] skip
+        /// in changelog.toml
+        #[arg(long, value_enum, value_name = "STEP", requires = "workflow")]
+        skip: Vec<WorkflowStep>,
+    },
+    /// Print the latest and next versions, unreleased entry count, and repository for scripts
+    Env {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: EnvFormat,
+    },
+    /// Suggest the next version from conventional commits since the latest release
+    Suggest {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Print the change type suggested for a commit message or description
+    GuessType {
+        /// Commit message or description, or `-` to read it from stdin
+        text: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Show what the next release would look like without changing anything
+    PreviewRelease {
+        /// Version or change type (major, minor, patch, stable) to preview
         version_or_type: String,
+        /// Preview a pre-release with this label
+        #[arg(long, value_name = "LABEL")]
+        pre: Option<String>,
         /// Release date (defaults to today)
         #[arg(short, long)]
         date: Option<String>,
+        /// Named unreleased queue to preview instead of the default Unreleased section
+        #[arg(short, long)]
+        queue: Option<String>,
+        /// Name to credit in the release's attribution line
+        #[arg(long, value_name = "NAME")]
+        released_by: Option<String>,
+    },
+    /// Freeze Unreleased for a code freeze, so `add` refuses new entries until thawed
+    Freeze {
+        /// Send new entries to this release train queue instead of refusing them
+        #[arg(long, value_name = "QUEUE")]
+        redirect: Option<String>,
+    },
+    /// Lift a code freeze on Unreleased
+    Thaw,
+    /// Mark a release as yanked
+    Yank {
+        /// Version to yank
+        version: String,
+    },
+    /// Remove the yanked marker from a release
+    Unyank {
+        /// Version to restore
+        version: String,
+    },
+    /// Note a re-release or hotfix after a release's date
+    Annotate {
+        /// Version to annotate
+        version: String,
+        /// The note, e.g. "re-released 2025-01-03"
+        #[arg(required_unless_present = "clear")]
+        annotation: Option<String>,
+        /// Remove the version's note instead
+        #[arg(long, conflicts_with = "annotation")]
+        clear: bool,
+    },
+    /// Correct a released version's number, along with its version links
+    RenameVersion {
+        /// Version as it's written now, e.g. 1.2
+        from: String,
+        /// Version it should be, e.g. 1.2.0
+        to: String,
+    },
+    /// Merge a run of releases, like release candidates, into one version
+    Squash {
+        /// Releases to merge, oldest first, e.g. 2.0.0-rc.1..2.0.0-rc.4
+        range: String,
+        /// Version they become, e.g. 2.0.0
+        target: String,
+    },
+    /// Copy an entry into a maintenance release
+    Backport {
+        /// Text to search for in existing entries
+        entry_match: String,
+        /// Maintenance version to copy the entry into (created if missing)
+        #[arg(long)]
+        to: String,
+        /// Only look for the entry in this version
+        #[arg(long)]
+        from: Option<String>,
+        /// Release date used when creating the maintenance version (defaults to today)
+        #[arg(short, long)]
+        date: Option<String>,
     },
     /// Review commits and add them to changelog
     Review {
         /// Version to add changes to
         #[arg(short, long)]
         version: Option<String>,
+        /// Credit the pull request, author, and co-authors of each selected commit
+        #[arg(long)]
+        credit: bool,
+        /// Append a link to the source commit of each entry
+        #[arg(long)]
+        commit_links: bool,
+        /// Nest entries from scoped commits under a bullet for their scope
+        #[arg(long)]
+        group_scopes: bool,
+    },
+    /// Suggest an entry for a pull request and confirm it in your editor
+    PrNotes {
+        /// Pull request number
+        number: u64,
+        /// Version to add the entry to (defaults to unreleased)
+        #[arg(short, long)]
+        version: Option<String>,
+    },
+    /// List feat/fix commits in a release's range that have no changelog entry
+    Coverage {
+        /// Version to check (defaults to unreleased changes since the latest release)
+        version: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Find entries across every version, printed with their version, date, and section
+    Search {
+        /// Text to look for, ignoring case
+        pattern: String,
+        /// Treat the pattern as a regular expression
+        #[arg(long)]
+        regex: bool,
+        /// Only search entries of this type
+        #[arg(short, long)]
+        section: Option<ChangeType>,
+        /// Only search the versions released after this one
+        #[arg(long, value_name = "VERSION")]
+        since: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Show every deprecation and removal with the version it shipped in
+    Deprecations {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Export changelog data in other formats
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+    /// Render the changelog for a website
+    Render {
+        #[command(subcommand)]
+        command: RenderCommands,
+    },
+    /// Publish release notes to a forge
+    Publish {
+        #[command(subcommand)]
+        command: PublishCommands,
+    },
+    /// Post a version's notes to Slack, Discord, or a JSON webhook
+    Notify {
+        /// Version to announce (defaults to the latest release)
+        version: Option<String>,
+        /// Webhook URL or name from [notify] webhooks in changelog.toml
+        /// (defaults to every configured webhook)
+        #[arg(long)]
+        webhook: Vec<String>,
+        /// What the webhooks expect, when their URLs don't say
+        #[arg(long, value_enum)]
+        kind: Option<notify::Kind>,
+        /// Print what would be posted instead of posting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Checks for ci that exit non-zero when the changelog falls behind
+    Check {
+        #[command(subcommand)]
+        command: CheckCommands,
+    },
+    /// Fail with a summary when Unreleased has grown too big or too old,
+    /// e.g. from a weekly job, as a nudge to cut a release
+    Nag {
+        /// Age of the oldest entry that's too old, in days like `30d` or
+        /// weeks like `4w`
+        #[arg(long, default_value = "30d", value_parser = parse_days)]
+        max_age: i64,
+        /// Number of entries that's too many
+        #[arg(long, default_value_t = 20)]
+        max_entries: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Run lint, link, tag, coverage, and forge release checks for a scorecard
+    Audit {
+        /// Fail on warnings as well as failed checks
+        #[arg(long)]
+        strict: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Compare each version's notes with its forge release and report drift
+    SyncCheck {
+        /// Update drifted release bodies from the changelog
+        #[arg(long)]
+        push: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Compare version tags with the changelog's releases and report tags
+    /// without a release, releases without a tag, and mismatched dates
+    VerifyTags {
+        /// Backfill releases for missing tags and set dates from the tags
+        #[arg(long)]
+        fix: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
     },
     /// Format the changelog file
-    Fmt,
+    Fmt {
+        /// Format markdown read from stdin and print it, leaving files alone
+        #[arg(long)]
+        stdin: bool,
+        /// Print a diff and exit non-zero if the changelog isn't formatted,
+        /// instead of rewriting it
+        #[arg(long, conflicts_with = "stdin")]
+        check: bool,
+        /// Reorder releases newest first by version
+        #[arg(long)]
+        sort: bool,
+        /// Write with these line endings instead of keeping the file's own
+        #[arg(long, value_enum)]
+        line_ending: Option<LineEnding>,
+    },
+    /// Merge two branches' changelogs entry by entry, as a git merge driver
+    ///
+    /// Writes the result to <OURS>, e.g. `changelog merge %A %O %B`
+    Merge {
+        /// Current branch's version, updated in place
+        ours: std::path::PathBuf,
+        /// Common ancestor's version
+        base: std::path::PathBuf,
+        /// Other branch's version
+        theirs: std::path::PathBuf,
+    },
+    /// Check the changelog against Keep a Changelog conventions
+    Lint {
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+        /// Lint markdown read from stdin instead of CHANGELOG.md
+        #[arg(long)]
+        stdin: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Lint and format-check changelog files anywhere, without a repository
+    Validate {
+        /// Files to check, or `-` to read one from stdin
+        #[arg(required = true)]
+        paths: Vec<std::path::PathBuf>,
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
     /// Initialize a new changelog
-    Init,
+    Init {
+        /// Start from the CHANGELOG.md and changelog.toml in this template
+        /// repository (owner/repo or a URL) instead of a blank changelog
+        #[arg(long, value_name = "REPO", conflicts_with_all = ["template", "from_tags"])]
+        from_repo: Option<String>,
+        /// Start from a built-in template (minimal, keepachangelog, or
+        /// detailed with guidelines for contributors) or a changelog file
+        #[arg(long, value_name = "NAME|PATH")]
+        template: Option<String>,
+        /// Make the newest version tag the first release
+        #[arg(long)]
+        from_tags: bool,
+    },
+    /// Create CHANGELOG.md from a changelog written by another tool
+    Import {
+        /// Changelog to convert, or `-` to read it from stdin
+        path: std::path::PathBuf,
+        /// Tool that wrote it (detected when omitted)
+        #[arg(long, value_enum)]
+        from: Option<changelog::import::Format>,
+        /// Replace CHANGELOG.md if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Add a release for every version tag missing from the changelog, with
+    /// entries from the conventional commits since the tag before it
+    Backfill {
+        /// Include commits that aren't conventional, filed by the verb they start with
+        #[arg(long)]
+        all: bool,
+    },
+    /// Merge git-cliff's output into the changelog, filing its groups into
+    /// sections by cliff.toml and keeping entries already there
+    SyncFromCliff {
+        /// git-cliff's markdown output, or `-` to read it from stdin
+        path: std::path::PathBuf,
+        /// cliff.toml with the commit_parsers that group commits (defaults to
+        /// the one next to the changelog)
+        #[arg(long, value_name = "PATH")]
+        cliff_config: Option<std::path::PathBuf>,
+        /// Show what would change without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Release a version or replace its notes without prompting, then print
+    /// its notes, for cargo-release's pre-release-hook or release-plz
+    ///
+    /// Exits 0 when done, 1 when the changelog can't be read or written, 2 for
+    /// invalid arguments, and 3 when the version isn't in the changelog and
+    /// Unreleased can't be released as it.
+    Replace {
+        /// Version to release or update, with or without a `v`
+        #[arg(env = "NEW_VERSION")]
+        version: String,
+        /// File with the version's new notes, or `-` to read them from stdin
+        /// (CHANGELOG_NOTES holds them otherwise)
+        #[arg(long, value_name = "PATH")]
+        notes: Option<std::path::PathBuf>,
+        /// Print the notes without writing anything
+        #[arg(long, env = "DRY_RUN")]
+        dry_run: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Keep entries in per-branch files under .changelog/unreleased until release
+    Fragment {
+        #[command(subcommand)]
+        command: FragmentCommands,
+    },
+    /// Work with changelogs across a workspace or monorepo
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommands,
+    },
     /// Generate shell completion scripts
     Completions {
         /// Shell to generate completions for
@@ -57,90 +662,1710 @@ enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command leaves files and forges alone, so it can read the
+    /// changelog at another ref
+    fn reads_only(&self) -> bool {
+        match self {
+            Commands::Version { .. }
+            | Commands::Entry { .. }
+            | Commands::Explain { .. }
+            | Commands::Diff { .. }
+            | Commands::Env { .. }
+            | Commands::Suggest { .. }
+            | Commands::GuessType { .. }
+            | Commands::PreviewRelease { .. }
+            | Commands::Coverage { .. }
+            | Commands::Deprecations { .. }
+            | Commands::Search { .. }
+            | Commands::Export { .. }
+            | Commands::Render { .. }
+            | Commands::Check { .. }
+            | Commands::Notify { .. }
+            | Commands::Nag { .. }
+            | Commands::Audit { .. }
+            | Commands::Lint { .. }
+            | Commands::Validate { .. }
+            | Commands::Completions { .. } => true,
+            Commands::Release { dry_run, .. } => *dry_run,
+            Commands::SyncFromCliff { dry_run, .. } => *dry_run,
+            Commands::Replace { dry_run, .. } => *dry_run,
+            Commands::SyncCheck { push, .. } => !*push,
+            Commands::VerifyTags { fix, .. } => !*fix,
+            Commands::Add { stdin, .. } => *stdin,
+            Commands::Fmt { stdin, check, .. } => *stdin || *check,
+            Commands::Fragment { command } => matches!(command, FragmentCommands::List { .. }),
+            Commands::Workspace { command } => !matches!(command, WorkspaceCommands::Fmt),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum VersionCommands {
     /// Show the latest version
-    Latest,
+    Latest {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
     /// List all versions
-    List,
+    List {
+        /// Also list releases marked as yanked
+        #[arg(long)]
+        include_yanked: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
     /// Show git revision range for a version
     Range {
-        /// Version to show range for (defaults to HEAD)
+        /// Version to show range for, or a partial one like 1.2 for the
+        /// newest 1.2.x (defaults to HEAD)
+        version: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Export Security entries as OSV advisory stubs
+    Osv {
+        /// Only export advisories fixed in this version
+        version: Option<String>,
+        /// Package name (defaults to the changelog's directory name)
+        #[arg(long)]
+        package: Option<String>,
+        /// OSV ecosystem of the package
+        #[arg(long, default_value = "crates.io")]
+        ecosystem: String,
+    },
+    /// Export the whole changelog as JSON: the header and every release with
+    /// its sections, markdown, and links
+    Json {
+        /// Print the JSON Schema of the export instead
+        #[arg(long)]
+        schema: bool,
+    },
+    /// Export released versions as an Atom or RSS feed
+    Feed {
+        /// Feed format
+        #[arg(long, value_enum, default_value_t)]
+        format: FeedFormat,
+        /// Feed title (defaults to "<directory name> releases")
+        #[arg(long)]
+        title: Option<String>,
+        /// URL of the published changelog, for linking to each version's
+        /// heading (defaults to links to the forge)
+        #[arg(long)]
+        link: Option<String>,
+        /// Only include the newest releases
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RenderCommands {
+    /// Render the changelog as an HTML page with an anchor on every version
+    Html {
+        /// Only include these versions
+        versions: Vec<String>,
+        /// HTML file to wrap the changelog in, with {content} where it goes
+        /// and {title} for the changelog's title
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PublishCommands {
+    /// Create or update the forge release (GitHub, GitLab, or Gitea) from a version's notes
+    #[command(alias = "github")]
+    Release {
+        /// Version to publish (defaults to the latest release)
         version: Option<String>,
+        /// Save the release as a draft
+        #[arg(long)]
+        draft: bool,
+        /// Mark the release as a pre-release
+        #[arg(long)]
+        prerelease: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CheckCommands {
+    /// Fail unless the Unreleased section has at least one entry
+    Unreleased {
+        /// Only require an entry when there are commits after this ref
+        /// (defaults to the latest release tag)
+        #[arg(long, value_name = "REF")]
+        since: Option<Option<String>>,
+        /// Label on the pull request being checked, repeated or comma
+        /// separated. No entry is required when one maps to `skip`
+        #[arg(long, value_delimiter = ',')]
+        label: Vec<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum FragmentCommands {
+    /// Add an entry to a fragment instead of the changelog
+    Add {
+        /// Description of the change
+        description: String,
+        /// Type of change: added, changed, deprecated, removed, fixed, security,
+        /// their first letters, or a section from changelog.toml
+        #[arg(short, long, required = true)]
+        r#type: ChangeType,
+        /// Fragment to add to (defaults to the current branch)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Add the description as markdown as-is instead of escaping it
+        #[arg(long)]
+        raw: bool,
+    },
+    /// List pending fragments and their entries
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Move pending fragments into the Unreleased section now rather than at release
+    Collect,
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// List changelogs found below the current directory
+    List,
+    /// Lint every changelog in the workspace
+    Lint {
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Format every changelog in the workspace
+    Fmt,
+    /// Audit every changelog in the workspace
+    Audit {
+        /// Fail on warnings as well as failed checks
+        #[arg(long)]
+        strict: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
+    match cli.color {
+        ColorChoice::Auto => {}
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+    }
+    if let Some(repo) = &cli.repo {
+        or_exit(changelog::set_repo(repo), "setting repository");
+    }
+    if let Some(prefix) = &cli.tag_prefix {
+        or_exit(changelog::set_tag_prefix(prefix), "setting tag prefix");
+    }
+    if cli.utc {
+        changelog::set_utc();
+    }
+    if let Some(reference) = &cli.worktree {
+        if !cli.command.reads_only() {
+            eprintln!("Error setting worktree: --worktree only works with commands that don't change files");
+            std::process::exit(1);
+        }
+        or_exit(changelog::set_worktree(reference), "setting worktree");
+    }
 
     match &cli.command {
         Commands::Add {
             description,
             r#type,
+            interactive,
             version,
+            queue,
+            raw,
+            scope,
+            breaking,
+            credit,
+            commit,
+            stdin,
+            allow_duplicate,
         } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.add(description, r#type, version.as_deref(), true) {
-                eprintln!("Error adding changelog entry: {}", e);
-                std::process::exit(1);
+            let version = queue.as_deref().map(queue_title).or(version.clone());
+            let (mut fields, r#type) = match (description, r#type) {
+                (Some(description), Some(r#type)) if !*interactive => {
+                    let description = if *raw {
+                        description.clone()
+                    } else {
+                        escape_entry(description)
+                    };
+                    let fields = EntryFields {
+                        description,
+                        scope: scope.clone(),
+                        links: Vec::new(),
+                        breaking: *breaking,
+                    };
+                    (fields, r#type.clone())
+                }
+                _ => add_wizard(
+                    &changelog,
+                    description.as_deref(),
+                    r#type.as_ref(),
+                    scope.clone(),
+                    *breaking,
+                    *raw,
+                ),
+            };
+            if let Some(credit) = or_exit(
+                changelog.credit(*credit, commit.as_deref()),
+                "looking up credit",
+            ) {
+                fields.links.push(credit);
+            }
+            let description = or_exit(changelog.render_entry(&fields), "loading config");
+            if *stdin {
+                let content = or_exit(
+                    std::io::read_to_string(std::io::stdin()).map_err(Error::from),
+                    "reading stdin",
+                );
+                let config = or_exit(changelog.config(), "loading config");
+                let duplicate = or_exit(
+                    changelog::duplicate_entry(
+                        &content,
+                        &description,
+                        &r#type,
+                        version.as_deref(),
+                        &config,
+                    ),
+                    "adding changelog entry",
+                );
+                if let Some(existing) = duplicate.filter(|_| !*allow_duplicate) {
+                    warn_duplicate(&existing);
+                    print!("{}", content);
+                    return;
+                }
+                let added = or_exit(
+                    changelog::add_entry(
+                        &content,
+                        &description,
+                        &r#type,
+                        version.as_deref(),
+                        &config,
+                    ),
+                    "adding changelog entry",
+                );
+                print!("{}", added);
+                return;
+            }
+            if !*allow_duplicate {
+                if let Some(existing) = or_exit(
+                    changelog.duplicate_entry(&description, &r#type, version.as_deref()),
+                    "adding changelog entry",
+                ) {
+                    warn_duplicate(&existing);
+                    return;
+                }
+            }
+            let change = or_exit(
+                changelog.add(&description, &r#type, version.as_deref()),
+                "adding changelog entry",
+            );
+            print!("{}", change.diff());
+        }
+        Commands::Remove {
+            entry_match,
+            index,
+            r#type,
+            version,
+        } => {
+            let changelog = Changelog::new();
+            let selector = entry_selector(&changelog, entry_match, *index, r#type, version);
+            let change = or_exit(
+                changelog.remove_entry(version.as_deref(), r#type.as_ref(), &selector),
+                "removing entry",
+            );
+            print!("{}", change.diff());
+        }
+        Commands::Edit {
+            entry_match,
+            index,
+            r#type,
+            version,
+            description,
+            raw,
+        } => {
+            let changelog = Changelog::new();
+            let selector = entry_selector(&changelog, entry_match, *index, r#type, version);
+            let description =
+                description
+                    .as_deref()
+                    .map(|d| if *raw { d.to_string() } else { escape_entry(d) });
+            let change = or_exit(
+                changelog.edit_entry(
+                    version.as_deref(),
+                    r#type.as_ref(),
+                    &selector,
+                    description.as_deref(),
+                ),
+                "editing entry",
+            );
+            print!("{}", change.diff());
+        }
+        Commands::Env { format } => {
+            let changelog = Changelog::new();
+            let env = or_exit(changelog.env(), "resolving environment");
+            match format {
+                EnvFormat::Shell => {
+                    let count = env.unreleased_entries.to_string();
+                    let vars = [
+                        ("LATEST_VERSION", env.latest_version.as_deref()),
+                        ("NEXT_PATCH", env.next_patch.as_deref()),
+                        ("NEXT_MINOR", env.next_minor.as_deref()),
+                        ("NEXT_MAJOR", env.next_major.as_deref()),
+                        ("UNRELEASED_ENTRIES", Some(count.as_str())),
+                        ("COMPARE_URL", env.compare_url.as_deref()),
+                        ("REPO", env.repo.as_deref()),
+                    ];
+                    for (name, value) in vars {
+                        // Single quoted, with embedded quotes closed and escaped
+                        let value = value.unwrap_or_default().replace('\'', "'\\''");
+                        println!("export CHANGELOG_{}='{}'", name, value);
+                    }
+                }
+                EnvFormat::Json => print_json(&env),
+            }
+        }
+        Commands::Suggest { format } => {
+            let changelog = Changelog::new();
+            let suggestion = or_exit(changelog.suggest(), "suggesting version");
+            match format {
+                OutputFormat::Text => print_suggestion(&suggestion),
+                OutputFormat::Json => print_json(&suggestion),
+            }
+        }
+        Commands::GuessType { text, format } => {
+            let text = if text == "-" {
+                or_exit(
+                    std::io::read_to_string(std::io::stdin()).map_err(Error::from),
+                    "reading text",
+                )
+            } else {
+                text.clone()
+            };
+            let guess = or_exit(Changelog::new().guess_type(&text), "guessing type");
+            match format {
+                OutputFormat::Text => println!("{}", guess.change_type),
+                OutputFormat::Json => print_json(&guess),
             }
         }
         Commands::Release {
             version_or_type,
+            amend,
+            pre,
+            apply,
             date,
+            queue,
+            bump_files,
+            commit,
+            tag,
+            released_by,
+            dry_run,
+            notes_out,
+            workflow,
+            skip,
+        } => {
+            let changelog = Changelog::new();
+            let config = or_exit(changelog.config(), "reading changelog.toml");
+            let workflow_config = &config.release.workflow;
+            let step = |step: WorkflowStep| {
+                *workflow && !skip.contains(&step) && !workflow_config.skip.contains(&step)
+            };
+            let bump_files = *bump_files || step(WorkflowStep::Bump);
+            let commit = *commit || step(WorkflowStep::Commit);
+            let tag = *tag || step(WorkflowStep::Tag);
+            let push = step(WorkflowStep::Push);
+            let publish = step(WorkflowStep::Publish);
+
+            let mut version_or_type = match version_or_type {
+                Some(version_or_type) => version_or_type.clone(),
+                None if *workflow && !*amend => "auto".to_string(),
+                None => String::new(),
+            };
+            if version_or_type == "auto" {
+                let suggestion = or_exit(changelog.suggest(), "suggesting version");
+                print_suggestion(&suggestion);
+                match suggestion.bump {
+                    Some(bump) if *apply || *dry_run || *workflow => {
+                        version_or_type = bump.to_string()
+                    }
+                    Some(_) => {
+                        println!("Run with --apply to release it");
+                        return;
+                    }
+                    None => return,
+                }
+            }
+            if *dry_run {
+                let dry_run = if *amend {
+                    or_exit(
+                        changelog.amend_release_dry_run(date.as_deref()),
+                        "previewing release",
+                    )
+                } else {
+                    or_exit(
+                        changelog.release_dry_run(
+                            &version_or_type,
+                            date.as_deref(),
+                            queue.as_deref(),
+                            released_by.as_deref(),
+                            pre.as_deref(),
+                        ),
+                        "previewing release",
+                    )
+                };
+                let action = if *amend { "amend" } else { "release" };
+                match &dry_run.date {
+                    Some(date) => {
+                        println!("Would {} version {} on {}", action, dry_run.version, date)
+                    }
+                    None => println!("Would {} version {}", action, dry_run.version),
+                }
+                print!("{}", dry_run.change.diff());
+
+                if bump_files || !config.release.bump_files.is_empty() {
+                    let bumps = or_exit(
+                        changelog.version_bumps(&dry_run.version),
+                        "bumping version files",
+                    );
+                    for bump in bumps {
+                        println!("Would bump {}", bump.path.display());
+                        print!("{}", bump.diff());
+                    }
+                }
+                let tag_name = format!(
+                    "{}{}",
+                    or_exit(changelog.tag_prefix(), "reading changelog.toml"),
+                    dry_run.version
+                );
+                if commit {
+                    println!(
+                        "Would commit the release as \"chore: release {}\"",
+                        tag_name
+                    );
+                }
+                if tag {
+                    println!("Would tag {}", tag_name);
+                }
+                if push {
+                    println!("Would push to {}", workflow_config.remote);
+                }
+                if publish {
+                    println!("Would publish the {} release", tag_name);
+                }
+                return;
+            }
+            let version = if *amend {
+                let version = or_exit(changelog.amend_release(date.as_deref()), "amending release");
+                println!("Amended version {}", version);
+                version
+            } else {
+                let version = or_exit(
+                    changelog.release(
+                        &version_or_type,
+                        date.as_deref(),
+                        queue.as_deref(),
+                        released_by.as_deref(),
+                        pre.as_deref(),
+                    ),
+                    "releasing version",
+                );
+                println!("Released version {}", version);
+                version
+            };
+
+            let mut bumped = Vec::new();
+            if bump_files || !config.release.bump_files.is_empty() {
+                let bumps = or_exit(
+                    changelog.bump_version_files(&version),
+                    "bumping version files",
+                );
+                if bumps.is_empty() {
+                    eprintln!("No version files found to bump");
+                }
+                for bump in bumps {
+                    println!("{}", bump.path.display());
+                    print!("{}", bump.diff());
+                    bumped.push(bump.path);
+                }
+            }
+
+            if let Some(path) = notes_out {
+                let notes = or_exit(changelog.tag_message(&version), "reading release notes");
+                or_exit(write_output(path, &notes), "writing release notes");
+                println!("Wrote release notes to {}", path.display());
+            }
+
+            if commit {
+                let id = or_exit(
+                    changelog.commit_release(&version, &bumped),
+                    "committing release",
+                );
+                println!("Committed release as {}", id);
+            }
+            let mut tag_name = None;
+            if tag {
+                let name = or_exit(changelog.tag_release(&version), "tagging release");
+                println!("Tagged {}", name);
+                tag_name = Some(name);
+            }
+            if push {
+                or_exit(
+                    changelog.push_release(&workflow_config.remote, tag_name.as_deref()),
+                    "pushing release",
+                );
+                println!("Pushed to {}", workflow_config.remote);
+            }
+            if publish {
+                let published = or_exit(
+                    changelog.publish(Some(&version), false, false),
+                    "publishing release",
+                );
+                let (action, release) = match published {
+                    Published::Created(release) => ("Created", release),
+                    Published::Updated(release) => ("Updated", release),
+                };
+                println!(
+                    "{} release {}: {}",
+                    action, release.tag_name, release.html_url
+                );
+            }
+            or_exit(
+                changelog.run_hook(Hook::PostRelease, &version),
+                "running hooks",
+            );
+        }
+        Commands::PreviewRelease {
+            version_or_type,
+            pre,
+            date,
+            queue,
+            released_by,
+        } => {
+            let changelog = Changelog::new();
+            let preview = or_exit(
+                changelog.preview_release(
+                    version_or_type,
+                    date.as_deref(),
+                    queue.as_deref(),
+                    released_by.as_deref(),
+                    pre.as_deref(),
+                ),
+                "previewing release",
+            );
+            print!("{}", preview);
+        }
+        Commands::Freeze { redirect } => {
+            let changelog = Changelog::new();
+            or_exit(changelog.freeze(redirect.as_deref()), "freezing Unreleased");
+            match redirect {
+                Some(queue) => println!(
+                    "Froze Unreleased; new entries go to {}",
+                    changelog::queue_title(queue)
+                ),
+                None => println!("Froze Unreleased"),
+            }
+        }
+        Commands::Thaw => {
+            let changelog = Changelog::new();
+            or_exit(changelog.thaw(), "thawing Unreleased");
+            println!("Thawed Unreleased");
+        }
+        Commands::Yank { version } => {
+            let changelog = Changelog::new();
+            or_exit(changelog.yank(version), "yanking version");
+            println!("Yanked version {}", version);
+        }
+        Commands::Unyank { version } => {
+            let changelog = Changelog::new();
+            or_exit(changelog.unyank(version), "unyanking version");
+            println!("Unyanked version {}", version);
+        }
+        Commands::RenameVersion { from, to } => {
+            let changelog = Changelog::new();
+            let from = from.strip_prefix('v').unwrap_or(from);
+            let to = to.strip_prefix('v').unwrap_or(to);
+            or_exit(changelog.rename_version(from, to), "renaming version");
+            println!("Renamed version {} to {}", from, to);
+        }
+        Commands::Squash { range, target } => {
+            let changelog = Changelog::new();
+            let Some((from, to)) = range.split_once("..") else {
+                eprintln!(
+                    "Error squashing versions: expected a range like 2.0.0-rc.1..2.0.0-rc.4, got {}",
+                    range
+                );
+                std::process::exit(1);
+            };
+            let strip = |v: &str| v.strip_prefix('v').unwrap_or(v).to_string();
+            let target = strip(target);
+            let squash = or_exit(
+                changelog.squash(&strip(from), &strip(to), &target),
+                "squashing versions",
+            );
+            print!("{}", squash.change.diff());
+            println!("Squashed {} into {}", squash.versions.join(", "), target);
+        }
+        Commands::Annotate {
+            version,
+            annotation,
+            clear: _,
+        } => {
+            let changelog = Changelog::new();
+            or_exit(
+                changelog.annotate(version, annotation.as_deref()),
+                "annotating version",
+            );
+            match annotation {
+                Some(annotation) => println!("Annotated version {}: {}", version, annotation),
+                None => println!("Removed the note from version {}", version),
+            }
+        }
+        Commands::Backport {
+            entry_match,
+            to,
+            from,
+            date,
+        } => {
+            let changelog = Changelog::new();
+            let backport = or_exit(
+                changelog.backport(entry_match, to, from.as_deref(), date.as_deref()),
+                "backporting entry",
+            );
+            print!("{}", backport.change.diff());
+            println!("Backported entry from {} to {}", backport.source, to);
+        }
+        Commands::Review {
+            version,
+            credit,
+            commit_links,
+            group_scopes,
+        } => {
+            let changelog = Changelog::new();
+            let review = or_exit(
+                changelog.review(version.as_deref(), *credit, *commit_links, *group_scopes),
+                "reviewing changes",
+            );
+            for warning in &review.warnings {
+                eprintln!("{}", warning);
+            }
+            if let Some(change) = review.change {
+                print!("{}", change.diff());
+            }
+        }
+        Commands::PrNotes { number, version } => {
+            let changelog = Changelog::new();
+            match or_exit(
+                changelog.pr_notes(*number, version.as_deref()),
+                "adding pull request notes",
+            ) {
+                Some(change) => print!("{}", change.diff()),
+                None => println!("No entry added for #{}", number),
+            }
+        }
+        Commands::Fmt {
+            stdin: true,
+            sort,
+            line_ending,
+            ..
         } => {
+            let input = or_exit(
+                std::io::read_to_string(std::io::stdin()).map_err(Error::from),
+                "reading stdin",
+            );
+            let content = Encoding::decode(&input);
+            let config = or_exit(Changelog::new().config(), "loading config");
+            let formatted = if *sort {
+                changelog::format_sorted(&content, &config)
+            } else {
+                changelog::format(&content, &config)
+            };
+            let formatted = or_exit(formatted, "formatting changelog");
+            let encoding = Encoding::detect(&input).with_line_ending(*line_ending);
+            print!("{}", encoding.encode(&formatted));
+        }
+        Commands::Fmt {
+            check: true,
+            sort,
+            line_ending,
+            ..
+        } => {
+            let changelog = Changelog::new();
+            if let Some(diff) = or_exit(
+                changelog.fmt_check(*sort, *line_ending),
+                "checking formatting",
+            ) {
+                print!("{}", diff);
+                eprintln!(
+                    "Error checking formatting: CHANGELOG.md is not formatted, run `changelog fmt{}{}`",
+                    if *sort { " --sort" } else { "" },
+                    match line_ending {
+                        Some(line_ending) => format!(
+                            " --line-ending {}",
+                            line_ending.to_possible_value().unwrap().get_name()
+                        ),
+                        None => String::new(),
+                    }
+                );
+                std::process::exit(1);
+            }
+        }
+        Commands::Fmt {
+            sort, line_ending, ..
+        } => {
+            let changelog = Changelog::new();
+            or_exit(changelog.fmt(*sort, *line_ending), "formatting changelog");
+            println!("Formatted CHANGELOG.md");
+        }
+        Commands::Merge { ours, base, theirs } => {
+            let changelog = Changelog::at(ours);
+            or_exit(changelog.merge(base, theirs), "merging changelog");
+        }
+        Commands::Coverage { version, format } => {
+            let changelog = Changelog::new();
+            // Accept tag style versions like v1.3.0 as well
+            let version = version.as_deref().map(|v| v.strip_prefix('v').unwrap_or(v));
+            let coverage = or_exit(changelog.coverage(version), "checking coverage");
+            match format {
+                OutputFormat::Text => {
+                    for commit in &coverage.uncovered {
+                        println!("{} {}", commit.short_id, commit.summary);
+                    }
+                }
+                OutputFormat::Json => print_json(&coverage),
+            }
+
+            if !coverage.uncovered.is_empty() {
+                eprintln!(
+                    "Error checking coverage: {} of {} user-facing commit(s) in {} have no changelog entry",
+                    coverage.uncovered.len(),
+                    coverage.commits,
+                    coverage.range
+                );
+                std::process::exit(1);
+            }
+        }
+        Commands::Search {
+            pattern,
+            regex,
+            section,
+            since,
+            format,
+        } => {
+            let changelog = Changelog::new();
+            let since = since.as_deref().map(|v| v.strip_prefix('v').unwrap_or(v));
+            let matches = or_exit(
+                changelog.search(pattern, *regex, section.as_ref(), since),
+                "searching entries",
+            );
+            match format {
+                OutputFormat::Text => {
+                    if matches.is_empty() {
+                        eprintln!("No entries match '{}'", pattern);
+                        std::process::exit(1);
+                    }
+                    let mut current: Option<&str> = None;
+                    for found in &matches {
+                        if current != Some(found.version.as_str()) {
+                            if current.is_some() {
+                                println!();
+                            }
+                            match &found.date {
+                                Some(date) => println!("{} ({})", found.version, date),
+                                None => println!("{}", found.version),
+                            }
+                            current = Some(&found.version);
+                        }
+                        println!("  {}: {}", found.section, found.entry.replace('\n', " "));
+                    }
+                }
+                OutputFormat::Json => print_json(&matches),
+            }
+        }
+        Commands::Deprecations { format } => {
+            let changelog = Changelog::new();
+            let timeline = or_exit(changelog.deprecations(), "listing deprecations");
+            match format {
+                OutputFormat::Text => {
+                    let mut current: Option<&str> = None;
+                    for item in &timeline {
+                        if current != Some(item.version.as_str()) {
+                            if current.is_some() {
+                                println!();
+                            }
+                            match &item.date {
+                                Some(date) => println!("{} ({})", item.version, date),
+                                None => println!("{}", item.version),
+                            }
+                            current = Some(&item.version);
+                        }
+                        println!("  {}: {}", item.kind, item.entry.replace('\n', " "));
+                    }
+                }
+                OutputFormat::Json => print_json(&timeline),
+            }
+        }
+        Commands::Export { command } => {
+            let changelog = Changelog::new();
+            match command {
+                ExportCommands::Osv {
+                    version,
+                    package,
+                    ecosystem,
+                } => {
+                    let advisories = or_exit(
+                        changelog.export_osv(version.as_deref(), package.as_deref(), ecosystem),
+                        "exporting advisories",
+                    );
+                    print_json(&advisories);
+                }
+                ExportCommands::Json { schema: true } => {
+                    print_json(&changelog::manifest::schema());
+                }
+                ExportCommands::Json { schema: false } => {
+                    let manifest = or_exit(changelog.manifest(), "exporting changelog");
+                    print_json(&manifest);
+                }
+                ExportCommands::Feed {
+                    format,
+                    title,
+                    link,
+                    limit,
+                } => {
+                    let mut feed = or_exit(
+                        changelog.feed(title.as_deref(), link.as_deref()),
+                        "exporting feed",
+                    );
+                    if let Some(limit) = limit {
+                        feed.items.truncate(*limit);
+                    }
+                    match format {
+                        FeedFormat::Atom => print!("{}", feed.atom()),
+                        FeedFormat::Rss => print!("{}", feed.rss()),
+                    }
+                }
+            }
+        }
+        Commands::Render { command } => {
+            let changelog = Changelog::new();
+            match command {
+                RenderCommands::Html {
+                    versions,
+                    template,
+                    output,
+                } => {
+                    let versions: Vec<String> = versions
+                        .iter()
+                        .map(|v| v.strip_prefix('v').unwrap_or(v).to_string())
+                        .collect();
+                    let template = template.as_ref().map(|path| {
+                        or_exit(
+                            std::fs::read_to_string(path).map_err(Error::from),
+                            "reading HTML template",
+                        )
+                    });
+                    let page = or_exit(changelog.page(&versions), "rendering changelog");
+                    let html = or_exit(page.document(template.as_deref()), "rendering changelog");
+                    match output {
+                        Some(path) => or_exit(write_output(path, &html), "writing HTML"),
+                        None => print!("{}", html),
+                    }
+                }
+            }
+        }
+        Commands::Publish { command } => {
+            let changelog = Changelog::new();
+            match command {
+                PublishCommands::Release {
+                    version,
+                    draft,
+                    prerelease,
+                } => {
+                    let published = or_exit(
+                        changelog.publish(version.as_deref(), *draft, *prerelease),
+                        "publishing release",
+                    );
+                    let (action, release) = match published {
+                        Published::Created(release) => ("Created", release),
+                        Published::Updated(release) => ("Updated", release),
+                    };
+                    println!(
+                        "{} release {}: {}",
+                        action, release.tag_name, release.html_url
+                    );
+                }
+            }
+        }
+        Commands::Notify {
+            version,
+            webhook,
+            kind,
+            dry_run,
+        } => {
+            let changelog = Changelog::new();
+            let version = version.as_deref().map(|v| v.strip_prefix('v').unwrap_or(v));
+            if *dry_run {
+                let notifications = or_exit(
+                    changelog.notifications(version, webhook, *kind),
+                    "notifying webhooks",
+                );
+                for (url, payload) in notifications {
+                    println!("Would post to {}", url);
+                    print_json(&payload);
+                }
+                return;
+            }
+            for url in or_exit(
+                changelog.notify(version, webhook, *kind),
+                "notifying webhooks",
+            ) {
+                println!("Posted to {}", url);
+            }
+        }
+        Commands::Check { command } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.release(version_or_type, date.as_deref()) {
-                eprintln!("Error releasing version: {}", e);
+            match command {
+                CheckCommands::Unreleased {
+                    since,
+                    label,
+                    format,
+                } => {
+                    let since = match since {
+                        Some(None) => {
+                            // Before the first release every commit counts
+                            or_exit(changelog.range(None), "checking unreleased").start
+                        }
+                        Some(Some(since)) => Some(since.clone()),
+                        None => None,
+                    };
+                    let check = or_exit(
+                        changelog.check_unreleased(since.as_deref(), label),
+                        "checking unreleased",
+                    );
+                    match format {
+                        OutputFormat::Text => {
+                            match (&check.skipped_by, &check.since, check.commits) {
+                                (Some(label), _, _) => {
+                                    println!("No entry needed, labeled {}", label)
+                                }
+                                (_, Some(since), Some(0)) => println!("No commits since {}", since),
+                                _ if check.passed() => {
+                                    println!("{} unreleased change(s)", check.entries)
+                                }
+                                _ => {}
+                            }
+                        }
+                        OutputFormat::Json => print_json(&check),
+                    }
+
+                    if !check.passed() {
+                        match (&check.since, check.commits) {
+                            (Some(since), Some(commits)) => eprintln!(
+                                "Error checking unreleased: {} commit(s) since {} but no entries in Unreleased",
+                                commits, since
+                            ),
+                            _ => eprintln!("Error checking unreleased: no entries in Unreleased"),
+                        }
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Commands::Nag {
+            max_age,
+            max_entries,
+            format,
+        } => {
+            let changelog = Changelog::new();
+            let buildup = or_exit(changelog.buildup(), "checking unreleased");
+            match format {
+                OutputFormat::Text => match (&buildup.oldest, buildup.age_days) {
+                    (Some(oldest), Some(age)) => println!(
+                        "{} unreleased change(s), the oldest from {} ({} days ago)",
+                        buildup.entries, oldest, age
+                    ),
+                    _ => println!("{} unreleased change(s)", buildup.entries),
+                },
+                OutputFormat::Json => print_json(&buildup),
+            }
+
+            let reasons = buildup.overdue(*max_age, *max_entries);
+            if !reasons.is_empty() {
+                eprintln!(
+                    "Error checking unreleased: {}; time to cut a release",
+                    reasons.join(" and ")
+                );
                 std::process::exit(1);
             }
         }
-        Commands::Review { version } => {
+        Commands::Audit { strict, format } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.review(version.as_deref()) {
-                eprintln!("Error reviewing changes: {}", e);
+            let audit = or_exit(changelog.audit(), "auditing changelog");
+            match format {
+                OutputFormat::Text => {
+                    for check in &audit.checks {
+                        match &check.reason {
+                            Some(reason) => {
+                                println!("{:<4}  {}: {}", check.status, check.name, reason)
+                            }
+                            None => println!("{:<4}  {}", check.status, check.name),
+                        }
+                        for problem in &check.problems {
+                            println!("        {}", problem);
+                        }
+                    }
+                    println!("Score: {}/100", audit.score);
+                }
+                OutputFormat::Json => print_json(&audit),
+            }
+
+            let failures = audit.failures(*strict);
+            if failures > 0 {
+                eprintln!(
+                    "Error auditing changelog: {} check(s) did not pass",
+                    failures
+                );
                 std::process::exit(1);
             }
         }
-        Commands::Fmt => {
+        Commands::SyncCheck { push, format } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.fmt() {
-                eprintln!("Error formatting changelog: {}", e);
+            let results = or_exit(changelog.sync_check(*push), "checking releases");
+            match format {
+                OutputFormat::Text => {
+                    for result in &results {
+                        match &result.status {
+                            SyncStatus::InSync => {}
+                            SyncStatus::Missing => {
+                                println!("{}: no release for {}", result.version, result.tag)
+                            }
+                            SyncStatus::Drifted {
+                                url,
+                                release,
+                                changelog,
+                            } => {
+                                println!("{}: {} differs from the changelog", result.version, url);
+                                let change = SectionChange {
+                                    before: format!("{}\n", release),
+                                    after: format!("{}\n", changelog),
+                                };
+                                print!("{}", change.diff());
+                            }
+                            SyncStatus::Updated { url } => {
+                                println!("Updated release {}: {}", result.tag, url)
+                            }
+                        }
+                    }
+                }
+                OutputFormat::Json => print_json(&results),
+            }
+
+            let drifted = results
+                .iter()
+                .filter(|r| matches!(r.status, SyncStatus::Drifted { .. }))
+                .count();
+            if drifted > 0 {
+                eprintln!(
+                    "Error checking releases: {} release(s) differ from the changelog; run with --push to update them",
+                    drifted
+                );
                 std::process::exit(1);
             }
         }
-        Commands::Init => {
+        Commands::VerifyTags { fix, format } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.init() {
-                eprintln!("Error initializing changelog: {}", e);
+            let checks = or_exit(changelog.verify_tags(*fix), "verifying tags");
+            match format {
+                OutputFormat::Text => {
+                    for check in &checks {
+                        let fixed = if check.fixed { " (fixed)" } else { "" };
+                        match &check.status {
+                            TagStatus::MissingRelease => {
+                                println!("{}: not in the changelog{}", check.tag, fixed)
+                            }
+                            TagStatus::MissingTag => {
+                                println!("{}: no {} tag", check.version, check.tag)
+                            }
+                            TagStatus::DateMismatch { changelog, tagged } => println!(
+                                "{}: dated {} but tagged {}{}",
+                                check.version,
+                                changelog.as_deref().unwrap_or("nothing"),
+                                tagged,
+                                fixed
+                            ),
+                        }
+                    }
+                }
+                OutputFormat::Json => print_json(&checks),
+            }
+
+            let problems = checks.iter().filter(|c| !c.fixed).count();
+            if problems > 0 {
+                eprintln!(
+                    "Error verifying tags: {} version(s) disagree with their tags",
+                    problems
+                );
                 std::process::exit(1);
             }
         }
-        Commands::Entry { version } => {
+        Commands::Lint {
+            strict,
+            stdin,
+            format,
+        } => {
             let changelog = Changelog::new();
-            if let Err(e) = changelog.version_show(version) {
-                eprintln!("Error showing entry: {}", e);
+            let violations = if *stdin {
+                let content = or_exit(
+                    std::io::read_to_string(std::io::stdin()).map_err(Error::from),
+                    "reading stdin",
+                );
+                let config = or_exit(changelog.config(), "loading config");
+                changelog::lint::lint(&content, &config)
+            } else {
+                or_exit(changelog.lint(), "linting changelog")
+            };
+            match format {
+                OutputFormat::Text => {
+                    for violation in &violations {
+                        println!("{}", violation);
+                    }
+                }
+                OutputFormat::Json => print_json(&violations),
+            }
+
+            let failures = violations
+                .iter()
+                .filter(|v| *strict || v.severity == Severity::Error)
+                .count();
+            if failures > 0 {
+                eprintln!("Error linting changelog: {} problem(s) found", failures);
                 std::process::exit(1);
             }
         }
+        Commands::Validate {
+            paths,
+            strict,
+            format,
+        } => {
+            let mut reports = Vec::new();
+            let mut unreadable = 0;
+            for path in paths {
+                let violations = if path.as_os_str() == "-" {
+                    std::io::read_to_string(std::io::stdin())
+                        .map(|content| changelog::validate(&Encoding::decode(&content), None))
+                        .map_err(Error::from)
+                } else {
+                    Changelog::at(path).validate()
+                };
+                match violations {
+                    Ok(violations) => reports.push(Report {
+                        path: path.display().to_string(),
+                        violations,
+                    }),
+                    Err(e) => {
+                        eprintln!("Error validating {}: {}", path.display(), e);
+                        unreadable += 1;
+                    }
+                }
+            }
+
+            match format {
+                OutputFormat::Text => {
+                    for report in &reports {
+                        for violation in &report.violations {
+                            println!("{}: {}", report.path, violation);
+                        }
+                    }
+                }
+                OutputFormat::Json => print_json(&reports),
+            }
+
+            let failures = reports
+                .iter()
+                .flat_map(|r| &r.violations)
+                .filter(|v| *strict || v.severity == Severity::Error)
+                .count();
+            if failures > 0 || unreadable > 0 {
+                eprintln!(
+                    "Error validating changelogs: {} problem(s) found in {} file(s)",
+                    failures + unreadable,
+                    paths.len()
+                );
+                std::process::exit(1);
+            }
+        }
+        Commands::Import { path, from, force } => {
+            let content = or_exit(
+                if path.as_os_str() == "-" {
+                    std::io::read_to_string(std::io::stdin())
+                } else {
+                    std::fs::read_to_string(path)
+                }
+                .map(|content| Encoding::decode(&content))
+                .map_err(Error::from),
+                "reading changelog to import",
+            );
+            let changelog = Changelog::new();
+            match changelog.import(&content, *from, *force) {
+                Ok(count) => println!("Imported {} releases into CHANGELOG.md", count),
+                Err(Error::AlreadyInitialized) => {
+                    eprintln!("CHANGELOG.md already exists, pass --force to replace it");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error importing changelog: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Backfill { all } => {
+            let changelog = Changelog::new();
+            let backfill = or_exit(changelog.backfill(*all), "backfilling releases");
+            if backfill.releases.is_empty() {
+                println!("Every version tag is already in the changelog");
+            }
+            for (version, entries) in &backfill.releases {
+                println!("Added {} with {} entries", version, entries);
+            }
+        }
+        Commands::Replace {
+            version,
+            notes,
+            dry_run,
+            format,
+        } => {
+            let notes = match notes {
+                Some(path) => Some(or_exit(
+                    if path.as_os_str() == "-" {
+                        std::io::read_to_string(std::io::stdin())
+                    } else {
+                        std::fs::read_to_string(path)
+                    }
+                    .map(|content| Encoding::decode(&content))
+                    .map_err(Error::from),
+                    "reading notes",
+                )),
+                None => std::env::var("CHANGELOG_NOTES").ok(),
+            };
+            let changelog = Changelog::new();
+            let replaced = match changelog.replace_release(version, notes.as_deref(), *dry_run) {
+                Ok(replaced) => replaced,
+                Err(e) => {
+                    eprintln!("Error replacing release notes: {}", e);
+                    std::process::exit(match e {
+                        Error::InvalidInput(_) => 2,
+                        Error::InvalidVersion(_) | Error::NotFound(_) => 3,
+                        _ => 1,
+                    });
+                }
+            };
+            if replaced.released {
+                let verb = if *dry_run {
+                    "Would release"
+                } else {
+                    "Released"
+                };
+                eprintln!("{} {}", verb, replaced.release.version);
+            }
+            match format {
+                OutputFormat::Text => println!("{}", replaced.release.notes),
+                OutputFormat::Json => print_json(&replaced.release),
+            }
+        }
+        Commands::SyncFromCliff {
+            path,
+            cliff_config,
+            dry_run,
+        } => {
+            let content = or_exit(
+                if path.as_os_str() == "-" {
+                    std::io::read_to_string(std::io::stdin())
+                } else {
+                    std::fs::read_to_string(path)
+                }
+                .map(|content| Encoding::decode(&content))
+                .map_err(Error::from),
+                "reading git-cliff output",
+            );
+            let changelog = Changelog::new();
+            let sync = or_exit(
+                changelog.sync_from_cliff(&content, cliff_config.as_deref(), *dry_run),
+                "syncing from git-cliff",
+            );
+            let verb = if *dry_run { "Would add" } else { "Added" };
+            if sync.added.is_empty() {
+                println!("Every entry is already in the changelog");
+            }
+            for version in &sync.created {
+                let verb = if *dry_run { "Would create" } else { "Created" };
+                println!("{} {}", verb, version);
+            }
+            for (version, entries) in &sync.added {
+                println!("{} {} entries to {}", verb, entries, version);
+            }
+        }
+        Commands::Init {
+            from_repo: Some(repo),
+            ..
+        } => {
+            let changelog = Changelog::new();
+            match changelog.init_from_repo(repo) {
+                Ok(files) => {
+                    for file in files {
+                        println!("Created {}", file.display());
+                    }
+                }
+                Err(Error::AlreadyInitialized) => eprintln!("CHANGELOG.md already exists"),
+                Err(e) => {
+                    eprintln!("Error initializing changelog: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Init {
+            from_repo: None,
+            template,
+            from_tags,
+        } => {
+            let changelog = Changelog::new();
+            match changelog.init_with(template.as_deref(), *from_tags) {
+                Ok(seeded) => {
+                    println!("Created CHANGELOG.md");
+                    if let Some(version) = seeded {
+                        println!("Started from the {} tag", version);
+                    }
+                }
+                Err(Error::AlreadyInitialized) => eprintln!("CHANGELOG.md already exists"),
+                Err(e) => {
+                    eprintln!("Error initializing changelog: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Entry {
+            version,
+            compare,
+            format,
+            anchor,
+            range,
+            output,
+        } => {
+            let changelog = Changelog::new();
+            let text = if let Some(range) = range {
+                let Some((from, to)) = range.split_once("..") else {
+                    eprintln!(
+                        "Error showing entries: '{}' is not a range like 1.2.0..latest",
+                        range
+                    );
+                    std::process::exit(1);
+                };
+                let entries = or_exit(changelog.version_entries(from, to), "showing entries");
+                let terminal = output.is_none() && styled(cli.color);
+                match format {
+                    EntryFormat::Json => json(&entries),
+                    _ => entries
+                        .iter()
+                        .map(|entry| match format {
+                            EntryFormat::Markdown if terminal => entry.terminal(),
+                            EntryFormat::Plain => entry.plain(),
+                            EntryFormat::Html => entry.html(),
+                            EntryFormat::Slack => entry.slack(),
+                            _ => entry.markdown(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                }
+            } else if *compare {
+                let comparison = or_exit(changelog.compare_entry(version), "comparing entry");
+                match format {
+                    EntryFormat::Json => json(&comparison),
+                    _ => comparison.side_by_side(),
+                }
+            } else {
+                let entry = or_exit(changelog.version_show(version), "showing entry");
+                if *anchor {
+                    format!("{}\n", entry.anchor)
+                } else {
+                    match format {
+                        EntryFormat::Markdown if output.is_none() && styled(cli.color) => {
+                            entry.terminal()
+                        }
+                        EntryFormat::Markdown => entry.markdown(),
+                        EntryFormat::Plain => entry.plain(),
+                        EntryFormat::Html => entry.html(),
+                        EntryFormat::Slack => entry.slack(),
+                        EntryFormat::Json => json(&entry),
+                    }
+                }
+            };
+            match output {
+                Some(path) => or_exit(write_output(path, &text), "writing entry"),
+                None => page(&text),
+            }
+        }
+        Commands::Explain { version, format } => {
+            let changelog = Changelog::new();
+            // Accept tag style versions like v1.3.0 as well
+            let version = version.strip_prefix('v').unwrap_or(version);
+            let explanation = or_exit(changelog.explain(version), "explaining version");
+            match format {
+                OutputFormat::Text => print!("{}", explanation.report()),
+                OutputFormat::Json => print_json(&explanation),
+            }
+        }
+        Commands::Diff {
+            from,
+            to,
+            include_from,
+            exclude_to,
+            format,
+        } => {
+            let changelog = Changelog::new();
+            // Accept tag style versions like v1.3.0 as well
+            let from = from.strip_prefix('v').unwrap_or(from);
+            let to = to.strip_prefix('v').unwrap_or(to);
+            let diff = or_exit(
+                changelog.diff(from, to, *include_from, *exclude_to),
+                "comparing versions",
+            );
+            match format {
+                OutputFormat::Text => print!("{}", diff.markdown()),
+                OutputFormat::Json => print_json(&diff),
+            }
+        }
         Commands::Version { command } => {
             let changelog = Changelog::new();
             match command {
-                VersionCommands::Latest => {
-                    if let Err(e) = changelog.version_latest() {
-                        eprintln!("Error showing latest version: {}", e);
+                VersionCommands::Latest { format } => {
+                    let latest = or_exit(changelog.version_latest(), "showing latest version");
+                    match format {
+                        OutputFormat::Text => println!("{}", latest.version),
+                        OutputFormat::Json => print_json(&latest),
+                    }
+                }
+                VersionCommands::List {
+                    include_yanked,
+                    format,
+                } => {
+                    let versions =
+                        or_exit(changelog.version_list(*include_yanked), "listing versions");
+                    match format {
+                        OutputFormat::Text if styled(cli.color) => {
+                            let mut text = String::new();
+                            for version in &versions {
+                                let date = version.date.as_deref().unwrap_or_default();
+                                let name = match version.yanked {
+                                    true => version.version.red().strikethrough(),
+                                    false => version.version.bold(),
+                                };
+                                text.push_str(&format!("{} {}\n", name, date.dimmed()));
+                            }
+                            page(&text);
+                        }
+                        OutputFormat::Text => {
+                            for version in &versions {
+                                println!("{}", version.version);
+                            }
+                        }
+                        OutputFormat::Json => print_json(&versions),
+                    }
+                }
+                VersionCommands::Range { version, format } => {
+                    let range = or_exit(changelog.range(version.as_deref()), "showing range");
+                    match format {
+                        OutputFormat::Text => println!("{}", range.range),
+                        OutputFormat::Json => print_json(&range),
+                    }
+                }
+            }
+        }
+        Commands::Fragment { command } => {
+            let changelog = Changelog::new();
+            match command {
+                FragmentCommands::Add {
+                    description,
+                    r#type,
+                    name,
+                    raw,
+                } => {
+                    let description = if *raw {
+                        description.clone()
+                    } else {
+                        escape_entry(description)
+                    };
+                    let path = or_exit(
+                        changelog.fragment_add(&description, r#type, name.as_deref()),
+                        "adding fragment",
+                    );
+                    println!("Added {} entry to {}", r#type, path.display());
+                }
+                FragmentCommands::List { format } => {
+                    let fragments = or_exit(changelog.fragments(), "listing fragments");
+                    match format {
+                        OutputFormat::Text => {
+                            for fragment in &fragments {
+                                println!("{}", fragment.path.display());
+                                for section in &fragment.sections {
+                                    for entry in &section.entries {
+                                        println!(
+                                            "  {}: {}",
+                                            section.name,
+                                            entry.replace('\n', " ")
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                        OutputFormat::Json => print_json(&fragments),
+                    }
+                }
+                FragmentCommands::Collect => {
+                    match or_exit(changelog.collect_fragments(), "collecting fragments") {
+                        Some(change) => print!("{}", change.diff()),
+                        None => println!("No fragments to collect"),
+                    }
+                }
+            }
+        }
+        Commands::Workspace { command } => {
+            let changelog = Changelog::new();
+            match command {
+                WorkspaceCommands::List => {
+                    for path in changelog.workspace_list() {
+                        println!("{}", path.display());
+                    }
+                }
+                WorkspaceCommands::Lint { strict, format } => {
+                    let mut reports = Vec::new();
+                    let mut unreadable = 0;
+                    for (path, violations) in changelog.workspace_map(Changelog::lint) {
+                        match violations {
+                            Ok(violations) => reports.push(Report {
+                                path: path.display().to_string(),
+                                violations,
+                            }),
+                            Err(e) => {
+                                eprintln!("Error linting {}: {}", path.display(), e);
+                                unreadable += 1;
+                            }
+                        }
+                    }
+
+                    match format {
+                        OutputFormat::Text => {
+                            for report in &reports {
+                                for violation in &report.violations {
+                                    println!("{}: {}", report.path, violation);
+                                }
+                            }
+                        }
+                        OutputFormat::Json => print_json(&reports),
+                    }
+
+                    let failures = reports
+                        .iter()
+                        .flat_map(|r| &r.violations)
+                        .filter(|v| *strict || v.severity == Severity::Error)
+                        .count();
+                    if failures > 0 || unreadable > 0 {
+                        eprintln!(
+                            "Error linting changelogs: {} problem(s) found in {} file(s)",
+                            failures + unreadable,
+                            reports.len() + unreadable
+                        );
                         std::process::exit(1);
                     }
                 }
-                VersionCommands::List => {
-                    if let Err(e) = changelog.version_list() {
-                        eprintln!("Error listing versions: {}", e);
+                WorkspaceCommands::Fmt => {
+                    let mut failed = false;
+                    for (path, result) in
+                        changelog.workspace_map(|changelog| changelog.fmt(false, None))
+                    {
+                        match result {
+                            Ok(()) => println!("Formatted {}", path.display()),
+                            Err(e) => {
+                                eprintln!("Error formatting {}: {}", path.display(), e);
+                                failed = true;
+                            }
+                        }
+                    }
+                    if failed {
                         std::process::exit(1);
                     }
                 }
-                VersionCommands::Range { version } => {
-                    if let Err(e) = changelog.range(version.as_deref()) {
-                        eprintln!("Error showing range: {}", e);
+                WorkspaceCommands::Audit { strict, format } => {
+                    let mut audits = Vec::new();
+                    let mut unreadable = 0;
+                    for (path, audit) in changelog.workspace_map(Changelog::audit) {
+                        match audit {
+                            Ok(audit) => audits.push(changelog::audit::Audit {
+                                path: path.display().to_string(),
+                                ..audit
+                            }),
+                            Err(e) => {
+                                eprintln!("Error auditing {}: {}", path.display(), e);
+                                unreadable += 1;
+                            }
+                        }
+                    }
+
+                    match format {
+                        OutputFormat::Text => {
+                            for audit in &audits {
+                                println!("{:>3}/100  {}", audit.score, audit.path);
+                                for check in &audit.checks {
+                                    if check.status == changelog::audit::Status::Pass {
+                                        continue;
+                                    }
+                                    match &check.reason {
+                                        Some(reason) => println!(
+                                            "         {:<4}  {}: {}",
+                                            check.status, check.name, reason
+                                        ),
+                                        None => {
+                                            println!("         {:<4}  {}", check.status, check.name)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        OutputFormat::Json => print_json(&audits),
+                    }
+
+                    let failures: usize = audits.iter().map(|a| a.failures(*strict)).sum();
+                    if failures > 0 || unreadable > 0 {
+                        eprintln!(
+                            "Error auditing changelogs: {} check(s) did not pass",
+                            failures + unreadable
+                        );
                         std::process::exit(1);
                     }
                 }
@@ -156,3 +2381,258 @@ fn main() {
         }
     }
 }
+
+/// The suggested bump and the commits that call for it
+fn print_suggestion(suggestion: &Suggestion) {
+    match (&suggestion.bump, &suggestion.next) {
+        (Some(bump), Some(next)) => {
+            println!("{}: {} -> {}", bump, suggestion.current, next);
+            for commit in &suggestion.commits {
+                println!("  {} {} {}", commit.bump, commit.short_id, commit.summary);
+            }
+            for entry in &suggestion.breaking {
+                println!("  {} {}", Bump::Major, entry);
+            }
+        }
+        _ => println!(
+            "No feat, fix, or breaking commits since {}",
+            suggestion.current
+        ),
+    }
+}
+
+/// Prompt for an entry's type, description, and an optional issue or pull
+/// request to link, showing the bullet before it's added. The description
+/// comes back escaped unless `raw`.
+fn add_wizard(
+    changelog: &Changelog,
+    description: Option<&str>,
+    r#type: Option<&ChangeType>,
+    scope: Option<String>,
+    breaking: bool,
+    raw: bool,
+) -> (EntryFields, ChangeType) {
+    if !std::io::stdin().is_terminal() {
+        eprintln!("Error adding changelog entry: a description and --type are required when not running in a terminal");
+        std::process::exit(1);
+    }
+    let prompt_error = |dialoguer::Error::IO(e)| Error::from(e);
+
+    let sections = or_exit(changelog.config(), "reading changelog.toml").sections;
+    let types = sections.all();
+    let items: Vec<String> = types
+        .iter()
+        .map(|t| format!("{:<11}{}", t, t.description().unwrap_or_default()))
+        .collect();
+    let mut selected = r#type
+        .and_then(|t| sections.section(t).ok())
+        .and_then(|t| types.iter().position(|v| *v == t))
+        .unwrap_or(0);
+    let mut text = description.unwrap_or_default().to_string();
+    let mut reference = String::new();
+
+    loop {
+        selected = or_exit(
+            dialoguer::Select::new()
+                .with_prompt("Type of change")
+                .items(&items)
+                .default(selected)
+                .interact()
+                .map_err(prompt_error),
+            "selecting change type",
+        );
+        text = or_exit(
+            dialoguer::Input::<String>::new()
+                .with_prompt("Description")
+                .with_initial_text(&text)
+                .validate_with(|input: &String| {
+                    if input.trim().is_empty() {
+                        Err("a description is required")
+                    } else {
+                        Ok(())
+                    }
+                })
+                .interact_text()
+                .map_err(prompt_error),
+            "reading description",
+        );
+        reference = or_exit(
+            dialoguer::Input::<String>::new()
+                .with_prompt("Issue or pull request to link (optional)")
+                .with_initial_text(&reference)
+                .allow_empty(true)
+                .validate_with(|input: &String| {
+                    let number = input.trim().trim_start_matches('#');
+                    if number.is_empty() || number.parse::<u64>().is_ok() {
+                        Ok(())
+                    } else {
+                        Err("enter a number like #12, or leave it empty")
+                    }
+                })
+                .interact_text()
+                .map_err(prompt_error),
+            "reading reference",
+        );
+
+        let mut fields = EntryFields {
+            description: if raw {
+                text.trim().to_string()
+            } else {
+                escape_entry(text.trim())
+            },
+            scope: scope.clone(),
+            links: Vec::new(),
+            breaking,
+        };
+        if let Ok(number) = reference.trim().trim_start_matches('#').parse() {
+            fields.links.push(changelog.reference(number));
+        }
+        let entry = or_exit(changelog.render_entry(&fields), "loading config");
+        eprintln!("\n### {}\n\n- {}\n", types[selected].heading(), entry);
+        let confirmed = or_exit(
+            dialoguer::Confirm::new()
+                .with_prompt("Add this entry?")
+                .default(true)
+                .interact()
+                .map_err(prompt_error),
+            "confirming entry",
+        );
+        if confirmed {
+            return (fields, types[selected].clone());
+        }
+    }
+}
+
+/// The entry picked on the command line, or interactively when none was given
+fn entry_selector(
+    changelog: &Changelog,
+    entry_match: &Option<String>,
+    index: Option<usize>,
+    r#type: &Option<ChangeType>,
+    version: &Option<String>,
+) -> EntrySelector {
+    if let Some(index) = index {
+        return EntrySelector::Index(index);
+    }
+    if let Some(pattern) = entry_match {
+        return EntrySelector::Match(pattern.clone());
+    }
+
+    let entries = or_exit(
+        changelog.entries(version.as_deref(), r#type.as_ref()),
+        "listing entries",
+    );
+    if entries.is_empty() {
+        eprintln!("No entries found");
+        std::process::exit(1);
+    }
+    let items: Vec<String> = entries
+        .iter()
+        .map(|entry| format!("{}: {}", entry.section, entry.text.replace('\n', " ")))
+        .collect();
+    let selected = or_exit(
+        dialoguer::Select::new()
+            .with_prompt("Select an entry")
+            .items(&items)
+            .interact()
+            .map_err(|dialoguer::Error::IO(e)| Error::from(e)),
+        "selecting entry",
+    );
+    EntrySelector::Index(entries[selected].index)
+}
+
+/// Duplicates are skipped rather than failing, so adding entries again is safe
+fn warn_duplicate(existing: &str) {
+    eprintln!(
+        "Skipping duplicate entry: already in the changelog as \"{}\". Pass --allow-duplicate to add it anyway.",
+        existing.replace('\n', " ")
+    );
+}
+
+/// Days in a duration like `30d`, `4w`, or plain `30`
+fn parse_days(value: &str) -> Result<i64, String> {
+    let (number, unit) = match value.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (value.strip_suffix('d').unwrap_or(value), 1),
+    };
+    number
+        .parse::<i64>()
+        .map(|n| n * unit)
+        .map_err(|_| format!("expected days like 30d or weeks like 4w, not '{}'", value))
+}
+
+/// Unwrap the result of a command, or report what failed and exit
+fn or_exit<T>(result: changelog::Result<T>, action: &str) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("Error {}: {}", action, e);
+        std::process::exit(1);
+    })
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    print!("{}", json(value));
+}
+
+/// `value` as pretty printed JSON, ending in a newline
+fn json<T: Serialize>(value: &T) -> String {
+    let json = or_exit(
+        serde_json::to_string_pretty(value).map_err(Error::from),
+        "writing JSON",
+    );
+    format!("{}\n", json)
+}
+
+/// Whether to style output for a terminal: always or never when `--color`
+/// says so, otherwise when it goes to one and the environment allows colors
+fn styled(color: ColorChoice) -> bool {
+    match color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::io::stdout().is_terminal() && colored::control::SHOULD_COLORIZE.should_colorize()
+        }
+    }
+}
+
+/// Print `text` through `$PAGER` when it goes to a terminal. Without one
+/// set, `less` shows output that fits on screen and exits, and Windows
+/// uses `more`. An empty `PAGER`, or a pager that won't start, prints as is.
+fn page(text: &str) {
+    if !std::io::stdout().is_terminal() {
+        print!("{}", text);
+        return;
+    }
+    let pager = std::env::var("PAGER")
+        .unwrap_or_else(|_| if cfg!(windows) { "more" } else { "less" }.to_string());
+    let words = shell_words::split(&pager).unwrap_or_default();
+    let Some((program, args)) = words.split_first() else {
+        print!("{}", text);
+        return;
+    };
+    let mut command = std::process::Command::new(program);
+    command.args(args).stdin(std::process::Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        // Quit when it fits, keep colors, and leave the text on screen
+        command.env("LESS", "FRX");
+    }
+    match command.spawn() {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // Quitting the pager early closes the pipe, which is fine
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            let _ = child.wait();
+        }
+        Err(_) => print!("{}", text),
+    }
+}
+
+/// Write `text` to `path`, ending it with a newline like stdout would
+fn write_output(path: &Path, text: &str) -> changelog::Result<()> {
+    let mut text = text.to_string();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    std::fs::write(path, text)?;
+    Ok(())
+}
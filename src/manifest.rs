@@ -0,0 +1,157 @@
+use crate::ReleaseEntry;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// The whole changelog as data for other tools, as `export json` prints it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Manifest {
+    /// Markdown above the first release, like the title and introduction
+    pub header: String,
+    /// Unreleased first, then every release newest first
+    pub releases: Vec<Release>,
+}
+
+/// One version in a manifest: what `entry --format json` shows, plus the
+/// yanked marker and the markdown as written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Release {
+    #[serde(flatten)]
+    pub entry: ReleaseEntry,
+    pub yanked: bool,
+    /// The heading and notes as they appear in the changelog
+    pub markdown: String,
+}
+
+/// JSON Schema for a [`Manifest`], for downstream tools to validate against
+pub fn schema() -> Value {
+    let nullable_string = json!({ "type": ["string", "null"] });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Changelog manifest",
+        "type": "object",
+        "required": ["header", "releases"],
+        "additionalProperties": false,
+        "properties": {
+            "header": {
+                "type": "string",
+                "description": "Markdown above the first release"
+            },
+            "releases": {
+                "type": "array",
+                "description": "Unreleased first, then every release newest first",
+                "items": { "$ref": "#/$defs/release" }
+            }
+        },
+        "$defs": {
+            "release": {
+                "type": "object",
+                "required": [
+                    "version", "title", "date", "annotation", "released_by", "sections",
+                    "references", "link", "anchor", "yanked", "markdown"
+                ],
+                "additionalProperties": false,
+                "properties": {
+                    "version": {
+                        "type": "string",
+                        "description": "Version without brackets, or Unreleased"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Heading as written, without the leading ##"
+                    },
+                    "date": {
+                        "type": ["string", "null"],
+                        "description": "Release date as written, usually YYYY-MM-DD"
+                    },
+                    "annotation": nullable_string,
+                    "released_by": nullable_string,
+                    "sections": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["name", "entries"],
+                            "additionalProperties": false,
+                            "properties": {
+                                "name": { "type": "string" },
+                                "entries": { "type": "array", "items": { "type": "string" } }
+                            }
+                        }
+                    },
+                    "references": {
+                        "type": "array",
+                        "description": "Issue and pull request numbers the entries refer to",
+                        "items": { "type": "integer", "minimum": 0 }
+                    },
+                    "link": {
+                        "type": ["string", "null"],
+                        "description": "Compare or release URL on the forge"
+                    },
+                    "anchor": {
+                        "type": "string",
+                        "description": "Id of the version's heading in the rendered changelog"
+                    },
+                    "yanked": { "type": "boolean" },
+                    "markdown": {
+                        "type": "string",
+                        "description": "The heading and notes as they appear in the changelog"
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_matches_manifest() {
+        let manifest = Manifest {
+            header: "# Changelog\n".to_string(),
+            releases: vec![Release {
+                entry: ReleaseEntry {
+                    version: "1.0.0".to_string(),
+                    title: "[1.0.0] - 2025-01-01".to_string(),
+                    date: Some("2025-01-01".to_string()),
+                    annotation: None,
+                    released_by: None,
+                    sections: Vec::new(),
+                    references: vec![12],
+                    link: None,
+                    anchor: "100---2025-01-01".to_string(),
+                    notes: String::new(),
+                },
+                yanked: false,
+                markdown: "## [1.0.0] - 2025-01-01\n".to_string(),
+            }],
+        };
+        let value = serde_json::to_value(&manifest).unwrap();
+        let schema = schema();
+
+        let keys = |object: &Value| {
+            let mut keys: Vec<String> = object.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let required = |schema: &Value| {
+            let mut required: Vec<String> = schema["required"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| key.as_str().unwrap().to_string())
+                .collect();
+            required.sort();
+            required
+        };
+        assert_eq!(keys(&value), required(&schema));
+        assert_eq!(
+            keys(&value["releases"][0]),
+            required(&schema["$defs"]["release"])
+        );
+        assert_eq!(
+            keys(&schema["$defs"]["release"]["properties"]),
+            required(&schema["$defs"]["release"])
+        );
+    }
+}
@@ -0,0 +1,711 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ops::Range;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// A `### Heading` inside a release and the bullet entries listed under it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<String>,
+}
+
+/// Split release notes into their sections. Continuation lines of a
+/// multiline bullet are kept with the entry they belong to.
+pub fn parse_sections(notes: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for line in notes.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("### ") {
+            sections.push(Section {
+                name: name.trim().to_string(),
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(section) = sections.last_mut() else {
+            continue;
+        };
+
+        if let Some(entry) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            section.entries.push(entry.to_string());
+        } else if !trimmed.is_empty() && line.starts_with(char::is_whitespace) {
+            if let Some(entry) = section.entries.last_mut() {
+                entry.push('\n');
+                entry.push_str(trimmed);
+            }
+        }
+    }
+
+    sections
+}
+
+/// The section and line range of every bullet in `notes`, in the same order
+/// as the entries `parse_sections` returns
+pub fn entry_lines(notes: &str) -> Vec<(String, Range<usize>)> {
+    let mut entries: Vec<(String, Range<usize>)> = Vec::new();
+    let mut section: Option<&str> = None;
+    let mut section_has_entry = false;
+
+    for (i, line) in notes.lines().enumerate() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("### ") {
+            section = Some(name.trim());
+            section_has_entry = false;
+            continue;
+        }
+
+        let Some(name) = section else {
+            continue;
+        };
+
+        if trimmed.starts_with("- ") || trimmed.starts_with("* ") {
+            entries.push((name.to_string(), i..i + 1));
+            section_has_entry = true;
+        } else if !trimmed.is_empty() && line.starts_with(char::is_whitespace) && section_has_entry
+        {
+            if let Some((_, lines)) = entries.last_mut() {
+                lines.end = i + 1;
+            }
+        }
+    }
+
+    entries
+}
+
+/// `notes` with `entry` added as the last item of the `group` bullet in the
+/// `section`, like `- **api:**` with its entries nested beneath. `None` when
+/// the section has no such bullet yet.
+pub fn add_to_group(notes: &str, section: &str, group: &str, entry: &str) -> Option<String> {
+    let mut lines: Vec<&str> = notes.lines().collect();
+    let (_, range) = entry_lines(notes).into_iter().find(|(name, range)| {
+        let line = lines[range.start];
+        name.eq_ignore_ascii_case(section)
+            && !line.starts_with(char::is_whitespace)
+            && line.trim()[2..].trim() == group
+    })?;
+    // Nested bullets count as entries of their own, so skip past them too
+    let mut end = range.end;
+    while lines
+        .get(end)
+        .is_some_and(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+    {
+        end += 1;
+    }
+    let item = format!("  - {}", entry);
+    lines.insert(end, &item);
+    Some(lines.join("\n"))
+}
+
+/// `text` cut to fit in `width` terminal columns, ending in an ellipsis when
+/// it had to be shortened. Wide characters such as CJK and emoji count as two.
+pub fn truncate_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut output = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        // Leave a column for the ellipsis
+        if used + w + 1 > width {
+            break;
+        }
+        output.push(c);
+        used += w;
+    }
+    output.push('…');
+    output
+}
+
+/// Date portion of a release title such as `[1.0.0] - 2025-01-01`
+pub fn release_date(title: &str) -> Option<&str> {
+    title.split(" - ").nth(1).and_then(|rest| {
+        rest.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .find(|s| !s.is_empty())
+    })
+}
+
+/// A release title split into its version as written, brackets and all,
+/// and everything after it
+pub fn split_title(title: &str) -> (&str, &str) {
+    let end = if title.starts_with('[') {
+        match title.find(']') {
+            // An inline link to the version goes with it
+            Some(i) if title[i + 1..].starts_with('(') => {
+                title[i..].find(')').map_or(title.len(), |j| i + j + 1)
+            }
+            Some(i) => i + 1,
+            None => title.len(),
+        }
+    } else if let Some(i) = title.find(" - ") {
+        i
+    } else if title.starts_with("Unreleased") {
+        title.len()
+    } else {
+        title.find(char::is_whitespace).unwrap_or(title.len())
+    };
+    title.split_at(end)
+}
+
+/// The version as [`split_title`] gives it, without brackets or an inline
+/// link, e.g. `1.2.0` from `[1.2.0](https://example.com)`
+pub fn bare_version(written: &str) -> &str {
+    let version = written
+        .split_once("](")
+        .map_or(written, |(version, _)| version);
+    version.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// A note after a release's date, like `re-released 2025-01-03` in
+/// `[1.2.0] - 2025-01-01 (re-released 2025-01-03)`
+pub fn release_annotation(title: &str) -> Option<&str> {
+    let title = strip_yanked(title).unwrap_or(title.trim_end());
+    let rest = split_title(title).1.trim_start();
+    let rest = match (rest.strip_prefix("- "), release_date(title)) {
+        (Some(dated), Some(date)) => {
+            let dated = dated.trim_start();
+            dated.strip_prefix(date).unwrap_or(dated)
+        }
+        (Some(dated), None) => dated,
+        (None, _) => rest,
+    };
+    let annotation = rest
+        .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '-'))
+        .trim_end();
+    let annotation = annotation
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+        .unwrap_or(annotation);
+    (!annotation.is_empty()).then_some(annotation)
+}
+
+/// `title` with its annotation replaced by `annotation` in parentheses, or
+/// removed
+pub fn set_annotation(title: &str, annotation: Option<&str>) -> String {
+    let bare = strip_yanked(title).unwrap_or(title.trim_end());
+    let mut result = split_title(bare).0.to_string();
+    if let Some(date) = release_date(bare) {
+        result.push_str(&format!(" - {}", date));
+    }
+    if let Some(annotation) = annotation {
+        result.push_str(&format!(" ({})", annotation));
+    }
+    set_yanked(&result, is_yanked(title))
+}
+
+/// Keep a Changelog's marker for releases that were pulled after publishing
+pub const YANKED: &str = "[YANKED]";
+
+/// Whether a title such as `[1.0.0] - 2025-01-01 [YANKED]` is marked as yanked
+pub fn is_yanked(title: &str) -> bool {
+    strip_yanked(title).is_some()
+}
+
+/// `title` with the yanked marker added or removed
+pub fn set_yanked(title: &str, yanked: bool) -> String {
+    let title = strip_yanked(title).unwrap_or(title.trim_end());
+    if yanked {
+        format!("{} {}", title, YANKED)
+    } else {
+        title.to_string()
+    }
+}
+
+fn strip_yanked(title: &str) -> Option<&str> {
+    let title = title.trim_end();
+    let split = title.len().checked_sub(YANKED.len())?;
+    let marker = title.get(split..)?;
+    marker
+        .eq_ignore_ascii_case(YANKED)
+        .then(|| title[..split].trim_end())
+}
+
+/// A code freeze on Unreleased, marked by `<!-- frozen -->` below its
+/// heading, or `<!-- frozen: next -->` to send new entries to a release
+/// train queue until it thaws
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Freeze {
+    /// Queue new entries go to instead, e.g. `next` for `Unreleased (next)`
+    pub redirect: Option<String>,
+}
+
+/// The freeze marked in release `notes`, if any
+pub fn freeze(notes: &str) -> Option<Freeze> {
+    notes.lines().find_map(freeze_marker)
+}
+
+/// `notes` with the freeze marker replaced by `freeze`, or removed
+pub fn set_freeze(notes: &str, freeze: Option<&Freeze>) -> String {
+    let rest = notes
+        .lines()
+        .filter(|line| freeze_marker(line).is_none())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let rest = rest.trim_start_matches('\n');
+    match freeze {
+        Some(Freeze {
+            redirect: Some(queue),
+        }) => format!("<!-- frozen: {} -->\n\n{}", queue, rest),
+        Some(Freeze { redirect: None }) => format!("<!-- frozen -->\n\n{}", rest),
+        None => rest.to_string(),
+    }
+}
+
+fn freeze_marker(line: &str) -> Option<Freeze> {
+    let marker = line
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix("frozen")?;
+    if marker.is_empty() {
+        return Some(Freeze { redirect: None });
+    }
+    let queue = marker.strip_prefix(':')?.trim();
+    Some(Freeze {
+        redirect: Some(queue.to_string()).filter(|q| !q.is_empty()),
+    })
+}
+
+/// Escape markdown in a plain text entry so it renders as typed and can't
+/// be mistaken for a heading, a nested list, or a link reference. Complete
+/// code spans, inline links, and autolinks are kept as they are.
+pub fn escape_entry(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    // Block syntax only matters at the start of the entry
+    let start = text.len() - text.trim_start().len();
+    output.push_str(&text[..start]);
+    let rest = &text[start..];
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if rest.starts_with(['#', '>'])
+        || ["- ", "+ ", "* "].iter().any(|p| rest.starts_with(p))
+        || rest == "-"
+    {
+        output.push('\\');
+    } else if digits > 0 && [". ", ") "].iter().any(|p| rest[digits..].starts_with(p)) {
+        output.push_str(&rest[..digits]);
+        output.push('\\');
+        return escape_inline(&rest[digits..], output);
+    }
+    escape_inline(rest, output)
+}
+
+fn escape_inline(text: &str, mut output: String) -> String {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+        let keep = match c {
+            // Already escaped by the author
+            '\\' => rest.chars().nth(1).map(|next| 1 + next.len_utf8()),
+            '`' => code_span_len(rest),
+            '[' => inline_link_len(rest),
+            '<' => autolink_len(rest),
+            _ => None,
+        };
+        if let Some(len) = keep {
+            output.push_str(&rest[..len]);
+            i += len;
+            continue;
+        }
+
+        let html = c == '<'
+            && rest[1..].starts_with(|n: char| n.is_ascii_alphabetic() || "/!?".contains(n));
+        if matches!(c, '`' | '[' | ']') || html {
+            output.push('\\');
+        }
+        output.push(c);
+        i += c.len_utf8();
+    }
+    output
+}
+
+/// Length of a code span opened by the backticks at the start of `text`
+fn code_span_len(text: &str) -> Option<usize> {
+    let ticks = text.len() - text.trim_start_matches('`').len();
+    let fence = &text[..ticks];
+    let mut from = ticks;
+    while let Some(found) = text[from..].find(fence) {
+        let end = from + found;
+        let run = text[end..].len() - text[end..].trim_start_matches('`').len();
+        if run == ticks {
+            return Some(end + ticks);
+        }
+        from = end + run;
+    }
+    None
+}
+
+/// Length of a `[text](destination)` link at the start of `text`
+fn inline_link_len(text: &str) -> Option<usize> {
+    let close = text.find("](")?;
+    if text[1..close].contains(['[', ']']) {
+        return None;
+    }
+    let end = close + 2 + text[close + 2..].find(')')?;
+    (!text[close + 2..end].contains(char::is_whitespace)).then_some(end + 1)
+}
+
+/// Length of an `<https://...>` autolink at the start of `text`
+fn autolink_len(text: &str) -> Option<usize> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    (inner.contains("://") && !inner.contains(char::is_whitespace)).then_some(end + 1)
+}
+
+/// Issue and pull request numbers an entry refers to, like `#12` or
+/// `[#34](url)`, in order and without repeats. Code spans are skipped.
+pub fn references(text: &str) -> Vec<u64> {
+    let reference = Regex::new(r"`[^`]*`|(?:^|[^\w#&/])#(\d+)\b").unwrap();
+    let mut numbers = Vec::new();
+    for caps in reference.captures_iter(text) {
+        let number = caps.get(1).and_then(|m| m.as_str().parse().ok());
+        if let Some(number) = number.filter(|n| !numbers.contains(n)) {
+            numbers.push(number);
+        }
+    }
+    numbers
+}
+
+/// What starts an entry for a breaking change
+pub const BREAKING: &str = "**Breaking:**";
+
+/// Whether an entry is marked as a breaking change, however the marker is
+/// capitalized
+pub fn is_breaking(entry: &str) -> bool {
+    entry
+        .trim_start()
+        .get(..BREAKING.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(BREAKING))
+}
+
+/// Three-way merge of one release's notes. Entries the other side removed
+/// since `base` are dropped, entries it added are listed after ours in the
+/// same section, and an entry is never listed twice in one section.
+/// Everything else, prose and formatting included, follows `ours`.
+pub fn merge_notes(ours: &str, base: &str, theirs: &str) -> String {
+    let lines: Vec<&str> = ours.lines().collect();
+    let their_lines: Vec<&str> = theirs.lines().collect();
+    let base_keys: HashSet<_> = keyed_entries(base, &base.lines().collect::<Vec<_>>())
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    let their_entries = keyed_entries(theirs, &their_lines);
+    let their_keys: HashSet<_> = their_entries.iter().map(|(key, _)| key).collect();
+
+    let mut seen = HashSet::new();
+    let mut dropped = vec![false; lines.len()];
+    // The line after which entries for a section get inserted
+    let mut insert_after: Vec<(&str, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(name) = line.trim().strip_prefix("### ") {
+            let mut end = i;
+            while lines.get(end + 1).is_some_and(|l| l.trim().is_empty()) {
+                end += 1;
+            }
+            insert_after.push((name.trim(), end));
+        }
+    }
+    for (key, range) in keyed_entries(ours, &lines) {
+        let removed = base_keys.contains(&key) && !their_keys.contains(&key);
+        if let Some(section) = insert_after.iter_mut().rev().find(|(n, _)| *n == key.0) {
+            section.1 = range.end - 1;
+        }
+        if removed || !seen.insert(key) {
+            dropped[range].fill(true);
+        }
+    }
+
+    let mut added: Vec<(&str, Vec<String>)> = Vec::new();
+    for ((section, text), range) in &their_entries {
+        if base_keys.contains(&(section.clone(), text.clone()))
+            || !seen.insert((section.clone(), text.clone()))
+        {
+            continue;
+        }
+        let entry = their_lines[range.clone()].join("\n");
+        match added.iter_mut().find(|(name, _)| name == section) {
+            Some((_, entries)) => entries.push(entry),
+            None => added.push((section, vec![entry])),
+        }
+    }
+
+    let mut output: Vec<String> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if !dropped[i] {
+            output.push(line.to_string());
+        }
+        for (name, end) in &insert_after {
+            if *end == i {
+                if let Some((_, entries)) = added.iter_mut().find(|(n, _)| n == name) {
+                    output.append(entries);
+                }
+            }
+        }
+    }
+
+    // Sections only they have go after ours, ahead of any link definitions
+    let new_sections: Vec<_> = added.into_iter().filter(|(_, e)| !e.is_empty()).collect();
+    if !new_sections.is_empty() {
+        let body_end = output
+            .iter()
+            .rposition(|line| {
+                let line = line.trim();
+                let link_definition = line.starts_with('[') && line.contains("]: ");
+                !line.is_empty() && !link_definition
+            })
+            .map_or(0, |i| i + 1);
+        let mut sections = Vec::new();
+        for (name, entries) in new_sections {
+            sections.push(String::new());
+            sections.push(format!("### {}", name));
+            sections.push(String::new());
+            sections.extend(entries);
+        }
+        output.splice(body_end..body_end, sections);
+    }
+
+    let mut merged = output.join("\n");
+    if ours.ends_with('\n') {
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Entries keyed by section and text, with surrounding whitespace ignored
+fn keyed_entries(notes: &str, lines: &[&str]) -> Vec<((String, String), Range<usize>)> {
+    entry_lines(notes)
+        .into_iter()
+        .map(|(section, range)| {
+            let text = lines[range.clone()]
+                .iter()
+                .map(|line| line.trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            ((section, text), range)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references() {
+        assert_eq!(
+            references("crash on login ([#12](https://github.com/o/r/pull/12), #34, #12)"),
+            [12, 34]
+        );
+        assert_eq!(
+            references("#5 in `a#6` and `#7`, not issue#8 or &#9; but\n#10"),
+            [5, 10]
+        );
+        assert!(references("no refs (https://github.com/o/r/issues/3)").is_empty());
+    }
+
+    #[test]
+    fn test_is_breaking() {
+        assert!(is_breaking("**Breaking:** drop node 16"));
+        assert!(is_breaking("**BREAKING:** drop node 16"));
+        assert!(!is_breaking("**cli**: breaking: drop node 16"));
+        assert!(!is_breaking("**Break"));
+    }
+
+    #[test]
+    fn test_parse_sections_with_multiline_entries() {
+        let notes = "### Added\n\n- one\n- two\n  continued\n\n### Fixed\n\n- bug";
+        let sections = parse_sections(notes);
+
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    name: "Added".to_string(),
+                    entries: vec!["one".to_string(), "two\ncontinued".to_string()],
+                },
+                Section {
+                    name: "Fixed".to_string(),
+                    entries: vec!["bug".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_release_date() {
+        assert_eq!(release_date("[1.0.0] - 2025-01-01"), Some("2025-01-01"));
+        assert_eq!(release_date("[1.0.0]"), None);
+        assert_eq!(
+            release_date("[1.0.0] - 2025-01-01 [YANKED]"),
+            Some("2025-01-01")
+        );
+    }
+
+    #[test]
+    fn test_release_annotation() {
+        let title = "[1.2.0] - 2025-01-01 (re-released 2025-01-03) [YANKED]";
+        assert_eq!(release_date(title), Some("2025-01-01"));
+        assert_eq!(release_annotation(title), Some("re-released 2025-01-03"));
+        assert_eq!(
+            release_annotation("1.1.0 - 2024-12-01 - hotfix 2024-12-02"),
+            Some("hotfix 2024-12-02")
+        );
+        assert_eq!(
+            release_date("[1.0.0] - 2024-11-01, 2024-11-03"),
+            Some("2024-11-01")
+        );
+        assert_eq!(release_annotation("[1.0.0] - 2024-11-01"), None);
+        assert_eq!(split_title("Unreleased (next)"), ("Unreleased (next)", ""));
+        let linked = "[1.2.0](https://example.com/compare/v1.1.0...v1.2.0) - 2025-01-01";
+        assert_eq!(
+            split_title(linked),
+            (
+                "[1.2.0](https://example.com/compare/v1.1.0...v1.2.0)",
+                " - 2025-01-01"
+            )
+        );
+        assert_eq!(bare_version(split_title(linked).0), "1.2.0");
+        assert_eq!(release_annotation(linked), None);
+
+        assert_eq!(
+            set_annotation(title, Some("re-released 2025-01-05")),
+            "[1.2.0] - 2025-01-01 (re-released 2025-01-05) [YANKED]"
+        );
+        assert_eq!(set_annotation(title, None), "[1.2.0] - 2025-01-01 [YANKED]");
+    }
+
+    #[test]
+    fn test_yanked_marker() {
+        assert!(is_yanked("[1.0.0] - 2025-01-01 [yanked]"));
+        assert!(!is_yanked("[1.0.0] - 2025-01-01"));
+        assert_eq!(
+            set_yanked("[1.0.0] - 2025-01-01 [yanked]", true),
+            "[1.0.0] - 2025-01-01 [YANKED]"
+        );
+        assert_eq!(
+            set_yanked("[1.0.0] - 2025-01-01 [YANKED]", false),
+            "[1.0.0] - 2025-01-01"
+        );
+    }
+
+    #[test]
+    fn test_freeze_marker() {
+        let notes = "\n### Added\n\n- export\n";
+        assert_eq!(freeze(notes), None);
+
+        let frozen = set_freeze(notes, Some(&Freeze { redirect: None }));
+        assert_eq!(frozen, "<!-- frozen -->\n\n### Added\n\n- export");
+        assert_eq!(freeze(&frozen), Some(Freeze { redirect: None }));
+
+        let redirected = set_freeze(
+            &frozen,
+            Some(&Freeze {
+                redirect: Some("next".to_string()),
+            }),
+        );
+        assert_eq!(redirected, "<!-- frozen: next -->\n\n### Added\n\n- export");
+        assert_eq!(
+            freeze(&redirected).and_then(|f| f.redirect),
+            Some("next".to_string())
+        );
+        assert_eq!(set_freeze(&redirected, None), "### Added\n\n- export");
+        assert_eq!(freeze("<!-- frozenset -->"), None);
+    }
+
+    #[test]
+    fn test_entry_lines() {
+        let notes = "### Added\n\n- one\n- two\n  continued\n\n### Fixed\n\n* three\n";
+        assert_eq!(
+            entry_lines(notes),
+            vec![
+                ("Added".to_string(), 2..3),
+                ("Added".to_string(), 3..5),
+                ("Fixed".to_string(), 8..9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_to_group() {
+        let notes = "### Added\n\n- **api:**\n  - one\n- **cli:**\n  - two\n\n### Fixed\n\n- **api:**\n  - three\n";
+        assert_eq!(
+            add_to_group(notes, "Added", "**api:**", "four").unwrap(),
+            "### Added\n\n- **api:**\n  - one\n  - four\n- **cli:**\n  - two\n\n### Fixed\n\n- **api:**\n  - three"
+        );
+        assert_eq!(add_to_group(notes, "Added", "**db:**", "four"), None);
+        assert_eq!(add_to_group(notes, "Removed", "**api:**", "four"), None);
+    }
+
+    #[test]
+    fn test_truncate_width() {
+        assert_eq!(truncate_width("short", 10), "short");
+        assert_eq!(truncate_width("abcdefghij", 5), "abcd…");
+        assert_eq!(truncate_width("修复启动崩溃", 7), "修复启…");
+        assert_eq!(truncate_width("🚀🚀🚀", 4), "🚀…");
+        assert_eq!(truncate_width("naïve café", 10), "naïve café");
+    }
+
+    #[test]
+    fn test_escape_entry() {
+        assert_eq!(
+            escape_entry("use `Vec<T>` instead of [T; N]"),
+            "use `Vec<T>` instead of \\[T; N\\]"
+        );
+        assert_eq!(
+            escape_entry("see [the docs](https://example.com) and <https://example.com>"),
+            "see [the docs](https://example.com) and <https://example.com>"
+        );
+        assert_eq!(
+            escape_entry("render <details> blocks"),
+            "render \\<details> blocks"
+        );
+        assert_eq!(escape_entry("a < b"), "a < b");
+        assert_eq!(
+            escape_entry("[1.0.0]: not a link"),
+            "\\[1.0.0\\]: not a link"
+        );
+        assert_eq!(escape_entry("stray ` tick"), "stray \\` tick");
+        assert_eq!(escape_entry("``a ` b``"), "``a ` b``");
+        assert_eq!(escape_entry("# of retries"), "\\# of retries");
+        assert_eq!(escape_entry("- dashes"), "\\- dashes");
+        assert_eq!(escape_entry("2. step"), "2\\. step");
+        assert_eq!(
+            escape_entry("already \\[escaped\\]"),
+            "already \\[escaped\\]"
+        );
+        assert_eq!(escape_entry("naïve [ü]"), "naïve \\[ü\\]");
+    }
+
+    #[test]
+    fn test_merge_notes() {
+        let base = "### Added\n\n- one\n- two\n\n### Fixed\n\n- bug\n";
+        let ours = "### Added\n\n- one\n- two\n- mine\n\n### Fixed\n\n- bug\n";
+        let theirs = "### Added\n\n- two\n- theirs\n  continued\n- mine\n\n### Fixed\n\n- bug\n\n### Security\n\n- patched\n";
+        assert_eq!(
+            merge_notes(ours, base, theirs),
+            "### Added\n\n- two\n- mine\n- theirs\n  continued\n\n### Fixed\n\n- bug\n\n### Security\n\n- patched\n"
+        );
+
+        // Sections they add go before the link definitions at the end of the file
+        let ours = "### Added\n\n- one\n\n[1.0.0]: https://example.com\n";
+        assert_eq!(
+            merge_notes(ours, "", "### Fixed\n\n- bug\n"),
+            "### Added\n\n- one\n\n### Fixed\n\n- bug\n\n[1.0.0]: https://example.com\n"
+        );
+
+        assert_eq!(
+            merge_notes("### Added\n\n- one\n- one\n", "", ""),
+            "### Added\n\n- one\n"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::ReleaseEntry;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Longest message Discord takes in one post
+const DISCORD_LIMIT: usize = 2000;
+
+/// What a webhook expects to be posted to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// `{"text": ...}` in Slack's mrkdwn, which Mattermost takes too
+    Slack,
+    /// `{"content": ...}` in markdown
+    Discord,
+    /// The version as `entry --format json` shows it, plus its markdown
+    Json,
+}
+
+impl Kind {
+    /// Slack and Discord webhooks going by their URL, anything else generic JSON
+    pub fn detect(url: &str) -> Self {
+        let host = host(url);
+        if host == "hooks.slack.com" {
+            Kind::Slack
+        } else if [
+            "discord.com",
+            "discordapp.com",
+            "ptb.discord.com",
+            "canary.discord.com",
+        ]
+        .contains(&host.as_str())
+        {
+            Kind::Discord
+        } else {
+            Kind::Json
+        }
+    }
+}
+
+/// The host a URL points at, lowercased and without a port
+pub(crate) fn host(url: &str) -> String {
+    url.split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// A webhook from `[notify] webhooks` in `changelog.toml`: a URL, or a table
+/// naming the kind for URLs it can't be told from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Webhook {
+    Url(String),
+    Table { url: String, kind: Option<Kind> },
+}
+
+impl Webhook {
+    pub fn url(&self) -> &str {
+        match self {
+            Webhook::Url(url) | Webhook::Table { url, .. } => url,
+        }
+    }
+
+    pub fn kind(&self) -> Kind {
+        match self {
+            Webhook::Table {
+                kind: Some(kind), ..
+            } => *kind,
+            _ => Kind::detect(self.url()),
+        }
+    }
+}
+
+/// The body announcing `entry` to a `kind` of webhook, headed by `project`
+pub fn payload(kind: Kind, project: &str, entry: &ReleaseEntry) -> Value {
+    match kind {
+        Kind::Slack => json!({
+            "text": format!("*{} {}*\n\n{}", project, entry.version, entry.slack().trim_end()),
+        }),
+        Kind::Discord => {
+            let mut content = format!(
+                "**{} {}**\n\n{}",
+                project,
+                entry.version,
+                entry.linked_markdown().trim_end()
+            );
+            if content.chars().count() > DISCORD_LIMIT {
+                let more = match &entry.link {
+                    Some(link) => format!("\n… [more]({})", link),
+                    None => "\n…".to_string(),
+                };
+                content = content
+                    .chars()
+                    .take(DISCORD_LIMIT - more.chars().count())
+                    .collect::<String>()
+                    + &more;
+            }
+            json!({ "content": content })
+        }
+        Kind::Json => {
+            let mut value = json!({ "project": project });
+            if let (Some(object), Ok(Value::Object(fields))) =
+                (value.as_object_mut(), serde_json::to_value(entry))
+            {
+                object.extend(fields);
+            }
+            value["markdown"] = entry.markdown().into();
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload() {
+        assert_eq!(
+            Kind::detect("https://hooks.slack.com/services/T0/B0/x"),
+            Kind::Slack
+        );
+        assert_eq!(
+            Kind::detect("https://discord.com/api/webhooks/1/x"),
+            Kind::Discord
+        );
+        assert_eq!(Kind::detect("https://ci.example.com/hooks"), Kind::Json);
+        let table = Webhook::Table {
+            url: "https://chat.example.com/hooks/x".to_string(),
+            kind: Some(Kind::Slack),
+        };
+        assert_eq!(table.kind(), Kind::Slack);
+
+        let entry = ReleaseEntry {
+            version: "1.1.0".to_string(),
+            title: "[1.1.0] - 2025-02-01".to_string(),
+            date: Some("2025-02-01".to_string()),
+            annotation: None,
+            released_by: None,
+            sections: Vec::new(),
+            references: Vec::new(),
+            link: Some("https://github.com/o/r/compare/v1.0.0...v1.1.0".to_string()),
+            anchor: "110---2025-02-01".to_string(),
+            notes: "### Added\n\n- export".to_string(),
+        };
+        assert_eq!(
+            payload(Kind::Slack, "app", &entry)["text"],
+            "*app 1.1.0*\n\n*<https://github.com/o/r/compare/v1.0.0...v1.1.0|1.1.0> - 2025-02-01*\n\n*Added*\n\n• export"
+        );
+        assert_eq!(
+            payload(Kind::Discord, "app", &entry)["content"],
+            "**app 1.1.0**\n\n## [1.1.0](https://github.com/o/r/compare/v1.0.0...v1.1.0) - 2025-02-01\n\n### Added\n\n- export"
+        );
+        let json = payload(Kind::Json, "app", &entry);
+        assert_eq!(json["project"], "app");
+        assert_eq!(json["version"], "1.1.0");
+        assert_eq!(json["markdown"], entry.markdown());
+
+        let long = ReleaseEntry {
+            notes: format!("### Added\n\n{}", "- export\n".repeat(300)),
+            ..entry
+        };
+        let content = payload(Kind::Discord, "app", &long)["content"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(content.chars().count(), DISCORD_LIMIT);
+        assert!(content.ends_with("\n… [more](https://github.com/o/r/compare/v1.0.0...v1.1.0)"));
+    }
+}
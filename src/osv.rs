@@ -0,0 +1,206 @@
+use serde::Serialize;
+
+const SCHEMA_VERSION: &str = "1.6.0";
+
+/// An OSV advisory stub generated from a Security changelog entry.
+///
+/// See https://ossf.github.io/osv-schema/ for the full format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Advisory {
+    pub schema_version: &'static str,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    /// Required by the schema, so an undated release's advisories are
+    /// modified when they're exported
+    pub modified: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub details: String,
+    pub affected: Vec<Affected>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Affected {
+    pub package: Package,
+    pub ranges: Vec<Range>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Range {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Introduced(String),
+    Fixed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub url: String,
+}
+
+/// Build an advisory for a Security entry that was fixed in `version`.
+///
+/// The first CVE, GHSA, or RUSTSEC identifier in the entry becomes the
+/// advisory id and the rest become aliases; entries without one get a
+/// placeholder id to be replaced once an identifier is assigned.
+pub fn advisory(
+    entry: &str,
+    version: &str,
+    date: Option<&str>,
+    package: &Package,
+    index: usize,
+) -> Advisory {
+    let mut ids = advisory_ids(entry);
+    let id = if ids.is_empty() {
+        format!("{}-{}-{}", package.name.to_uppercase(), version, index + 1)
+    } else {
+        ids.remove(0)
+    };
+    let references = std::iter::once(&id)
+        .chain(ids.iter())
+        .filter_map(|id| reference_url(id))
+        .map(|url| Reference {
+            kind: "ADVISORY",
+            url,
+        })
+        .collect();
+    let timestamp = date.map(|d| format!("{}T00:00:00Z", d));
+
+    Advisory {
+        schema_version: SCHEMA_VERSION,
+        id,
+        published: timestamp.clone(),
+        modified: timestamp
+            .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+        aliases: ids,
+        summary: entry.lines().next().unwrap_or_default().to_string(),
+        details: entry.to_string(),
+        affected: vec![Affected {
+            package: package.clone(),
+            ranges: vec![Range {
+                kind: "SEMVER",
+                events: vec![
+                    Event::Introduced("0".to_string()),
+                    Event::Fixed(version.to_string()),
+                ],
+            }],
+        }],
+        references,
+    }
+}
+
+/// CVE, GHSA, and RUSTSEC identifiers mentioned in `text`, in order
+pub fn advisory_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for token in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-')) {
+        let token = token.trim_matches('-');
+        if is_advisory_id(token) && !ids.iter().any(|id| id == token) {
+            ids.push(token.to_string());
+        }
+    }
+    ids
+}
+
+fn is_advisory_id(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('-').collect();
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match parts.as_slice() {
+        ["CVE", year, number] | ["RUSTSEC", year, number] => {
+            year.len() == 4 && digits(year) && digits(number)
+        }
+        ["GHSA", a, b, c] => [a, b, c]
+            .iter()
+            .all(|p| p.len() == 4 && p.chars().all(|c| c.is_ascii_alphanumeric())),
+        _ => false,
+    }
+}
+
+fn reference_url(id: &str) -> Option<String> {
+    if id.starts_with("CVE-") {
+        Some(format!("https://nvd.nist.gov/vuln/detail/{}", id))
+    } else if id.starts_with("GHSA-") {
+        Some(format!("https://github.com/advisories/{}", id))
+    } else if id.starts_with("RUSTSEC-") {
+        Some(format!("https://rustsec.org/advisories/{}.html", id))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advisory_ids() {
+        assert_eq!(
+            advisory_ids("fix path traversal (CVE-2025-1234, GHSA-abcd-1234-wxyz)"),
+            vec!["CVE-2025-1234", "GHSA-abcd-1234-wxyz"]
+        );
+        assert!(advisory_ids("CVE-25-1 and GHSA-short").is_empty());
+    }
+
+    #[test]
+    fn test_advisory_json() {
+        let package = Package {
+            ecosystem: "crates.io".to_string(),
+            name: "changelog".to_string(),
+        };
+        let advisory = advisory(
+            "fix path traversal (CVE-2025-1234, GHSA-abcd-1234-wxyz)",
+            "1.2.3",
+            Some("2025-01-01"),
+            &package,
+            0,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&advisory).unwrap(),
+            serde_json::json!({
+                "schema_version": "1.6.0",
+                "id": "CVE-2025-1234",
+                "published": "2025-01-01T00:00:00Z",
+                "modified": "2025-01-01T00:00:00Z",
+                "aliases": ["GHSA-abcd-1234-wxyz"],
+                "summary": "fix path traversal (CVE-2025-1234, GHSA-abcd-1234-wxyz)",
+                "details": "fix path traversal (CVE-2025-1234, GHSA-abcd-1234-wxyz)",
+                "affected": [{
+                    "package": { "ecosystem": "crates.io", "name": "changelog" },
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{ "introduced": "0" }, { "fixed": "1.2.3" }]
+                    }]
+                }],
+                "references": [
+                    { "type": "ADVISORY", "url": "https://nvd.nist.gov/vuln/detail/CVE-2025-1234" },
+                    { "type": "ADVISORY", "url": "https://github.com/advisories/GHSA-abcd-1234-wxyz" }
+                ]
+            })
+        );
+
+        let undated = super::advisory("fix crash", "1.2.4", None, &package, 0);
+        assert_eq!(undated.published, None);
+        assert!(chrono::DateTime::parse_from_rfc3339(&undated.modified).is_ok());
+        let value = serde_json::to_value(&undated).unwrap();
+        assert!(value["modified"].is_string());
+        assert!(value.get("published").is_none());
+    }
+}
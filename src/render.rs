@@ -0,0 +1,200 @@
+use colored::Colorize;
+use comrak::nodes::{AstNode, ListType, NodeValue};
+use comrak::{Arena, Options};
+
+/// Markdown rendered to an HTML fragment. Raw HTML in the notes is left out.
+pub fn html(markdown: &str) -> String {
+    comrak::markdown_to_html(markdown, &Options::default())
+}
+
+/// The id forges give a heading when they render markdown, e.g.
+/// `120---2025-02-01` for `## [1.2.0] - 2025-02-01`
+pub fn anchor(heading: &str) -> String {
+    let text = plain(&format!("## {}", heading));
+    comrak::Anchorizer::new().anchorize(text.trim().to_string())
+}
+
+/// Markdown as plain text: markup and link targets are dropped, headings
+/// become lines of their own, and list items keep a `- ` bullet
+pub fn plain(markdown: &str) -> String {
+    render(markdown, Style::Plain)
+}
+
+/// Markdown in Slack's mrkdwn dialect, for chat messages and webhooks
+pub fn slack(markdown: &str) -> String {
+    render(markdown, Style::Slack)
+}
+
+/// Markdown styled for a terminal: bold headings, bullets, and
+/// highlighted code and links. Colors follow `colored`'s settings.
+pub fn terminal(markdown: &str) -> String {
+    render(markdown, Style::Terminal)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Slack,
+    Terminal,
+}
+
+fn render(markdown: &str, style: Style) -> String {
+    let arena = Arena::new();
+    let root = comrak::parse_document(&arena, markdown, &Options::default());
+    let mut blocks = Vec::new();
+    for node in root.children() {
+        blocks.push(block(node, style, 0));
+    }
+    let mut output = blocks
+        .into_iter()
+        .filter(|b| !b.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    output.push('\n');
+    output
+}
+
+fn block<'a>(node: &'a AstNode<'a>, style: Style, depth: usize) -> String {
+    match &node.data.borrow().value {
+        NodeValue::Heading(heading) => {
+            let text = inlines(node, style);
+            match style {
+                Style::Plain => text,
+                Style::Slack => format!("*{}*", text),
+                Style::Terminal if heading.level <= 2 => text.bold().underline().to_string(),
+                Style::Terminal => text.bold().yellow().to_string(),
+            }
+        }
+        NodeValue::List(list) => {
+            let mut items = Vec::new();
+            for (i, item) in node.children().enumerate() {
+                let bullet = match (list.list_type, style) {
+                    (ListType::Ordered, _) => format!("{}. ", list.start + i),
+                    (ListType::Bullet, Style::Plain) => "- ".to_string(),
+                    (ListType::Bullet, Style::Slack | Style::Terminal) => "• ".to_string(),
+                };
+                let indent = "  ".repeat(depth);
+                let content = item
+                    .children()
+                    .map(|child| block(child, style, depth + 1))
+                    .filter(|b| !b.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                items.push(format!("{}{}{}", indent, bullet, content));
+            }
+            items.join("\n")
+        }
+        NodeValue::CodeBlock(code) => match style {
+            Style::Plain => code.literal.trim_end().to_string(),
+            Style::Slack => format!("```\n{}\n```", escape_slack(code.literal.trim_end())),
+            Style::Terminal => code
+                .literal
+                .trim_end()
+                .lines()
+                .map(|line| format!("    {}", line.cyan()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        },
+        NodeValue::BlockQuote => node
+            .children()
+            .map(|child| block(child, style, depth))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .lines()
+            .map(|line| format!("> {}", line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        NodeValue::ThematicBreak | NodeValue::HtmlBlock(_) => String::new(),
+        _ => inlines(node, style),
+    }
+}
+
+fn inlines<'a>(node: &'a AstNode<'a>, style: Style) -> String {
+    node.children().map(|child| inline(child, style)).collect()
+}
+
+fn inline<'a>(node: &'a AstNode<'a>, style: Style) -> String {
+    let slack = style == Style::Slack;
+    match &node.data.borrow().value {
+        NodeValue::Text(text) if slack => escape_slack(text),
+        NodeValue::Text(text) => text.clone(),
+        NodeValue::Code(code) if slack => format!("`{}`", escape_slack(&code.literal)),
+        NodeValue::Code(code) if style == Style::Terminal => code.literal.cyan().to_string(),
+        NodeValue::Code(code) => code.literal.clone(),
+        NodeValue::SoftBreak => " ".to_string(),
+        NodeValue::LineBreak => "\n".to_string(),
+        NodeValue::Emph if slack => format!("_{}_", inlines(node, style)),
+        NodeValue::Strong if slack => format!("*{}*", inlines(node, style)),
+        NodeValue::Strikethrough if slack => format!("~{}~", inlines(node, style)),
+        NodeValue::Link(link) if slack => {
+            format!("<{}|{}>", link.url, inlines(node, style))
+        }
+        NodeValue::Emph if style == Style::Terminal => inlines(node, style).italic().to_string(),
+        NodeValue::Strong if style == Style::Terminal => inlines(node, style).bold().to_string(),
+        NodeValue::Link(_) if style == Style::Terminal => {
+            inlines(node, style).underline().to_string()
+        }
+        NodeValue::HtmlInline(_) => String::new(),
+        // Emphasis, link text, and image alt text keep just their words
+        _ => inlines(node, style),
+    }
+}
+
+/// Slack treats these as control characters in message text
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "## [1.0.0](https://github.com/owner/repo/releases/tag/v1.0.0) - 2025-01-01\n\n### Added\n\n- **bold** support for `Vec<T>` ([#12](https://github.com/owner/repo/pull/12))\n- a <b>tag</b> & more\n  continued\n";
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(
+            plain(NOTES),
+            "1.0.0 - 2025-01-01\n\nAdded\n\n- bold support for Vec<T> (#12)\n- a tag & more continued\n"
+        );
+    }
+
+    #[test]
+    fn test_slack() {
+        assert_eq!(
+            slack(NOTES),
+            "*<https://github.com/owner/repo/releases/tag/v1.0.0|1.0.0> - 2025-01-01*\n\n*Added*\n\n• *bold* support for `Vec&lt;T&gt;` (<https://github.com/owner/repo/pull/12|#12>)\n• a tag &amp; more continued\n"
+        );
+    }
+
+    #[test]
+    fn test_terminal() {
+        colored::control::set_override(false);
+        assert_eq!(
+            terminal(NOTES),
+            "1.0.0 - 2025-01-01\n\nAdded\n\n• bold support for Vec<T> (#12)\n• a tag & more continued\n"
+        );
+    }
+
+    #[test]
+    fn test_anchor() {
+        assert_eq!(anchor("[1.2.0] - 2025-02-01"), "120---2025-02-01");
+        assert_eq!(anchor("Unreleased"), "unreleased");
+        assert_eq!(
+            anchor("1.0.0 - 2025-01-01 [YANKED]"),
+            "100---2025-01-01-yanked"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let html = html(NOTES);
+        assert!(html.contains(
+            "<h2><a href=\"https://github.com/owner/repo/releases/tag/v1.0.0\">1.0.0</a> - 2025-01-01</h2>"
+        ));
+        assert!(html.contains("<li><strong>bold</strong> support for <code>Vec&lt;T&gt;</code>"));
+        assert!(!html.contains("<b>"));
+    }
+}
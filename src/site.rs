@@ -0,0 +1,102 @@
+use crate::feed::escape;
+use crate::{Error, Result};
+
+/// Styles for pages rendered without a template of their own
+const STYLE: &str = "body { max-width: 48rem; margin: 2rem auto; padding: 0 1rem; font-family: system-ui, sans-serif; line-height: 1.5; color: #1f2328; }
+h2 { margin-top: 2.5rem; padding-bottom: 0.3rem; border-bottom: 1px solid #d1d9e0; }
+h2 .anchor { float: left; margin-left: -1.2rem; padding-right: 0.2rem; color: #d1d9e0; text-decoration: none; }
+h2:hover .anchor { color: #59636e; }
+h3 { margin-bottom: 0.25rem; }
+a { color: #0969da; }
+code { padding: 0.1rem 0.3rem; background: #eff1f3; border-radius: 4px; }";
+
+/// A changelog as a standalone HTML page, e.g. a docs site's `/changelog`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub title: String,
+    /// The changelog's header, like its title and introduction, as an HTML fragment
+    pub header: String,
+    /// Newest first
+    pub releases: Vec<PageRelease>,
+}
+
+/// One version on a page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRelease {
+    /// Id of the version's heading, the same one forges give it
+    pub anchor: String,
+    /// The heading and notes as an HTML fragment
+    pub html: String,
+}
+
+impl Page {
+    /// The header and every release, each heading linking to itself
+    pub fn body(&self) -> String {
+        let mut html = self.header.clone();
+        for release in &self.releases {
+            let heading = format!("<h2 id=\"{}\">", escape(&release.anchor));
+            let linked = format!(
+                "{}<a class=\"anchor\" href=\"#{}\" aria-hidden=\"true\">#</a>",
+                heading,
+                escape(&release.anchor)
+            );
+            html.push_str("<section class=\"release\">\n");
+            html.push_str(&release.html.replacen(&heading, &linked, 1));
+            html.push_str("</section>\n");
+        }
+        html
+    }
+
+    /// The page as an HTML document. A `template` wraps the body in its own
+    /// markup, with `{content}` where the body goes and `{title}` for the
+    /// page title.
+    pub fn document(&self, template: Option<&str>) -> Result<String> {
+        let title = escape(&self.title);
+        match template {
+            Some(template) if !template.contains("{content}") => Err(Error::InvalidInput(
+                "The HTML template needs a {content} placeholder".to_string(),
+            )),
+            Some(template) => Ok(template
+                .replace("{title}", &title)
+                .replace("{content}", &self.body())),
+            None => Ok(format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<main>\n{}</main>\n</body>\n</html>\n",
+                title,
+                STYLE,
+                self.body()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_document() {
+        let page = Page {
+            title: "app & friends".to_string(),
+            header: "<h1>Changelog</h1>\n".to_string(),
+            releases: vec![PageRelease {
+                anchor: "110---2025-02-01".to_string(),
+                html: "<h2 id=\"110---2025-02-01\">1.1.0 - 2025-02-01</h2>\n<h3>Added</h3>\n<ul>\n<li>export</li>\n</ul>\n".to_string(),
+            }],
+        };
+
+        let document = page.document(None).unwrap();
+        assert!(document.contains("<title>app &amp; friends</title>"));
+        assert!(document.contains(
+            "<main>\n<h1>Changelog</h1>\n<section class=\"release\">\n<h2 id=\"110---2025-02-01\"><a class=\"anchor\" href=\"#110---2025-02-01\" aria-hidden=\"true\">#</a>1.1.0 - 2025-02-01</h2>\n"
+        ));
+
+        assert_eq!(
+            page.document(Some("<div>{title}</div>{content}")).unwrap(),
+            format!("<div>app &amp; friends</div>{}", page.body())
+        );
+        assert!(matches!(
+            page.document(Some("<div>{title}</div>")),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+use crate::notes::BREAKING;
+use regex::Regex;
+use serde::Deserialize;
+
+/// What goes into an entry before it's laid out as a bullet
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFields {
+    pub description: String,
+    /// Part of the project the change is in, e.g. a conventional commit scope
+    pub scope: Option<String>,
+    /// Pull request, issue, and commit links, e.g. `[#12](url)`
+    pub links: Vec<String>,
+    /// Marks the entry as a breaking change, ahead of the template
+    pub breaking: bool,
+}
+
+impl EntryFields {
+    pub fn new(description: impl Into<String>) -> Self {
+        EntryFields {
+            description: description.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The entry laid out by `template`, or without one the description
+    /// followed by each link in parentheses
+    pub fn render(&self, template: Option<&Template>) -> String {
+        let entry = match template {
+            Some(template) => template.render(self),
+            None => std::iter::once(self.description.clone())
+                .chain(self.links.iter().map(|link| format!("({})", link)))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        if self.breaking {
+            format!("{} {}", BREAKING, entry)
+        } else {
+            entry
+        }
+    }
+
+    fn value(&self, field: Field) -> Option<String> {
+        match field {
+            Field::Description => Some(self.description.clone()),
+            Field::Scope => self.scope.clone().filter(|s| !s.is_empty()),
+            Field::Links => (!self.links.is_empty()).then(|| self.links.join("; ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Description,
+    Scope,
+    Links,
+}
+
+impl Field {
+    const ALL: [Field; 3] = [Field::Description, Field::Scope, Field::Links];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Description => "description",
+            Field::Scope => "scope",
+            Field::Links => "links",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Field(Field),
+}
+
+/// How entries are laid out, from `[entries] template` in `changelog.toml`,
+/// e.g. `- **{scope}**: {description} ({links})`. A word holding a
+/// placeholder with no value, like `({links})` for an entry without links,
+/// is left out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Template {
+    words: Vec<Vec<Segment>>,
+}
+
+impl TryFrom<String> for Template {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        // Entries are always bullets, so the template's own marker is optional
+        let trimmed = value.trim_start();
+        let body = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .unwrap_or(trimmed);
+
+        let mut seen = Vec::new();
+        let mut words = Vec::new();
+        for word in body.split_whitespace() {
+            let mut segments = Vec::new();
+            let mut rest = word;
+            while let Some(start) = rest.find('{') {
+                let end = rest[start..]
+                    .find('}')
+                    .map(|i| start + i)
+                    .ok_or_else(|| format!("unclosed placeholder in entry template '{}'", value))?;
+                let name = &rest[start + 1..end];
+                let field = Field::ALL
+                    .into_iter()
+                    .find(|f| f.name() == name)
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown placeholder '{{{}}}' in entry template, expected {{description}}, {{scope}}, or {{links}}",
+                            name
+                        )
+                    })?;
+                if seen.contains(&field) {
+                    return Err(format!(
+                        "placeholder '{{{}}}' appears more than once in entry template",
+                        name
+                    ));
+                }
+                seen.push(field);
+                if start > 0 {
+                    segments.push(Segment::Text(rest[..start].to_string()));
+                }
+                segments.push(Segment::Field(field));
+                rest = &rest[end + 1..];
+            }
+            if !rest.is_empty() {
+                segments.push(Segment::Text(rest.to_string()));
+            }
+            words.push(segments);
+        }
+
+        if !seen.contains(&Field::Description) {
+            return Err("entry template must include {description}".to_string());
+        }
+        Ok(Template { words })
+    }
+}
+
+impl Template {
+    pub fn render(&self, fields: &EntryFields) -> String {
+        let mut words = Vec::new();
+        'words: for segments in &self.words {
+            let mut word = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Text(text) => word.push_str(text),
+                    Segment::Field(field) => match fields.value(*field) {
+                        Some(value) => word.push_str(&value),
+                        None => continue 'words,
+                    },
+                }
+            }
+            words.push(word);
+        }
+        words.join(" ")
+    }
+
+    /// The fields of an entry laid out by this template, give or take
+    /// whitespace between words, or `None` if it doesn't fit the pattern. A
+    /// breaking change marker ahead of it is taken off.
+    pub fn parse(&self, entry: &str) -> Option<EntryFields> {
+        let breaking = crate::notes::is_breaking(entry);
+        let entry = match breaking {
+            true => &entry.trim_start()[BREAKING.len()..],
+            false => entry,
+        };
+        let mut pattern = String::from(r"(?s)^\s*");
+        for (i, segments) in self.words.iter().enumerate() {
+            let separator = if i == 0 { "" } else { r"\s*" };
+            let mut word = String::new();
+            let mut optional = false;
+            for segment in segments {
+                match segment {
+                    Segment::Text(text) => word.push_str(&regex::escape(text)),
+                    Segment::Field(field) => {
+                        optional |= *field != Field::Description;
+                        word.push_str(&format!("(?P<{}>.+?)", field.name()));
+                    }
+                }
+            }
+            if optional {
+                pattern.push_str(&format!("(?:{}{})?", separator, word));
+            } else {
+                pattern.push_str(separator);
+                pattern.push_str(&word);
+            }
+        }
+        pattern.push_str(r"\s*$");
+
+        let captures = Regex::new(&pattern).ok()?.captures(entry)?;
+        let capture = |name: &str| captures.name(name).map(|m| m.as_str().trim().to_string());
+        Some(EntryFields {
+            description: capture("description")?,
+            scope: capture("scope"),
+            links: capture("links")
+                .map(|links| links.split("; ").map(String::from).collect())
+                .unwrap_or_default(),
+            breaking,
+        })
+    }
+}
+
+/// How a release credits whoever cut it, from `[release.attribution]
+/// template` in `changelog.toml`, e.g. `Released by {name}`. The line goes
+/// below the release heading, so it also reads back the name it holds.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Attribution {
+    prefix: String,
+    suffix: String,
+}
+
+impl Default for Attribution {
+    fn default() -> Self {
+        Attribution {
+            prefix: "Released by ".to_string(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl TryFrom<String> for Attribution {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        let value = value.trim();
+        match value.split_once("{name}") {
+            Some((prefix, suffix)) if !suffix.contains("{name}") => Ok(Attribution {
+                prefix: prefix.to_string(),
+                suffix: suffix.to_string(),
+            }),
+            _ => Err(format!(
+                "attribution template '{}' must include {{name}} exactly once",
+                value
+            )),
+        }
+    }
+}
+
+impl Attribution {
+    pub fn render(&self, name: &str) -> String {
+        format!("{}{}{}", self.prefix, name, self.suffix)
+    }
+
+    /// The name credited in release `notes`, from a line above the first section
+    pub fn parse(&self, notes: &str) -> Option<String> {
+        notes
+            .lines()
+            .take_while(|line| !line.trim_start().starts_with("### "))
+            .find_map(|line| {
+                line.trim()
+                    .strip_prefix(self.prefix.as_str())?
+                    .strip_suffix(self.suffix.as_str())
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(source: &str) -> Template {
+        Template::try_from(source.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_render_template() {
+        let fields = EntryFields {
+            description: "crash on startup".to_string(),
+            scope: Some("cli".to_string()),
+            links: vec!["[#12](url)".to_string(), "[abc1234](url)".to_string()],
+            breaking: false,
+        };
+        assert_eq!(
+            template("- **{scope}**: {description} ({links})").render(&fields),
+            "**cli**: crash on startup ([#12](url); [abc1234](url))"
+        );
+        assert_eq!(
+            fields.render(None),
+            "crash on startup ([#12](url)) ([abc1234](url))"
+        );
+
+        // The breaking marker goes ahead of whatever the template lays out
+        let breaking = EntryFields {
+            breaking: true,
+            ..fields.clone()
+        };
+        assert_eq!(
+            breaking.render(Some(&template("**{scope}**: {description}"))),
+            "**Breaking:** **cli**: crash on startup"
+        );
+
+        // Words with an empty placeholder are left out
+        let bare = EntryFields::new("crash");
+        assert_eq!(
+            template("**{scope}**: {description} ({links})").render(&bare),
+            "crash"
+        );
+
+        assert!(Template::try_from("{scope}: text".to_string()).is_err());
+        assert!(Template::try_from("{description} {author}".to_string()).is_err());
+        assert!(Template::try_from("{description} {description}".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let template = template("- **{scope}**: {description} ({links})");
+        assert_eq!(
+            template.parse("**cli**:crash  on startup  ([#12](url); [#13](url))"),
+            Some(EntryFields {
+                description: "crash  on startup".to_string(),
+                scope: Some("cli".to_string()),
+                links: vec!["[#12](url)".to_string(), "[#13](url)".to_string()],
+                breaking: false,
+            })
+        );
+        assert_eq!(
+            template.parse("plain entry"),
+            Some(EntryFields::new("plain entry"))
+        );
+        assert_eq!(
+            template.parse("**Breaking:** **cli**: drop node 16"),
+            Some(EntryFields {
+                scope: Some("cli".to_string()),
+                breaking: true,
+                ..EntryFields::new("drop node 16")
+            })
+        );
+
+        let strict = self::template("[{scope}] {description}.");
+        assert_eq!(strict.parse("no full stop"), None);
+    }
+
+    #[test]
+    fn test_attribution() {
+        let attribution = Attribution::try_from("_Released by {name}._".to_string()).unwrap();
+        assert_eq!(attribution.render("Jane Doe"), "_Released by Jane Doe._");
+        assert_eq!(
+            attribution.parse("_Released by Jane Doe._\n\n### Added\n\n- export\n"),
+            Some("Jane Doe".to_string())
+        );
+        assert_eq!(
+            attribution.parse("### Added\n\n_Released by Jane Doe._\n"),
+            None
+        );
+        assert_eq!(
+            Attribution::default().parse("Released by @octocat\n"),
+            Some("@octocat".to_string())
+        );
+
+        assert!(Attribution::try_from("Released".to_string()).is_err());
+        assert!(Attribution::try_from("{name} and {name}".to_string()).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Error, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Manifests bumped when `changelog.toml` doesn't list any files itself
+pub const DEFAULT_FILES: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+/// A file holding the project version, relative to the changelog
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum VersionFile {
+    /// A Cargo.toml, package.json, or pyproject.toml, recognized by name
+    Manifest(PathBuf),
+    /// Any file; the first capture group of each match is the version
+    Pattern { path: PathBuf, pattern: String },
+}
+
+impl VersionFile {
+    pub fn path(&self) -> &Path {
+        match self {
+            VersionFile::Manifest(path) => path,
+            VersionFile::Pattern { path, .. } => path,
+        }
+    }
+
+    /// `content` with its version replaced by `version`
+    pub fn bump(&self, content: &str, version: &str) -> Result<String> {
+        let bumped = match self {
+            VersionFile::Manifest(path) => bump_manifest(path, content, version)?,
+            VersionFile::Pattern { path, pattern } => {
+                let regex = Regex::new(pattern).map_err(|e| {
+                    Error::InvalidInput(format!("Invalid pattern for {}: {}", path.display(), e))
+                })?;
+                replace_captures(&regex, content, version, usize::MAX)
+            }
+        };
+
+        bumped.ok_or_else(|| {
+            Error::NotFound(format!(
+                "No version field found in {}",
+                self.path().display()
+            ))
+        })
+    }
+}
+
+fn bump_manifest(path: &Path, content: &str, version: &str) -> Result<Option<String>> {
+    let bumped = match path.file_name().and_then(|name| name.to_str()) {
+        Some("Cargo.toml") => bump_toml(content, &["package", "workspace.package"], version),
+        Some("pyproject.toml") => bump_toml(content, &["project", "tool.poetry"], version),
+        Some("package.json") => {
+            let field = Regex::new(r#""version"\s*:\s*"([^"]*)""#).unwrap();
+            replace_captures(&field, content, version, 1)
+        }
+        _ => {
+            return Err(Error::InvalidInput(format!(
+                "Don't know where the version is in {}; give it a pattern in changelog.toml",
+                path.display()
+            )))
+        }
+    };
+    Ok(bumped)
+}
+
+/// Set `version = "..."` in the first of `tables` that has one, leaving the
+/// rest of the file, comments and formatting included, untouched
+fn bump_toml(content: &str, tables: &[&str], version: &str) -> Option<String> {
+    let field = Regex::new(r#"^(\s*version\s*=\s*)(["'])[^"']*(["'])"#).unwrap();
+    let mut table = String::new();
+    let mut bumped = false;
+    let mut output = String::with_capacity(content.len());
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            table = trimmed
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim()
+                .to_string();
+        } else if !bumped && tables.contains(&table.as_str()) {
+            if let Some(caps) = field.captures(line) {
+                output.push_str(&caps[1]);
+                output.push_str(&caps[2]);
+                output.push_str(version);
+                output.push_str(&caps[3]);
+                output.push_str(&line[caps[0].len()..]);
+                bumped = true;
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+
+    bumped.then_some(output)
+}
+
+/// Replace the first capture group, or the whole match when there is none,
+/// in up to `limit` matches
+fn replace_captures(regex: &Regex, content: &str, version: &str, limit: usize) -> Option<String> {
+    let mut output = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut replaced = false;
+    for caps in regex.captures_iter(content).take(limit) {
+        let found = caps.get(1).or_else(|| caps.get(0))?;
+        output.push_str(&content[last..found.start()]);
+        output.push_str(version);
+        last = found.end();
+        replaced = true;
+    }
+    if !replaced {
+        return None;
+    }
+    output.push_str(&content[last..]);
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str) -> VersionFile {
+        VersionFile::Manifest(PathBuf::from(name))
+    }
+
+    #[test]
+    fn test_bump_manifests() {
+        let cargo = "[package]\nname = \"app\"\nversion = \"1.0.0\" # keep me\n\n[dependencies]\nserde = { version = \"1.0\" }\n";
+        assert_eq!(
+            manifest("Cargo.toml").bump(cargo, "1.1.0").unwrap(),
+            "[package]\nname = \"app\"\nversion = \"1.1.0\" # keep me\n\n[dependencies]\nserde = { version = \"1.0\" }\n"
+        );
+
+        let workspace = "[workspace]\nmembers = []\n\n[workspace.package]\nversion = '0.3.0'\n";
+        assert!(manifest("Cargo.toml")
+            .bump(workspace, "0.4.0")
+            .unwrap()
+            .contains("version = '0.4.0'"));
+
+        let pyproject =
+            "[build-system]\nrequires = []\n\n[project]\nname = \"app\"\nversion = \"2.0.0\"\n";
+        assert!(manifest("pyproject.toml")
+            .bump(pyproject, "2.1.0")
+            .unwrap()
+            .ends_with("version = \"2.1.0\"\n"));
+
+        let package = "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"engines\": { \"version\": \"18\" }\n}\n";
+        assert_eq!(
+            manifest("package.json").bump(package, "1.0.1").unwrap(),
+            "{\n  \"name\": \"app\",\n  \"version\": \"1.0.1\",\n  \"engines\": { \"version\": \"18\" }\n}\n"
+        );
+
+        assert!(manifest("Cargo.toml")
+            .bump("[dependencies]\nversion = \"1\"\n", "2.0.0")
+            .is_err());
+        assert!(manifest("setup.py").bump("", "1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_bump_pattern() {
+        let file = VersionFile::Pattern {
+            path: PathBuf::from("src/version.rs"),
+            pattern: r#"VERSION: &str = "([^"]+)""#.to_string(),
+        };
+        assert_eq!(
+            file.bump("pub const VERSION: &str = \"1.0.0\";\n", "1.2.0")
+                .unwrap(),
+            "pub const VERSION: &str = \"1.2.0\";\n"
+        );
+        assert!(file.bump("nothing here", "1.2.0").is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use crate::{Bump, Error, Result};
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// How releases are numbered, from `[release] scheme` in `changelog.toml`:
+/// `semver`, `calver` for `YYYY.0M.MICRO`, or a calendar version pattern
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Scheme {
+    #[default]
+    Semver,
+    CalVer(CalVer),
+}
+
+impl TryFrom<String> for Scheme {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "semver" => Ok(Scheme::Semver),
+            "calver" => CalVer::try_from(CalVer::DEFAULT.to_string()).map(Scheme::CalVer),
+            _ => CalVer::try_from(value).map(Scheme::CalVer),
+        }
+    }
+}
+
+impl std::fmt::Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scheme::Semver => f.write_str("semver"),
+            Scheme::CalVer(calver) => f.write_str(&calver.pattern),
+        }
+    }
+}
+
+impl Scheme {
+    /// Whether `version` is numbered the way the scheme says
+    pub fn is_valid(&self, version: &str) -> bool {
+        match self {
+            Scheme::Semver => semver::Version::parse(version).is_ok(),
+            Scheme::CalVer(calver) => calver.numbers(version).is_some(),
+        }
+    }
+
+    /// How two versions compare, when both follow the scheme
+    pub fn compare(&self, a: &str, b: &str) -> Option<Ordering> {
+        match self {
+            Scheme::Semver => {
+                let a = semver::Version::parse(a).ok()?;
+                let b = semver::Version::parse(b).ok()?;
+                Some(a.cmp(&b))
+            }
+            Scheme::CalVer(calver) => Some(calver.numbers(a)?.cmp(&calver.numbers(b)?)),
+        }
+    }
+
+    /// The version after `latest` for a `bump`. A semver pre-release is
+    /// finalized when it's already that big a step, so `minor` after
+    /// `1.3.0-rc.2` is `1.3.0`. Calendar versions don't bump: they move to the
+    /// period `date` falls in, counting MICRO up from `latest` within the same
+    /// period.
+    pub fn next(&self, latest: Option<&str>, bump: Bump, date: NaiveDate) -> Result<String> {
+        match self {
+            Scheme::Semver => Ok(bumped(&parse_latest(latest)?, bump).to_string()),
+            Scheme::CalVer(calver) => calver.next(latest, date),
+        }
+    }
+
+    /// The `label` pre-release after `latest` for a `bump`, like `1.3.0-rc.1`
+    /// after `1.2.0`. Another pre-release of the same version counts up
+    /// instead, so `1.3.0-rc.1` is followed by `1.3.0-rc.2`.
+    pub fn pre_release(&self, latest: Option<&str>, bump: Bump, label: &str) -> Result<String> {
+        let Scheme::Semver = self else {
+            return Err(Error::InvalidInput(
+                "Pre-releases need the semver version scheme".to_string(),
+            ));
+        };
+        let latest = parse_latest(latest)?;
+        let mut next = bumped(&latest, bump);
+        let number = match latest.pre.as_str().strip_prefix(label) {
+            Some(number) if !latest.pre.is_empty() && next == final_of(&latest) => number
+                .strip_prefix('.')
+                .and_then(|n| n.parse::<u64>().ok())
+                .map_or(1, |n| n + 1),
+            _ => 1,
+        };
+        next.pre = semver::Prerelease::new(&format!("{}.{}", label, number)).map_err(|_| {
+            Error::InvalidInput(format!("'{}' is not a valid pre-release label", label))
+        })?;
+        if next <= latest {
+            return Err(Error::InvalidVersion(format!(
+                "{} would come before {}",
+                next, latest
+            )));
+        }
+        Ok(next.to_string())
+    }
+
+    /// The final release of `latest`, which has to be a pre-release
+    pub fn stable(&self, latest: Option<&str>) -> Result<String> {
+        let Scheme::Semver = self else {
+            return Err(Error::InvalidInput(
+                "Pre-releases need the semver version scheme".to_string(),
+            ));
+        };
+        let latest = parse_latest(latest)?;
+        if latest.pre.is_empty() {
+            return Err(Error::InvalidVersion(format!(
+                "{} is not a pre-release",
+                latest
+            )));
+        }
+        Ok(final_of(&latest).to_string())
+    }
+}
+
+fn parse_latest(latest: Option<&str>) -> Result<semver::Version> {
+    let latest = latest.ok_or_else(|| Error::NotFound("No previous version found".to_string()))?;
+    semver::Version::parse(latest).map_err(|e| Error::InvalidVersion(format!("{}: {}", latest, e)))
+}
+
+/// `version` without its pre-release or build metadata
+fn final_of(version: &semver::Version) -> semver::Version {
+    semver::Version::new(version.major, version.minor, version.patch)
+}
+
+fn bumped(version: &semver::Version, bump: Bump) -> semver::Version {
+    // How big a step the pre-release's final version already is
+    let step = match (version.minor, version.patch) {
+        (0, 0) => Bump::Major,
+        (_, 0) => Bump::Minor,
+        _ => Bump::Patch,
+    };
+    if !version.pre.is_empty() && bump <= step {
+        return final_of(version);
+    }
+    match bump {
+        Bump::Major => semver::Version::new(version.major + 1, 0, 0),
+        Bump::Minor => semver::Version::new(version.major, version.minor + 1, 0),
+        Bump::Patch => semver::Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+/// A calendar versioning pattern like `YYYY.0M.MICRO`, built from the
+/// calver.org tokens `YYYY`, `YY`, `0Y`, `MM`, `0M`, `WW`, `0W`, `DD`, `0D`,
+/// and `MICRO` joined by separators
+#[derive(Debug, Clone)]
+pub struct CalVer {
+    pattern: String,
+    parts: Vec<Part>,
+    regex: Regex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Token(Token),
+    Separator(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    FullYear,
+    ShortYear,
+    PaddedYear,
+    Month,
+    PaddedMonth,
+    Week,
+    PaddedWeek,
+    Day,
+    PaddedDay,
+    Micro,
+}
+
+const TOKENS: &[(&str, Token)] = &[
+    ("YYYY", Token::FullYear),
+    ("MICRO", Token::Micro),
+    ("YY", Token::ShortYear),
+    ("0Y", Token::PaddedYear),
+    ("MM", Token::Month),
+    ("0M", Token::PaddedMonth),
+    ("WW", Token::Week),
+    ("0W", Token::PaddedWeek),
+    ("DD", Token::Day),
+    ("0D", Token::PaddedDay),
+];
+
+impl Token {
+    fn regex(self) -> &'static str {
+        match self {
+            Token::FullYear => r"(\d{4})",
+            Token::ShortYear | Token::Micro => r"(0|[1-9]\d*)",
+            Token::PaddedYear => r"(\d{2,})",
+            Token::Month => r"([1-9]|1[0-2])",
+            Token::PaddedMonth => r"(0[1-9]|1[0-2])",
+            Token::Week => r"([1-9]|[1-4]\d|5[0-3])",
+            Token::PaddedWeek => r"(0[1-9]|[1-4]\d|5[0-3])",
+            Token::Day => r"([1-9]|[12]\d|3[01])",
+            Token::PaddedDay => r"(0[1-9]|[12]\d|3[01])",
+        }
+    }
+
+    /// The token's value on `date`
+    fn value(self, date: NaiveDate) -> u64 {
+        let year = date.year() as u64;
+        match self {
+            Token::FullYear => year,
+            Token::ShortYear | Token::PaddedYear => year - 2000,
+            Token::Month | Token::PaddedMonth => date.month() as u64,
+            Token::Week | Token::PaddedWeek => date.iso_week().week() as u64,
+            Token::Day | Token::PaddedDay => date.day() as u64,
+            Token::Micro => 0,
+        }
+    }
+
+    fn render(self, value: u64) -> String {
+        match self {
+            Token::PaddedYear | Token::PaddedMonth | Token::PaddedWeek | Token::PaddedDay => {
+                format!("{:02}", value)
+            }
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Patterns are the same when they're written the same
+impl PartialEq for CalVer {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for CalVer {}
+
+impl CalVer {
+    /// The pattern `scheme = "calver"` stands for
+    pub const DEFAULT: &'static str = "YYYY.0M.MICRO";
+
+    fn tokens(&self) -> impl Iterator<Item = Token> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Token(token) => Some(*token),
+            Part::Separator(_) => None,
+        })
+    }
+
+    /// Each token's number in `version`, in pattern order
+    fn numbers(&self, version: &str) -> Option<Vec<u64>> {
+        let captures = self.regex.captures(version)?;
+        captures
+            .iter()
+            .skip(1)
+            .map(|m| m?.as_str().parse().ok())
+            .collect()
+    }
+
+    fn next(&self, latest: Option<&str>, date: NaiveDate) -> Result<String> {
+        let today: Vec<u64> = self.tokens().map(|token| token.value(date)).collect();
+        let latest = latest.and_then(|latest| Some((latest, self.numbers(latest)?)));
+        let has_micro = self.tokens().any(|token| token == Token::Micro);
+        let same_period = latest.as_ref().is_some_and(|(_, numbers)| {
+            self.tokens()
+                .zip(numbers.iter().zip(&today))
+                .all(|(token, (a, b))| token == Token::Micro || a == b)
+        });
+
+        let mut values = today;
+        if let (true, Some((latest, numbers))) = (same_period, &latest) {
+            if !has_micro {
+                return Err(Error::InvalidVersion(format!(
+                    "{} is already released and {} has no MICRO to count up",
+                    latest, self.pattern
+                )));
+            }
+            for ((token, value), latest) in self.tokens().zip(values.iter_mut()).zip(numbers) {
+                if token == Token::Micro {
+                    *value = latest + 1;
+                }
+            }
+        }
+
+        let mut values = values.into_iter();
+        Ok(self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Token(token) => token.render(values.next().unwrap_or_default()),
+                Part::Separator(c) => c.to_string(),
+            })
+            .collect())
+    }
+}
+
+impl TryFrom<String> for CalVer {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = pattern.as_str();
+        while !rest.is_empty() {
+            if let Some((name, token)) = TOKENS.iter().find(|(name, _)| rest.starts_with(name)) {
+                parts.push(Part::Token(*token));
+                rest = &rest[name.len()..];
+            } else {
+                let c = rest.chars().next().unwrap_or_default();
+                if c.is_alphanumeric() {
+                    return Err(format!(
+                        "unknown token in version scheme '{}', expected semver, calver, or a pattern of YYYY, YY, 0Y, MM, 0M, WW, 0W, DD, 0D, and MICRO",
+                        pattern
+                    ));
+                }
+                parts.push(Part::Separator(c));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !parts
+            .iter()
+            .any(|part| matches!(part, Part::Token(token) if *token != Token::Micro))
+        {
+            return Err(format!("version scheme '{}' has no date in it", pattern));
+        }
+
+        let regex: String = parts
+            .iter()
+            .map(|part| match part {
+                Part::Token(token) => token.regex().to_string(),
+                Part::Separator(c) => regex::escape(&c.to_string()),
+            })
+            .collect();
+        let regex = Regex::new(&format!("^{}$", regex)).map_err(|e| e.to_string())?;
+        Ok(CalVer {
+            pattern,
+            parts,
+            regex,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calver() {
+        let scheme = Scheme::try_from("calver".to_string()).unwrap();
+        let may = NaiveDate::from_ymd_opt(2025, 5, 14).unwrap();
+        assert_eq!(scheme.next(None, Bump::Patch, may).unwrap(), "2025.05.0");
+        assert_eq!(
+            scheme.next(Some("2025.05.3"), Bump::Major, may).unwrap(),
+            "2025.05.4"
+        );
+        assert_eq!(
+            scheme.next(Some("2025.04.3"), Bump::Patch, may).unwrap(),
+            "2025.05.0"
+        );
+        assert!(scheme.is_valid("2025.12.10"));
+        assert!(!scheme.is_valid("2025.5.1"));
+        assert!(!scheme.is_valid("1.2.3"));
+        assert_eq!(
+            scheme.compare("2025.10.0", "2025.09.12"),
+            Some(Ordering::Greater)
+        );
+
+        let daily = Scheme::try_from("YY.MM.DD".to_string()).unwrap();
+        assert_eq!(
+            daily.next(Some("25.5.13"), Bump::Patch, may).unwrap(),
+            "25.5.14"
+        );
+        assert!(daily.next(Some("25.5.14"), Bump::Patch, may).is_err());
+
+        assert!(Scheme::try_from("YYYY.MINOR".to_string()).is_err());
+        assert!(Scheme::try_from("MICRO".to_string()).is_err());
+        assert_eq!(
+            Scheme::Semver
+                .next(Some("1.2.3"), Bump::Minor, may)
+                .unwrap(),
+            "1.3.0"
+        );
+    }
+
+    #[test]
+    fn test_pre_releases() {
+        let semver = Scheme::Semver;
+        let pre = |latest, bump, label| semver.pre_release(Some(latest), bump, label);
+        assert_eq!(pre("1.2.0", Bump::Minor, "rc").unwrap(), "1.3.0-rc.1");
+        assert_eq!(pre("1.3.0-rc.1", Bump::Minor, "rc").unwrap(), "1.3.0-rc.2");
+        assert_eq!(pre("1.3.0-rc.1", Bump::Patch, "rc").unwrap(), "1.3.0-rc.2");
+        assert_eq!(
+            pre("1.3.0-beta.4", Bump::Minor, "rc").unwrap(),
+            "1.3.0-rc.1"
+        );
+        assert_eq!(pre("1.3.0-rc.2", Bump::Major, "rc").unwrap(), "2.0.0-rc.1");
+        assert_eq!(
+            pre("1.2.0+build.5", Bump::Patch, "rc").unwrap(),
+            "1.2.1-rc.1"
+        );
+        // Going from rc back to beta would sort before the latest release
+        assert!(pre("1.3.0-rc.1", Bump::Minor, "beta").is_err());
+        assert!(pre("1.2.0", Bump::Minor, "r c").is_err());
+
+        let may = NaiveDate::from_ymd_opt(2025, 5, 14).unwrap();
+        assert_eq!(
+            semver.next(Some("1.3.0-rc.2"), Bump::Minor, may).unwrap(),
+            "1.3.0"
+        );
+        assert_eq!(
+            semver.next(Some("1.3.0-rc.2"), Bump::Major, may).unwrap(),
+            "2.0.0"
+        );
+        assert_eq!(semver.stable(Some("1.3.0-rc.2")).unwrap(), "1.3.0");
+        assert!(semver.stable(Some("1.3.0")).is_err());
+
+        let calver = Scheme::try_from("calver".to_string()).unwrap();
+        assert!(calver
+            .pre_release(Some("2025.05.0"), Bump::Patch, "rc")
+            .is_err());
+    }
+}
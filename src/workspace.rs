@@ -0,0 +1,69 @@
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+/// Ignore file with gitignore syntax that only affects changelog discovery
+pub const IGNORE_FILENAME: &str = ".changelogignore";
+
+const CHANGELOG_FILENAME: &str = "CHANGELOG.md";
+
+/// Find every CHANGELOG.md below `root`, skipping anything excluded by
+/// .gitignore, .ignore or .changelogignore files along the way.
+pub fn discover_changelogs(root: &Path) -> Vec<PathBuf> {
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILENAME)
+        .build();
+
+    let mut paths: Vec<PathBuf> = walker
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.eq_ignore_ascii_case(CHANGELOG_FILENAME))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn touch(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "# Changelog\n").unwrap();
+    }
+
+    #[test]
+    fn test_discover_respects_ignore_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        touch(root, "CHANGELOG.md");
+        touch(root, "packages/api/CHANGELOG.md");
+        touch(root, "node_modules/dep/CHANGELOG.md");
+        touch(root, "vendor/lib/CHANGELOG.md");
+        fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(root.join(IGNORE_FILENAME), "vendor/\n").unwrap();
+
+        let found: Vec<PathBuf> = discover_changelogs(root)
+            .into_iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                PathBuf::from("CHANGELOG.md"),
+                PathBuf::from("packages/api/CHANGELOG.md"),
+            ]
+        );
+    }
+}